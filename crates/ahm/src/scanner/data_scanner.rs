@@ -171,6 +171,11 @@ impl Scanner {
         Ok(())
     }
 
+    /// Change the time between scan cycles, the scan loop picks it up after its current cycle
+    pub async fn set_scan_interval(&self, scan_interval: Duration) {
+        self.config.write().await.scan_interval = scan_interval;
+    }
+
     /// Stop the scanner gracefully
     pub async fn stop(&self) -> Result<()> {
         let mut state = self.state.write().await;
@@ -376,6 +381,9 @@ impl Scanner {
             }
         }
 
+        // Phase 3: Generate the inventory reports that are due
+        rustfs_ecstore::bucket::inventory::run_due_inventories();
+
         // Update scan duration
         let scan_duration = SystemTime::now().duration_since(start_time).unwrap_or(Duration::ZERO);
 
@@ -911,6 +919,12 @@ impl Scanner {
                     if let Err(e) = self.scan_cycle().await {
                         error!("Scan cycle failed: {}", e);
                     }
+
+                    let scan_interval = self.config.read().await.scan_interval;
+                    if !scan_interval.is_zero() && scan_interval != interval.period() {
+                        info!("Scan interval changed to {:?}", scan_interval);
+                        interval = tokio::time::interval_at(tokio::time::Instant::now() + scan_interval, scan_interval);
+                    }
                 }
                 _ = cancel_token.cancelled() => {
                     info!("Received cancellation, stopping scanner loop");
@@ -86,6 +86,10 @@ pub const RUSTFS_TLS_KEY: &str = "rustfs_key.pem";
 /// This is the default cert for TLS.
 pub const RUSTFS_TLS_CERT: &str = "rustfs_cert.pem";
 
+/// Default CA cert for internode mutual TLS
+/// When present in the TLS directory, nodes authenticate each other with certificates signed by this CA.
+pub const RUSTFS_TLS_CA_CERT: &str = "rustfs_ca.pem";
+
 /// Default port for rustfs
 /// This is the default port for rustfs.
 /// This is used to bind the server to a specific port.
@@ -219,6 +223,9 @@ mod tests {
 
         assert_eq!(RUSTFS_TLS_CERT, "rustfs_cert.pem");
         assert!(RUSTFS_TLS_CERT.ends_with(".pem"), "TLS cert should be PEM format");
+
+        assert_eq!(RUSTFS_TLS_CA_CERT, "rustfs_ca.pem");
+        assert!(RUSTFS_TLS_CA_CERT.ends_with(".pem"), "TLS CA cert should be PEM format");
     }
 
     #[test]
@@ -277,6 +284,7 @@ mod tests {
             DEFAULT_SECRET_KEY,
             RUSTFS_TLS_KEY,
             RUSTFS_TLS_CERT,
+            RUSTFS_TLS_CA_CERT,
             DEFAULT_ADDRESS,
             DEFAULT_CONSOLE_ADDRESS,
         ];
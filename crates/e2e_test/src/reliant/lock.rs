@@ -13,13 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::node_service_client;
 use rustfs_lock::{
     drwmutex::Options,
     lock_args::LockArgs,
     namespace_lock::{NsLockMap, new_nslock},
     new_lock_api,
 };
-use rustfs_protos::{node_service_time_out_client, proto_gen::node_service::GenerallyLockRequest};
+use rustfs_protos::proto_gen::node_service::GenerallyLockRequest;
 use std::{error::Error, sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 use tonic::Request;
@@ -38,7 +39,7 @@ async fn test_lock_unlock_rpc() -> Result<(), Box<dyn Error>> {
     };
     let args = serde_json::to_string(&args)?;
 
-    let mut client = node_service_time_out_client(&CLUSTER_ADDR.to_string()).await?;
+    let mut client = node_service_client(CLUSTER_ADDR).await?;
     println!("got client");
     let request = Request::new(GenerallyLockRequest { args: args.clone() });
 
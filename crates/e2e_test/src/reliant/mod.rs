@@ -14,3 +14,18 @@
 
 mod lock;
 mod node_interact_test;
+
+use rustfs_config::{DEFAULT_ACCESS_KEY, DEFAULT_SECRET_KEY};
+use rustfs_protos::auth::{SignedChannel, init_rpc_secret};
+use rustfs_protos::node_service_time_out_client;
+use rustfs_protos::proto_gen::node_service::node_service_client::NodeServiceClient;
+use std::error::Error;
+
+/// Connects to a node of the test cluster, signing the calls with the credentials the cluster runs with.
+pub(crate) async fn node_service_client(addr: &str) -> Result<NodeServiceClient<SignedChannel>, Box<dyn Error>> {
+    let access_key = std::env::var("RUSTFS_ACCESS_KEY").unwrap_or_else(|_| DEFAULT_ACCESS_KEY.to_string());
+    let secret_key = std::env::var("RUSTFS_SECRET_KEY").unwrap_or_else(|_| DEFAULT_SECRET_KEY.to_string());
+    init_rpc_secret(&access_key, &secret_key);
+
+    node_service_time_out_client(&addr.to_string()).await
+}
@@ -0,0 +1,404 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! S3 Inventory.
+//!
+//! Inventory configurations are stored in the bucket metadata. After every cycle the data scanner
+//! calls [`run_due_inventories`], and the first node of the cluster generates the reports whose
+//! daily or weekly schedule is due. A report lists the objects of the source bucket in one or more
+//! data files under `[prefix/]source-bucket/config-id/data/` of the destination bucket, and a
+//! `manifest.json` in `[prefix/]source-bucket/config-id/YYYY-MM-DDTHH-MMZ/` describes it.
+//!
+//! The time of the last report of each configuration is persisted to
+//! `buckets/<bucket>/inventory/status.json` in the meta bucket.
+
+pub mod report;
+
+use crate::bucket::metadata_sys;
+use crate::bucket::versioning_sys::BucketVersioningSys;
+use crate::config::com::{read_config, save_config};
+use crate::disk::BUCKET_META_PREFIX;
+use crate::error::{Error, Result};
+use crate::global::get_global_endpoints;
+use crate::new_object_layer_fn;
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, ObjectIO, ObjectInfo, ObjectOptions, PutObjReader, StorageAPI};
+use chrono::{DateTime, Utc};
+use md5::{Digest as _, Md5};
+use report::{Field, ReportFormat, ReportWriter};
+use s3s::dto::{InventoryConfiguration, InventoryFrequency, InventoryIncludedObjectVersions, InventoryS3BucketDestination};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+/// Maximum number of inventory configurations of a bucket.
+pub const MAX_INVENTORY_CONFIGURATIONS: usize = 1000;
+
+const STATUS_FILE_NAME: &str = "status.json";
+
+/// Version of the manifest format, as used by S3.
+const MANIFEST_VERSION: &str = "2016-11-30";
+
+const BUCKET_ARN_PREFIX: &str = "arn:aws:s3:::";
+
+const LIST_PAGE_SIZE: i32 = 1000;
+
+/// Rows written into one data file before the next file is started.
+const MAX_ROWS_PER_FILE: usize = 1_000_000;
+
+/// Time to wait before a failed report is generated again.
+const RETRY_INTERVAL: chrono::Duration = chrono::Duration::hours(1);
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Returns the name of the destination bucket, which is given as a bucket ARN.
+pub fn destination_bucket(dest: &InventoryS3BucketDestination) -> &str {
+    dest.bucket.strip_prefix(BUCKET_ARN_PREFIX).unwrap_or(&dest.bucket)
+}
+
+/// Returns the inventory configurations of `bucket`, empty when none is stored.
+pub async fn get_inventory_configurations(bucket: &str) -> Result<Vec<InventoryConfiguration>> {
+    match metadata_sys::get_inventory_config(bucket).await {
+        Ok((cfg, _)) => Ok(cfg.inventory_configuration_list.unwrap_or_default()),
+        Err(Error::ConfigNotFound) => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Generates the inventory reports that are due in the background.
+///
+/// Reports are generated by the first node of the cluster only, and a call made while reports are
+/// still being generated does nothing.
+pub fn run_due_inventories() {
+    if !get_global_endpoints().first_local() || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        if let Some(store) = new_object_layer_fn() {
+            run_inventories(store).await;
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+async fn run_inventories(store: Arc<ECStore>) {
+    let buckets = match store.list_bucket(&BucketOptions::default()).await {
+        Ok(buckets) => buckets,
+        Err(err) => {
+            warn!("inventory: list buckets failed: {}", err);
+            return;
+        }
+    };
+
+    for bucket in buckets {
+        let configs = match get_inventory_configurations(&bucket.name).await {
+            Ok(configs) => configs,
+            Err(err) => {
+                warn!("inventory: get inventory configurations of bucket {} failed: {}", bucket.name, err);
+                continue;
+            }
+        };
+        if configs.is_empty() {
+            continue;
+        }
+
+        let mut status = match InventoryStatus::load(store.clone(), &bucket.name).await {
+            Ok(status) => status,
+            Err(err) => {
+                warn!("inventory: load status of bucket {} failed: {}", bucket.name, err);
+                continue;
+            }
+        };
+
+        let before = status.configs.len();
+        status.configs.retain(|id, _| configs.iter().any(|c| &c.id == id));
+        let mut changed = status.configs.len() != before;
+
+        for cfg in configs.iter().filter(|c| c.is_enabled) {
+            let now = Utc::now();
+            let entry = status.configs.entry(cfg.id.clone()).or_default();
+            if !entry.is_due(&cfg.schedule.frequency, now) {
+                continue;
+            }
+
+            match generate(store.clone(), &bucket.name, cfg, now).await {
+                Ok(manifest) => {
+                    info!("inventory: report {} of bucket {} written to {}", cfg.id, bucket.name, manifest);
+                    entry.last_run = Some(now);
+                    entry.last_error = None;
+                }
+                Err(err) => {
+                    warn!("inventory: report {} of bucket {} failed: {}", cfg.id, bucket.name, err);
+                    entry.last_error = Some(err.to_string());
+                }
+            }
+            entry.last_attempt = Some(now);
+            changed = true;
+        }
+
+        if changed {
+            if let Err(err) = status.save(store.clone(), &bucket.name).await {
+                warn!("inventory: save status of bucket {} failed: {}", bucket.name, err);
+            }
+        }
+    }
+}
+
+/// Report times of the inventory configurations of a bucket, by configuration id.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InventoryStatus {
+    configs: HashMap<String, ConfigStatus>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ConfigStatus {
+    last_run: Option<DateTime<Utc>>,
+    last_attempt: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+impl ConfigStatus {
+    fn is_due(&self, frequency: &InventoryFrequency, now: DateTime<Utc>) -> bool {
+        if self.last_attempt.is_some_and(|t| t > now - RETRY_INTERVAL) && self.last_error.is_some() {
+            return false;
+        }
+
+        let period = if frequency.as_str() == InventoryFrequency::WEEKLY {
+            chrono::Duration::weeks(1)
+        } else {
+            chrono::Duration::days(1)
+        };
+        self.last_run.is_none_or(|t| t + period <= now)
+    }
+}
+
+impl InventoryStatus {
+    fn config_path(bucket: &str) -> String {
+        format!("{BUCKET_META_PREFIX}/{bucket}/inventory/{STATUS_FILE_NAME}")
+    }
+
+    async fn load(store: Arc<ECStore>, bucket: &str) -> Result<Self> {
+        match read_config(store, &Self::config_path(bucket)).await {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(Error::ConfigNotFound) => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn save(&self, store: Arc<ECStore>, bucket: &str) -> Result<()> {
+        save_config(store, &Self::config_path(bucket), serde_json::to_vec(self)?).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    source_bucket: String,
+    destination_bucket: String,
+    version: String,
+    /// Milliseconds since the epoch.
+    creation_timestamp: String,
+    file_format: String,
+    file_schema: String,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestFile {
+    key: String,
+    size: usize,
+    #[serde(rename = "MD5checksum")]
+    md5_checksum: String,
+}
+
+/// Key prefix of the reports of an inventory configuration in the destination bucket.
+fn report_prefix(dest_prefix: Option<&str>, bucket: &str, id: &str) -> String {
+    match dest_prefix.map(|p| p.trim_end_matches('/')).filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{prefix}/{bucket}/{id}"),
+        None => format!("{bucket}/{id}"),
+    }
+}
+
+/// Writes the data files of one report and their manifest, and returns the key of the manifest.
+async fn generate(store: Arc<ECStore>, bucket: &str, cfg: &InventoryConfiguration, now: DateTime<Utc>) -> Result<String> {
+    let dest = &cfg.destination.s3_bucket_destination;
+    let format = ReportFormat::parse(dest.format.as_str())
+        .ok_or_else(|| Error::other(format!("unsupported inventory format: {}", dest.format.as_str())))?;
+    let all_versions = cfg.included_object_versions.as_str() == InventoryIncludedObjectVersions::ALL;
+    let fields = report::fields(all_versions, cfg.optional_fields.as_deref().unwrap_or_default());
+    let filter_prefix = cfg.filter.as_ref().map(|f| f.prefix.as_str()).unwrap_or_default();
+
+    let mut builder = ReportBuilder {
+        store: store.clone(),
+        bucket: bucket.to_string(),
+        dest_bucket: destination_bucket(dest).to_string(),
+        prefix: report_prefix(dest.prefix.as_deref(), bucket, &cfg.id),
+        format,
+        writer: ReportWriter::new(format, &fields)?,
+        fields,
+        files: Vec::new(),
+    };
+
+    if all_versions {
+        let (mut marker, mut version_marker) = (None, None);
+        loop {
+            let page = store
+                .clone()
+                .list_object_versions(bucket, filter_prefix, marker, version_marker, None, LIST_PAGE_SIZE)
+                .await?;
+            for oi in page.objects.iter() {
+                builder.add(oi).await?;
+            }
+            if !page.is_truncated {
+                break;
+            }
+            marker = page.next_marker;
+            version_marker = page.next_version_idmarker;
+        }
+    } else {
+        let mut token = None;
+        loop {
+            let page = store
+                .clone()
+                .list_objects_v2(bucket, filter_prefix, token, None, LIST_PAGE_SIZE, false, None)
+                .await?;
+            for oi in page.objects.iter() {
+                builder.add(oi).await?;
+            }
+            if !page.is_truncated || page.next_continuation_token.is_none() {
+                break;
+            }
+            token = page.next_continuation_token;
+        }
+    }
+
+    let fields = builder.fields.clone();
+    let prefix = builder.prefix.clone();
+    let dest_bucket = builder.dest_bucket.clone();
+    let files = builder.finish().await?;
+
+    let manifest = Manifest {
+        source_bucket: bucket.to_string(),
+        destination_bucket: format!("{BUCKET_ARN_PREFIX}{dest_bucket}"),
+        version: MANIFEST_VERSION.to_string(),
+        creation_timestamp: now.timestamp_millis().to_string(),
+        file_format: format.as_str().to_string(),
+        file_schema: fields.iter().map(Field::as_str).collect::<Vec<_>>().join(", "),
+        files,
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+    let checksum = format!("{:x}", Md5::digest(&manifest));
+
+    let dir = format!("{prefix}/{}", now.format("%Y-%m-%dT%H-%MZ"));
+    let manifest_key = format!("{dir}/manifest.json");
+    put(&store, &dest_bucket, &manifest_key, manifest).await?;
+    put(&store, &dest_bucket, &format!("{dir}/manifest.checksum"), checksum.into_bytes()).await?;
+
+    Ok(manifest_key)
+}
+
+/// Collects the rows of a report and uploads a data file whenever it is full.
+struct ReportBuilder {
+    store: Arc<ECStore>,
+    bucket: String,
+    dest_bucket: String,
+    prefix: String,
+    format: ReportFormat,
+    fields: Vec<Field>,
+    writer: ReportWriter,
+    files: Vec<ManifestFile>,
+}
+
+impl ReportBuilder {
+    async fn add(&mut self, oi: &ObjectInfo) -> Result<()> {
+        self.writer.write(report::record(&self.bucket, oi, &self.fields))?;
+        if self.writer.rows() >= MAX_ROWS_PER_FILE {
+            let writer = std::mem::replace(&mut self.writer, ReportWriter::new(self.format, &self.fields)?);
+            self.upload(writer).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<Vec<ManifestFile>> {
+        let writer = std::mem::replace(&mut self.writer, ReportWriter::new(self.format, &self.fields)?);
+        if writer.rows() > 0 {
+            self.upload(writer).await?;
+        }
+        Ok(self.files)
+    }
+
+    async fn upload(&mut self, writer: ReportWriter) -> Result<()> {
+        let data = writer.finish()?;
+        let key = format!("{}/data/{}.{}", self.prefix, uuid::Uuid::new_v4(), self.format.extension());
+        let file = ManifestFile {
+            key: key.clone(),
+            size: data.len(),
+            md5_checksum: format!("{:x}", Md5::digest(&data)),
+        };
+        put(&self.store, &self.dest_bucket, &key, data).await?;
+        self.files.push(file);
+        Ok(())
+    }
+}
+
+async fn put(store: &Arc<ECStore>, bucket: &str, key: &str, data: Vec<u8>) -> Result<()> {
+    let opts = ObjectOptions {
+        versioned: BucketVersioningSys::prefix_enabled(bucket, key).await,
+        ..Default::default()
+    };
+    store
+        .put_object(bucket, key, &mut PutObjReader::from_vec(data), &opts)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_prefix() {
+        assert_eq!(report_prefix(None, "src", "daily"), "src/daily");
+        assert_eq!(report_prefix(Some(""), "src", "daily"), "src/daily");
+        assert_eq!(report_prefix(Some("inventory/"), "src", "daily"), "inventory/src/daily");
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let daily = InventoryFrequency::from_static(InventoryFrequency::DAILY);
+        let weekly = InventoryFrequency::from_static(InventoryFrequency::WEEKLY);
+
+        assert!(ConfigStatus::default().is_due(&daily, now));
+
+        let status = ConfigStatus {
+            last_run: Some(now - chrono::Duration::days(2)),
+            last_attempt: Some(now - chrono::Duration::days(2)),
+            last_error: None,
+        };
+        assert!(status.is_due(&daily, now));
+        assert!(!status.is_due(&weekly, now));
+
+        // A failed report is retried after the retry interval only
+        let status = ConfigStatus {
+            last_run: None,
+            last_attempt: Some(now - chrono::Duration::minutes(5)),
+            last_error: Some("destination bucket not found".to_string()),
+        };
+        assert!(!status.is_due(&daily, now));
+        assert!(status.is_due(&daily, now + RETRY_INTERVAL));
+    }
+}
@@ -0,0 +1,498 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Rows and data file formats of inventory reports.
+
+use crate::bucket::lifecycle::lifecycle::TRANSITION_COMPLETE;
+use crate::encryption::SseType;
+use crate::error::{Error, Result};
+use crate::store_api::ObjectInfo;
+use arrow_array::builder::{BooleanBuilder, Int64Builder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field as ArrowField, Schema, SchemaRef};
+use chrono::{DateTime, SecondsFormat};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use parquet::arrow::ArrowWriter;
+use rustfs_filemeta::headers::AMZ_STORAGE_CLASS;
+use s3s::dto::InventoryOptionalField;
+use s3s::header::{X_AMZ_OBJECT_LOCK_LEGAL_HOLD, X_AMZ_OBJECT_LOCK_MODE, X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE};
+use std::io::Write;
+use std::sync::Arc;
+
+/// Rows encoded into one Parquet record batch.
+const PARQUET_BATCH_ROWS: usize = 8192;
+
+const DEFAULT_STORAGE_CLASS: &str = "STANDARD";
+
+/// A column of an inventory report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Bucket,
+    Key,
+    VersionId,
+    IsLatest,
+    IsDeleteMarker,
+    Size,
+    LastModifiedDate,
+    ETag,
+    StorageClass,
+    IsMultipartUploaded,
+    ReplicationStatus,
+    EncryptionStatus,
+    ObjectLockRetainUntilDate,
+    ObjectLockMode,
+    ObjectLockLegalHoldStatus,
+}
+
+/// Optional fields that can be requested, in the order their columns appear in a report.
+pub const OPTIONAL_FIELDS: [Field; 10] = [
+    Field::Size,
+    Field::LastModifiedDate,
+    Field::ETag,
+    Field::StorageClass,
+    Field::IsMultipartUploaded,
+    Field::ReplicationStatus,
+    Field::EncryptionStatus,
+    Field::ObjectLockRetainUntilDate,
+    Field::ObjectLockMode,
+    Field::ObjectLockLegalHoldStatus,
+];
+
+impl Field {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Field::Bucket => "Bucket",
+            Field::Key => "Key",
+            Field::VersionId => "VersionId",
+            Field::IsLatest => "IsLatest",
+            Field::IsDeleteMarker => "IsDeleteMarker",
+            Field::Size => InventoryOptionalField::SIZE,
+            Field::LastModifiedDate => InventoryOptionalField::LAST_MODIFIED_DATE,
+            Field::ETag => InventoryOptionalField::E_TAG,
+            Field::StorageClass => InventoryOptionalField::STORAGE_CLASS,
+            Field::IsMultipartUploaded => InventoryOptionalField::IS_MULTIPART_UPLOADED,
+            Field::ReplicationStatus => InventoryOptionalField::REPLICATION_STATUS,
+            Field::EncryptionStatus => InventoryOptionalField::ENCRYPTION_STATUS,
+            Field::ObjectLockRetainUntilDate => InventoryOptionalField::OBJECT_LOCK_RETAIN_UNTIL_DATE,
+            Field::ObjectLockMode => InventoryOptionalField::OBJECT_LOCK_MODE,
+            Field::ObjectLockLegalHoldStatus => InventoryOptionalField::OBJECT_LOCK_LEGAL_HOLD_STATUS,
+        }
+    }
+
+    /// Returns the optional field named `name`, or `None` when it is not supported.
+    pub fn from_optional(name: &str) -> Option<Self> {
+        OPTIONAL_FIELDS.into_iter().find(|f| f.as_str() == name)
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Field::IsLatest | Field::IsDeleteMarker | Field::IsMultipartUploaded => DataType::Boolean,
+            Field::Size => DataType::Int64,
+            _ => DataType::Utf8,
+        }
+    }
+}
+
+/// Returns the columns of a report, the optional fields are sorted in the report order.
+pub fn fields(all_versions: bool, optional: &[InventoryOptionalField]) -> Vec<Field> {
+    let mut fields = vec![Field::Bucket, Field::Key];
+    if all_versions {
+        fields.extend([Field::VersionId, Field::IsLatest, Field::IsDeleteMarker]);
+    }
+    fields.extend(
+        OPTIONAL_FIELDS
+            .into_iter()
+            .filter(|f| optional.iter().any(|o| o.as_str() == f.as_str())),
+    );
+    fields
+}
+
+/// Value of one report cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+/// Builds the row of `oi` with the given columns.
+pub fn record(bucket: &str, oi: &ObjectInfo, fields: &[Field]) -> Vec<Value> {
+    fields.iter().map(|f| value(bucket, oi, *f)).collect()
+}
+
+fn value(bucket: &str, oi: &ObjectInfo, field: Field) -> Value {
+    // Delete markers only have the identifying columns
+    if oi.delete_marker
+        && !matches!(
+            field,
+            Field::Bucket | Field::Key | Field::VersionId | Field::IsLatest | Field::IsDeleteMarker | Field::LastModifiedDate
+        )
+    {
+        return Value::Null;
+    }
+
+    match field {
+        Field::Bucket => Value::Str(bucket.to_string()),
+        Field::Key => Value::Str(oi.name.clone()),
+        Field::VersionId => oi
+            .version_id
+            .filter(|v| !v.is_nil())
+            .map_or(Value::Null, |v| Value::Str(v.to_string())),
+        Field::IsLatest => Value::Bool(oi.is_latest),
+        Field::IsDeleteMarker => Value::Bool(oi.delete_marker),
+        Field::Size => Value::Int(oi.get_actual_size().unwrap_or(oi.size)),
+        Field::LastModifiedDate => oi
+            .mod_time
+            .and_then(|t| DateTime::from_timestamp(t.unix_timestamp(), t.nanosecond()))
+            .map_or(Value::Null, |t| Value::Str(t.to_rfc3339_opts(SecondsFormat::Millis, true))),
+        Field::ETag => oi
+            .etag
+            .as_ref()
+            .map_or(Value::Null, |v| Value::Str(v.trim_matches('"').to_string())),
+        Field::StorageClass => {
+            if oi.transitioned_object.status == TRANSITION_COMPLETE {
+                Value::Str(oi.transitioned_object.tier.clone())
+            } else {
+                Value::Str(
+                    oi.user_defined
+                        .get(AMZ_STORAGE_CLASS)
+                        .cloned()
+                        .unwrap_or_else(|| DEFAULT_STORAGE_CLASS.to_string()),
+                )
+            }
+        }
+        Field::IsMultipartUploaded => Value::Bool(oi.is_multipart()),
+        Field::ReplicationStatus => meta(oi, "x-amz-bucket-replication-status")
+            .or_else(|| meta(oi, "x-amz-replication-status"))
+            .map_or(Value::Null, Value::Str),
+        Field::EncryptionStatus => Value::Str(
+            SseType::from_metadata(&oi.user_defined)
+                .map_or("NOT-SSE", |t| t.as_str())
+                .to_string(),
+        ),
+        Field::ObjectLockRetainUntilDate => {
+            meta(oi, X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str()).map_or(Value::Null, Value::Str)
+        }
+        Field::ObjectLockMode => meta(oi, X_AMZ_OBJECT_LOCK_MODE.as_str()).map_or(Value::Null, Value::Str),
+        Field::ObjectLockLegalHoldStatus => meta(oi, X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str()).map_or(Value::Null, Value::Str),
+    }
+}
+
+/// Looks up user metadata regardless of the case it was stored with.
+fn meta(oi: &ObjectInfo, name: &str) -> Option<String> {
+    oi.user_defined
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.clone())
+        .filter(|v| !v.is_empty())
+}
+
+/// File format of the report data files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Parquet,
+    Json,
+}
+
+impl ReportFormat {
+    /// Parses the `Format` of an inventory destination, ORC is not supported.
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "CSV" => Some(ReportFormat::Csv),
+            "Parquet" => Some(ReportFormat::Parquet),
+            "JSON" => Some(ReportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "CSV",
+            ReportFormat::Parquet => "Parquet",
+            ReportFormat::Json => "JSON",
+        }
+    }
+
+    /// Extension of the data files, CSV and JSON files are gzip compressed.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv.gz",
+            ReportFormat::Parquet => "parquet",
+            ReportFormat::Json => "json.gz",
+        }
+    }
+}
+
+/// Encodes rows into one data file.
+pub struct ReportWriter {
+    fields: Vec<Field>,
+    rows: usize,
+    inner: WriterKind,
+}
+
+enum WriterKind {
+    Csv(GzEncoder<Vec<u8>>),
+    Json(GzEncoder<Vec<u8>>),
+    Parquet {
+        writer: ArrowWriter<Vec<u8>>,
+        schema: SchemaRef,
+        pending: Vec<Vec<Value>>,
+    },
+}
+
+impl ReportWriter {
+    pub fn new(format: ReportFormat, fields: &[Field]) -> Result<Self> {
+        let inner = match format {
+            ReportFormat::Csv => WriterKind::Csv(GzEncoder::new(Vec::new(), Compression::default())),
+            ReportFormat::Json => WriterKind::Json(GzEncoder::new(Vec::new(), Compression::default())),
+            ReportFormat::Parquet => {
+                let schema = Arc::new(Schema::new(
+                    fields
+                        .iter()
+                        .map(|f| ArrowField::new(f.as_str(), f.data_type(), true))
+                        .collect::<Vec<_>>(),
+                ));
+                let writer = ArrowWriter::try_new(Vec::new(), schema.clone(), None).map_err(Error::other)?;
+                WriterKind::Parquet {
+                    writer,
+                    schema,
+                    pending: Vec::new(),
+                }
+            }
+        };
+
+        Ok(Self {
+            fields: fields.to_vec(),
+            rows: 0,
+            inner,
+        })
+    }
+
+    /// Number of rows written so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn write(&mut self, row: Vec<Value>) -> Result<()> {
+        match &mut self.inner {
+            WriterKind::Csv(w) => {
+                w.write_all(csv_line(&self.fields, &row).as_bytes())?;
+            }
+            WriterKind::Json(w) => {
+                w.write_all(json_line(&self.fields, &row)?.as_bytes())?;
+            }
+            WriterKind::Parquet { writer, schema, pending } => {
+                pending.push(row);
+                if pending.len() >= PARQUET_BATCH_ROWS {
+                    write_batch(writer, schema, &self.fields, pending)?;
+                }
+            }
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Finishes the file and returns its content.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        match self.inner {
+            WriterKind::Csv(w) | WriterKind::Json(w) => Ok(w.finish()?),
+            WriterKind::Parquet {
+                mut writer,
+                schema,
+                mut pending,
+            } => {
+                write_batch(&mut writer, &schema, &self.fields, &mut pending)?;
+                writer.into_inner().map_err(Error::other)
+            }
+        }
+    }
+}
+
+/// Formats a row as a CSV line, every value is quoted and keys are URL encoded.
+fn csv_line(fields: &[Field], row: &[Value]) -> String {
+    let cells: Vec<String> = fields
+        .iter()
+        .zip(row.iter())
+        .map(|(field, value)| {
+            let text = match value {
+                Value::Null => String::new(),
+                Value::Str(s) if *field == Field::Key => urlencoding::encode(s).into_owned(),
+                Value::Str(s) => s.clone(),
+                Value::Int(i) => i.to_string(),
+                Value::Bool(b) => b.to_string(),
+            };
+            format!("\"{}\"", text.replace('"', "\"\""))
+        })
+        .collect();
+    let mut line = cells.join(",");
+    line.push('\n');
+    line
+}
+
+/// Formats a row as one JSON object per line, null values are left out.
+fn json_line(fields: &[Field], row: &[Value]) -> Result<String> {
+    let mut object = serde_json::Map::new();
+    for (field, value) in fields.iter().zip(row.iter()) {
+        let value = match value {
+            Value::Null => continue,
+            Value::Str(s) => serde_json::Value::from(s.as_str()),
+            Value::Int(i) => serde_json::Value::from(*i),
+            Value::Bool(b) => serde_json::Value::from(*b),
+        };
+        object.insert(field.as_str().to_string(), value);
+    }
+    let mut line = serde_json::to_string(&object)?;
+    line.push('\n');
+    Ok(line)
+}
+
+fn write_batch(
+    writer: &mut ArrowWriter<Vec<u8>>,
+    schema: &SchemaRef,
+    fields: &[Field],
+    rows: &mut Vec<Vec<Value>>,
+) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let columns: Vec<ArrayRef> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| -> ArrayRef {
+            match field.data_type() {
+                DataType::Boolean => {
+                    let mut b = BooleanBuilder::with_capacity(rows.len());
+                    for row in rows.iter() {
+                        b.append_option(match &row[i] {
+                            Value::Bool(v) => Some(*v),
+                            _ => None,
+                        });
+                    }
+                    Arc::new(b.finish())
+                }
+                DataType::Int64 => {
+                    let mut b = Int64Builder::with_capacity(rows.len());
+                    for row in rows.iter() {
+                        b.append_option(match &row[i] {
+                            Value::Int(v) => Some(*v),
+                            _ => None,
+                        });
+                    }
+                    Arc::new(b.finish())
+                }
+                _ => {
+                    let mut b = StringBuilder::new();
+                    for row in rows.iter() {
+                        b.append_option(match &row[i] {
+                            Value::Str(v) => Some(v.as_str()),
+                            _ => None,
+                        });
+                    }
+                    Arc::new(b.finish())
+                }
+            }
+        })
+        .collect();
+
+    let batch = RecordBatch::try_new(schema.clone(), columns).map_err(Error::other)?;
+    writer.write(&batch).map_err(Error::other)?;
+    rows.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn object() -> ObjectInfo {
+        ObjectInfo {
+            name: "dir/a b.txt".to_string(),
+            size: 42,
+            etag: Some("0123456789abcdef0123456789abcdef".to_string()),
+            is_latest: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let optional = vec![
+            InventoryOptionalField::from_static(InventoryOptionalField::E_TAG),
+            InventoryOptionalField::from_static(InventoryOptionalField::SIZE),
+            InventoryOptionalField::from_static(InventoryOptionalField::OBJECT_LOCK_MODE),
+        ];
+
+        assert_eq!(
+            fields(false, &optional),
+            vec![Field::Bucket, Field::Key, Field::Size, Field::ETag, Field::ObjectLockMode]
+        );
+        assert_eq!(fields(true, &[])[2..], [Field::VersionId, Field::IsLatest, Field::IsDeleteMarker]);
+        assert_eq!(Field::from_optional("StorageClass"), Some(Field::StorageClass));
+        assert_eq!(Field::from_optional("IntelligentTieringAccessTier"), None);
+    }
+
+    #[test]
+    fn test_csv_and_json() {
+        let fields = vec![
+            Field::Bucket,
+            Field::Key,
+            Field::Size,
+            Field::ETag,
+            Field::StorageClass,
+            Field::IsMultipartUploaded,
+            Field::EncryptionStatus,
+            Field::ObjectLockMode,
+        ];
+        let row = record("src", &object(), &fields);
+
+        assert_eq!(
+            csv_line(&fields, &row),
+            "\"src\",\"dir%2Fa%20b.txt\",\"42\",\"0123456789abcdef0123456789abcdef\",\"STANDARD\",\"false\",\"NOT-SSE\",\"\"\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&json_line(&fields, &row).unwrap()).unwrap();
+        assert_eq!(json["Key"], "dir/a b.txt");
+        assert_eq!(json["Size"], 42);
+        assert!(json.get("ObjectLockMode").is_none());
+
+        let mut writer = ReportWriter::new(ReportFormat::Csv, &fields).unwrap();
+        writer.write(row).unwrap();
+        let mut csv = String::new();
+        flate2::read::GzDecoder::new(writer.finish().unwrap().as_slice())
+            .read_to_string(&mut csv)
+            .unwrap();
+        assert!(csv.starts_with("\"src\","));
+    }
+
+    #[test]
+    fn test_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let fields = vec![Field::Bucket, Field::Key, Field::Size, Field::IsMultipartUploaded];
+        let mut writer = ReportWriter::new(ReportFormat::Parquet, &fields).unwrap();
+        for _ in 0..PARQUET_BATCH_ROWS + 1 {
+            writer.write(record("src", &object(), &fields)).unwrap();
+        }
+        let data = writer.finish().unwrap();
+
+        let reader = SerializedFileReader::new(bytes::Bytes::from(data)).unwrap();
+        let meta = reader.metadata();
+        assert_eq!(meta.file_metadata().num_rows(), PARQUET_BATCH_ROWS as i64 + 1);
+        assert_eq!(meta.file_metadata().schema_descr().column(2).name(), "Size");
+    }
+}
@@ -31,6 +31,8 @@ use std::io::Write;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime, Time, UtcOffset};
 use tokio::select;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{RwLock, mpsc};
@@ -61,7 +63,10 @@ use crate::store::ECStore;
 use crate::store_api::StorageAPI;
 use crate::store_api::{GetObjectReader, HTTPRangeSpec, ObjectInfo, ObjectOptions, ObjectToDelete};
 use crate::tier::warm_backend::WarmBackendGetOpts;
+use rustfs_filemeta::headers::{AMZ_OBJECT_TAGGING, RESERVED_METADATA_PREFIX_LOWER};
+use rustfs_filemeta::{RestoreStatus, X_AMZ_RESTORE_EXPIRY_DAYS, X_AMZ_RESTORE_REQUEST_DATE};
 use s3s::dto::BucketLifecycleConfiguration;
+use s3s::header::X_AMZ_RESTORE;
 
 pub type TimeFn = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync + 'static>;
 pub type TraceFn =
@@ -658,7 +663,9 @@ pub async fn expire_transitioned_object(
         opts.version_id = oi.version_id.map(|id| id.to_string());
     }
     //let tags = LcAuditEvent::new(src, lcEvent).Tags();
-    if lc_event.action == IlmAction::DeleteRestoredAction {
+    if lc_event.action.delete_restored() {
+        // Only the local copy goes away; the version and its data on the tier stay.
+        opts.version_id = oi.version_id.map(|id| id.to_string());
         opts.transition.expire_restored = true;
         match api.delete_object(&oi.bucket, &oi.name, opts).await {
             Ok(dobj) => {
@@ -790,8 +797,25 @@ pub fn post_restore_opts(_r: http::Request<Body>, _bucket: &str, _object: &str)
     todo!();
 }
 
-pub fn put_restore_opts(_bucket: &str, _object: &str, _rreq: &RestoreObjectRequest, _oi: &ObjectInfo) -> ObjectOptions {
-    todo!();
+/// Options for writing a restored copy back into the transitioned version, keeping its version, mod time and ETag.
+pub fn put_restore_opts(_bucket: &str, _object: &str, rreq: &RestoreObjectRequest, oi: &ObjectInfo) -> ObjectOptions {
+    let now = OffsetDateTime::now_utc();
+    let mut meta = oi.user_defined.clone();
+    meta.remove(&format!("{RESERVED_METADATA_PREFIX_LOWER}inline-data"));
+    if !oi.user_tags.is_empty() {
+        meta.insert(AMZ_OBJECT_TAGGING.to_string(), oi.user_tags.clone());
+    }
+    meta.insert(X_AMZ_RESTORE.as_str().to_string(), RestoreStatus::completed(rreq.expiry(now)).to_string());
+    meta.insert(X_AMZ_RESTORE_EXPIRY_DAYS.to_string(), rreq.days.to_string());
+    meta.insert(X_AMZ_RESTORE_REQUEST_DATE.to_string(), now.format(&Rfc3339).unwrap_or_default());
+
+    ObjectOptions {
+        version_id: oi.version_id.filter(|v| !v.is_nil()).map(|v| v.to_string()),
+        mod_time: oi.mod_time,
+        preserve_etag: oi.etag.clone(),
+        user_defined: meta,
+        ..Default::default()
+    }
 }
 
 pub trait LifecycleOps {
@@ -810,8 +834,8 @@ impl LifecycleOps for ObjectInfo {
             num_versions: self.num_versions,
             delete_marker: self.delete_marker,
             successor_mod_time: self.successor_mod_time,
-            //restore_ongoing:    self.restore_ongoing,
-            //restore_expires:    self.restore_expires,
+            restore_ongoing: self.restore_ongoing,
+            restore_expires: self.restore_expires,
             transition_status: self.transitioned_object.status.clone(),
             ..Default::default()
         }
@@ -841,4 +865,11 @@ pub struct RestoreObjectRequest {
     pub output_location: OutputLocation,
 }
 
+impl RestoreObjectRequest {
+    /// The restored copy is kept for `days` and removed at the following midnight UTC.
+    pub fn expiry(&self, now: OffsetDateTime) -> OffsetDateTime {
+        (now.to_offset(UtcOffset::UTC) + Duration::days(self.days + 1)).replace_time(Time::MIDNIGHT)
+    }
+}
+
 const _MAX_RESTORE_OBJECT_REQUEST_SIZE: i64 = 2 << 20;
@@ -324,7 +324,7 @@ impl Lifecycle for BucketLifecycleConfiguration {
         }
 
         if let Some(restore_expires) = obj.restore_expires {
-            if !restore_expires.unix_timestamp() == 0 && now.unix_timestamp() > restore_expires.unix_timestamp() {
+            if restore_expires.unix_timestamp() != 0 && now.unix_timestamp() > restore_expires.unix_timestamp() {
                 let mut action = IlmAction::DeleteRestoredAction;
                 if !obj.is_latest {
                     action = IlmAction::DeleteRestoredVersionAction;
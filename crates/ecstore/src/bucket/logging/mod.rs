@@ -0,0 +1,320 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Server access logging.
+//!
+//! Requests on buckets with a logging configuration are recorded in the S3 server access log format
+//! and buffered per source bucket. Every node delivers its own buffers as log objects into the
+//! target bucket periodically, or sooner once a buffer grows large.
+
+use crate::bucket::metadata_sys;
+use crate::bucket::versioning_sys::BucketVersioningSys;
+use crate::error::{Error, Result};
+use crate::global::{get_global_deployment_id, get_global_region};
+use crate::new_object_layer_fn;
+use crate::store_api::{ObjectIO, ObjectOptions, PutObjReader};
+use chrono::{DateTime, Utc};
+use s3s::dto::{LoggingEnabled, PartitionDateSource};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Interval between two deliveries, overridden by `RUSTFS_ACCESS_LOG_FLUSH_INTERVAL` in seconds.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Records buffered for one bucket before they are delivered without waiting for the interval.
+const MAX_BUFFERED_RECORDS: usize = 10_000;
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+pub static GLOBAL_ACCESS_LOG: LazyLock<AccessLogSys> = LazyLock::new(AccessLogSys::default);
+
+/// One request in the S3 server access log format, unknown fields are logged as `-`.
+#[derive(Debug, Clone, Default)]
+pub struct AccessLogRecord {
+    pub bucket_owner: Option<String>,
+    pub bucket: String,
+    pub time: DateTime<Utc>,
+    pub remote_ip: Option<String>,
+    pub requester: Option<String>,
+    pub request_id: String,
+    /// Operation such as `REST.GET.OBJECT`.
+    pub operation: String,
+    pub key: Option<String>,
+    /// Request line, such as `GET /bucket/key HTTP/1.1`.
+    pub request_uri: String,
+    pub http_status: u16,
+    pub error_code: Option<String>,
+    pub bytes_sent: Option<u64>,
+    pub object_size: Option<u64>,
+    pub total_time_ms: u64,
+    pub turn_around_time_ms: Option<u64>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub version_id: Option<String>,
+    pub signature_version: Option<String>,
+    pub auth_type: Option<String>,
+    pub host_header: Option<String>,
+}
+
+impl AccessLogRecord {
+    /// Formats the record as one line of a log object.
+    pub fn to_log_line(&self) -> String {
+        let fields = [
+            field(self.bucket_owner.as_deref()),
+            field(Some(&self.bucket)),
+            format!("[{}]", self.time.format("%d/%b/%Y:%H:%M:%S %z")),
+            field(self.remote_ip.as_deref()),
+            field(self.requester.as_deref()),
+            field(Some(&self.request_id)),
+            field(Some(&self.operation)),
+            field(
+                self.key
+                    .as_deref()
+                    .map(|k| urlencoding::encode(k).replace("%2F", "/"))
+                    .as_deref(),
+            ),
+            quoted(Some(&self.request_uri)),
+            self.http_status.to_string(),
+            field(self.error_code.as_deref()),
+            number(self.bytes_sent),
+            number(self.object_size),
+            self.total_time_ms.to_string(),
+            self.turn_around_time_ms.map_or_else(|| "-".to_string(), |v| v.to_string()),
+            quoted(self.referer.as_deref()),
+            quoted(self.user_agent.as_deref()),
+            field(self.version_id.as_deref()),
+            // Host id
+            field(get_global_deployment_id().as_deref()),
+            field(self.signature_version.as_deref()),
+            // Cipher suite
+            field(None),
+            field(self.auth_type.as_deref()),
+            field(self.host_header.as_deref()),
+            // TLS version, access point ARN and whether an ACL granted the access
+            field(None),
+            field(None),
+            field(None),
+        ];
+        fields.join(" ")
+    }
+}
+
+fn field(v: Option<&str>) -> String {
+    match v {
+        Some(v) if !v.is_empty() => v.replace(' ', "%20"),
+        _ => "-".to_string(),
+    }
+}
+
+fn quoted(v: Option<&str>) -> String {
+    match v {
+        Some(v) if !v.is_empty() => format!("\"{}\"", v.replace('"', "\\\"")),
+        _ => "\"-\"".to_string(),
+    }
+}
+
+/// Byte counts, zero is logged as `-`.
+fn number(v: Option<u64>) -> String {
+    v.filter(|v| *v > 0).map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+#[derive(Default)]
+pub struct AccessLogSys {
+    buffers: Mutex<HashMap<String, Vec<AccessLogRecord>>>,
+}
+
+impl AccessLogSys {
+    /// Buffers `record` when its bucket has access logging enabled.
+    pub async fn log(&self, record: AccessLogRecord) {
+        if logging_target(&record.bucket).await.is_none() {
+            return;
+        }
+
+        let full = {
+            let mut buffers = self.buffers.lock().await;
+            let records = buffers.entry(record.bucket.clone()).or_default();
+            records.push(record);
+            if records.len() >= MAX_BUFFERED_RECORDS {
+                let records = std::mem::take(records);
+                Some((records[0].bucket.clone(), records))
+            } else {
+                None
+            }
+        };
+
+        if let Some((bucket, records)) = full {
+            tokio::spawn(async move {
+                if let Err(err) = deliver(&bucket, records).await {
+                    warn!("access log: deliver logs of bucket {} failed: {}", bucket, err);
+                }
+            });
+        }
+    }
+
+    /// Delivers the records buffered for every bucket.
+    pub async fn flush(&self) {
+        let buffers = std::mem::take(&mut *self.buffers.lock().await);
+        for (bucket, records) in buffers {
+            if let Err(err) = deliver(&bucket, records).await {
+                warn!("access log: deliver logs of bucket {} failed: {}", bucket, err);
+            }
+        }
+    }
+}
+
+/// Starts delivering the buffered access logs of this node in the background. Records still buffered
+/// on shutdown are delivered by [`AccessLogSys::flush`].
+pub fn init_access_log() {
+    let interval = std::env::var("RUSTFS_ACCESS_LOG_FLUSH_INTERVAL")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .map_or(DEFAULT_FLUSH_INTERVAL, Duration::from_secs);
+    info!("access log: delivering logs every {:?}", interval);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            GLOBAL_ACCESS_LOG.flush().await;
+        }
+    });
+}
+
+async fn logging_target(bucket: &str) -> Option<LoggingEnabled> {
+    metadata_sys::get_logging_config(bucket)
+        .await
+        .ok()
+        .and_then(|(cfg, _)| cfg.logging_enabled)
+}
+
+/// Writes `records` of `bucket` as one log object into its target bucket.
+async fn deliver(bucket: &str, records: Vec<AccessLogRecord>) -> Result<()> {
+    // Logging may have been disabled since the records were buffered
+    let Some(target) = logging_target(bucket).await else {
+        return Ok(());
+    };
+    let Some(store) = new_object_layer_fn() else {
+        return Err(Error::other("errServerNotInitialized"));
+    };
+
+    let event_time = records.first().map_or_else(Utc::now, |r| r.time);
+    let key = log_object_key(&target, bucket, Utc::now(), event_time);
+
+    let mut data = String::new();
+    for record in records.iter() {
+        data.push_str(&record.to_log_line());
+        data.push('\n');
+    }
+
+    let opts = ObjectOptions {
+        versioned: BucketVersioningSys::prefix_enabled(&target.target_bucket, &key).await,
+        ..Default::default()
+    };
+    store
+        .put_object(&target.target_bucket, &key, &mut PutObjReader::from_vec(data.into_bytes()), &opts)
+        .await?;
+    Ok(())
+}
+
+/// Builds the key of a log object in the simple `[prefix]YYYY-mm-DD-HH-MM-SS-[unique]` format, or in the
+/// `[prefix][account]/[region]/[bucket]/YYYY/mm/DD/YYYY-mm-DD-HH-MM-SS-[unique]` format for partitioned prefixes.
+fn log_object_key(target: &LoggingEnabled, bucket: &str, delivery_time: DateTime<Utc>, event_time: DateTime<Utc>) -> String {
+    let unique = uuid::Uuid::new_v4().simple().to_string().to_uppercase();
+    let name = format!("{}-{}", delivery_time.format("%Y-%m-%d-%H-%M-%S"), &unique[..16]);
+
+    let partitioned = target
+        .target_object_key_format
+        .as_ref()
+        .and_then(|f| f.partitioned_prefix.as_ref());
+    let Some(partitioned) = partitioned else {
+        return format!("{}{}", target.target_prefix, name);
+    };
+
+    let date = match &partitioned.partition_date_source {
+        Some(source) if source.as_str() == PartitionDateSource::EVENT_TIME => event_time,
+        _ => delivery_time,
+    };
+    format!(
+        "{}{}/{}/{}/{}/{}",
+        target.target_prefix,
+        get_global_deployment_id().unwrap_or_default(),
+        get_global_region().unwrap_or_else(|| DEFAULT_REGION.to_string()),
+        bucket,
+        date.format("%Y/%m/%d"),
+        name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use s3s::dto::{PartitionedPrefix, TargetObjectKeyFormat};
+
+    #[test]
+    fn test_to_log_line() {
+        let record = AccessLogRecord {
+            bucket: "src".to_string(),
+            time: Utc.with_ymd_and_hms(2019, 2, 6, 0, 0, 38).unwrap(),
+            remote_ip: Some("192.0.2.3".to_string()),
+            requester: Some("AKIAEXAMPLE".to_string()),
+            request_id: "3E57427F3EXAMPLE".to_string(),
+            operation: "REST.GET.OBJECT".to_string(),
+            key: Some("a b.txt".to_string()),
+            request_uri: "GET /src/a%20b.txt HTTP/1.1".to_string(),
+            http_status: 200,
+            bytes_sent: Some(113),
+            object_size: Some(113),
+            total_time_ms: 7,
+            user_agent: Some("curl/8.0".to_string()),
+            ..Default::default()
+        };
+
+        let line = record.to_log_line();
+        assert!(line.starts_with(
+            "- src [06/Feb/2019:00:00:38 +0000] 192.0.2.3 AKIAEXAMPLE 3E57427F3EXAMPLE REST.GET.OBJECT a%20b.txt \
+             \"GET /src/a%20b.txt HTTP/1.1\" 200 - 113 113 7 - \"-\" \"curl/8.0\" -"
+        ));
+        assert_eq!(line.split(' ').count(), 29);
+    }
+
+    #[test]
+    fn test_log_object_key() {
+        let delivery = Utc.with_ymd_and_hms(2024, 5, 1, 10, 20, 30).unwrap();
+        let event = Utc.with_ymd_and_hms(2024, 4, 30, 23, 59, 59).unwrap();
+        let mut target = LoggingEnabled {
+            target_bucket: "logs".to_string(),
+            target_grants: None,
+            target_object_key_format: None,
+            target_prefix: "src/".to_string(),
+        };
+
+        let key = log_object_key(&target, "src", delivery, event);
+        assert!(key.starts_with("src/2024-05-01-10-20-30-"));
+        assert_eq!(key.len(), "src/2024-05-01-10-20-30-".len() + 16);
+
+        target.target_object_key_format = Some(TargetObjectKeyFormat {
+            partitioned_prefix: Some(PartitionedPrefix {
+                partition_date_source: Some(PartitionDateSource::from_static(PartitionDateSource::EVENT_TIME)),
+            }),
+            simple_prefix: None,
+        });
+        let key = log_object_key(&target, "src", delivery, event);
+        assert!(key.contains("/src/2024/04/30/2024-05-01-10-20-30-"));
+    }
+}
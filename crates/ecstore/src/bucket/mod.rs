@@ -13,7 +13,9 @@
 // limitations under the License.
 
 pub mod error;
+pub mod inventory;
 pub mod lifecycle;
+pub mod logging;
 pub mod metadata;
 pub mod metadata_sys;
 pub mod object_lock;
@@ -32,63 +32,50 @@ pub fn utc_now_ntp() -> OffsetDateTime {
     OffsetDateTime::now_utc()
 }
 
-pub fn get_object_retention_meta(meta: HashMap<String, String>) -> ObjectLockRetention {
-    let mut retain_until_date: Date = Date::from(OffsetDateTime::UNIX_EPOCH);
-
-    let mut mode_str = meta.get(X_AMZ_OBJECT_LOCK_MODE.as_str().to_lowercase().as_str());
-    if mode_str.is_none() {
-        mode_str = Some(&meta[X_AMZ_OBJECT_LOCK_MODE.as_str()]);
-    }
-    let mode = if let Some(mode_str) = mode_str {
-        parse_ret_mode(mode_str.as_str())
-    } else {
+/// Reads the retention stored with an object; a missing or malformed mode means no retention.
+pub fn get_object_retention_meta(meta: &HashMap<String, String>) -> ObjectLockRetention {
+    let mode = meta
+        .get(X_AMZ_OBJECT_LOCK_MODE.as_str())
+        .and_then(|v| parse_ret_mode(v.as_str()));
+    let Some(mode) = mode else {
         return ObjectLockRetention {
             mode: None,
             retain_until_date: None,
         };
     };
 
-    let mut till_str = meta.get(X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str().to_lowercase().as_str());
-    if till_str.is_none() {
-        till_str = Some(&meta[X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str()]);
-    }
-    if let Some(till_str) = till_str {
-        let t = OffsetDateTime::parse(till_str, &format_description::well_known::Iso8601::DEFAULT);
-        if t.is_err() {
-            retain_until_date = Date::from(t.expect("err")); //TODO: utc
-        }
-    }
+    let retain_until_date = meta
+        .get(X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str())
+        .and_then(|v| OffsetDateTime::parse(v, &format_description::well_known::Rfc3339).ok())
+        .map(Date::from);
+
     ObjectLockRetention {
         mode: Some(mode),
-        retain_until_date: Some(retain_until_date),
+        retain_until_date,
     }
 }
 
-pub fn get_object_legalhold_meta(meta: HashMap<String, String>) -> ObjectLockLegalHold {
-    let mut hold_str = meta.get(X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str().to_lowercase().as_str());
-    if hold_str.is_none() {
-        hold_str = Some(&meta[X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str()]);
-    }
-    if let Some(hold_str) = hold_str {
-        return ObjectLockLegalHold {
-            status: Some(parse_legalhold_status(hold_str)),
-        };
+/// Reads the legal hold stored with an object.
+pub fn get_object_legalhold_meta(meta: &HashMap<String, String>) -> ObjectLockLegalHold {
+    ObjectLockLegalHold {
+        status: meta
+            .get(X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str())
+            .and_then(|v| parse_legalhold_status(v.as_str())),
     }
-    ObjectLockLegalHold { status: None }
 }
 
-pub fn parse_ret_mode(mode_str: &str) -> ObjectLockRetentionMode {
+pub fn parse_ret_mode(mode_str: &str) -> Option<ObjectLockRetentionMode> {
     match mode_str.to_uppercase().as_str() {
-        "GOVERNANCE" => ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::GOVERNANCE),
-        "COMPLIANCE" => ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::COMPLIANCE),
-        _ => unreachable!(),
+        "GOVERNANCE" => Some(ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::GOVERNANCE)),
+        "COMPLIANCE" => Some(ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::COMPLIANCE)),
+        _ => None,
     }
 }
 
-pub fn parse_legalhold_status(hold_str: &str) -> ObjectLockLegalHoldStatus {
+pub fn parse_legalhold_status(hold_str: &str) -> Option<ObjectLockLegalHoldStatus> {
     match hold_str {
-        "ON" => ObjectLockLegalHoldStatus::from_static(ObjectLockLegalHoldStatus::ON),
-        "OFF" => ObjectLockLegalHoldStatus::from_static(ObjectLockLegalHoldStatus::OFF),
-        _ => unreachable!(),
+        "ON" => Some(ObjectLockLegalHoldStatus::from_static(ObjectLockLegalHoldStatus::ON)),
+        "OFF" => Some(ObjectLockLegalHoldStatus::from_static(ObjectLockLegalHoldStatus::OFF)),
+        _ => None,
     }
 }
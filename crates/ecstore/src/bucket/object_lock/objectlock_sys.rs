@@ -13,14 +13,14 @@
 // limitations under the License.
 
 use std::sync::Arc;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 
 use s3s::dto::{DefaultRetention, ObjectLockLegalHoldStatus, ObjectLockRetentionMode};
 
 use crate::bucket::metadata_sys::get_object_lock_config;
 use crate::store_api::ObjectInfo;
 
-use super::objectlock;
+use super::{ObjectLockApi, objectlock};
 
 pub struct BucketObjectLockSys {}
 
@@ -38,30 +38,174 @@ impl BucketObjectLockSys {
         }
         None
     }
+
+    /// Returns whether object lock is enabled on the bucket.
+    pub async fn enabled(bucket: &str) -> bool {
+        get_object_lock_config(bucket).await.is_ok_and(|(config, _)| config.enabled())
+    }
+}
+
+/// Returns the retention mode and retain-until date the bucket default retention gives an object created at `now`.
+pub fn default_retention_until(
+    retention: &DefaultRetention,
+    now: OffsetDateTime,
+) -> Option<(ObjectLockRetentionMode, OffsetDateTime)> {
+    let mode = retention.mode.clone()?;
+    let until = match (retention.days, retention.years) {
+        (Some(days), _) if days > 0 => now + Duration::days(days as i64),
+        (_, Some(years)) if years > 0 => now + Duration::days(365 * years as i64),
+        _ => return None,
+    };
+
+    Some((mode, until))
+}
+
+/// Returns the retention of an object if it still protects the object at `now`.
+fn active_retention(obj_info: &ObjectInfo, now: OffsetDateTime) -> Option<(ObjectLockRetentionMode, OffsetDateTime)> {
+    let ret = objectlock::get_object_retention_meta(&obj_info.user_defined);
+    let until = OffsetDateTime::from(ret.retain_until_date?);
+    if until <= now {
+        return None;
+    }
+
+    Some((ret.mode?, until))
+}
+
+fn is_legal_hold_on(obj_info: &ObjectInfo) -> bool {
+    objectlock::get_object_legalhold_meta(&obj_info.user_defined)
+        .status
+        .is_some_and(|st| st.as_str() == ObjectLockLegalHoldStatus::ON)
 }
 
 pub fn enforce_retention_for_deletion(obj_info: &ObjectInfo) -> bool {
+    enforce_retention_bypass_for_deletion(obj_info, false)
+}
+
+/// Returns true if deleting the object version must be refused.
+///
+/// A legal hold and COMPLIANCE retention always protect the version, GOVERNANCE retention can be bypassed.
+pub fn enforce_retention_bypass_for_deletion(obj_info: &ObjectInfo, bypass_governance: bool) -> bool {
     if obj_info.delete_marker {
         return false;
     }
 
-    let lhold = objectlock::get_object_legalhold_meta(obj_info.user_defined.clone());
-    match lhold.status {
-        Some(st) if st.as_str() == ObjectLockLegalHoldStatus::ON => {
-            return true;
-        }
-        _ => (),
+    if is_legal_hold_on(obj_info) {
+        return true;
     }
 
-    let ret = objectlock::get_object_retention_meta(obj_info.user_defined.clone());
-    match ret.mode {
-        Some(r) if (r.as_str() == ObjectLockRetentionMode::COMPLIANCE || r.as_str() == ObjectLockRetentionMode::GOVERNANCE) => {
-            let t = objectlock::utc_now_ntp();
-            if OffsetDateTime::from(ret.retain_until_date.expect("err!")).unix_timestamp() > t.unix_timestamp() {
-                return true;
-            }
-        }
-        _ => (),
+    match active_retention(obj_info, objectlock::utc_now_ntp()) {
+        Some((mode, _)) if mode.as_str() == ObjectLockRetentionMode::COMPLIANCE => true,
+        Some(_) => !bypass_governance,
+        None => false,
+    }
+}
+
+/// Returns whether the retention of an object version may be replaced by `mode` until `retain_until`.
+///
+/// Active COMPLIANCE retention can only be extended. Active GOVERNANCE retention can be extended or turned into
+/// COMPLIANCE; shortening or removing it requires bypassing governance.
+pub fn is_retention_update_allowed(
+    obj_info: &ObjectInfo,
+    mode: Option<&ObjectLockRetentionMode>,
+    retain_until: Option<OffsetDateTime>,
+    bypass_governance: bool,
+) -> bool {
+    let Some((cur_mode, cur_until)) = active_retention(obj_info, objectlock::utc_now_ntp()) else {
+        return true;
+    };
+
+    let extends = retain_until.is_some_and(|until| until >= cur_until);
+    if cur_mode.as_str() == ObjectLockRetentionMode::COMPLIANCE {
+        return extends && mode.is_some_and(|m| m.as_str() == ObjectLockRetentionMode::COMPLIANCE);
+    }
+
+    bypass_governance || (extends && mode.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s3s::header::{X_AMZ_OBJECT_LOCK_LEGAL_HOLD, X_AMZ_OBJECT_LOCK_MODE, X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE};
+    use time::format_description::well_known::Rfc3339;
+
+    fn locked(mode: &str, until: OffsetDateTime) -> ObjectInfo {
+        let mut info = ObjectInfo::default();
+        info.user_defined
+            .insert(X_AMZ_OBJECT_LOCK_MODE.as_str().to_string(), mode.to_string());
+        info.user_defined
+            .insert(X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str().to_string(), until.format(&Rfc3339).unwrap());
+        info
+    }
+
+    #[test]
+    fn test_enforce_retention_for_deletion() {
+        let now = OffsetDateTime::now_utc();
+        let future = now + Duration::days(1);
+
+        assert!(!enforce_retention_for_deletion(&ObjectInfo::default()));
+        assert!(!enforce_retention_for_deletion(&locked("COMPLIANCE", now - Duration::days(1))));
+
+        let compliance = locked("COMPLIANCE", future);
+        assert!(enforce_retention_bypass_for_deletion(&compliance, true));
+
+        let governance = locked("GOVERNANCE", future);
+        assert!(enforce_retention_for_deletion(&governance));
+        assert!(!enforce_retention_bypass_for_deletion(&governance, true));
+
+        let mut held = ObjectInfo::default();
+        held.user_defined
+            .insert(X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str().to_string(), "ON".to_string());
+        assert!(enforce_retention_bypass_for_deletion(&held, true));
+
+        held.delete_marker = true;
+        assert!(!enforce_retention_for_deletion(&held));
+    }
+
+    #[test]
+    fn test_is_retention_update_allowed() {
+        let now = OffsetDateTime::now_utc();
+        let until = now + Duration::days(10);
+        let longer = Some(until + Duration::days(1));
+        let shorter = Some(until - Duration::days(1));
+        let compliance = ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::COMPLIANCE);
+        let governance = ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::GOVERNANCE);
+
+        assert!(is_retention_update_allowed(&ObjectInfo::default(), None, None, false));
+
+        let info = locked("COMPLIANCE", until);
+        assert!(is_retention_update_allowed(&info, Some(&compliance), longer, false));
+        assert!(!is_retention_update_allowed(&info, Some(&compliance), shorter, true));
+        assert!(!is_retention_update_allowed(&info, Some(&governance), longer, true));
+        assert!(!is_retention_update_allowed(&info, None, None, true));
+
+        let info = locked("GOVERNANCE", until);
+        assert!(is_retention_update_allowed(&info, Some(&governance), longer, false));
+        assert!(is_retention_update_allowed(&info, Some(&compliance), longer, false));
+        assert!(!is_retention_update_allowed(&info, Some(&governance), shorter, false));
+        assert!(is_retention_update_allowed(&info, Some(&governance), shorter, true));
+        assert!(!is_retention_update_allowed(&info, None, None, false));
+        assert!(is_retention_update_allowed(&info, None, None, true));
+    }
+
+    #[test]
+    fn test_default_retention_until() {
+        let now = OffsetDateTime::now_utc();
+        let retention = DefaultRetention {
+            days: Some(3),
+            mode: Some(ObjectLockRetentionMode::from_static(ObjectLockRetentionMode::GOVERNANCE)),
+            years: None,
+        };
+        let (mode, until) = default_retention_until(&retention, now).unwrap();
+        assert_eq!(mode.as_str(), ObjectLockRetentionMode::GOVERNANCE);
+        assert_eq!(until, now + Duration::days(3));
+
+        let retention = DefaultRetention {
+            years: Some(1),
+            days: None,
+            ..retention
+        };
+        assert_eq!(default_retention_until(&retention, now).unwrap().1, now + Duration::days(365));
+
+        assert!(default_retention_until(&DefaultRetention::default(), now).is_none());
     }
-    false
 }
@@ -12,41 +12,82 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::Result;
-use rmp_serde::Serializer as rmpSerializer;
-use serde::{Deserialize, Serialize};
-
-// 定义 QuotaType 枚举类型
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum QuotaType {
-    Hard,
-}
+use super::metadata_sys;
+use crate::error::{Error, Result};
+use crate::heal::data_usage::load_data_usage_from_backend;
+use crate::new_object_layer_fn;
+use lazy_static::lazy_static;
+use rustfs_filemeta::{Cache, Opts};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
 
-// 定义 BucketQuota 结构体
-#[derive(Debug, Deserialize, Serialize, Default, Clone)]
-pub struct BucketQuota {
-    quota: Option<u64>, // 使用 Option 来表示可能不存在的字段
+pub use rustfs_madmin::{BucketQuota, QuotaType};
 
-    size: u64,
+lazy_static! {
+    /// Bucket sizes from the last data usage scan, refreshed at most every ten seconds.
+    static ref BUCKET_USAGE_CACHE: Arc<Cache<HashMap<String, u64>>> = Arc::new(Cache::new(
+        Box::new(|| {
+            Box::pin(async move {
+                let Some(store) = new_object_layer_fn() else {
+                    return Err(Error::other("errServerNotInitialized").into());
+                };
+                let info = load_data_usage_from_backend(store).await?;
+                Ok(info.buckets_usage.into_iter().map(|(bucket, usage)| (bucket, usage.size)).collect())
+            })
+        }),
+        Duration::from_secs(10),
+        Opts::default(),
+    ));
+}
 
-    rate: u64,
+pub struct BucketQuotaSys {}
 
-    requests: u64,
+impl BucketQuotaSys {
+    /// Returns the quota configuration of `bucket`, `None` if it has none.
+    pub async fn get(bucket: &str) -> Result<Option<BucketQuota>> {
+        match metadata_sys::get_quota_config(bucket).await {
+            Ok((quota, _)) => Ok(Some(quota)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
 
-    quota_type: Option<QuotaType>,
-}
+    /// Returns the size of `bucket` as recorded by the last data usage scan.
+    pub async fn bucket_usage(bucket: &str) -> Result<u64> {
+        let usage = BUCKET_USAGE_CACHE.clone().get().await?;
+        Ok(usage.get(bucket).copied().unwrap_or_default())
+    }
 
-impl BucketQuota {
-    pub fn marshal_msg(&self) -> Result<Vec<u8>> {
-        let mut buf = Vec::new();
+    /// Rejects a write of `size` bytes to `bucket` if it would exceed the bucket's hard quota.
+    ///
+    /// A negative size means the size is unknown and is always allowed.
+    pub async fn enforce_hard_quota(bucket: &str, size: i64) -> Result<()> {
+        if size < 0 {
+            return Ok(());
+        }
 
-        self.serialize(&mut rmpSerializer::new(&mut buf).with_struct_map())?;
+        let Some(limit) = Self::get(bucket).await?.and_then(|quota| quota.hard_limit()) else {
+            return Ok(());
+        };
 
-        Ok(buf)
-    }
+        let size = size as u64;
+        if size > limit {
+            return Err(Error::BucketQuotaExceeded(bucket.to_owned()));
+        }
+
+        let used = match Self::bucket_usage(bucket).await {
+            Ok(used) => used,
+            Err(err) => {
+                warn!("failed to load usage of bucket {}, skipping quota check: {:?}", bucket, err);
+                return Ok(());
+            }
+        };
+        if used > 0 && used.saturating_add(size) > limit {
+            return Err(Error::BucketQuotaExceeded(bucket.to_owned()));
+        }
 
-    pub fn unmarshal(buf: &[u8]) -> Result<Self> {
-        let t: BucketQuota = rmp_serde::from_slice(buf)?;
-        Ok(t)
+        Ok(())
     }
 }
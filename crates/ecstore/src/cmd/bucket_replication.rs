@@ -14,6 +14,7 @@
 // limitations under the License.
 #![allow(dead_code)]
 // use error::Error;
+use super::bucket_replication_resync::GLOBAL_REPLICATION_RESYNCER;
 use crate::StorageAPI;
 use crate::bucket::metadata_sys::get_replication_config;
 use crate::bucket::versioning_sys::BucketVersioningSys;
@@ -64,6 +65,7 @@ use time::OffsetDateTime;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::oneshot;
 use tokio::task;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -406,7 +408,7 @@ pub async fn check_replicate_delete(
 // use crate::crypto;
 // use crate::global::*;
 
-fn target_reset_header(arn: &str) -> String {
+pub(crate) fn target_reset_header(arn: &str) -> String {
     format!("{RESERVED_METADATA_PREFIX_LOWER}{REPLICATION_RESET}-{arn}")
 }
 
@@ -531,6 +533,7 @@ pub async fn get_heal_replicate_object_info(
         op_type: 1,
         dsc,
         existing_obj_resync: Default::default(),
+        result_tx: Default::default(),
         target_statuses: tgt_statuses,
         target_purge_statuses: purge_statuses,
         replication_timestamp: tm.unwrap_or_else(Utc::now),
@@ -701,11 +704,12 @@ impl ReplicationPool {
         let obj_layer_clone = pool.obj_layer.clone();
 
         // 启动后台任务
-        let resyncer = Arc::new(RwLock::new(ReplicationResyncer::new()));
+        let resyncer = GLOBAL_REPLICATION_RESYNCER.clone();
         let x = Arc::new(RwLock::new(&pool));
-        // tokio::spawn(async move {
-        //     resyncer.lock().await.persist_to_disk(ctx_clone, obj_layer_clone).await;
-        // });
+        tokio::spawn(async move {
+            resyncer.load_and_resume(obj_layer_clone.clone()).await;
+            resyncer.persist_to_disk(obj_layer_clone).await;
+        });
 
         tokio::spawn(async move {
             //pool4.process_mrf().await
@@ -719,6 +723,11 @@ impl ReplicationPool {
         global_pool.replace(pool);
     }
 
+    /// Number of workers currently replicating an object, including the large object workers.
+    pub fn active_workers(&self) -> i32 {
+        self.active_workers.load(Ordering::SeqCst) + self.active_lrg_workers.load(Ordering::SeqCst)
+    }
+
     pub async fn resize_lrg_workers(&mut self, n: usize, check_old: Option<usize>) {
         //let mut lrg_workers = self.lrg_workers.lock().unwrap();
         if (check_old.is_some() && self.lrg_workers_sender.len() != check_old.unwrap())
@@ -747,7 +756,8 @@ impl ReplicationPool {
                     active_workers_clone.fetch_add(1, Ordering::SeqCst);
 
                     if let Some(info) = operation.as_any().downcast_ref::<ReplicateObjectInfo>() {
-                        replicate_object(info.clone(), obj_layer_clone.clone()).await;
+                        let status = replicate_object(info.clone(), obj_layer_clone.clone()).await;
+                        info.result_tx.send(status);
                     } else if let Some(info) = operation.as_any().downcast_ref::<DeletedObjectReplicationInfo>() {
                         replicate_delete(&info.clone(), obj_layer_clone.clone()).await;
                     } else {
@@ -797,7 +807,8 @@ impl ReplicationPool {
                     if let Some(info) = operation.as_any().downcast_ref::<ReplicateObjectInfo>() {
                         //self.stats.inc_q(&info.bucket, info.size, info.delete_marker, &info.op_type);
                         let _layer = Arc::clone(&layer_clone);
-                        replicate_object(info.clone(), _layer).await;
+                        let status = replicate_object(info.clone(), _layer).await;
+                        info.result_tx.send(status);
                         //self.stats.dec_q(&info.bucket, info.size, info.delete_marker, &info.op_type);
                     } else if let Some(info) = operation.as_any().downcast_ref::<DeletedObjectReplicationInfo>() {
                         let _layer = Arc::clone(&layer_clone);
@@ -948,24 +959,6 @@ impl ReplicationPool {
     }
 }
 
-pub struct ReplicationResyncer;
-
-impl Default for ReplicationResyncer {
-    fn default() -> Self {
-        Self
-    }
-}
-
-impl ReplicationResyncer {
-    pub fn new() -> Self {
-        Self
-    }
-
-    pub async fn persist_to_disk(&self, _ctx: Arc<Context>, _obj_layer: Arc<store::ECStore>) {
-        // 实现持久化到磁盘的逻辑
-    }
-}
-
 pub async fn init_bucket_replication_pool() {
     if let Some(store) = new_object_layer_fn() {
         let opts = ReplicationPoolOpts::default();
@@ -1350,6 +1343,16 @@ impl ResyncDecision {
             false
         }
     }
+
+    /// Returns the resync decision for a target.
+    pub fn target(&self, tgt_arn: &str) -> Option<&ResyncTargetDecision> {
+        self.targets.get(tgt_arn)
+    }
+
+    /// Sets the resync decision for a target.
+    pub fn set_target(&mut self, tgt_arn: String, decision: ResyncTargetDecision) {
+        self.targets.insert(tgt_arn, decision);
+    }
 }
 
 /// 解析字符串为 ReplicateDecision 结构
@@ -1779,6 +1782,7 @@ pub async fn schedule_replication(oi: ObjectInfo, o: Arc<store::ECStore>, dsc: R
         existing_obj_resync: Default::default(),
         target_arn: "".to_string(),
         actual_size: 0,
+        result_tx: Default::default(),
     };
 
     if dsc.synchronous() {
@@ -1786,9 +1790,17 @@ pub async fn schedule_replication(oi: ObjectInfo, o: Arc<store::ECStore>, dsc: R
         replicate_object(ri, o).await;
     } else {
         warn!("object need async replication");
-        //GLOBAL_REPLICATION_POOL.lock().unwrap().queue_replica_task(ri);
-        let mut pool = GLOBAL_REPLICATION_POOL.write().await;
-        pool.as_mut().unwrap().queue_replica_task(ri).await;
+        queue_replication(ri).await;
+    }
+}
+
+/// Queues `ri` to the replication workers, its `result_tx` receives the status once replicated.
+pub async fn queue_replication(ri: ReplicateObjectInfo) {
+    //GLOBAL_REPLICATION_POOL.lock().unwrap().queue_replica_task(ri);
+    let mut pool = GLOBAL_REPLICATION_POOL.write().await;
+    match pool.as_mut() {
+        Some(pool) => pool.queue_replica_task(ri).await,
+        None => error!("replication pool is not initialized"),
     }
 }
 
@@ -1996,7 +2008,32 @@ pub struct ReplicateObjectInfo {
     pub target_purge_statuses: HashMap<String, VersionPurgeStatusType>,
     pub replication_timestamp: DateTime<Utc>,
     pub checksum: Vec<u8>,
+    /// Receives the status once a worker has replicated the object.
+    #[serde(skip)]
+    pub result_tx: ReplicationResultSender,
+}
+
+/// Reports the replication status of a queued object back to the caller that queued it.
+///
+/// The receiver sees a closed channel when the object was dropped without being replicated.
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationResultSender(Option<Arc<std::sync::Mutex<Option<ReplicationResultTx>>>>);
+
+type ReplicationResultTx = oneshot::Sender<ReplicationStatusType>;
+
+impl ReplicationResultSender {
+    pub fn channel() -> (Self, oneshot::Receiver<ReplicationStatusType>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Some(Arc::new(std::sync::Mutex::new(Some(tx))))), rx)
+    }
+
+    fn send(&self, status: ReplicationStatusType) {
+        if let Some(tx) = self.0.as_ref().and_then(|tx| tx.lock().unwrap().take()) {
+            let _ = tx.send(status);
+        }
+    }
 }
+
 impl ReplicateObjectInfo {
     pub fn to_object_info(&self) -> ObjectInfo {
         ObjectInfo {
@@ -2014,6 +2051,8 @@ impl ReplicateObjectInfo {
             version_id: Uuid::try_parse(&self.version_id).ok(),
             delete_marker: self.delete_marker,
             transitioned_object: TransitionedObject::default(),
+            restore_ongoing: false,
+            restore_expires: None,
             user_tags: self.user_tags.clone(),
             parts: Vec::new(),
             is_latest: true,
@@ -2253,7 +2292,7 @@ impl ReplicateObjectInfo {
             replication_action: ReplicationAction::ReplicateAll,
             endpoint: target.endpoint.clone(),
             secure: target.endpoint.clone().contains("https://"),
-            resync_timestamp: Utc::now().to_string(),
+            resync_timestamp: String::new(),
             replication_resynced: false,
             duration: Duration::default(),
             err: None,
@@ -2270,6 +2309,12 @@ impl ReplicateObjectInfo {
             return rinfo;
         }
 
+        // A resynced object records the reset it was replicated for, so a resumed resync can skip it.
+        if let Some(decision) = self.existing_obj_resync.target(&_arn).filter(|d| d.replicate) {
+            rinfo.resync_timestamp = format!("{};{}", Utc::now().to_rfc3339(), decision.reset_id);
+            rinfo.replication_resynced = true;
+        }
+
         // 模拟远程目标离线的检查
         // if self.is_target_offline(&target.endpoint) {
         //     rinfo.err = Some(format!(
@@ -2559,7 +2604,8 @@ impl ReplicatedTargetInfo {
     }
 }
 
-pub async fn replicate_object(ri: ReplicateObjectInfo, object_api: Arc<store::ECStore>) {
+/// Replicates an object version to its targets and returns the resulting replication status.
+pub async fn replicate_object(ri: ReplicateObjectInfo, object_api: Arc<store::ECStore>) -> ReplicationStatusType {
     let bucket = ri.bucket.clone();
     let obj = ri.name.clone();
     match get_replication_config(&bucket).await {
@@ -2659,12 +2705,9 @@ pub async fn replicate_object(ri: ReplicateObjectInfo, object_api: Arc<store::EC
                 eval_metadata.insert("x-amz-bucket-replication-status".to_string(), replication_status.as_str().to_owned());
 
                 for rinfo in &rs.targets {
-                    // if !rinfo.resync_timestamp.is_empty() {
-                    //     eval_metadata.insert(
-                    //         format!("x-rustfs-replication-reset-status-{}", rinfo.arn),
-                    //         rinfo.resync_timestamp.clone(),
-                    //     );
-                    // }
+                    if rinfo.replication_status == ReplicationStatusType::Completed && !rinfo.resync_timestamp.is_empty() {
+                        eval_metadata.insert(target_reset_header(&rinfo.arn), rinfo.resync_timestamp.clone());
+                    }
                 }
 
                 if !ri.user_tags.is_empty() {
@@ -2726,9 +2769,12 @@ pub async fn replicate_object(ri: ReplicateObjectInfo, object_api: Arc<store::EC
             //     ri.retry_count += 1;
             //     // global_replication_pool.get().queue_mrf_save(ri.to_mrf_entry());
             // }
+
+            replication_status
         }
         Err(err) => {
             println!("Failed to get replication config: {err:?}");
+            ReplicationStatusType::Failed
         }
     }
 }
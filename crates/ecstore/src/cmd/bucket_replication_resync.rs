@@ -0,0 +1,693 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resync of existing objects to a replication target.
+//!
+//! A resync walks every object version of a bucket and replicates the versions last modified
+//! before the resync started to one target again, even when they were already replicated to it.
+//! The status of each target is persisted to `buckets/<bucket>/replication/resync.bin` in the
+//! meta bucket, so an interrupted resync is resumed after a restart. Replicated versions record
+//! the resync id in their metadata and are skipped when the resync is resumed.
+//!
+//! Versions are queued to the replication workers, the counters are updated from the status the
+//! workers report and versions that failed are queued again up to [`RESYNC_MAX_RETRIES`] times.
+
+use super::bucket_replication::{
+    ReplicateObjectInfo, ReplicationResultSender, ReplicationStatusType, ReplicationType, ResyncTargetDecision,
+    get_heal_replicate_object_info, queue_replication, target_reset_header,
+};
+use super::bucket_targets::list_bucket_targets;
+use crate::bucket::metadata_sys::get_replication_config;
+use crate::config::com::{read_config, save_config};
+use crate::disk::BUCKET_META_PREFIX;
+use crate::error::{Error, Result};
+use crate::global::get_global_endpoints;
+use crate::pools::ListCallback;
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, ObjectInfo, StorageAPI};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use rustfs_filemeta::MetaCacheEntry;
+use s3s::dto::{ExistingObjectReplicationStatus, ReplicationConfiguration, ReplicationRuleStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore, broadcast};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const RESYNC_FILE_NAME: &str = "resync.bin";
+const RESYNC_META_FORMAT: u16 = 1;
+const RESYNC_META_VERSION: u16 = 1;
+
+/// Interval at which the status of running resyncs is saved
+const RESYNC_TIME_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of versions of one resync waiting in the replication queue
+const RESYNC_MAX_QUEUED: usize = 1000;
+
+/// Number of times a version that failed to replicate is queued again
+const RESYNC_MAX_RETRIES: u32 = 3;
+
+pub static GLOBAL_REPLICATION_RESYNCER: Lazy<Arc<ReplicationResyncer>> = Lazy::new(|| Arc::new(ReplicationResyncer::new()));
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResyncStatusType {
+    #[default]
+    NoResync,
+    ResyncPending,
+    ResyncCanceled,
+    ResyncStarted,
+    ResyncCompleted,
+    ResyncFailed,
+}
+
+impl ResyncStatusType {
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::ResyncPending | Self::ResyncStarted)
+    }
+}
+
+impl fmt::Display for ResyncStatusType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::NoResync => "",
+            Self::ResyncPending => "Pending",
+            Self::ResyncCanceled => "Canceled",
+            Self::ResyncStarted => "Ongoing",
+            Self::ResyncCompleted => "Completed",
+            Self::ResyncFailed => "Failed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Resync status of a replication target
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetReplicationResyncStatus {
+    pub start_time: Option<DateTime<Utc>>,
+    pub last_update: Option<DateTime<Utc>>,
+    pub resync_id: String,
+    /// Versions modified after this date are left to regular replication.
+    pub resync_before_date: Option<DateTime<Utc>>,
+    pub resync_status: ResyncStatusType,
+    pub failed_size: i64,
+    pub failed_count: i64,
+    pub replicated_size: i64,
+    pub replicated_count: i64,
+    pub bucket: String,
+    /// Last object resynced
+    pub object: String,
+}
+
+/// Resync status of the replication targets of a bucket
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketReplicationResyncStatus {
+    pub version: u16,
+    pub targets_map: HashMap<String, TargetReplicationResyncStatus>,
+    pub id: u64,
+    pub last_update: Option<DateTime<Utc>>,
+}
+
+impl BucketReplicationResyncStatus {
+    fn config_path(bucket: &str) -> String {
+        format!("{BUCKET_META_PREFIX}/{bucket}/replication/{RESYNC_FILE_NAME}")
+    }
+
+    pub fn marshal_msg(&self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        data.extend(&RESYNC_META_FORMAT.to_le_bytes());
+        data.extend(&RESYNC_META_VERSION.to_le_bytes());
+        data.extend(rmp_serde::to_vec(self)?);
+        Ok(data)
+    }
+
+    pub fn unmarshal(data: &[u8]) -> Result<Self> {
+        if data.len() <= 4 {
+            return Err(Error::other("resync status: no data"));
+        }
+
+        match u16::from_le_bytes([data[0], data[1]]) {
+            RESYNC_META_FORMAT => {}
+            fmt => return Err(Error::other(format!("resync status: unknown format: {fmt}"))),
+        }
+        match u16::from_le_bytes([data[2], data[3]]) {
+            RESYNC_META_VERSION => {}
+            ver => return Err(Error::other(format!("resync status: unknown version: {ver}"))),
+        }
+
+        Ok(rmp_serde::from_slice(&data[4..])?)
+    }
+
+    /// Loads the resync status of a bucket, a bucket that was never resynced has an empty status.
+    pub async fn load(store: Arc<ECStore>, bucket: &str) -> Result<Self> {
+        match read_config(store, &Self::config_path(bucket)).await {
+            Ok(data) => Self::unmarshal(&data),
+            Err(Error::ConfigNotFound) => Ok(Self {
+                version: RESYNC_META_VERSION,
+                ..Default::default()
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn save(&self, store: Arc<ECStore>, bucket: &str) -> Result<()> {
+        save_config(store, &Self::config_path(bucket), self.marshal_msg()?).await
+    }
+}
+
+/// A resync of one bucket to one target
+#[derive(Clone)]
+struct ResyncJob {
+    bucket: String,
+    arn: String,
+    resync_id: String,
+    before: DateTime<Utc>,
+}
+
+impl ResyncJob {
+    fn key(&self) -> String {
+        resync_key(&self.bucket, &self.arn)
+    }
+}
+
+fn resync_key(bucket: &str, arn: &str) -> String {
+    format!("{bucket}/{arn}")
+}
+
+/// Runs the resyncs started on this node and keeps their status on disk up to date.
+#[derive(Default)]
+pub struct ReplicationResyncer {
+    /// Status of the resyncs run by this node, by bucket and target
+    status_map: RwLock<HashMap<String, HashMap<String, TargetReplicationResyncStatus>>>,
+    /// Cancel channels of the running resyncs
+    running: Mutex<HashMap<String, broadcast::Sender<bool>>>,
+    /// Targets whose status changed since it was last saved
+    dirty: Mutex<HashSet<(String, String)>>,
+}
+
+impl ReplicationResyncer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts resyncing the objects of `bucket` to the target `arn`.
+    ///
+    /// Versions modified less than `older_than` ago are skipped, without it every version is resynced.
+    pub async fn start(
+        self: &Arc<Self>,
+        store: Arc<ECStore>,
+        bucket: &str,
+        arn: &str,
+        older_than: Option<Duration>,
+    ) -> Result<TargetReplicationResyncStatus> {
+        let (cfg, _) = get_replication_config(bucket)
+            .await
+            .map_err(|_| Error::other(format!("replication is not configured for bucket {bucket}")))?;
+        if !has_existing_object_replication(&cfg, arn) {
+            return Err(Error::other(format!("existing object replication is not enabled for target {arn}")));
+        }
+
+        let targets = list_bucket_targets(bucket)
+            .await
+            .map_err(|_| Error::other(format!("remote target {arn} not found")))?;
+        if !targets.targets.iter().any(|t| t.arn.as_deref() == Some(arn)) {
+            return Err(Error::other(format!("remote target {arn} not found")));
+        }
+
+        if self.running.lock().await.contains_key(&resync_key(bucket, arn)) {
+            return Err(Error::other(format!("a resync to {arn} is already in progress")));
+        }
+
+        let now = Utc::now();
+        let before = match older_than {
+            Some(d) => now - chrono::Duration::from_std(d).map_err(Error::other)?,
+            None => now,
+        };
+        let status = TargetReplicationResyncStatus {
+            start_time: Some(now),
+            last_update: Some(now),
+            resync_id: Uuid::new_v4().to_string(),
+            resync_before_date: Some(before),
+            resync_status: ResyncStatusType::ResyncPending,
+            bucket: bucket.to_string(),
+            ..Default::default()
+        };
+
+        self.status_map
+            .write()
+            .await
+            .entry(bucket.to_string())
+            .or_default()
+            .insert(arn.to_string(), status.clone());
+        self.save_targets(store.clone(), bucket, &[arn.to_string()]).await?;
+
+        self.spawn(
+            store,
+            ResyncJob {
+                bucket: bucket.to_string(),
+                arn: arn.to_string(),
+                resync_id: status.resync_id.clone(),
+                before,
+            },
+        )
+        .await;
+
+        Ok(status)
+    }
+
+    /// Cancels the resync to `arn`.
+    ///
+    /// A resync run by another node stops once that node sees the canceled status on disk.
+    pub async fn cancel(&self, store: Arc<ECStore>, bucket: &str, arn: &str) -> Result<TargetReplicationResyncStatus> {
+        if let Some(tx) = self.running.lock().await.remove(&resync_key(bucket, arn)) {
+            let _ = tx.send(true);
+        }
+
+        let local = self.status_map.read().await.get(bucket).and_then(|m| m.get(arn).cloned());
+        let mut status = match local {
+            Some(status) => status,
+            None => BucketReplicationResyncStatus::load(store.clone(), bucket)
+                .await?
+                .targets_map
+                .remove(arn)
+                .unwrap_or_default(),
+        };
+        if !status.resync_status.is_running() {
+            return Err(Error::other(format!("no resync to {arn} is in progress")));
+        }
+
+        status.resync_status = ResyncStatusType::ResyncCanceled;
+        status.last_update = Some(Utc::now());
+        self.status_map
+            .write()
+            .await
+            .entry(bucket.to_string())
+            .or_default()
+            .insert(arn.to_string(), status.clone());
+        self.save_targets(store, bucket, &[arn.to_string()]).await?;
+
+        Ok(status)
+    }
+
+    /// Returns the resync status of the targets of a bucket.
+    pub async fn status(&self, store: Arc<ECStore>, bucket: &str) -> Result<BucketReplicationResyncStatus> {
+        let mut status = BucketReplicationResyncStatus::load(store, bucket).await?;
+        let running = self.running.lock().await;
+        if let Some(targets) = self.status_map.read().await.get(bucket) {
+            // Resyncs run by this node are more recent than their saved status
+            for (arn, target) in targets {
+                if running.contains_key(&resync_key(bucket, arn)) {
+                    status.targets_map.insert(arn.clone(), target.clone());
+                }
+            }
+        }
+        Ok(status)
+    }
+
+    /// Loads the saved resync status of all buckets and resumes the resyncs that did not finish.
+    ///
+    /// Resyncs are resumed by the first node of the cluster only.
+    pub async fn load_and_resume(self: &Arc<Self>, store: Arc<ECStore>) {
+        if !get_global_endpoints().first_local() {
+            return;
+        }
+
+        let buckets = match store.list_bucket(&BucketOptions::default()).await {
+            Ok(buckets) => buckets,
+            Err(err) => {
+                error!("resync: list buckets failed: {}", err);
+                return;
+            }
+        };
+
+        for bucket in buckets {
+            let status = match BucketReplicationResyncStatus::load(store.clone(), &bucket.name).await {
+                Ok(status) => status,
+                Err(err) => {
+                    warn!("resync: load status of bucket {} failed: {}", bucket.name, err);
+                    continue;
+                }
+            };
+
+            for (arn, target) in status.targets_map {
+                if !target.resync_status.is_running() {
+                    continue;
+                }
+                info!("resync: resuming resync of bucket {} to {}", bucket.name, arn);
+
+                let job = ResyncJob {
+                    bucket: bucket.name.clone(),
+                    arn: arn.clone(),
+                    resync_id: target.resync_id.clone(),
+                    before: target.resync_before_date.or(target.start_time).unwrap_or_else(Utc::now),
+                };
+                self.status_map
+                    .write()
+                    .await
+                    .entry(bucket.name.clone())
+                    .or_default()
+                    .insert(arn, target);
+                self.spawn(store.clone(), job).await;
+            }
+        }
+    }
+
+    /// Periodically saves the status of the resyncs run by this node.
+    pub async fn persist_to_disk(&self, store: Arc<ECStore>) {
+        let mut interval = tokio::time::interval(RESYNC_TIME_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let dirty = std::mem::take(&mut *self.dirty.lock().await);
+            let mut by_bucket: HashMap<String, Vec<String>> = HashMap::new();
+            for (bucket, arn) in dirty {
+                by_bucket.entry(bucket).or_default().push(arn);
+            }
+
+            for (bucket, arns) in by_bucket {
+                if let Err(err) = self.save_targets(store.clone(), &bucket, &arns).await {
+                    error!("resync: save status of bucket {} failed: {}", bucket, err);
+                }
+            }
+        }
+    }
+
+    /// Saves the status of some targets of a bucket.
+    ///
+    /// A running resync that was canceled on another node is stopped here.
+    async fn save_targets(&self, store: Arc<ECStore>, bucket: &str, arns: &[String]) -> Result<()> {
+        let mut saved = BucketReplicationResyncStatus::load(store.clone(), bucket).await?;
+
+        for arn in arns {
+            let canceled = saved
+                .targets_map
+                .get(arn)
+                .filter(|t| t.resync_status == ResyncStatusType::ResyncCanceled)
+                .map(|t| t.resync_id.clone());
+
+            let mut status_map = self.status_map.write().await;
+            let Some(target) = status_map.get_mut(bucket).and_then(|m| m.get_mut(arn)) else {
+                continue;
+            };
+
+            if target.resync_status.is_running() && canceled.as_deref() == Some(target.resync_id.as_str()) {
+                if let Some(tx) = self.running.lock().await.remove(&resync_key(bucket, arn)) {
+                    let _ = tx.send(true);
+                }
+                target.resync_status = ResyncStatusType::ResyncCanceled;
+            }
+
+            saved.targets_map.insert(arn.clone(), target.clone());
+        }
+
+        saved.version = RESYNC_META_VERSION;
+        saved.id += 1;
+        saved.last_update = Some(Utc::now());
+        saved.save(store, bucket).await
+    }
+
+    async fn spawn(self: &Arc<Self>, store: Arc<ECStore>, job: ResyncJob) {
+        let (tx, rx) = broadcast::channel::<bool>(1);
+        self.running.lock().await.insert(job.key(), tx);
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            this.resync_bucket(store, job, rx).await;
+        });
+    }
+
+    async fn is_running(&self, job: &ResyncJob) -> bool {
+        self.running.lock().await.contains_key(&job.key())
+    }
+
+    async fn update_status(&self, job: &ResyncJob, f: impl FnOnce(&mut TargetReplicationResyncStatus)) {
+        let mut status_map = self.status_map.write().await;
+        let Some(target) = status_map.get_mut(&job.bucket).and_then(|m| m.get_mut(&job.arn)) else {
+            return;
+        };
+        if target.resync_id != job.resync_id {
+            return;
+        }
+
+        f(target);
+        target.last_update = Some(Utc::now());
+        self.dirty.lock().await.insert((job.bucket.clone(), job.arn.clone()));
+    }
+
+    async fn resync_bucket(self: Arc<Self>, store: Arc<ECStore>, job: ResyncJob, rx: broadcast::Receiver<bool>) {
+        info!("resync: start resync of bucket {} to {}", job.bucket, job.arn);
+
+        self.update_status(&job, |st| st.resync_status = ResyncStatusType::ResyncStarted)
+            .await;
+
+        let cfg = match get_replication_config(&job.bucket).await {
+            Ok((cfg, _)) => Arc::new(cfg),
+            Err(err) => {
+                error!("resync: get replication config of bucket {} failed: {}", job.bucket, err);
+                self.finish(store, &job, ResyncStatusType::ResyncFailed).await;
+                return;
+            }
+        };
+
+        let queued = Arc::new(Semaphore::new(RESYNC_MAX_QUEUED));
+        let mut jobs = Vec::new();
+        for pool in store.pools.iter() {
+            for set in pool.disk_set.iter() {
+                let resync_entry: ListCallback = Arc::new({
+                    let this = self.clone();
+                    let job = job.clone();
+                    let cfg = cfg.clone();
+                    let queued = queued.clone();
+                    move |entry: MetaCacheEntry| {
+                        let this = this.clone();
+                        let job = job.clone();
+                        let cfg = cfg.clone();
+                        let queued = queued.clone();
+                        Box::pin(async move { this.resync_entry(&job, &cfg, &queued, entry).await })
+                    }
+                });
+
+                let set = set.clone();
+                let rx = rx.resubscribe();
+                let bucket = job.bucket.clone();
+                jobs.push(tokio::spawn(async move { set.list_objects_to_rebalance(rx, bucket, resync_entry).await }));
+            }
+        }
+
+        let mut failed = false;
+        for res in futures::future::join_all(jobs).await {
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => {
+                    error!("resync: list bucket {} failed: {}", job.bucket, err);
+                    failed = true;
+                }
+                Err(err) => {
+                    error!("resync: list bucket {} failed: {}", job.bucket, err);
+                    failed = true;
+                }
+            }
+        }
+
+        // Wait for the workers to report the versions still queued.
+        let _ = queued.acquire_many(RESYNC_MAX_QUEUED as u32).await;
+
+        let status = if failed {
+            ResyncStatusType::ResyncFailed
+        } else {
+            ResyncStatusType::ResyncCompleted
+        };
+        self.finish(store, &job, status).await;
+    }
+
+    /// Records the end of a resync, a canceled resync keeps its status.
+    async fn finish(&self, store: Arc<ECStore>, job: &ResyncJob, status: ResyncStatusType) {
+        if self.running.lock().await.remove(&job.key()).is_none() {
+            info!("resync: resync of bucket {} to {} canceled", job.bucket, job.arn);
+            return;
+        }
+
+        self.update_status(job, |st| st.resync_status = status).await;
+        if let Err(err) = self.save_targets(store, &job.bucket, std::slice::from_ref(&job.arn)).await {
+            error!("resync: save status of bucket {} failed: {}", job.bucket, err);
+        }
+        info!("resync: resync of bucket {} to {} done: {}", job.bucket, job.arn, status);
+    }
+
+    async fn resync_entry(
+        self: Arc<Self>,
+        job: &ResyncJob,
+        cfg: &ReplicationConfiguration,
+        queued: &Arc<Semaphore>,
+        entry: MetaCacheEntry,
+    ) {
+        if entry.is_dir() || !self.is_running(job).await {
+            return;
+        }
+
+        let fivs = match entry.file_info_versions(&job.bucket) {
+            Ok(fivs) => fivs,
+            Err(err) => {
+                error!("resync: get file info versions of {} failed: {}", entry.name, err);
+                return;
+            }
+        };
+
+        for version in fivs.versions.iter() {
+            // Delete markers are not replicated
+            if version.deleted {
+                continue;
+            }
+
+            let mut oi = ObjectInfo::from_file_info(version, &job.bucket, &version.name, true);
+            if oi.mod_time.is_none_or(|t| t.unix_timestamp() > job.before.timestamp()) {
+                continue;
+            }
+
+            let reset_id = oi
+                .user_defined
+                .get(&target_reset_header(&job.arn))
+                .and_then(|v| v.rsplit(';').next());
+            if reset_id == Some(job.resync_id.as_str()) {
+                continue;
+            }
+
+            let mut roi = get_heal_replicate_object_info(&mut oi, cfg).await;
+            if !roi.dsc.replicate_any() {
+                continue;
+            }
+
+            roi.existing_obj_resync.set_target(
+                job.arn.clone(),
+                ResyncTargetDecision {
+                    replicate: true,
+                    reset_id: job.resync_id.clone(),
+                    reset_before_date: job.before,
+                },
+            );
+            roi.target_arn = job.arn.clone();
+            roi.op_type = ReplicationType::ObjectReplicationType as i32;
+
+            // Bounds the versions waiting in the queue, so the listing can't run away from the workers.
+            let Ok(permit) = queued.clone().acquire_owned().await else {
+                return;
+            };
+            let size = oi.get_actual_size().unwrap_or(oi.size);
+            tokio::spawn(self.clone().replicate_version(job.clone(), roi, size, permit));
+        }
+    }
+
+    /// Queues `roi` to the replication workers and counts the status they report, failed versions are queued again.
+    async fn replicate_version(
+        self: Arc<Self>,
+        job: ResyncJob,
+        mut roi: ReplicateObjectInfo,
+        size: i64,
+        _permit: OwnedSemaphorePermit,
+    ) {
+        let status = loop {
+            // Only the queued copy holds the sender, so the channel closes if the workers drop it.
+            let (result_tx, result_rx) = ReplicationResultSender::channel();
+            queue_replication(ReplicateObjectInfo {
+                result_tx,
+                ..roi.clone()
+            })
+            .await;
+
+            let status = result_rx.await.unwrap_or(ReplicationStatusType::Failed);
+            if status == ReplicationStatusType::Completed || roi.retry_count >= RESYNC_MAX_RETRIES || !self.is_running(&job).await
+            {
+                break status;
+            }
+            roi.retry_count += 1;
+            warn!(
+                "resync: replication of {}/{} to {} failed, retry {}",
+                roi.bucket, roi.name, job.arn, roi.retry_count
+            );
+        };
+
+        self.update_status(&job, |st| {
+            if status == ReplicationStatusType::Completed {
+                st.replicated_count += 1;
+                st.replicated_size += size;
+            } else {
+                st.failed_count += 1;
+                st.failed_size += size;
+            }
+            st.object = roi.name.clone();
+        })
+        .await;
+    }
+}
+
+/// Checks that a target is the destination of an enabled rule replicating existing objects.
+fn has_existing_object_replication(cfg: &ReplicationConfiguration, arn: &str) -> bool {
+    cfg.rules.iter().any(|rule| {
+        rule.status.as_str() != ReplicationRuleStatus::DISABLED
+            && (rule.destination.bucket == arn || cfg.role == arn)
+            && rule
+                .existing_object_replication
+                .as_ref()
+                .is_some_and(|e| e.status.as_str() == ExistingObjectReplicationStatus::ENABLED)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resync_status_marshal() {
+        let mut status = BucketReplicationResyncStatus {
+            version: RESYNC_META_VERSION,
+            id: 3,
+            last_update: Some(Utc::now()),
+            ..Default::default()
+        };
+        status.targets_map.insert(
+            "arn:rustfs:replication::id:bucket".to_string(),
+            TargetReplicationResyncStatus {
+                start_time: Some(Utc::now()),
+                resync_id: Uuid::new_v4().to_string(),
+                resync_status: ResyncStatusType::ResyncStarted,
+                replicated_count: 10,
+                replicated_size: 1024,
+                bucket: "bucket".to_string(),
+                object: "object".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let data = status.marshal_msg().unwrap();
+        assert_eq!(BucketReplicationResyncStatus::unmarshal(&data).unwrap(), status);
+
+        assert!(BucketReplicationResyncStatus::unmarshal(&data[..4]).is_err());
+        let mut bad = data.clone();
+        bad[0] = 9;
+        assert!(BucketReplicationResyncStatus::unmarshal(&bad).is_err());
+    }
+
+    #[test]
+    fn test_resync_status_type() {
+        assert!(ResyncStatusType::ResyncPending.is_running());
+        assert!(ResyncStatusType::ResyncStarted.is_running());
+        assert!(!ResyncStatusType::ResyncCanceled.is_running());
+        assert_eq!(ResyncStatusType::ResyncStarted.to_string(), "Ongoing");
+    }
+}
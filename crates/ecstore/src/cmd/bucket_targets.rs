@@ -297,6 +297,8 @@ impl BucketTargetSys {
         // 更新 targets_map
         targets_map.insert(bucket.to_string(), targets);
         arn_remotes_map.remove(arn_str);
+        drop(arn_remotes_map);
+        drop(targets_map);
 
         let targets = self.list_targets(Some(bucket), None).await;
         println!("targets is {}", targets.len());
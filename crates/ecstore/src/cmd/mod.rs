@@ -13,4 +13,6 @@
 // limitations under the License.
 
 pub mod bucket_replication;
+pub mod bucket_replication_resync;
 pub mod bucket_targets;
+pub mod site_replication;
@@ -0,0 +1,1022 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Site replication links independent deployments so that they hold the same buckets, bucket
+//! metadata, IAM entities and objects.
+//!
+//! Every site keeps the list of linked sites in `config/site-replication/state.json`. Changes made
+//! on one site are pushed to the others through the peer site replication admin API, and objects
+//! are replicated by the bucket replication rules each site sets up towards all other sites.
+//!
+//! The admin credentials given when the sites are linked are only used to join them. The sites then
+//! call each other with the [`SITE_REPLICATOR_ACCESS_KEY`] service account, created with the same
+//! credentials on every site, and the state holding it is stored encrypted by the root credentials.
+//! The bodies of the peer calls carry the service account and IAM secrets, so they are encrypted by
+//! the secret key the call is signed with.
+
+use crate::bucket::metadata::{
+    BUCKET_LIFECYCLE_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_QUOTA_CONFIG_FILE, BUCKET_REPLICATION_CONFIG, BUCKET_SSECONFIG,
+    BUCKET_TAGGING_CONFIG, BUCKET_TARGETS_FILE, BUCKET_VERSIONING_CONFIG, BucketMetadata, OBJECT_LOCK_CONFIG,
+};
+use crate::bucket::metadata_sys;
+use crate::bucket::target::{BucketTarget, Credentials};
+use crate::bucket::utils::serialize;
+use crate::cmd::bucket_targets::GLOBAL_Bucket_Target_Sys;
+use crate::config::com::{CONFIG_PREFIX, delete_config, read_config, save_config};
+use crate::error::{Error, Result, is_err_bucket_exists, is_err_bucket_not_found};
+use crate::global::{get_global_action_cred, get_global_deployment_id};
+use crate::notification_sys::get_global_notification_sys;
+use crate::store::ECStore;
+use crate::store_api::{BucketOptions, DeleteBucketOptions, MakeBucketOptions, StorageAPI};
+use futures::future::join_all;
+use http::Method;
+use once_cell::sync::Lazy;
+use rustfs_madmin::site_replication::{
+    PeerInfo, PeerSite, ReplicateAddStatus, ReplicateRemoveStatus, SRBucketMeta, SRBucketOp, SRIAMItem, SRPeerState, SRRemoveReq,
+    SRSiteStatus, SRStatusInfo, SiteReplicationInfo,
+};
+use rustfs_utils::crypto::hex_sha256;
+use s3s::Body;
+use s3s::dto::{
+    BucketVersioningStatus, DeleteMarkerReplication, DeleteMarkerReplicationStatus, DeleteReplication, DeleteReplicationStatus,
+    Destination, ExistingObjectReplication, ExistingObjectReplicationStatus, ReplicationConfiguration, ReplicationRule,
+    ReplicationRuleStatus, VersioningConfiguration,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+const SITE_REPLICATION_STATE_FILE: &str = "site-replication/state.json";
+
+/// Prefix of the peer site replication admin API
+pub const SITE_REPLICATION_PEER_PREFIX: &str = "/rustfs/admin/v3/site-replication/peer";
+
+/// Prefix of the ids of the bucket replication rules created by site replication
+const SITE_REPLICATION_RULE_PREFIX: &str = "site-repl-";
+
+const PEER_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Access key of the service account the sites use to call each other
+pub const SITE_REPLICATOR_ACCESS_KEY: &str = "site-replicator-0";
+
+/// Bucket metadata kept in sync between the sites
+pub const REPLICATED_BUCKET_CONFIGS: &[&str] = &[
+    BUCKET_POLICY_CONFIG,
+    BUCKET_TAGGING_CONFIG,
+    BUCKET_LIFECYCLE_CONFIG,
+    BUCKET_SSECONFIG,
+    OBJECT_LOCK_CONFIG,
+    BUCKET_VERSIONING_CONFIG,
+    BUCKET_QUOTA_CONFIG_FILE,
+];
+
+pub static GLOBAL_SITE_REPLICATION_SYS: Lazy<SiteReplicationSys> = Lazy::new(SiteReplicationSys::default);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerCredentials {
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+impl std::fmt::Debug for PeerCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PeerCredentials")
+            .field("access_key", &self.access_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Sites linked by site replication, persisted by every site.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteReplicationState {
+    /// Name of the local site
+    pub name: String,
+    /// Sites by deployment id, including the local site
+    pub peers: HashMap<String, PeerInfo>,
+    /// The [`SITE_REPLICATOR_ACCESS_KEY`] service account, the same on every site
+    pub service_account: PeerCredentials,
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl SiteReplicationState {
+    fn config_path() -> String {
+        format!("{CONFIG_PREFIX}/{SITE_REPLICATION_STATE_FILE}")
+    }
+
+    /// The state holds the service account secret, so it is stored encrypted by the root credentials.
+    fn encryption_key() -> Vec<u8> {
+        get_global_action_cred().unwrap_or_default().secret_key.into_bytes()
+    }
+
+    fn seal(&self, key: &[u8]) -> Result<Vec<u8>> {
+        rustfs_crypto::encrypt_data(key, &serde_json::to_vec(self)?).map_err(Error::other)
+    }
+
+    fn unseal(data: &[u8], key: &[u8]) -> Result<Self> {
+        let data = rustfs_crypto::decrypt_data(key, data)
+            .map_err(|e| Error::other(format!("decrypt site replication state failed: {e}")))?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Loads the state, a deployment without site replication has an empty state.
+    pub async fn load(store: Arc<ECStore>) -> Result<Self> {
+        match read_config(store, &Self::config_path()).await {
+            Ok(data) => Self::unseal(&data, &Self::encryption_key()),
+            Err(Error::ConfigNotFound) => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn save(&self, store: Arc<ECStore>) -> Result<()> {
+        if self.peers.is_empty() {
+            return match delete_config(store, &Self::config_path()).await {
+                Err(Error::ConfigNotFound) => Ok(()),
+                res => res,
+            };
+        }
+        save_config(store, &Self::config_path(), self.seal(&Self::encryption_key())?).await
+    }
+
+    /// Name of the site with the given deployment id
+    fn site_name(&self, deployment_id: &str) -> String {
+        self.peers.get(deployment_id).map(|p| p.name.clone()).unwrap_or_default()
+    }
+
+    fn client(&self, deployment_id: &str) -> Option<PeerClient> {
+        let peer = self.peers.get(deployment_id)?;
+        let cred = &self.service_account;
+        if cred.access_key.is_empty() {
+            return None;
+        }
+        Some(PeerClient::new(&peer.endpoint, &cred.access_key, &cred.secret_key))
+    }
+}
+
+/// Client of the peer site replication admin API of another site.
+#[derive(Clone)]
+pub struct PeerClient {
+    endpoint: String,
+    access_key: String,
+    secret_key: String,
+    client: reqwest::Client,
+}
+
+impl PeerClient {
+    pub fn new(endpoint: &str, access_key: &str, secret_key: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn call(&self, method: Method, path: &str, query: &[(&str, &str)], body: Vec<u8>) -> Result<Vec<u8>> {
+        let body = if body.is_empty() {
+            body
+        } else {
+            seal_peer_body(&self.secret_key, &body)?
+        };
+
+        let mut url = format!("{}{}{}", self.endpoint, SITE_REPLICATION_PEER_PREFIX, path);
+        if !query.is_empty() {
+            url.push('?');
+            let query: Vec<String> = query
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+                .collect();
+            url.push_str(&query.join("&"));
+        }
+
+        let content_sha256 = hex_sha256(&body, |s| s.to_string());
+        let req = http::Request::builder()
+            .method(method.clone())
+            .uri(&url)
+            .header("X-Amz-Content-Sha256", &content_sha256)
+            .body(Body::from(body.clone()))
+            .map_err(Error::other)?;
+        let req = rustfs_signer::sign_v4(req, body.len() as i64, &self.access_key, &self.secret_key, "", "us-east-1");
+
+        let resp = self
+            .client
+            .request(method, &url)
+            .headers(req.headers().clone())
+            .body(body)
+            .timeout(PEER_REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| Error::other(format!("{}: {}", self.endpoint, e)))?;
+
+        let status = resp.status();
+        let data = resp.bytes().await.map_err(Error::other)?;
+        if !status.is_success() {
+            return Err(Error::other(format!("{}: {} {}", self.endpoint, status, String::from_utf8_lossy(&data))));
+        }
+        Ok(data.to_vec())
+    }
+
+    async fn call_json<T: DeserializeOwned>(&self, method: Method, path: &str, body: Vec<u8>) -> Result<T> {
+        let data = self.call(method, path, &[], body).await?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    pub async fn state(&self) -> Result<SRPeerState> {
+        self.call_json(Method::GET, "/state", Vec::new()).await
+    }
+
+    pub async fn join(&self, state: &SiteReplicationState) -> Result<()> {
+        self.call(Method::PUT, "/join", &[], serde_json::to_vec(state)?)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn remove(&self, req: &SRRemoveReq) -> Result<()> {
+        self.call(Method::PUT, "/remove", &[], serde_json::to_vec(req)?)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn bucket_op(&self, bucket: &str, op: SRBucketOp, lock_enabled: bool) -> Result<()> {
+        let op = serde_json::to_value(op)?;
+        let query = [
+            ("bucket", bucket),
+            ("operation", op.as_str().unwrap_or_default()),
+            ("lock", if lock_enabled { "true" } else { "false" }),
+        ];
+        self.call(Method::PUT, "/bucket-ops", &query, Vec::new()).await.map(|_| ())
+    }
+
+    pub async fn bucket_meta(&self, meta: &SRBucketMeta) -> Result<()> {
+        self.call(Method::PUT, "/bucket-meta", &[], serde_json::to_vec(meta)?)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn iam_item(&self, item: &SRIAMItem) -> Result<()> {
+        self.call(Method::PUT, "/iam-item", &[], serde_json::to_vec(item)?)
+            .await
+            .map(|_| ())
+    }
+}
+
+fn seal_peer_body(secret_key: &str, body: &[u8]) -> Result<Vec<u8>> {
+    rustfs_crypto::encrypt_data(secret_key.as_bytes(), body).map_err(Error::other)
+}
+
+/// Opens the body of a call made by a [`PeerClient`], `secret_key` is the one of the caller.
+pub fn open_peer_body(secret_key: &str, body: &[u8]) -> Result<Vec<u8>> {
+    rustfs_crypto::decrypt_data(secret_key.as_bytes(), body)
+        .map_err(|e| Error::other(format!("decrypt site replication request failed: {e}")))
+}
+
+#[derive(Default)]
+pub struct SiteReplicationSys {
+    state: RwLock<SiteReplicationState>,
+}
+
+impl SiteReplicationSys {
+    pub async fn init(&self, store: Arc<ECStore>) -> Result<()> {
+        self.reload(store).await?;
+        if self.is_enabled().await {
+            info!("site replication enabled with {} sites", self.state.read().await.peers.len());
+        }
+        Ok(())
+    }
+
+    /// Reloads the state saved by another node of the deployment.
+    pub async fn reload(&self, store: Arc<ECStore>) -> Result<()> {
+        let state = SiteReplicationState::load(store).await?;
+        *self.state.write().await = state;
+        Ok(())
+    }
+
+    pub async fn is_enabled(&self) -> bool {
+        !self.state.read().await.peers.is_empty()
+    }
+
+    /// The service account the linked sites call each other with, `None` while not linked.
+    pub async fn service_account(&self) -> Option<PeerCredentials> {
+        let state = self.state.read().await;
+        (!state.peers.is_empty()).then(|| state.service_account.clone())
+    }
+
+    pub async fn info(&self) -> SiteReplicationInfo {
+        let state = self.state.read().await;
+        let mut sites: Vec<PeerInfo> = state.peers.values().cloned().collect();
+        sites.sort_by(|a, b| a.name.cmp(&b.name));
+        SiteReplicationInfo {
+            enabled: !state.peers.is_empty(),
+            name: state.name.clone(),
+            sites,
+        }
+    }
+
+    /// Clients of all sites but the local one, by deployment id.
+    async fn peer_clients(&self) -> Vec<(String, PeerClient)> {
+        let local = get_global_deployment_id().unwrap_or_default();
+        let state = self.state.read().await;
+        state
+            .peers
+            .keys()
+            .filter(|id| **id != local)
+            .filter_map(|id| state.client(id).map(|c| (id.clone(), c)))
+            .collect()
+    }
+
+    async fn set_state(&self, store: Arc<ECStore>, mut state: SiteReplicationState) -> Result<()> {
+        state.updated_at = Some(OffsetDateTime::now_utc());
+        state.save(store).await?;
+        *self.state.write().await = state;
+
+        if let Some(notification_sys) = get_global_notification_sys() {
+            notification_sys.reload_site_replication_config().await;
+        }
+        Ok(())
+    }
+
+    /// Runs a call on all peer sites and returns the combined errors.
+    async fn for_each_peer<F, Fut>(&self, f: F) -> Result<()>
+    where
+        F: Fn(PeerClient) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let peers = self.peer_clients().await;
+        let results = join_all(peers.iter().map(|(_, client)| f(client.clone()))).await;
+
+        let state = self.state.read().await;
+        let errs: Vec<String> = peers
+            .iter()
+            .zip(results)
+            .filter_map(|((id, _), res)| res.err().map(|e| format!("site {}: {}", state.site_name(id), e)))
+            .collect();
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::other(format!("site replication: {}", errs.join("; "))))
+        }
+    }
+
+    /// Links the given sites, one of which must be the local site, and syncs the local buckets to them.
+    ///
+    /// The admin credentials of `sites` are only used for the join calls, the sites keep calling each
+    /// other with `service_account`, which the caller created locally. Sites are added to an existing
+    /// link by listing the linked sites along with the new ones and passing the account they share.
+    /// If a site fails to join, the sites which already joined are unlinked again.
+    pub async fn add_peer_sites(
+        &self,
+        store: Arc<ECStore>,
+        sites: Vec<PeerSite>,
+        service_account: PeerCredentials,
+    ) -> Result<ReplicateAddStatus> {
+        let current = self.state.read().await.clone();
+        if !current.peers.is_empty() && current.service_account.secret_key != service_account.secret_key {
+            return Err(Error::other("the linked sites use another site replicator account"));
+        }
+        if sites.len() < 2 {
+            return Err(Error::other("at least two sites are required"));
+        }
+        let mut names = HashSet::new();
+        for site in sites.iter() {
+            if site.name.is_empty() || site.endpoint.is_empty() {
+                return Err(Error::other("site name and endpoint are required"));
+            }
+            if !names.insert(site.name.as_str()) {
+                return Err(Error::other(format!("duplicate site name {}", site.name)));
+            }
+        }
+
+        let Some(local_id) = get_global_deployment_id() else {
+            return Err(Error::other("deployment id is not initialized"));
+        };
+
+        let clients: Vec<PeerClient> = sites
+            .iter()
+            .map(|s| PeerClient::new(&s.endpoint, &s.access_key, &s.secret_key))
+            .collect();
+        let peer_states = join_all(clients.iter().map(|c| c.state())).await;
+
+        let mut state = SiteReplicationState {
+            service_account,
+            ..Default::default()
+        };
+        let mut join_clients = Vec::new();
+        for ((site, client), peer_state) in sites.iter().zip(clients).zip(peer_states) {
+            let peer_state = peer_state.map_err(|e| Error::other(format!("site {} is unreachable: {}", site.name, e)))?;
+            if state.peers.contains_key(&peer_state.deployment_id) {
+                return Err(Error::other(format!("site {} is already listed with another endpoint", site.name)));
+            }
+            if peer_state.deployment_id == local_id {
+                state.name = site.name.clone();
+            } else {
+                if !peer_state.buckets.is_empty() && !current.peers.contains_key(&peer_state.deployment_id) {
+                    warn!("site replication: site {} already has {} buckets", site.name, peer_state.buckets.len());
+                }
+                join_clients.push((peer_state.deployment_id.clone(), client));
+            }
+            if let Some(linked) = current.peers.get(&peer_state.deployment_id) {
+                if linked.name != site.name {
+                    return Err(Error::other(format!("site {} is already linked as {}", site.name, linked.name)));
+                }
+            }
+
+            state.peers.insert(
+                peer_state.deployment_id.clone(),
+                PeerInfo {
+                    name: site.name.clone(),
+                    endpoint: site.endpoint.trim_end_matches('/').to_string(),
+                    deployment_id: peer_state.deployment_id,
+                },
+            );
+        }
+        if state.name.is_empty() {
+            return Err(Error::other("the local site must be one of the sites"));
+        }
+        if let Some(missing) = current.peers.values().find(|p| !state.peers.contains_key(&p.deployment_id)) {
+            return Err(Error::other(format!("linked site {} must be listed when adding sites", missing.name)));
+        }
+        if !current.peers.is_empty() && state.peers.len() == current.peers.len() {
+            return Err(Error::other("all sites are already linked"));
+        }
+
+        let joins = join_all(join_clients.iter().map(|(id, client)| {
+            let state = &state;
+            async move { client.join(state).await.map_err(|e| (id, e)) }
+        }))
+        .await;
+
+        let mut join_errs = Vec::new();
+        let mut joined = Vec::new();
+        for ((id, client), res) in join_clients.iter().zip(joins) {
+            match res {
+                Ok(()) => joined.push((id, client)),
+                Err((id, err)) => join_errs.push(format!("site {} failed to join: {}", state.site_name(id), err)),
+            }
+        }
+        if !join_errs.is_empty() {
+            // New sites are unlinked, the linked ones forget the new sites.
+            let unlink = SRRemoveReq {
+                remove_all: true,
+                ..Default::default()
+            };
+            let forget = SRRemoveReq {
+                site_names: state
+                    .peers
+                    .iter()
+                    .filter(|(id, _)| !current.peers.contains_key(*id))
+                    .map(|(_, p)| p.name.clone())
+                    .collect(),
+                ..Default::default()
+            };
+            let removes = joined.iter().map(|(id, client)| {
+                let req = if current.peers.contains_key(*id) { &forget } else { &unlink };
+                client.remove(req)
+            });
+            for ((_, client), res) in joined.iter().zip(join_all(removes).await) {
+                if let Err(err) = res {
+                    warn!("site replication: unlink {} after a failed join failed: {}", client.endpoint, err);
+                    join_errs.push(format!("site at {} could not be unlinked: {}", client.endpoint, err));
+                }
+            }
+            return Err(Error::other(join_errs.join("; ")));
+        }
+
+        self.set_state(store.clone(), state).await?;
+
+        let initial_sync_errors = self.sync_buckets(store).await;
+        Ok(ReplicateAddStatus {
+            success: true,
+            status: "Requested sites were configured for replication successfully.".to_string(),
+            initial_sync_errors,
+            ..Default::default()
+        })
+    }
+
+    /// Saves the state sent by the site which links the sites or adds sites to their link.
+    pub async fn peer_join(&self, store: Arc<ECStore>, mut state: SiteReplicationState) -> Result<()> {
+        let Some(local_id) = get_global_deployment_id() else {
+            return Err(Error::other("deployment id is not initialized"));
+        };
+        let Some(local) = state.peers.get(&local_id) else {
+            return Err(Error::other("the local site is not one of the sites"));
+        };
+        if state.service_account.access_key != SITE_REPLICATOR_ACCESS_KEY || state.service_account.secret_key.is_empty() {
+            return Err(Error::other("the site replicator service account is missing"));
+        }
+
+        // A linked site only takes a state which adds sites to its link.
+        let current = self.state.read().await.clone();
+        if !current.peers.is_empty() {
+            if current.service_account.secret_key != state.service_account.secret_key {
+                return Err(Error::other(
+                    "site replication is already configured with another site replicator account",
+                ));
+            }
+            if current.peers.keys().any(|id| !state.peers.contains_key(id)) {
+                return Err(Error::other("site replication is already configured with other sites"));
+            }
+        }
+
+        state.name = local.name.clone();
+        self.set_state(store, state).await
+    }
+
+    /// Syncs the local buckets and their metadata to the peers, returns the items which failed.
+    async fn sync_buckets(&self, store: Arc<ECStore>) -> Vec<String> {
+        let mut errs = Vec::new();
+        let buckets = match store.list_bucket(&BucketOptions::default()).await {
+            Ok(buckets) => buckets,
+            Err(err) => return vec![format!("list buckets: {err}")],
+        };
+
+        for bucket in buckets.iter() {
+            if let Err(err) = self.sync_bucket(&bucket.name).await {
+                errs.push(format!("bucket {}: {}", bucket.name, err));
+            }
+        }
+
+        // Replication is set up once the buckets exist on all sites
+        for bucket in buckets.iter() {
+            if let Err(err) = self.configure_replication_everywhere(&bucket.name).await {
+                errs.push(format!("bucket {} replication: {}", bucket.name, err));
+            }
+        }
+        errs
+    }
+
+    async fn sync_bucket(&self, bucket: &str) -> Result<()> {
+        let meta = metadata_sys::get(bucket).await?;
+        if !meta.versioning() {
+            metadata_sys::update(bucket, BUCKET_VERSIONING_CONFIG, enabled_versioning_config()?).await?;
+        }
+
+        let lock_enabled = meta.object_locking();
+        self.for_each_peer(|client| async move { client.bucket_op(bucket, SRBucketOp::MakeWithVersioning, lock_enabled).await })
+            .await?;
+
+        let meta = metadata_sys::get(bucket).await?;
+        self.replicate_bucket_metadata(&meta).await
+    }
+
+    /// Sends the replicated configs of a bucket's metadata to the peers.
+    pub async fn replicate_bucket_metadata(&self, meta: &BucketMetadata) -> Result<()> {
+        for config_file in REPLICATED_BUCKET_CONFIGS {
+            let data = bucket_config_data(meta, config_file);
+            if !data.is_empty() {
+                self.bucket_meta_hook(&meta.name, config_file, Some(data)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn configure_replication_everywhere(&self, bucket: &str) -> Result<()> {
+        self.configure_bucket_replication(bucket).await?;
+        self.for_each_peer(|client| async move { client.bucket_op(bucket, SRBucketOp::ConfigureReplication, false).await })
+            .await
+    }
+
+    /// Unlinks sites. Removing the local site unlinks all sites.
+    pub async fn remove_peer_sites(&self, store: Arc<ECStore>, req: SRRemoveReq) -> Result<ReplicateRemoveStatus> {
+        if !self.is_enabled().await {
+            return Err(Error::other("site replication is not configured"));
+        }
+        {
+            let state = self.state.read().await;
+            for name in req.site_names.iter() {
+                if !state.peers.values().any(|p| p.name == *name) {
+                    return Err(Error::other(format!("site {name} not found")));
+                }
+            }
+        }
+        if !req.remove_all && req.site_names.is_empty() {
+            return Err(Error::other("no sites to remove"));
+        }
+
+        let peer_result = self.for_each_peer(|client| {
+            let req = &req;
+            async move { client.remove(req).await }
+        });
+        let err_detail = peer_result.await.err().map(|e| e.to_string()).unwrap_or_default();
+
+        self.peer_remove(store, &req).await?;
+
+        Ok(ReplicateRemoveStatus {
+            status: "Requested site(s) were removed from cluster replication successfully.".to_string(),
+            err_detail,
+        })
+    }
+
+    /// Drops the removed sites from the state and stops replicating buckets to them.
+    pub async fn peer_remove(&self, store: Arc<ECStore>, req: &SRRemoveReq) -> Result<()> {
+        let local_id = get_global_deployment_id().unwrap_or_default();
+        let mut state = self.state.read().await.clone();
+        let remove_all = req.remove_all || req.site_names.contains(&state.name);
+
+        let removed: HashSet<String> = state
+            .peers
+            .values()
+            .filter(|p| p.deployment_id != local_id && (remove_all || req.site_names.contains(&p.name)))
+            .map(|p| p.deployment_id.clone())
+            .collect();
+
+        let buckets = store.list_bucket(&BucketOptions::default()).await?;
+        for bucket in buckets.iter() {
+            if let Err(err) = remove_bucket_replication(&bucket.name, &removed).await {
+                warn!("site replication: remove replication of bucket {} failed: {}", bucket.name, err);
+            }
+        }
+
+        for id in removed.iter() {
+            state.peers.remove(id);
+        }
+        if remove_all || state.peers.len() < 2 {
+            state = SiteReplicationState::default();
+        }
+        self.set_state(store, state).await
+    }
+
+    /// Reports which peers are reachable and which local buckets they lack.
+    pub async fn status(&self, store: Arc<ECStore>) -> Result<SRStatusInfo> {
+        let local = self.peer_state(store).await?;
+        let peers = self.peer_clients().await;
+        let results = join_all(peers.iter().map(|(_, client)| client.state())).await;
+
+        let state = self.state.read().await;
+        let mut sites = Vec::with_capacity(peers.len());
+        for ((id, _), res) in peers.iter().zip(results) {
+            let peer = state.peers.get(id).cloned().unwrap_or_default();
+            let mut status = SRSiteStatus {
+                name: peer.name,
+                endpoint: peer.endpoint,
+                deployment_id: peer.deployment_id,
+                ..Default::default()
+            };
+            match res {
+                Ok(peer_state) => {
+                    let buckets: HashSet<&String> = peer_state.buckets.iter().collect();
+                    status.online = true;
+                    status.missing_buckets = local.buckets.iter().filter(|b| !buckets.contains(b)).cloned().collect();
+                }
+                Err(err) => status.error = err.to_string(),
+            }
+            sites.push(status);
+        }
+        sites.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(SRStatusInfo {
+            enabled: !state.peers.is_empty(),
+            name: state.name.clone(),
+            buckets: local.buckets.len(),
+            sites,
+        })
+    }
+
+    pub async fn peer_state(&self, store: Arc<ECStore>) -> Result<SRPeerState> {
+        let buckets = store.list_bucket(&BucketOptions::default()).await?;
+        Ok(SRPeerState {
+            deployment_id: get_global_deployment_id().unwrap_or_default(),
+            buckets: buckets.into_iter().map(|b| b.name).collect(),
+        })
+    }
+
+    /// Creates a new bucket on the peers and replicates its objects to them.
+    pub async fn make_bucket_hook(&self, bucket: &str, lock_enabled: bool) -> Result<()> {
+        if !self.is_enabled().await {
+            return Ok(());
+        }
+
+        metadata_sys::update(bucket, BUCKET_VERSIONING_CONFIG, enabled_versioning_config()?).await?;
+        self.for_each_peer(|client| async move { client.bucket_op(bucket, SRBucketOp::MakeWithVersioning, lock_enabled).await })
+            .await?;
+        self.configure_replication_everywhere(bucket).await
+    }
+
+    pub async fn delete_bucket_hook(&self, bucket: &str) -> Result<()> {
+        if !self.is_enabled().await {
+            return Ok(());
+        }
+        self.for_each_peer(|client| async move { client.bucket_op(bucket, SRBucketOp::DeleteBucket, false).await })
+            .await
+    }
+
+    /// Sends a bucket metadata config to the peers, `None` deletes it.
+    pub async fn bucket_meta_hook(&self, bucket: &str, config_file: &str, data: Option<&[u8]>) -> Result<()> {
+        if !REPLICATED_BUCKET_CONFIGS.contains(&config_file) || !self.is_enabled().await {
+            return Ok(());
+        }
+
+        let meta = SRBucketMeta {
+            bucket: bucket.to_string(),
+            config_file: config_file.to_string(),
+            data: data.map(|d| String::from_utf8_lossy(d).into_owned()),
+        };
+        self.for_each_peer(|client| {
+            let meta = &meta;
+            async move { client.bucket_meta(meta).await }
+        })
+        .await
+    }
+
+    pub async fn iam_change_hook(&self, item: &SRIAMItem) -> Result<()> {
+        if !self.is_enabled().await {
+            return Ok(());
+        }
+        self.for_each_peer(|client| async move { client.iam_item(item).await }).await
+    }
+
+    /// Applies a bucket operation sent by a peer.
+    pub async fn peer_bucket_op(&self, store: Arc<ECStore>, bucket: &str, op: SRBucketOp, lock_enabled: bool) -> Result<()> {
+        match op {
+            SRBucketOp::MakeWithVersioning => {
+                let opts = MakeBucketOptions {
+                    lock_enabled,
+                    versioning_enabled: true,
+                    ..Default::default()
+                };
+                match store.make_bucket(bucket, &opts).await {
+                    Ok(()) => Ok(()),
+                    Err(err) if is_err_bucket_exists(&err) => {
+                        if !metadata_sys::get(bucket).await?.versioning() {
+                            metadata_sys::update(bucket, BUCKET_VERSIONING_CONFIG, enabled_versioning_config()?).await?;
+                        }
+                        Ok(())
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            SRBucketOp::ConfigureReplication => self.configure_bucket_replication(bucket).await,
+            SRBucketOp::DeleteBucket => match store.delete_bucket(bucket, &DeleteBucketOptions::default()).await {
+                Err(err) if !is_err_bucket_not_found(&err) => Err(err),
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Applies a bucket metadata config sent by a peer.
+    pub async fn peer_bucket_meta(&self, meta: SRBucketMeta) -> Result<()> {
+        if !REPLICATED_BUCKET_CONFIGS.contains(&meta.config_file.as_str()) {
+            return Err(Error::other(format!("config {} is not replicated", meta.config_file)));
+        }
+        match meta.data {
+            Some(data) => metadata_sys::update(&meta.bucket, &meta.config_file, data.into_bytes()).await?,
+            None => metadata_sys::delete(&meta.bucket, &meta.config_file).await?,
+        };
+        Ok(())
+    }
+
+    /// Sets up a replication target and rule to every other site for a bucket, keeping the other rules.
+    async fn configure_bucket_replication(&self, bucket: &str) -> Result<()> {
+        let Some(target_sys) = GLOBAL_Bucket_Target_Sys.get() else {
+            return Err(Error::other("bucket target system is not initialized"));
+        };
+
+        let local_id = get_global_deployment_id().unwrap_or_default();
+        let state = self.state.read().await.clone();
+
+        let mut rules = match metadata_sys::get_replication_config(bucket).await {
+            Ok((cfg, _)) => cfg
+                .rules
+                .into_iter()
+                .filter(|r| !r.id.as_deref().is_some_and(|id| id.starts_with(SITE_REPLICATION_RULE_PREFIX)))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        let mut peers: Vec<&PeerInfo> = state.peers.values().filter(|p| p.deployment_id != local_id).collect();
+        peers.sort_by(|a, b| a.name.cmp(&b.name));
+        let cred = &state.service_account;
+        for (i, peer) in peers.into_iter().enumerate() {
+            let mut target = BucketTarget::new_replication(
+                bucket,
+                &endpoint_host(&peer.endpoint)?,
+                Credentials {
+                    access_key: cred.access_key.clone(),
+                    secret_key: cred.secret_key.clone(),
+                    ..Default::default()
+                },
+                &peer.deployment_id,
+            );
+            let (arn, exists) = target_sys.get_remote_arn(bucket, Some(&target), &peer.deployment_id).await;
+            target.arn = arn.clone();
+            if !exists {
+                target_sys
+                    .set_target(bucket, &target, false, false)
+                    .await
+                    .map_err(|e| Error::other(format!("set target {} failed: {}", peer.name, e)))?;
+            }
+
+            rules.push(site_replication_rule(&peer.deployment_id, arn.unwrap_or_default(), i as i32 + 1));
+        }
+
+        save_bucket_targets(bucket).await?;
+
+        let cfg = ReplicationConfiguration {
+            role: String::new(),
+            rules,
+        };
+        metadata_sys::update(bucket, BUCKET_REPLICATION_CONFIG, serialize(&cfg)?).await?;
+        Ok(())
+    }
+}
+
+/// Removes the replication rules and targets of a bucket to the given sites.
+async fn remove_bucket_replication(bucket: &str, deployment_ids: &HashSet<String>) -> Result<()> {
+    let Ok((cfg, _)) = metadata_sys::get_replication_config(bucket).await else {
+        return Ok(());
+    };
+
+    let (removed, rules): (Vec<ReplicationRule>, Vec<ReplicationRule>) = cfg.rules.into_iter().partition(|r| {
+        r.id.as_deref()
+            .and_then(|id| id.strip_prefix(SITE_REPLICATION_RULE_PREFIX))
+            .is_some_and(|id| deployment_ids.contains(id))
+    });
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(target_sys) = GLOBAL_Bucket_Target_Sys.get() {
+        for rule in removed.iter() {
+            target_sys
+                .remove_target(bucket, &rule.destination.bucket)
+                .await
+                .map_err(|e| Error::other(e.to_string()))?;
+        }
+    }
+    save_bucket_targets(bucket).await?;
+
+    if rules.is_empty() {
+        metadata_sys::delete(bucket, BUCKET_REPLICATION_CONFIG).await?;
+    } else {
+        let cfg = ReplicationConfiguration { role: cfg.role, rules };
+        metadata_sys::update(bucket, BUCKET_REPLICATION_CONFIG, serialize(&cfg)?).await?;
+    }
+    Ok(())
+}
+
+async fn save_bucket_targets(bucket: &str) -> Result<()> {
+    let Some(target_sys) = GLOBAL_Bucket_Target_Sys.get() else {
+        return Ok(());
+    };
+    let targets = target_sys.list_targets(Some(bucket), None).await;
+    metadata_sys::update(bucket, BUCKET_TARGETS_FILE, serde_json::to_vec(&targets)?).await?;
+    Ok(())
+}
+
+fn site_replication_rule(deployment_id: &str, arn: String, priority: i32) -> ReplicationRule {
+    ReplicationRule {
+        id: Some(format!("{SITE_REPLICATION_RULE_PREFIX}{deployment_id}")),
+        status: ReplicationRuleStatus::from_static(ReplicationRuleStatus::ENABLED),
+        priority: Some(priority),
+        destination: Destination {
+            bucket: arn,
+            access_control_translation: None,
+            account: None,
+            encryption_configuration: None,
+            metrics: None,
+            replication_time: None,
+            storage_class: None,
+        },
+        delete_marker_replication: Some(DeleteMarkerReplication {
+            status: Some(DeleteMarkerReplicationStatus::from_static(DeleteMarkerReplicationStatus::ENABLED)),
+        }),
+        delete_replication: Some(DeleteReplication {
+            status: DeleteReplicationStatus::from_static(DeleteReplicationStatus::ENABLED),
+        }),
+        existing_object_replication: Some(ExistingObjectReplication {
+            status: ExistingObjectReplicationStatus::from_static(ExistingObjectReplicationStatus::ENABLED),
+        }),
+        filter: None,
+        prefix: None,
+        source_selection_criteria: None,
+    }
+}
+
+fn enabled_versioning_config() -> Result<Vec<u8>> {
+    Ok(serialize(&VersioningConfiguration {
+        status: Some(BucketVersioningStatus::from_static(BucketVersioningStatus::ENABLED)),
+        ..Default::default()
+    })?)
+}
+
+/// The `host:port` of a site endpoint, as bucket targets expect.
+fn endpoint_host(endpoint: &str) -> Result<String> {
+    let url = url::Url::parse(endpoint).map_err(|e| Error::other(format!("invalid endpoint {endpoint}: {e}")))?;
+    let Some(host) = url.host_str() else {
+        return Err(Error::other(format!("invalid endpoint {endpoint}")));
+    };
+    Ok(match url.port_or_known_default() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
+}
+
+fn bucket_config_data<'a>(meta: &'a BucketMetadata, config_file: &str) -> &'a [u8] {
+    match config_file {
+        BUCKET_POLICY_CONFIG => &meta.policy_config_json,
+        BUCKET_TAGGING_CONFIG => &meta.tagging_config_xml,
+        BUCKET_LIFECYCLE_CONFIG => &meta.lifecycle_config_xml,
+        BUCKET_SSECONFIG => &meta.encryption_config_xml,
+        OBJECT_LOCK_CONFIG => &meta.object_lock_config_xml,
+        BUCKET_VERSIONING_CONFIG => &meta.versioning_config_xml,
+        BUCKET_QUOTA_CONFIG_FILE => &meta.quota_config_json,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_host() {
+        assert_eq!(endpoint_host("http://127.0.0.1:9000").unwrap(), "127.0.0.1:9000");
+        assert_eq!(endpoint_host("https://site-b.example.com/").unwrap(), "site-b.example.com:443");
+        assert!(endpoint_host("site-b:9000/").is_err());
+    }
+
+    #[test]
+    fn test_site_replication_rule() {
+        let rule = site_replication_rule("dep-b", "arn:rustfs:replication:us-east-1:dep-b:bucket".to_string(), 1);
+        assert_eq!(rule.id.as_deref(), Some("site-repl-dep-b"));
+        assert_eq!(rule.destination.bucket, "arn:rustfs:replication:us-east-1:dep-b:bucket");
+
+        let cfg = ReplicationConfiguration {
+            role: String::new(),
+            rules: vec![rule],
+        };
+        let data = serialize(&cfg).unwrap();
+        let parsed: ReplicationConfiguration = crate::bucket::utils::deserialize(&data).unwrap();
+        assert_eq!(parsed.rules[0].id.as_deref(), Some("site-repl-dep-b"));
+    }
+
+    #[test]
+    fn test_state_client() {
+        let mut state = SiteReplicationState::default();
+        state.peers.insert(
+            "dep-b".to_string(),
+            PeerInfo {
+                name: "site-b".to_string(),
+                endpoint: "http://127.0.0.1:9100".to_string(),
+                deployment_id: "dep-b".to_string(),
+            },
+        );
+        assert!(state.client("dep-b").is_none());
+
+        state.service_account = PeerCredentials {
+            access_key: SITE_REPLICATOR_ACCESS_KEY.to_string(),
+            secret_key: "sk".to_string(),
+        };
+        assert_eq!(state.client("dep-b").unwrap().endpoint, "http://127.0.0.1:9100");
+        assert_eq!(state.client("dep-b").unwrap().access_key, SITE_REPLICATOR_ACCESS_KEY);
+        assert!(state.client("dep-c").is_none());
+        assert_eq!(state.site_name("dep-b"), "site-b");
+    }
+
+    #[test]
+    fn test_state_sealed() {
+        let state = SiteReplicationState {
+            name: "site-a".to_string(),
+            service_account: PeerCredentials {
+                access_key: SITE_REPLICATOR_ACCESS_KEY.to_string(),
+                secret_key: "replicator-secret".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let data = state.seal(b"root-secret").unwrap();
+        assert!(!String::from_utf8_lossy(&data).contains("replicator-secret"));
+        assert!(!format!("{state:?}").contains("replicator-secret"));
+
+        let opened = SiteReplicationState::unseal(&data, b"root-secret").unwrap();
+        assert_eq!(opened.name, "site-a");
+        assert_eq!(opened.service_account.secret_key, "replicator-secret");
+        assert!(SiteReplicationState::unseal(&data, b"other-secret").is_err());
+    }
+
+    #[test]
+    fn test_peer_body_sealed() {
+        let item = br#"{"user":{"accessKey":"alice","secretKey":"alice-secret","status":"enabled"}}"#;
+
+        let data = seal_peer_body("replicator-secret", item).unwrap();
+        assert!(!String::from_utf8_lossy(&data).contains("alice-secret"));
+        assert_eq!(open_peer_body("replicator-secret", &data).unwrap(), item);
+        assert!(open_peer_body("other-secret", &data).is_err());
+    }
+}
@@ -12,20 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{Config, GLOBAL_StorageClass, storageclass};
+use super::{Config, scanner, set_global_storage_class, storageclass};
 use crate::disk::RUSTFS_META_BUCKET;
 use crate::error::{Error, Result};
+use crate::global::get_global_action_cred;
+use crate::heal::data_scanner::update_scanner_config;
 use crate::store_api::{ObjectInfo, ObjectOptions, PutObjReader, StorageAPI};
 use http::HeaderMap;
 use lazy_static::lazy_static;
 use rustfs_config::DEFAULT_DELIMITER;
+use rustfs_config::notify::{NOTIFY_MQTT_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS};
+use rustfs_madmin::ConfigHistoryEntry;
 use rustfs_utils::path::SLASH_SEPARATOR;
 use std::collections::HashSet;
 use std::sync::Arc;
+use time::OffsetDateTime;
 use tracing::{error, warn};
+use uuid::Uuid;
 
 pub const CONFIG_PREFIX: &str = "config";
 const CONFIG_FILE: &str = "config.json";
+const CONFIG_HISTORY_PREFIX: &str = "history";
+const KV_SUFFIX: &str = ".kv";
+
+/// Number of history entries returned when no count is requested.
+pub const DEFAULT_CONFIG_HISTORY_COUNT: usize = 10;
 
 pub const STORAGE_CLASS_SUB_SYS: &str = "storage_class";
 
@@ -34,9 +45,18 @@ lazy_static! {
     static ref SubSystemsDynamic: HashSet<String> = {
         let mut h = HashSet::new();
         h.insert(STORAGE_CLASS_SUB_SYS.to_owned());
+        h.insert(scanner::SCANNER_SUB_SYS.to_owned());
+        h.insert(NOTIFY_WEBHOOK_SUB_SYS.to_owned());
+        h.insert(NOTIFY_MQTT_SUB_SYS.to_owned());
         h
     };
 }
+
+/// Reports whether a change of `sub_sys` is applied without restarting the server.
+pub fn is_dynamic_sub_sys(sub_sys: &str) -> bool {
+    SubSystemsDynamic.contains(sub_sys)
+}
+
 pub async fn read_config<S: StorageAPI>(api: Arc<S>, file: &str) -> Result<Vec<u8>> {
     let (data, _obj) = read_config_with_metadata(api, file, &ObjectOptions::default()).await?;
     Ok(data)
@@ -189,6 +209,88 @@ pub async fn save_server_config<S: StorageAPI>(api: Arc<S>, cfg: &Config) -> Res
     save_config(api, &config_file, data).await
 }
 
+fn get_config_history_file(restore_id: &str) -> String {
+    format!("{CONFIG_PREFIX}{SLASH_SEPARATOR}{CONFIG_HISTORY_PREFIX}{SLASH_SEPARATOR}{restore_id}{KV_SUFFIX}")
+}
+
+fn config_history_key() -> Vec<u8> {
+    get_global_action_cred().unwrap_or_default().secret_key.into_bytes()
+}
+
+/// Saves `kv`, the config exported before a change, as a new history entry encrypted with the root credentials.
+pub async fn save_server_config_history<S: StorageAPI>(api: Arc<S>, kv: &[u8]) -> Result<()> {
+    let data = rustfs_crypto::encrypt_data(&config_history_key(), kv).map_err(Error::other)?;
+
+    save_config(api, &get_config_history_file(&Uuid::new_v4().to_string()), data).await
+}
+
+pub async fn read_server_config_history<S: StorageAPI>(api: Arc<S>, restore_id: &str) -> Result<Vec<u8>> {
+    Uuid::parse_str(restore_id).map_err(|_| Error::other(format!("invalid restore id '{restore_id}'")))?;
+    let data = read_config(api, &get_config_history_file(restore_id)).await?;
+
+    rustfs_crypto::decrypt_data(&config_history_key(), &data).map_err(Error::other)
+}
+
+/// Lists the newest `count` history entries, newest first, optionally with their decrypted config.
+pub async fn list_server_config_history<S: StorageAPI>(
+    api: Arc<S>,
+    with_data: bool,
+    count: usize,
+) -> Result<Vec<ConfigHistoryEntry>> {
+    let prefix = format!("{CONFIG_PREFIX}{SLASH_SEPARATOR}{CONFIG_HISTORY_PREFIX}{SLASH_SEPARATOR}");
+
+    let mut objects = Vec::new();
+    let mut token = None;
+    loop {
+        let page = api
+            .clone()
+            .list_objects_v2(RUSTFS_META_BUCKET, &prefix, token, None, 1000, false, None)
+            .await?;
+        objects.extend(page.objects);
+        if !page.is_truncated || page.next_continuation_token.is_none() {
+            break;
+        }
+        token = page.next_continuation_token;
+    }
+
+    objects.sort_by(|a, b| b.mod_time.cmp(&a.mod_time));
+
+    let mut entries = Vec::new();
+    for obj in objects.into_iter().take(count) {
+        let Some(restore_id) = obj.name.strip_prefix(&prefix).and_then(|name| name.strip_suffix(KV_SUFFIX)) else {
+            continue;
+        };
+
+        let data = if with_data {
+            let data = read_server_config_history(api.clone(), restore_id).await?;
+            String::from_utf8(data).map_err(Error::other)?
+        } else {
+            String::new()
+        };
+
+        entries.push(ConfigHistoryEntry {
+            restore_id: restore_id.to_owned(),
+            create_time: obj.mod_time.unwrap_or(OffsetDateTime::UNIX_EPOCH),
+            data,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Checks that the dynamic subsystems of `cfg` can be applied to this deployment.
+pub fn validate_config<S: StorageAPI>(cfg: &Config, api: &S) -> Result<()> {
+    let kvs = cfg.get_value(STORAGE_CLASS_SUB_SYS, DEFAULT_DELIMITER).unwrap_or_default();
+    for count in api.set_drive_counts() {
+        storageclass::lookup_config(&kvs, count)?;
+    }
+
+    let kvs = cfg.get_value(scanner::SCANNER_SUB_SYS, DEFAULT_DELIMITER).unwrap_or_default();
+    scanner::lookup_config(&kvs)?;
+
+    Ok(())
+}
+
 pub async fn lookup_configs<S: StorageAPI>(cfg: &mut Config, api: Arc<S>) {
     // TODO: from etcd
     if let Err(err) = apply_dynamic_config(cfg, api).await {
@@ -198,13 +300,17 @@ pub async fn lookup_configs<S: StorageAPI>(cfg: &mut Config, api: Arc<S>) {
 
 async fn apply_dynamic_config<S: StorageAPI>(cfg: &mut Config, api: Arc<S>) -> Result<()> {
     for key in SubSystemsDynamic.iter() {
-        apply_dynamic_config_for_sub_sys(cfg, api.clone(), key).await?;
+        if let Err(err) = apply_dynamic_config_for_sub_sys(cfg, api.clone(), key).await {
+            error!("apply dynamic config of {} err {:?}", key, &err);
+        }
     }
 
     Ok(())
 }
 
-async fn apply_dynamic_config_for_sub_sys<S: StorageAPI>(cfg: &mut Config, api: Arc<S>, subsys: &str) -> Result<()> {
+/// Applies `subsys` of `cfg` to the running server. The notification targets live outside of
+/// ecstore and are reloaded by the subscribers of [`super::subscribe_config_changes`].
+pub(crate) async fn apply_dynamic_config_for_sub_sys<S: StorageAPI>(cfg: &mut Config, api: Arc<S>, subsys: &str) -> Result<()> {
     let set_drive_counts = api.set_drive_counts();
     if subsys == STORAGE_CLASS_SUB_SYS {
         let kvs = cfg.get_value(STORAGE_CLASS_SUB_SYS, DEFAULT_DELIMITER).unwrap_or_default();
@@ -212,18 +318,20 @@ async fn apply_dynamic_config_for_sub_sys<S: StorageAPI>(cfg: &mut Config, api:
         for (i, count) in set_drive_counts.iter().enumerate() {
             match storageclass::lookup_config(&kvs, *count) {
                 Ok(res) => {
-                    if i == 0 && GLOBAL_StorageClass.get().is_none() {
-                        if let Err(r) = GLOBAL_StorageClass.set(res) {
-                            error!("GLOBAL_StorageClass.set failed {:?}", r);
-                        }
+                    if i == 0 {
+                        set_global_storage_class(res);
                     }
                 }
                 Err(err) => {
                     error!("init storage class err:{:?}", &err);
-                    break;
+                    return Err(err);
                 }
             }
         }
+    } else if subsys == scanner::SCANNER_SUB_SYS {
+        let kvs = cfg.get_value(scanner::SCANNER_SUB_SYS, DEFAULT_DELIMITER).unwrap_or_default();
+        let scanner_cfg = scanner::lookup_config(&kvs)?;
+        update_scanner_config(&scanner_cfg).await;
     }
 
     Ok(())
@@ -0,0 +1,166 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{COMMENT_KEY, ENABLE_KEY, com::STORAGE_CLASS_SUB_SYS, scanner, storageclass};
+use rustfs_config::notify::{
+    MQTT_BROKER, MQTT_KEEP_ALIVE_INTERVAL, MQTT_PASSWORD, MQTT_QOS, MQTT_QUEUE_DIR, MQTT_QUEUE_LIMIT, MQTT_RECONNECT_INTERVAL,
+    MQTT_TOPIC, MQTT_USERNAME, NOTIFY_MQTT_SUB_SYS, NOTIFY_WEBHOOK_SUB_SYS, WEBHOOK_AUTH_TOKEN, WEBHOOK_CLIENT_CERT,
+    WEBHOOK_CLIENT_KEY, WEBHOOK_ENDPOINT, WEBHOOK_QUEUE_DIR, WEBHOOK_QUEUE_LIMIT,
+};
+use rustfs_madmin::{Help, HelpKV};
+
+fn kv(key: &str, type_: &str, description: &str, optional: bool) -> HelpKV {
+    HelpKV {
+        key: key.to_owned(),
+        type_: type_.to_owned(),
+        description: description.to_owned(),
+        optional,
+        multiple_targets: false,
+    }
+}
+
+fn comment() -> HelpKV {
+    kv(COMMENT_KEY, "sentence", "optionally add a comment to this setting", true)
+}
+
+/// Subsystems that can be configured through the config KV API, with their descriptions.
+const SUB_SYSTEMS: &[(&str, &str, bool)] = &[
+    (STORAGE_CLASS_SUB_SYS, "define object level redundancy", false),
+    (
+        scanner::SCANNER_SUB_SYS,
+        "manage namespace scanning for usage calculation, lifecycle, healing and more",
+        false,
+    ),
+    (NOTIFY_WEBHOOK_SUB_SYS, "publish bucket notifications to webhook endpoints", true),
+    (NOTIFY_MQTT_SUB_SYS, "publish bucket notifications to MQTT endpoints", true),
+];
+
+/// Returns the names of all subsystems that can be configured through the config KV API.
+pub fn sub_system_names() -> impl Iterator<Item = &'static str> {
+    SUB_SYSTEMS.iter().map(|(name, _, _)| *name)
+}
+
+/// Reports whether `sub_sys` is known, and whether it accepts named targets such as `notify_webhook:1`.
+pub fn lookup_sub_sys(sub_sys: &str) -> Option<bool> {
+    SUB_SYSTEMS
+        .iter()
+        .find(|(name, _, _)| *name == sub_sys)
+        .map(|(_, _, multi)| *multi)
+}
+
+/// Returns the help of `sub_sys`, or the list of all subsystems when `sub_sys` is empty.
+pub fn lookup_help(sub_sys: &str) -> Option<Help> {
+    let keys_help = match sub_sys {
+        "" => SUB_SYSTEMS
+            .iter()
+            .map(|(name, description, multi)| HelpKV {
+                multiple_targets: *multi,
+                ..kv(name, "", description, false)
+            })
+            .collect(),
+        STORAGE_CLASS_SUB_SYS => vec![
+            kv(
+                storageclass::CLASS_STANDARD,
+                "string",
+                "set the parity count for default standard storage class e.g. \"EC:4\"",
+                true,
+            ),
+            kv(
+                storageclass::CLASS_RRS,
+                "string",
+                "set the parity count for reduced redundancy storage class e.g. \"EC:2\"",
+                true,
+            ),
+            kv(
+                storageclass::OPTIMIZE,
+                "string",
+                "optimize parity calculation for standard storage class, set 'capacity' for capacity optimized",
+                true,
+            ),
+            kv(
+                storageclass::INLINE_BLOCK,
+                "string",
+                "set the shard size below which objects are inlined into their metadata e.g. \"64KiB\"",
+                true,
+            ),
+            comment(),
+        ],
+        scanner::SCANNER_SUB_SYS => vec![
+            kv(
+                scanner::SPEED,
+                "string",
+                "scanner speed, one of 'fastest', 'fast', 'default', 'slow' or 'slowest'",
+                true,
+            ),
+            comment(),
+        ],
+        NOTIFY_WEBHOOK_SUB_SYS => vec![
+            kv(ENABLE_KEY, "on|off", "enable or disable the webhook target", false),
+            kv(
+                WEBHOOK_ENDPOINT,
+                "url",
+                "webhook server endpoint e.g. http://localhost:8080/rustfs/events",
+                false,
+            ),
+            kv(WEBHOOK_AUTH_TOKEN, "string", "opaque string or JWT authorization token", true),
+            kv(
+                WEBHOOK_QUEUE_DIR,
+                "path",
+                "staging dir for undelivered messages e.g. '/home/events'",
+                true,
+            ),
+            kv(WEBHOOK_QUEUE_LIMIT, "number", "maximum limit for undelivered messages", true),
+            kv(WEBHOOK_CLIENT_CERT, "string", "client cert for webhook mTLS auth", true),
+            kv(WEBHOOK_CLIENT_KEY, "string", "client cert key for webhook mTLS auth", true),
+            comment(),
+        ],
+        NOTIFY_MQTT_SUB_SYS => vec![
+            kv(ENABLE_KEY, "on|off", "enable or disable the MQTT target", false),
+            kv(MQTT_BROKER, "uri", "MQTT server endpoint e.g. `tcp://localhost:1883`", false),
+            kv(MQTT_TOPIC, "string", "name of the MQTT topic to publish", false),
+            kv(MQTT_USERNAME, "string", "MQTT username", true),
+            kv(MQTT_PASSWORD, "string", "MQTT password", true),
+            kv(MQTT_QOS, "number", "set the quality of service priority, defaults to '0'", true),
+            kv(
+                MQTT_KEEP_ALIVE_INTERVAL,
+                "duration",
+                "keep-alive interval for MQTT connections in s,m,h,d",
+                true,
+            ),
+            kv(
+                MQTT_RECONNECT_INTERVAL,
+                "duration",
+                "reconnect interval for MQTT connections in s,m,h,d",
+                true,
+            ),
+            kv(MQTT_QUEUE_DIR, "path", "staging dir for undelivered messages e.g. '/home/events'", true),
+            kv(MQTT_QUEUE_LIMIT, "number", "maximum limit for undelivered messages", true),
+            comment(),
+        ],
+        _ => return None,
+    };
+
+    let (description, multiple_targets) = SUB_SYSTEMS
+        .iter()
+        .find(|(name, _, _)| *name == sub_sys)
+        .map(|(_, description, multi)| (description.to_string(), *multi))
+        .unwrap_or_default();
+
+    Some(Help {
+        sub_sys: sub_sys.to_owned(),
+        description,
+        multiple_targets,
+        keys_help,
+    })
+}
@@ -15,23 +15,51 @@
 pub mod com;
 #[allow(dead_code)]
 pub mod heal;
+pub mod help;
 mod notify;
+pub mod scanner;
 pub mod storageclass;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::store::ECStore;
-use com::{STORAGE_CLASS_SUB_SYS, lookup_configs, read_config_without_migrate};
+use com::{STORAGE_CLASS_SUB_SYS, apply_dynamic_config_for_sub_sys, lookup_configs, read_config_without_migrate};
 use lazy_static::lazy_static;
 use rustfs_config::DEFAULT_DELIMITER;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, OnceLock};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::sync::broadcast;
 
 lazy_static! {
-    pub static ref GLOBAL_StorageClass: OnceLock<storageclass::Config> = OnceLock::new();
+    pub static ref GLOBAL_StorageClass: RwLock<Option<Arc<storageclass::Config>>> = RwLock::new(None);
     pub static ref DefaultKVS: OnceLock<HashMap<String, KVS>> = OnceLock::new();
-    pub static ref GLOBAL_ServerConfig: OnceLock<Config> = OnceLock::new();
+    pub static ref GLOBAL_ServerConfig: RwLock<Option<Config>> = RwLock::new(None);
     pub static ref GLOBAL_ConfigSys: ConfigSys = ConfigSys::new();
+    static ref GLOBAL_ConfigChanged: broadcast::Sender<String> = broadcast::channel(16).0;
+}
+
+pub fn get_global_storage_class() -> Option<Arc<storageclass::Config>> {
+    GLOBAL_StorageClass.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_global_storage_class(sc: storageclass::Config) {
+    *GLOBAL_StorageClass.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(sc));
+}
+
+pub fn get_global_server_config() -> Option<Config> {
+    GLOBAL_ServerConfig.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn set_global_server_config(cfg: Config) {
+    *GLOBAL_ServerConfig.write().unwrap_or_else(|e| e.into_inner()) = Some(cfg);
+}
+
+/// Subscribes to the names of the subsystems whose configuration was changed at runtime.
+///
+/// Subsystems that live outside of ecstore, such as the notification targets, apply their new
+/// configuration from [`get_global_server_config`] when they receive their name.
+pub fn subscribe_config_changes() -> broadcast::Receiver<String> {
+    GLOBAL_ConfigChanged.subscribe()
 }
 
 /// Standard config keys and values.
@@ -49,6 +77,10 @@ pub const ENV_ROOT_PASSWORD: &str = "RUSTFS_ROOT_PASSWORD";
 
 pub static RUSTFS_CONFIG_PREFIX: &str = "config";
 
+/// Separates a subsystem from its target name, e.g. `notify_webhook:1`.
+pub const SUB_SYSTEM_SEPARATOR: &str = ":";
+const KV_SEPARATOR: &str = "=";
+
 pub struct ConfigSys {}
 
 impl Default for ConfigSys {
@@ -66,10 +98,27 @@ impl ConfigSys {
 
         lookup_configs(&mut cfg, api).await;
 
-        let _ = GLOBAL_ServerConfig.set(cfg);
+        set_global_server_config(cfg);
 
         Ok(())
     }
+
+    /// Reloads the server config from the backend and applies the dynamic subsystem `sub_sys`,
+    /// this is what a peer does when another node changed its configuration.
+    pub async fn reload_dynamic(&self, api: Arc<ECStore>, sub_sys: &str) -> Result<()> {
+        let cfg = read_config_without_migrate(api.clone()).await?;
+        self.apply_dynamic(api, cfg, sub_sys).await
+    }
+
+    /// Makes `cfg` the running server config and applies `sub_sys` without a restart.
+    pub async fn apply_dynamic(&self, api: Arc<ECStore>, mut cfg: Config, sub_sys: &str) -> Result<()> {
+        let res = apply_dynamic_config_for_sub_sys(&mut cfg, api, sub_sys).await;
+        set_global_server_config(cfg);
+        res?;
+
+        let _ = GLOBAL_ConfigChanged.send(sub_sys.to_owned());
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -131,6 +180,22 @@ impl KVS {
 
         keys
     }
+
+    /// Sets `key` to `value`, appending the key when it is not present yet.
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.0.iter_mut().find(|kv| kv.key == key) {
+            Some(kv) => kv.value = value.to_owned(),
+            None => self.0.push(KV {
+                key: key.to_owned(),
+                value: value.to_owned(),
+                hidden_if_empty: false,
+            }),
+        }
+    }
+
+    pub fn delete(&mut self, key: &str) {
+        self.0.retain(|kv| kv.key != key);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -190,6 +255,169 @@ impl Config {
         // TODO: merge default
         self.clone()
     }
+
+    /// Applies a `sub_sys[:target] key=value ...` line and returns the changed subsystem.
+    pub fn set_kvs(&mut self, line: &str) -> Result<String> {
+        let fields = split_fields(line)?;
+        let Some((sub_sys_target, kvs)) = fields.split_first() else {
+            return Err(Error::other("no sub-system specified"));
+        };
+        let (sub_sys, target) = parse_sub_sys_target(sub_sys_target)?;
+        if kvs.is_empty() {
+            return Err(Error::other(format!("no keys specified for sub-system {sub_sys}")));
+        }
+
+        let defaults = default_kvs(&sub_sys)?;
+        let mut current = self.get_value(&sub_sys, &target).unwrap_or_else(|| defaults.clone());
+        for field in kvs {
+            let Some((key, value)) = field.split_once(KV_SEPARATOR) else {
+                return Err(Error::other(format!("'{field}' is not in key=value format")));
+            };
+            if key != COMMENT_KEY && defaults.lookup(key).is_none() {
+                return Err(Error::other(format!("unknown key '{key}' for sub-system {sub_sys}")));
+            }
+            current.set(key, value);
+        }
+
+        self.0.entry(sub_sys.clone()).or_default().insert(target, current);
+        Ok(sub_sys)
+    }
+
+    /// Removes the target named by `sub_sys[:target]`, the default target is reset to its defaults.
+    pub fn del_kvs(&mut self, line: &str) -> Result<String> {
+        let fields = split_fields(line)?;
+        let Some(sub_sys_target) = fields.first() else {
+            return Err(Error::other("no sub-system specified"));
+        };
+        let (sub_sys, target) = parse_sub_sys_target(sub_sys_target)?;
+
+        let defaults = default_kvs(&sub_sys)?;
+        let targets = self.0.entry(sub_sys.clone()).or_default();
+        if target == DEFAULT_DELIMITER {
+            targets.insert(target, defaults.clone());
+        } else if targets.remove(&target).is_none() {
+            return Err(Error::other(format!("target {sub_sys_target} not found")));
+        }
+
+        Ok(sub_sys)
+    }
+
+    /// Returns the config lines of `sub_sys[:target]`, all targets are returned without a target name.
+    pub fn get_kvs(&self, key: &str) -> Result<Vec<String>> {
+        let (sub_sys, target) = parse_sub_sys_target(key)?;
+        let Some(targets) = self.0.get(&sub_sys) else {
+            return Ok(Vec::new());
+        };
+
+        let mut names: Vec<&String> = if key.contains(SUB_SYSTEM_SEPARATOR) {
+            targets.keys().filter(|name| **name == target).collect()
+        } else {
+            targets.keys().collect()
+        };
+        names.sort();
+
+        Ok(names
+            .into_iter()
+            .map(|name| kvs_line(&sub_sys, name, &targets[name]))
+            .collect())
+    }
+
+    /// Exports every configurable subsystem, one line per target, in the format read by [`Config::read_from`].
+    pub fn export(&self) -> String {
+        let mut sub_systems: Vec<&String> = self.0.keys().filter(|s| help::lookup_sub_sys(s).is_some()).collect();
+        sub_systems.sort();
+
+        let mut lines = Vec::new();
+        for sub_sys in sub_systems {
+            if let Ok(sub_sys_lines) = self.get_kvs(sub_sys) {
+                lines.extend(sub_sys_lines);
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Builds a config from exported lines, lines starting with `#` are comments.
+    pub fn read_from(data: &str) -> Result<Config> {
+        let mut cfg = Config::new();
+        for line in data.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            cfg.set_kvs(line)?;
+        }
+
+        Ok(cfg)
+    }
+}
+
+fn default_kvs(sub_sys: &str) -> Result<&'static KVS> {
+    DefaultKVS
+        .get()
+        .and_then(|defaults| defaults.get(sub_sys))
+        .ok_or_else(|| Error::other(format!("sub-system {sub_sys} has no default config")))
+}
+
+/// Splits `sub_sys[:target]` and checks that the subsystem accepts the target.
+fn parse_sub_sys_target(s: &str) -> Result<(String, String)> {
+    let (sub_sys, target) = s.split_once(SUB_SYSTEM_SEPARATOR).unwrap_or((s, ""));
+    let Some(multiple_targets) = help::lookup_sub_sys(sub_sys) else {
+        return Err(Error::other(format!("unknown sub-system '{sub_sys}'")));
+    };
+
+    let target = if target.is_empty() { DEFAULT_DELIMITER } else { target };
+    if target != DEFAULT_DELIMITER && !multiple_targets {
+        return Err(Error::other(format!("sub-system {sub_sys} does not support targets")));
+    }
+
+    Ok((sub_sys.to_owned(), target.to_owned()))
+}
+
+/// Splits a config line on whitespace, double quotes keep a value with spaces together.
+fn split_fields(line: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(Error::other(format!("unterminated quote in '{line}'")));
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+
+    Ok(fields)
+}
+
+fn kvs_line(sub_sys: &str, target: &str, kvs: &KVS) -> String {
+    let mut line = if target == DEFAULT_DELIMITER {
+        sub_sys.to_owned()
+    } else {
+        format!("{sub_sys}{SUB_SYSTEM_SEPARATOR}{target}")
+    };
+
+    for kv in kvs.0.iter() {
+        if kv.hidden_if_empty && kv.value.is_empty() {
+            continue;
+        }
+        if kv.value.contains(char::is_whitespace) {
+            line.push_str(&format!(" {}{KV_SEPARATOR}\"{}\"", kv.key, kv.value));
+        } else {
+            line.push_str(&format!(" {}{KV_SEPARATOR}{}", kv.key, kv.value));
+        }
+    }
+
+    line
 }
 
 pub fn register_default_kvs(kvs: HashMap<String, KVS>) {
@@ -212,7 +440,39 @@ pub fn init() {
         notify::DefaultWebhookKVS.clone(),
     );
     kvs.insert(rustfs_config::notify::NOTIFY_MQTT_SUB_SYS.to_owned(), notify::DefaultMqttKVS.clone());
+    kvs.insert(scanner::SCANNER_SUB_SYS.to_owned(), scanner::DefaultKVS.clone());
 
     // Register all default configurations
     register_default_kvs(kvs)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_kvs_round_trip() {
+        init();
+
+        let mut cfg = Config::new();
+        assert_eq!(cfg.set_kvs("scanner speed=slow").unwrap(), scanner::SCANNER_SUB_SYS);
+        cfg.set_kvs("notify_webhook:1 enable=on endpoint=http://localhost:8080 comment=\"primary hook\"")
+            .unwrap();
+        assert!(cfg.set_kvs("scanner unknown=1").is_err());
+        assert!(cfg.set_kvs("scanner:1 speed=fast").is_err());
+        assert!(cfg.set_kvs("scanner comment=\"unterminated").is_err());
+
+        assert_eq!(cfg.get_kvs("scanner").unwrap(), vec!["scanner speed=slow".to_owned()]);
+        let hook = cfg.get_kvs("notify_webhook:1").unwrap();
+        assert_eq!(hook.len(), 1);
+        assert!(hook[0].contains("comment=\"primary hook\""));
+        assert_eq!(cfg.get_kvs("notify_webhook").unwrap().len(), 2);
+
+        let restored = Config::read_from(&cfg.export()).unwrap();
+        assert_eq!(restored.export(), cfg.export());
+
+        cfg.del_kvs("notify_webhook:1").unwrap();
+        assert!(cfg.get_kvs("notify_webhook:1").unwrap().is_empty());
+        assert!(cfg.del_kvs("notify_webhook:1").is_err());
+    }
+}
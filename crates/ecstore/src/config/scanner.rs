@@ -0,0 +1,71 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::KVS;
+use crate::config::KV;
+use crate::error::{Error, Result};
+use lazy_static::lazy_static;
+use std::env;
+use std::time::Duration;
+
+pub const SCANNER_SUB_SYS: &str = "scanner";
+
+pub const SPEED: &str = "speed";
+
+// Scanner speed environment variable
+pub const SPEED_ENV: &str = "RUSTFS_SCANNER_SPEED";
+
+pub const SPEED_FASTEST: &str = "fastest";
+pub const SPEED_FAST: &str = "fast";
+pub const SPEED_DEFAULT: &str = "default";
+pub const SPEED_SLOW: &str = "slow";
+pub const SPEED_SLOWEST: &str = "slowest";
+
+lazy_static! {
+    pub static ref DefaultKVS: KVS = KVS(vec![KV {
+        key: SPEED.to_owned(),
+        value: SPEED_DEFAULT.to_owned(),
+        hidden_if_empty: false,
+    }]);
+}
+
+// Config scanner throttling configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Sleep factor applied to the time spent on each scanned folder.
+    pub delay: f64,
+    /// Upper bound of a single sleep, zero disables the bound.
+    pub max_wait: Duration,
+    /// Time to wait between scanner cycles.
+    pub cycle: Duration,
+}
+
+pub fn lookup_config(kvs: &KVS) -> Result<Config> {
+    let speed = env::var(SPEED_ENV).unwrap_or_else(|_| kvs.get(SPEED));
+
+    let (delay, max_wait, cycle) = match speed.as_str() {
+        SPEED_FASTEST => (0.0, Duration::ZERO, Duration::from_secs(1)),
+        SPEED_FAST => (1.0, Duration::from_millis(100), Duration::from_secs(60)),
+        SPEED_DEFAULT | "" => (2.0, Duration::from_secs(1), Duration::from_secs(60)),
+        SPEED_SLOW => (10.0, Duration::from_secs(15), Duration::from_secs(60)),
+        SPEED_SLOWEST => (100.0, Duration::from_secs(15), Duration::from_secs(30 * 60)),
+        _ => {
+            return Err(Error::other(format!(
+                "unknown scanner speed '{speed}', expected one of {SPEED_FASTEST}, {SPEED_FAST}, {SPEED_DEFAULT}, {SPEED_SLOW}, {SPEED_SLOWEST}"
+            )));
+        }
+    };
+
+    Ok(Config { delay, max_wait, cycle })
+}
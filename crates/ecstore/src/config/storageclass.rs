@@ -187,11 +187,12 @@ pub fn lookup_config(kvs: &KVS, set_drive_count: usize) -> Result<Config> {
             if let Ok(ssc_str) = env::var(RRS_ENV) {
                 ssc_str
             } else {
-                kvs.get(RRS)
+                kvs.get(CLASS_RRS)
             }
         };
 
-        if !ssc_str.is_empty() {
+        // A single drive has no room for parity, whatever the default rrs class says
+        if !ssc_str.is_empty() && set_drive_count != 1 {
             parse_storage_class(&ssc_str)?
         } else {
             StorageClass {
@@ -202,10 +203,13 @@ pub fn lookup_config(kvs: &KVS, set_drive_count: usize) -> Result<Config> {
 
     validate_parity_inner(standard.parity, rrs.parity, set_drive_count)?;
 
-    let optimize = { env::var(OPTIMIZE_ENV).ok() };
+    let optimize = env::var(OPTIMIZE_ENV)
+        .ok()
+        .or_else(|| Some(kvs.get(OPTIMIZE)).filter(|v| !v.is_empty()));
 
     let inline_block = {
-        if let Ok(ev) = env::var(INLINE_BLOCK_ENV) {
+        let ev = env::var(INLINE_BLOCK_ENV).unwrap_or_else(|_| kvs.get(INLINE_BLOCK));
+        if !ev.is_empty() {
             if let Ok(block) = ev.parse::<bytesize::ByteSize>() {
                 if block.as_u64() as usize > DEFAULT_INLINE_BLOCK {
                     warn!(
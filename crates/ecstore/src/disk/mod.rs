@@ -36,6 +36,7 @@ use crate::heal::{
     heal_commands::{HealScanMode, HealingTracker},
 };
 use crate::rpc::RemoteDisk;
+use crate::trace;
 use bytes::Bytes;
 use endpoint::Endpoint;
 use error::DiskError;
@@ -43,8 +44,9 @@ use error::{Error, Result};
 use local::LocalDisk;
 use rustfs_filemeta::{FileInfo, ObjectPartInfo, RawFileInfo};
 use rustfs_madmin::info_commands::DiskMetrics;
+use rustfs_madmin::trace::{TraceInfo, TraceType};
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, path::PathBuf, sync::Arc};
+use std::{fmt::Debug, future::Future, path::PathBuf, sync::Arc, time::Instant};
 use time::OffsetDateTime;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
@@ -63,6 +65,34 @@ pub enum Disk {
     Remote(Box<RemoteDisk>),
 }
 
+impl Disk {
+    /// Runs a call on a local disk, publishing a storage trace event while a trace is running.
+    async fn trace_local<T>(
+        &self,
+        func_name: &str,
+        volume: &str,
+        path: &str,
+        call: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        if !trace::subscribed(TraceType::STORAGE) {
+            return call.await;
+        }
+
+        let start = Instant::now();
+        let res = call.await;
+
+        let mut info = TraceInfo::new(TraceType::STORAGE, "", format!("storage.{func_name}"));
+        info.path = format!("{}/{}/{}", DiskAPI::to_string(self), volume, path)
+            .trim_end_matches('/')
+            .to_owned();
+        info.duration = start.elapsed();
+        info.error = res.as_ref().err().map(|err| err.to_string());
+        trace::publish(info).await;
+
+        res
+    }
+}
+
 #[async_trait::async_trait]
 impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
@@ -172,7 +202,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn stat_volume(&self, volume: &str) -> Result<VolumeInfo> {
         match self {
-            Disk::Local(local_disk) => local_disk.stat_volume(volume).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("StatVolume", volume, "", local_disk.stat_volume(volume))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.stat_volume(volume).await,
         }
     }
@@ -180,7 +213,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn delete_volume(&self, volume: &str) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.delete_volume(volume).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("DeleteVolume", volume, "", local_disk.delete_volume(volume))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.delete_volume(volume).await,
         }
     }
@@ -203,7 +239,15 @@ impl DiskAPI for Disk {
         opts: DeleteOptions,
     ) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.delete_version(volume, path, fi, force_del_marker, opts).await,
+            Disk::Local(local_disk) => {
+                self.trace_local(
+                    "DeleteVersion",
+                    volume,
+                    path,
+                    local_disk.delete_version(volume, path, fi, force_del_marker, opts),
+                )
+                .await
+            }
             Disk::Remote(remote_disk) => remote_disk.delete_version(volume, path, fi, force_del_marker, opts).await,
         }
     }
@@ -216,7 +260,10 @@ impl DiskAPI for Disk {
         opts: DeleteOptions,
     ) -> Result<Vec<Option<Error>>> {
         match self {
-            Disk::Local(local_disk) => local_disk.delete_versions(volume, versions, opts).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("DeleteVersions", volume, "", local_disk.delete_versions(volume, versions, opts))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.delete_versions(volume, versions, opts).await,
         }
     }
@@ -224,7 +271,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn delete_paths(&self, volume: &str, paths: &[String]) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.delete_paths(volume, paths).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("DeletePaths", volume, "", local_disk.delete_paths(volume, paths))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.delete_paths(volume, paths).await,
         }
     }
@@ -232,7 +282,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn write_metadata(&self, _org_volume: &str, volume: &str, path: &str, fi: FileInfo) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.write_metadata(_org_volume, volume, path, fi).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("WriteMetadata", volume, path, local_disk.write_metadata(_org_volume, volume, path, fi))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.write_metadata(_org_volume, volume, path, fi).await,
         }
     }
@@ -240,7 +293,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn update_metadata(&self, volume: &str, path: &str, fi: FileInfo, opts: &UpdateMetadataOpts) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.update_metadata(volume, path, fi, opts).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("UpdateMetadata", volume, path, local_disk.update_metadata(volume, path, fi, opts))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.update_metadata(volume, path, fi, opts).await,
         }
     }
@@ -255,7 +311,15 @@ impl DiskAPI for Disk {
         opts: &ReadOptions,
     ) -> Result<FileInfo> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_version(_org_volume, volume, path, version_id, opts).await,
+            Disk::Local(local_disk) => {
+                self.trace_local(
+                    "ReadVersion",
+                    volume,
+                    path,
+                    local_disk.read_version(_org_volume, volume, path, version_id, opts),
+                )
+                .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_version(_org_volume, volume, path, version_id, opts).await,
         }
     }
@@ -263,7 +327,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn read_xl(&self, volume: &str, path: &str, read_data: bool) -> Result<RawFileInfo> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_xl(volume, path, read_data).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("ReadXL", volume, path, local_disk.read_xl(volume, path, read_data))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_xl(volume, path, read_data).await,
         }
     }
@@ -278,7 +345,15 @@ impl DiskAPI for Disk {
         dst_path: &str,
     ) -> Result<RenameDataResp> {
         match self {
-            Disk::Local(local_disk) => local_disk.rename_data(src_volume, src_path, fi, dst_volume, dst_path).await,
+            Disk::Local(local_disk) => {
+                self.trace_local(
+                    "RenameData",
+                    dst_volume,
+                    dst_path,
+                    local_disk.rename_data(src_volume, src_path, fi, dst_volume, dst_path),
+                )
+                .await
+            }
             Disk::Remote(remote_disk) => remote_disk.rename_data(src_volume, src_path, fi, dst_volume, dst_path).await,
         }
     }
@@ -294,7 +369,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn read_file(&self, volume: &str, path: &str) -> Result<FileReader> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_file(volume, path).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("ReadFile", volume, path, local_disk.read_file(volume, path))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_file(volume, path).await,
         }
     }
@@ -302,7 +380,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn read_file_stream(&self, volume: &str, path: &str, offset: usize, length: usize) -> Result<FileReader> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_file_stream(volume, path, offset, length).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("ReadFileStream", volume, path, local_disk.read_file_stream(volume, path, offset, length))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_file_stream(volume, path, offset, length).await,
         }
     }
@@ -310,7 +391,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn append_file(&self, volume: &str, path: &str) -> Result<FileWriter> {
         match self {
-            Disk::Local(local_disk) => local_disk.append_file(volume, path).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("AppendFile", volume, path, local_disk.append_file(volume, path))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.append_file(volume, path).await,
         }
     }
@@ -318,7 +402,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn create_file(&self, _origvolume: &str, volume: &str, path: &str, _file_size: i64) -> Result<FileWriter> {
         match self {
-            Disk::Local(local_disk) => local_disk.create_file(_origvolume, volume, path, _file_size).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("CreateFile", volume, path, local_disk.create_file(_origvolume, volume, path, _file_size))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.create_file(_origvolume, volume, path, _file_size).await,
         }
     }
@@ -326,7 +413,15 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn rename_file(&self, src_volume: &str, src_path: &str, dst_volume: &str, dst_path: &str) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.rename_file(src_volume, src_path, dst_volume, dst_path).await,
+            Disk::Local(local_disk) => {
+                self.trace_local(
+                    "RenameFile",
+                    dst_volume,
+                    dst_path,
+                    local_disk.rename_file(src_volume, src_path, dst_volume, dst_path),
+                )
+                .await
+            }
             Disk::Remote(remote_disk) => remote_disk.rename_file(src_volume, src_path, dst_volume, dst_path).await,
         }
     }
@@ -334,7 +429,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn read_parts(&self, bucket: &str, paths: &[String]) -> Result<Vec<ObjectPartInfo>> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_parts(bucket, paths).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("ReadParts", bucket, "", local_disk.read_parts(bucket, paths))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_parts(bucket, paths).await,
         }
     }
@@ -342,7 +440,15 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn rename_part(&self, src_volume: &str, src_path: &str, dst_volume: &str, dst_path: &str, meta: Bytes) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.rename_part(src_volume, src_path, dst_volume, dst_path, meta).await,
+            Disk::Local(local_disk) => {
+                self.trace_local(
+                    "RenamePart",
+                    dst_volume,
+                    dst_path,
+                    local_disk.rename_part(src_volume, src_path, dst_volume, dst_path, meta),
+                )
+                .await
+            }
             Disk::Remote(remote_disk) => {
                 remote_disk
                     .rename_part(src_volume, src_path, dst_volume, dst_path, meta)
@@ -354,7 +460,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn delete(&self, volume: &str, path: &str, opt: DeleteOptions) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.delete(volume, path, opt).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("Delete", volume, path, local_disk.delete(volume, path, opt))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.delete(volume, path, opt).await,
         }
     }
@@ -362,7 +471,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn verify_file(&self, volume: &str, path: &str, fi: &FileInfo) -> Result<CheckPartsResp> {
         match self {
-            Disk::Local(local_disk) => local_disk.verify_file(volume, path, fi).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("VerifyFile", volume, path, local_disk.verify_file(volume, path, fi))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.verify_file(volume, path, fi).await,
         }
     }
@@ -370,7 +482,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn check_parts(&self, volume: &str, path: &str, fi: &FileInfo) -> Result<CheckPartsResp> {
         match self {
-            Disk::Local(local_disk) => local_disk.check_parts(volume, path, fi).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("CheckParts", volume, path, local_disk.check_parts(volume, path, fi))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.check_parts(volume, path, fi).await,
         }
     }
@@ -386,7 +501,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn write_all(&self, volume: &str, path: &str, data: Bytes) -> Result<()> {
         match self {
-            Disk::Local(local_disk) => local_disk.write_all(volume, path, data).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("WriteAll", volume, path, local_disk.write_all(volume, path, data))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.write_all(volume, path, data).await,
         }
     }
@@ -394,7 +512,10 @@ impl DiskAPI for Disk {
     #[tracing::instrument(skip(self))]
     async fn read_all(&self, volume: &str, path: &str) -> Result<Bytes> {
         match self {
-            Disk::Local(local_disk) => local_disk.read_all(volume, path).await,
+            Disk::Local(local_disk) => {
+                self.trace_local("ReadAll", volume, path, local_disk.read_all(volume, path))
+                    .await
+            }
             Disk::Remote(remote_disk) => remote_disk.read_all(volume, path).await,
         }
     }
@@ -0,0 +1,722 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server-side encryption of object data (SSE-S3, SSE-KMS and SSE-C).
+//!
+//! Every encrypted object gets a random 256-bit object key which encrypts the data through
+//! [`rustfs_rio::EncryptReader`]. The object key is sealed and stored in the reserved object
+//! metadata: for SSE-S3 and SSE-KMS it is a data key generated by the [`crate::kms`] under the
+//! default or the requested master key, for SSE-C it is sealed with the key the client sends with
+//! every request. The sealed key is bound to the bucket and object name.
+
+use crate::bucket::metadata_sys;
+use crate::error::{Error, Result};
+use crate::kms::{self, Kms};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use base64::Engine as _;
+use base64::engine::general_purpose;
+use http::HeaderMap;
+use md5::{Digest as _, Md5};
+use rand::RngCore;
+use rustfs_filemeta::ObjectPartInfo;
+use rustfs_filemeta::headers::RESERVED_METADATA_PREFIX_LOWER;
+use rustfs_rio::{ENCRYPT_PACKAGE_OVERHEAD, ENCRYPT_PACKAGE_SIZE, EncryptReader, HashReader, Reader};
+use s3s::dto::ServerSideEncryption;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const AMZ_SERVER_SIDE_ENCRYPTION: &str = "x-amz-server-side-encryption";
+pub const AMZ_SERVER_SIDE_ENCRYPTION_KMS_ID: &str = "x-amz-server-side-encryption-aws-kms-key-id";
+pub const AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM: &str = "x-amz-server-side-encryption-customer-algorithm";
+pub const AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY: &str = "x-amz-server-side-encryption-customer-key";
+pub const AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5: &str = "x-amz-server-side-encryption-customer-key-md5";
+pub const AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM: &str =
+    "x-amz-copy-source-server-side-encryption-customer-algorithm";
+pub const AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY: &str = "x-amz-copy-source-server-side-encryption-customer-key";
+pub const AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5: &str =
+    "x-amz-copy-source-server-side-encryption-customer-key-md5";
+
+/// Reserved metadata holding the encryption scheme of the object, see [`SseType`].
+const META_SSE: &str = "server-side-encryption";
+/// Reserved metadata holding the sealed object key.
+const META_SEALED_KEY: &str = "server-side-encryption-sealed-key";
+/// Reserved metadata holding the id of the KMS key that sealed an SSE-S3 or SSE-KMS object key.
+const META_KEY_ID: &str = "server-side-encryption-key-id";
+
+fn meta_key(name: &str) -> String {
+    format!("{RESERVED_METADATA_PREFIX_LOWER}{name}")
+}
+
+/// Encryption scheme of an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SseType {
+    /// Object key generated by the KMS under its default key.
+    S3,
+    /// Object key generated by the KMS under a key chosen by the client or the bucket.
+    Kms,
+    /// Object key sealed with a customer-provided key.
+    C,
+}
+
+impl SseType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SseType::S3 => "SSE-S3",
+            SseType::Kms => "SSE-KMS",
+            SseType::C => "SSE-C",
+        }
+    }
+
+    /// Returns the encryption scheme recorded in the object metadata, if any.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        match metadata.get(&meta_key(META_SSE)).map(String::as_str) {
+            Some("SSE-S3") => Some(SseType::S3),
+            Some("SSE-KMS") => Some(SseType::Kms),
+            Some("SSE-C") => Some(SseType::C),
+            _ => None,
+        }
+    }
+}
+
+pub fn is_encrypted(metadata: &HashMap<String, String>) -> bool {
+    metadata.contains_key(&meta_key(META_SEALED_KEY))
+}
+
+/// Returns the id of the KMS key that sealed the object key.
+pub fn kms_key_id(metadata: &HashMap<String, String>) -> Option<&str> {
+    metadata.get(&meta_key(META_KEY_ID)).map(String::as_str)
+}
+
+/// Removes all encryption state from object metadata, e.g. before the data is re-encrypted by a copy.
+pub fn remove_encryption_metadata(metadata: &mut HashMap<String, String>) {
+    for name in [META_SSE, META_SEALED_KEY, META_KEY_ID] {
+        metadata.remove(&meta_key(name));
+    }
+}
+
+/// A per-object data encryption key.
+#[derive(Clone)]
+pub struct ObjectKey([u8; 32]);
+
+impl std::fmt::Debug for ObjectKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ObjectKey(..)")
+    }
+}
+
+impl ObjectKey {
+    pub fn generate() -> Self {
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+        Self(key)
+    }
+
+    pub fn as_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Seals the object key with `kek`, binding it to `bucket/object`.
+    pub fn seal(&self, kek: &[u8; 32], sse: SseType, bucket: &str, object: &str) -> Result<String> {
+        let cipher = Aes256Gcm::new_from_slice(kek).map_err(Error::other)?;
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+        let aad = seal_context(sse, bucket, object);
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &self.0,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|e| Error::other(format!("seal object key: {e}")))?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(general_purpose::STANDARD.encode(out))
+    }
+
+    /// Unseals an object key sealed by [`ObjectKey::seal`]. Fails if `kek` is not the sealing key.
+    pub fn unseal(sealed: &str, kek: &[u8; 32], sse: SseType, bucket: &str, object: &str) -> Option<Self> {
+        let sealed = general_purpose::STANDARD.decode(sealed).ok()?;
+        if sealed.len() <= 12 {
+            return None;
+        }
+        let cipher = Aes256Gcm::new_from_slice(kek).ok()?;
+        let aad = seal_context(sse, bucket, object);
+        let key = cipher
+            .decrypt(
+                Nonce::from_slice(&sealed[..12]),
+                Payload {
+                    msg: &sealed[12..],
+                    aad: aad.as_bytes(),
+                },
+            )
+            .ok()?;
+        Some(Self(key.try_into().ok()?))
+    }
+}
+
+fn seal_context(sse: SseType, bucket: &str, object: &str) -> String {
+    format!("{}:{}/{}", sse.as_str(), bucket, object)
+}
+
+/// A customer-provided key taken from SSE-C request headers.
+pub struct CustomerKey {
+    key: [u8; 32],
+    key_md5: String,
+}
+
+impl CustomerKey {
+    /// Base64 MD5 of the key, echoed back in responses.
+    pub fn key_md5(&self) -> &str {
+        &self.key_md5
+    }
+}
+
+/// Parses the SSE-C headers of a request, or the `x-amz-copy-source-` variants for the source of a copy.
+pub fn parse_customer_key(h: &HeaderMap, copy_source: bool) -> Result<Option<CustomerKey>> {
+    let (algo_header, key_header, md5_header) = if copy_source {
+        (
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM,
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+        )
+    } else {
+        (
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM,
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+        )
+    };
+
+    let header = |name: &str| h.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+    let (algo, key, key_md5) = (header(algo_header), header(key_header), header(md5_header));
+    if algo.is_none() && key.is_none() && key_md5.is_none() {
+        return Ok(None);
+    }
+
+    let invalid = |msg: &str| Error::InvalidEncryptionParameters(msg.to_owned());
+
+    if algo != Some(ServerSideEncryption::AES256) {
+        return Err(invalid("The encryption algorithm must be AES256"));
+    }
+    let key = general_purpose::STANDARD
+        .decode(key.unwrap_or_default())
+        .map_err(|_| invalid("The secret key was invalid for the specified algorithm"))?;
+    let key: [u8; 32] = key
+        .try_into()
+        .map_err(|_| invalid("The secret key was invalid for the specified algorithm"))?;
+
+    let computed_md5 = general_purpose::STANDARD.encode(Md5::digest(key));
+    if key_md5 != Some(computed_md5.as_str()) {
+        return Err(invalid("The calculated MD5 hash of the key did not match the hash that was provided"));
+    }
+
+    Ok(Some(CustomerKey {
+        key,
+        key_md5: computed_md5,
+    }))
+}
+
+/// Returns a copy of `h` with the copy-source SSE-C headers renamed to the regular SSE-C headers,
+/// so the source of a copy can be read with [`crate::store_api::StorageAPI::get_object_reader`].
+pub fn copy_source_headers(h: &HeaderMap) -> HeaderMap {
+    let mut out = HeaderMap::new();
+    for (from, to) in [
+        (
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM,
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM,
+        ),
+        (
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+        ),
+        (
+            AMZ_COPY_SOURCE_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+        ),
+    ] {
+        if let Some(v) = h.get(from) {
+            out.insert(to, v.clone());
+        }
+    }
+    out
+}
+
+/// Encryption applied to a newly written object.
+#[derive(Debug)]
+pub struct ObjectEncryption {
+    pub sse: SseType,
+    pub key: ObjectKey,
+    /// Base64 MD5 of the customer key for SSE-C.
+    pub customer_key_md5: Option<String>,
+    /// KMS key that sealed the object key for SSE-S3 and SSE-KMS.
+    pub kms_key_id: Option<String>,
+}
+
+fn global_kms() -> Result<Arc<dyn Kms>> {
+    kms::get_global_kms().ok_or(Error::KmsNotConfigured)
+}
+
+/// Decides how a new object is encrypted from the request headers and the bucket default
+/// encryption, generating its object key. Returns the encryption together with the reserved
+/// metadata to store, or `None` for unencrypted objects.
+pub async fn new_object_encryption(
+    bucket: &str,
+    object: &str,
+    h: &HeaderMap,
+) -> Result<Option<(ObjectEncryption, HashMap<String, String>)>> {
+    let customer_key = parse_customer_key(h, false)?;
+    let header = |name: &str| h.get(name).map(|v| v.to_str().unwrap_or_default().trim().to_owned());
+    let requested = header(AMZ_SERVER_SIDE_ENCRYPTION);
+    let requested_key_id = header(AMZ_SERVER_SIDE_ENCRYPTION_KMS_ID).filter(|id| !id.is_empty());
+
+    if requested_key_id.is_some() && requested.as_deref() != Some(ServerSideEncryption::AWS_KMS) {
+        return Err(Error::InvalidEncryptionParameters(
+            "A KMS key id can only be specified with aws:kms server side encryption".to_owned(),
+        ));
+    }
+
+    let (sse, key_id) = match (&customer_key, requested.as_deref()) {
+        (Some(_), Some(_)) => {
+            return Err(Error::InvalidEncryptionParameters(
+                "Server side encryption with customer keys cannot be combined with other server side encryption".to_owned(),
+            ));
+        }
+        (Some(_), None) => (SseType::C, None),
+        (None, Some(ServerSideEncryption::AES256)) => (SseType::S3, None),
+        (None, Some(ServerSideEncryption::AWS_KMS)) => (SseType::Kms, requested_key_id),
+        (None, Some(algo)) => {
+            return Err(Error::InvalidEncryptionParameters(format!(
+                "The encryption method specified is not supported: {algo}"
+            )));
+        }
+        (None, None) => match bucket_default_encryption(bucket).await {
+            Some((algo, _)) if algo == ServerSideEncryption::AES256 => (SseType::S3, None),
+            Some((_, key_id)) => (SseType::Kms, key_id),
+            None => return Ok(None),
+        },
+    };
+
+    let mut metadata = HashMap::new();
+
+    let (key, sealed, kms_key_id) = match sse {
+        SseType::S3 | SseType::Kms => {
+            let (key, sealed, key_id) = generate_kms_object_key(global_kms()?.as_ref(), key_id, sse, bucket, object).await?;
+            metadata.insert(meta_key(META_KEY_ID), key_id.clone());
+            (key, sealed, Some(key_id))
+        }
+        SseType::C => {
+            let customer = customer_key.as_ref().ok_or_else(|| {
+                Error::InvalidEncryptionParameters("Requests specifying Server Side Encryption with Customer provided keys must provide an appropriate secret key".to_owned())
+            })?;
+            let key = ObjectKey::generate();
+            let sealed = key.seal(&customer.key, sse, bucket, object)?;
+            (key, sealed, None)
+        }
+    };
+    metadata.insert(meta_key(META_SSE), sse.as_str().to_owned());
+    metadata.insert(meta_key(META_SEALED_KEY), sealed);
+
+    let encryption = ObjectEncryption {
+        sse,
+        key,
+        customer_key_md5: customer_key.as_ref().map(|k| k.key_md5.clone()),
+        kms_key_id,
+    };
+
+    Ok(Some((encryption, metadata)))
+}
+
+/// Generates an SSE-S3 or SSE-KMS object key sealed by the KMS key `key_id`, or the default key of
+/// `kms`. Returns the key, its sealed form and the id of the KMS key that sealed it.
+async fn generate_kms_object_key(
+    kms: &dyn Kms,
+    key_id: Option<String>,
+    sse: SseType,
+    bucket: &str,
+    object: &str,
+) -> Result<(ObjectKey, String, String)> {
+    let key_id = key_id.unwrap_or_else(|| kms.default_key_id().to_owned());
+    let data_key = kms
+        .generate_key(&key_id, seal_context(sse, bucket, object).as_bytes())
+        .await?;
+    Ok((
+        ObjectKey(data_key.plaintext),
+        general_purpose::STANDARD.encode(data_key.ciphertext),
+        key_id,
+    ))
+}
+
+/// Unseals an SSE-S3 or SSE-KMS object key with the KMS key recorded in the object metadata.
+async fn unseal_kms_object_key(
+    kms: &dyn Kms,
+    sealed: &str,
+    metadata: &HashMap<String, String>,
+    sse: SseType,
+    bucket: &str,
+    object: &str,
+) -> Result<ObjectKey> {
+    let key_id = kms_key_id(metadata).unwrap_or(kms.default_key_id());
+    let ciphertext = general_purpose::STANDARD
+        .decode(sealed)
+        .map_err(|_| Error::other(format!("object {bucket}/{object} has a malformed sealed key")))?;
+    let key = kms
+        .decrypt_key(key_id, &ciphertext, seal_context(sse, bucket, object).as_bytes())
+        .await?;
+    Ok(ObjectKey(key))
+}
+
+/// Returns the default encryption algorithm of the bucket and its KMS key id, if any.
+async fn bucket_default_encryption(bucket: &str) -> Option<(String, Option<String>)> {
+    let (config, _) = metadata_sys::get_sse_config(bucket).await.ok()?;
+    config
+        .rules
+        .iter()
+        .find_map(|rule| rule.apply_server_side_encryption_by_default.as_ref())
+        .map(|by_default| {
+            (
+                by_default.sse_algorithm.as_str().to_owned(),
+                by_default.kms_master_key_id.clone().filter(|id| !id.is_empty()),
+            )
+        })
+}
+
+/// Recovers the object key of an encrypted object from its metadata, using the KMS for SSE-S3
+/// and SSE-KMS or the SSE-C headers in `h`. Returns `None` if the object is not encrypted.
+pub async fn object_key(
+    bucket: &str,
+    object: &str,
+    metadata: &HashMap<String, String>,
+    h: &HeaderMap,
+) -> Result<Option<ObjectKey>> {
+    let Some(sealed) = metadata.get(&meta_key(META_SEALED_KEY)) else {
+        return Ok(None);
+    };
+
+    match SseType::from_metadata(metadata) {
+        Some(sse @ (SseType::S3 | SseType::Kms)) => {
+            let key = unseal_kms_object_key(global_kms()?.as_ref(), sealed, metadata, sse, bucket, object).await?;
+            Ok(Some(key))
+        }
+        Some(SseType::C) => {
+            let Some(customer) = parse_customer_key(h, false)? else {
+                return Err(Error::ObjectEncryptionKeyRequired(bucket.to_owned(), object.to_owned()));
+            };
+            ObjectKey::unseal(sealed, &customer.key, SseType::C, bucket, object)
+                .map(Some)
+                .ok_or_else(|| Error::ObjectEncryptionKeyMismatch(bucket.to_owned(), object.to_owned()))
+        }
+        None => Err(Error::other(format!("object {bucket}/{object} has an unknown encryption scheme"))),
+    }
+}
+
+/// Wraps a plaintext reader of `size` bytes so that it produces encrypted data.
+/// The returned reader has an unknown size, like a compressed reader.
+pub fn encrypt_reader(reader: Box<dyn Reader>, size: i64, actual_size: i64, key: &ObjectKey) -> Result<Box<dyn Reader>> {
+    let hrd = HashReader::new(reader, size, actual_size, None, false)?;
+    let mut nonce = [0u8; 12];
+    rand::rng().fill_bytes(&mut nonce);
+    Ok(Box::new(EncryptReader::new(hrd, key.as_bytes(), nonce)))
+}
+
+/// The encrypted byte range that has to be read to decrypt a plaintext range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedRange {
+    /// Offset of the first encrypted byte to read.
+    pub offset: usize,
+    /// Number of encrypted bytes to read.
+    pub length: i64,
+    /// Package number the read starts at within its part.
+    pub sequence: u32,
+    /// Encrypted bytes read from each part.
+    pub parts: Vec<usize>,
+    /// Plaintext bytes to drop from the first decrypted package.
+    pub skip: usize,
+}
+
+/// Maps the plaintext range `offset..offset + length` of an object to the encrypted range that
+/// covers it. Each part of an object is an independent encrypted stream.
+pub fn encrypted_range(parts: &[ObjectPartInfo], offset: usize, length: i64) -> Result<EncryptedRange> {
+    let sealed_package = (ENCRYPT_PACKAGE_SIZE + ENCRYPT_PACKAGE_OVERHEAD) as u64;
+    let package = ENCRYPT_PACKAGE_SIZE as u64;
+
+    let mut range = EncryptedRange {
+        offset: 0,
+        length: 0,
+        sequence: 0,
+        parts: Vec::new(),
+        skip: 0,
+    };
+    if length <= 0 {
+        return Ok(range);
+    }
+
+    let start = offset as u64;
+    let end = start + length as u64; // exclusive
+    let (mut plain_start, mut enc_start) = (0u64, 0u64);
+    let mut enc_end = None;
+
+    for part in parts {
+        let (plain_size, enc_size) = (part.actual_size.max(0) as u64, part.size as u64);
+        let plain_end = plain_start + plain_size;
+
+        if plain_end > start && plain_start < end {
+            // First and last encrypted byte of this part that must be read.
+            let from = if range.parts.is_empty() {
+                let rel = start - plain_start;
+                range.sequence = u32::try_from(rel / package).map_err(Error::other)?;
+                range.skip = (rel % package) as usize;
+                range.offset = (enc_start + (rel / package) * sealed_package) as usize;
+                (rel / package) * sealed_package
+            } else {
+                0
+            };
+            let to = if end <= plain_end {
+                let last = (end - 1 - plain_start) / package;
+                ((last + 1) * sealed_package).min(enc_size)
+            } else {
+                enc_size
+            };
+
+            range.parts.push((to - from) as usize);
+            enc_end = Some(enc_start + to);
+        }
+
+        plain_start = plain_end;
+        enc_start += enc_size;
+        if plain_start >= end {
+            break;
+        }
+    }
+
+    let Some(enc_end) = enc_end else {
+        return Err(Error::other("The requested range is not satisfiable"));
+    };
+    if plain_start < end {
+        return Err(Error::other("The requested range is not satisfiable"));
+    }
+    range.length = (enc_end - range.offset as u64) as i64;
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustfs_rio::encrypted_size;
+
+    fn part(number: usize, actual_size: usize) -> ObjectPartInfo {
+        ObjectPartInfo {
+            number,
+            size: encrypted_size(actual_size),
+            actual_size: actual_size as i64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_encrypted_range_single_part() {
+        let sealed = ENCRYPT_PACKAGE_SIZE + ENCRYPT_PACKAGE_OVERHEAD;
+        let parts = vec![part(1, 3 * ENCRYPT_PACKAGE_SIZE + 10)];
+
+        let range = encrypted_range(&parts, 0, parts[0].actual_size).unwrap();
+        assert_eq!(range.offset, 0);
+        assert_eq!(range.length as usize, parts[0].size);
+        assert_eq!(range.parts, vec![parts[0].size]);
+
+        // Starts in the second package and ends in the third.
+        let range = encrypted_range(&parts, ENCRYPT_PACKAGE_SIZE + 5, ENCRYPT_PACKAGE_SIZE as i64).unwrap();
+        assert_eq!(range.sequence, 1);
+        assert_eq!(range.skip, 5);
+        assert_eq!(range.offset, sealed);
+        assert_eq!(range.length as usize, 2 * sealed);
+
+        // The last, short package.
+        let range = encrypted_range(&parts, 3 * ENCRYPT_PACKAGE_SIZE + 1, 9).unwrap();
+        assert_eq!(range.sequence, 3);
+        assert_eq!(range.offset, 3 * sealed);
+        assert_eq!(range.length as usize, 10 + ENCRYPT_PACKAGE_OVERHEAD);
+    }
+
+    #[test]
+    fn test_encrypted_range_multiple_parts() {
+        let sealed = ENCRYPT_PACKAGE_SIZE + ENCRYPT_PACKAGE_OVERHEAD;
+        let parts = vec![
+            part(1, 2 * ENCRYPT_PACKAGE_SIZE),
+            part(2, ENCRYPT_PACKAGE_SIZE + 1),
+            part(3, 100),
+        ];
+
+        // From the second package of part 1 to the first package of part 2.
+        let range = encrypted_range(&parts, ENCRYPT_PACKAGE_SIZE + 1, ENCRYPT_PACKAGE_SIZE as i64).unwrap();
+        assert_eq!(range.sequence, 1);
+        assert_eq!(range.skip, 1);
+        assert_eq!(range.offset, sealed);
+        assert_eq!(range.parts, vec![sealed, sealed]);
+
+        // Entirely within part 3.
+        let start = 3 * ENCRYPT_PACKAGE_SIZE + 1 + 10;
+        let range = encrypted_range(&parts, start, 20).unwrap();
+        assert_eq!(range.sequence, 0);
+        assert_eq!(range.skip, 10);
+        assert_eq!(range.offset, parts[0].size + parts[1].size);
+        assert_eq!(range.parts, vec![parts[2].size]);
+
+        assert!(encrypted_range(&parts, start, 1000).is_err());
+    }
+
+    #[test]
+    fn test_object_key_seal_unseal() {
+        let key = ObjectKey::generate();
+        let kek = [7u8; 32];
+        let sealed = key.seal(&kek, SseType::C, "bucket", "object").unwrap();
+
+        let unsealed = ObjectKey::unseal(&sealed, &kek, SseType::C, "bucket", "object").unwrap();
+        assert_eq!(unsealed.as_bytes(), key.as_bytes());
+
+        assert!(ObjectKey::unseal(&sealed, &[8u8; 32], SseType::C, "bucket", "object").is_none());
+        assert!(ObjectKey::unseal(&sealed, &kek, SseType::C, "bucket", "other").is_none());
+        assert!(ObjectKey::unseal(&sealed, &kek, SseType::S3, "bucket", "object").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_kms_object_key_round_trip() {
+        let root = kms::MasterKey::parse("root-key:AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=").unwrap();
+        let kms = kms::LocalKms::new(root, None).await.unwrap();
+
+        let (key, sealed, key_id) = generate_kms_object_key(&kms, None, SseType::S3, "bucket", "object")
+            .await
+            .unwrap();
+        assert_eq!(key_id, "root-key");
+
+        let metadata = HashMap::from([(meta_key(META_KEY_ID), key_id)]);
+        let unsealed = unseal_kms_object_key(&kms, &sealed, &metadata, SseType::S3, "bucket", "object")
+            .await
+            .unwrap();
+        assert_eq!(unsealed.as_bytes(), key.as_bytes());
+        assert!(
+            unseal_kms_object_key(&kms, &sealed, &metadata, SseType::S3, "bucket", "other")
+                .await
+                .is_err()
+        );
+        assert!(
+            unseal_kms_object_key(&kms, &sealed, &metadata, SseType::Kms, "bucket", "object")
+                .await
+                .is_err()
+        );
+
+        assert!(matches!(
+            generate_kms_object_key(&kms, Some("missing".to_owned()), SseType::Kms, "bucket", "object").await,
+            Err(Error::KmsKeyNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_range_of_compressed_encrypted_object() {
+        use crate::store_api::{GetObjectReader, HTTPRangeSpec, ObjectInfo, ObjectOptions};
+        use rustfs_rio::{CompressReader, WarpReader};
+        use rustfs_utils::CompressionAlgorithm;
+        use std::io::Cursor;
+        use tokio::io::AsyncReadExt;
+
+        let customer = [5u8; 32];
+        let mut h = HeaderMap::new();
+        h.insert(AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM, "AES256".parse().unwrap());
+        h.insert(
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+            general_purpose::STANDARD.encode(customer).parse().unwrap(),
+        );
+        h.insert(
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+            general_purpose::STANDARD.encode(Md5::digest(customer)).parse().unwrap(),
+        );
+        let (encryption, mut metadata) = new_object_encryption("bucket", "object", &h).await.unwrap().unwrap();
+
+        let data: Vec<u8> = (0..3 * ENCRYPT_PACKAGE_SIZE).map(|i| (i % 251) as u8).collect();
+        let compressed = CompressReader::new(WarpReader::new(Cursor::new(data.clone())), CompressionAlgorithm::Zstd);
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+        let mut stored = Vec::new();
+        EncryptReader::new(compressed, encryption.key.as_bytes(), nonce)
+            .read_to_end(&mut stored)
+            .await
+            .unwrap();
+
+        metadata.insert(
+            format!("{RESERVED_METADATA_PREFIX_LOWER}compression"),
+            CompressionAlgorithm::Zstd.to_string(),
+        );
+        let oi = ObjectInfo {
+            bucket: "bucket".to_owned(),
+            name: "object".to_owned(),
+            size: stored.len() as i64,
+            actual_size: data.len() as i64,
+            user_defined: metadata,
+            parts: vec![ObjectPartInfo {
+                number: 1,
+                size: stored.len(),
+                actual_size: data.len() as i64,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let (start, end) = (ENCRYPT_PACKAGE_SIZE as i64 + 7, 2 * ENCRYPT_PACKAGE_SIZE as i64 + 99);
+        let rs = HTTPRangeSpec {
+            is_suffix_length: false,
+            start,
+            end,
+        };
+        let (mut reader, offset, length) = GetObjectReader::new(
+            Box::new(WarpReader::new(Cursor::new(stored.clone()))),
+            Some(rs),
+            &oi,
+            &ObjectOptions::default(),
+            &h,
+        )
+        .await
+        .unwrap();
+        // The whole stored object is read and the range is cut out after decompression.
+        assert_eq!((offset, length), (0, stored.len() as i64));
+        assert_eq!(reader.read_all().await.unwrap(), &data[start as usize..=end as usize]);
+    }
+
+    #[test]
+    fn test_parse_customer_key() {
+        let key = [3u8; 32];
+        let mut h = HeaderMap::new();
+        assert!(parse_customer_key(&h, false).unwrap().is_none());
+
+        h.insert(AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM, "AES256".parse().unwrap());
+        h.insert(
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY,
+            general_purpose::STANDARD.encode(key).parse().unwrap(),
+        );
+        h.insert(
+            AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5,
+            general_purpose::STANDARD.encode(Md5::digest(key)).parse().unwrap(),
+        );
+        let parsed = parse_customer_key(&h, false).unwrap().unwrap();
+        assert_eq!(parsed.key, key);
+        assert!(parse_customer_key(&h, true).unwrap().is_none());
+        assert!(parse_customer_key(&copy_source_headers(&h), false).unwrap().is_none());
+
+        h.insert(AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_KEY_MD5, "bogus".parse().unwrap());
+        assert!(parse_customer_key(&h, false).is_err());
+    }
+}
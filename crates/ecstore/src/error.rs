@@ -181,6 +181,33 @@ pub enum StorageError {
     #[error("first disk wait")]
     FirstDiskWait,
 
+    #[error("Server side encryption specified but KMS is not configured")]
+    KmsNotConfigured,
+
+    #[error("{0}")]
+    InvalidEncryptionParameters(String),
+
+    #[error("Object {0}/{1} is encrypted with a customer key, the key must be provided to access it")]
+    ObjectEncryptionKeyRequired(String, String),
+
+    #[error("The provided encryption key does not match the key of object {0}/{1}")]
+    ObjectEncryptionKeyMismatch(String, String),
+
+    #[error("KMS key {0} does not exist")]
+    KmsKeyNotFound(String),
+
+    #[error("KMS key {0} already exists")]
+    KmsKeyExists(String),
+
+    #[error("Bucket quota exceeded for bucket: {0}")]
+    BucketQuotaExceeded(String),
+
+    #[error("At least one of the preconditions did not hold for {0}/{1}")]
+    PreconditionFailed(String, String),
+
+    #[error("The operation is not valid for the current state of the object {0}/{1}")]
+    InvalidObjectState(String, String),
+
     #[error("Io error: {0}")]
     Io(std::io::Error),
 }
@@ -409,6 +436,15 @@ impl Clone for StorageError {
             StorageError::FirstDiskWait => StorageError::FirstDiskWait,
             StorageError::TooManyOpenFiles => StorageError::TooManyOpenFiles,
             StorageError::NoHealRequired => StorageError::NoHealRequired,
+            StorageError::KmsNotConfigured => StorageError::KmsNotConfigured,
+            StorageError::InvalidEncryptionParameters(a) => StorageError::InvalidEncryptionParameters(a.clone()),
+            StorageError::ObjectEncryptionKeyRequired(a, b) => StorageError::ObjectEncryptionKeyRequired(a.clone(), b.clone()),
+            StorageError::ObjectEncryptionKeyMismatch(a, b) => StorageError::ObjectEncryptionKeyMismatch(a.clone(), b.clone()),
+            StorageError::KmsKeyNotFound(a) => StorageError::KmsKeyNotFound(a.clone()),
+            StorageError::KmsKeyExists(a) => StorageError::KmsKeyExists(a.clone()),
+            StorageError::BucketQuotaExceeded(a) => StorageError::BucketQuotaExceeded(a.clone()),
+            StorageError::PreconditionFailed(a, b) => StorageError::PreconditionFailed(a.clone(), b.clone()),
+            StorageError::InvalidObjectState(a, b) => StorageError::InvalidObjectState(a.clone(), b.clone()),
         }
     }
 }
@@ -471,6 +507,15 @@ impl StorageError {
             StorageError::ConfigNotFound => 0x35,
             StorageError::TooManyOpenFiles => 0x36,
             StorageError::NoHealRequired => 0x37,
+            StorageError::KmsNotConfigured => 0x38,
+            StorageError::InvalidEncryptionParameters(_) => 0x39,
+            StorageError::ObjectEncryptionKeyRequired(_, _) => 0x3A,
+            StorageError::ObjectEncryptionKeyMismatch(_, _) => 0x3B,
+            StorageError::KmsKeyNotFound(_) => 0x3C,
+            StorageError::KmsKeyExists(_) => 0x3D,
+            StorageError::BucketQuotaExceeded(_) => 0x3E,
+            StorageError::PreconditionFailed(_, _) => 0x3F,
+            StorageError::InvalidObjectState(_, _) => 0x40,
         }
     }
 
@@ -535,6 +580,15 @@ impl StorageError {
             0x35 => Some(StorageError::ConfigNotFound),
             0x36 => Some(StorageError::TooManyOpenFiles),
             0x37 => Some(StorageError::NoHealRequired),
+            0x38 => Some(StorageError::KmsNotConfigured),
+            0x39 => Some(StorageError::InvalidEncryptionParameters(Default::default())),
+            0x3A => Some(StorageError::ObjectEncryptionKeyRequired(Default::default(), Default::default())),
+            0x3B => Some(StorageError::ObjectEncryptionKeyMismatch(Default::default(), Default::default())),
+            0x3C => Some(StorageError::KmsKeyNotFound(Default::default())),
+            0x3D => Some(StorageError::KmsKeyExists(Default::default())),
+            0x3E => Some(StorageError::BucketQuotaExceeded(Default::default())),
+            0x3F => Some(StorageError::PreconditionFailed(Default::default(), Default::default())),
+            0x40 => Some(StorageError::InvalidObjectState(Default::default(), Default::default())),
             _ => None,
         }
     }
@@ -89,6 +89,8 @@ pub fn init_global_action_cred(ak: Option<String>, sk: Option<String>) {
         }
     };
 
+    rustfs_protos::auth::init_rpc_secret(&ak, &sk);
+
     GLOBAL_ACTIVE_CRED
         .set(Credentials {
             access_key: ak,
@@ -23,7 +23,7 @@ use std::{
         Arc,
         atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     },
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
 use time::{self, OffsetDateTime};
@@ -40,6 +40,7 @@ use crate::bucket::{
 };
 use crate::cmd::bucket_replication::queue_replication_heal;
 use crate::event::name::EventName;
+use crate::trace;
 use crate::{
     bucket::{
         lifecycle::{
@@ -64,6 +65,7 @@ use crate::{
     config::{
         com::{read_config, save_config},
         heal::Config,
+        scanner,
     },
     disk::{DiskInfoOptions, DiskStore},
     global::{GLOBAL_BackgroundHealState, GLOBAL_IsErasure, GLOBAL_IsErasureSD},
@@ -89,8 +91,11 @@ use lazy_static::lazy_static;
 use rand::Rng;
 use rmp_serde::{Deserializer, Serializer};
 use rustfs_filemeta::{FileInfo, MetaCacheEntries, MetaCacheEntry, MetadataResolutionParams};
+use rustfs_madmin::trace::{TraceInfo, TraceType};
 use rustfs_utils::path::encode_dir_object;
-use rustfs_utils::path::{SLASH_SEPARATOR, path_join, path_to_bucket_object, path_to_bucket_object_with_base_path};
+use rustfs_utils::path::{
+    SLASH_SEPARATOR, path_join, path_join_buf, path_to_bucket_object, path_to_bucket_object_with_base_path,
+};
 use s3s::dto::{
     BucketLifecycleConfiguration, DefaultRetention, ExpirationStatus, LifecycleRule, ReplicationConfiguration,
     ReplicationRuleStatus,
@@ -163,18 +168,22 @@ impl DynamicSleeper {
         sleep(want_sleep).await;
     }
 
-    fn _update(&mut self, factor: f64, max_wait: Duration) -> Result<()> {
+    fn update(&mut self, factor: f64, max_wait: Duration) {
         if (self.factor - factor).abs() < 1e-10 && self.max_sleep == max_wait {
-            return Ok(());
+            return;
         }
 
         self.factor = factor;
         self.max_sleep = max_wait;
-
-        Ok(())
     }
 }
 
+/// Applies the `scanner` subsystem configuration to the running scanner.
+pub async fn update_scanner_config(cfg: &scanner::Config) {
+    SCANNER_SLEEPER.write().await.update(cfg.delay, cfg.max_wait);
+    SCANNER_CYCLE.store(cfg.cycle.as_secs(), Ordering::SeqCst);
+}
+
 fn new_dynamic_sleeper(factor: f64, max_wait: Duration, is_scanner: bool) -> DynamicSleeper {
     DynamicSleeper {
         factor,
@@ -1456,7 +1465,10 @@ async fn scan(folder: &CachedFolder, into: &mut DataUsageEntry, folder_scanner:
         into.clone()
     };
 
-    if Box::pin(folder_scanner.scan_folder(folder, &mut dst)).await.is_err() {
+    let start = Instant::now();
+    let res = Box::pin(folder_scanner.scan_folder(folder, &mut dst)).await;
+    trace_scanner("ScanFolder", &folder.name, start, res.as_ref().err()).await;
+    if res.is_err() {
         return;
     }
     if !into.compacted {
@@ -1470,6 +1482,19 @@ async fn scan(folder: &CachedFolder, into: &mut DataUsageEntry, folder_scanner:
     }
 }
 
+/// Publishes a scanner trace event for a call which started at `start`.
+async fn trace_scanner(func_name: &str, path: &str, start: Instant, err: Option<&Error>) {
+    if !trace::subscribed(TraceType::SCANNER) {
+        return;
+    }
+
+    let mut info = TraceInfo::new(TraceType::SCANNER, "", format!("scanner.{func_name}"));
+    info.path = path.to_owned();
+    info.duration = start.elapsed();
+    info.error = err.map(|err| err.to_string());
+    trace::publish(info).await;
+}
+
 fn lc_get_prefix(rule: &LifecycleRule) -> String {
     if let Some(p) = &rule.prefix {
         return p.to_string();
@@ -1626,7 +1651,10 @@ pub async fn scan_data_folder(
         parent: DataUsageHash("".to_string()),
     };
 
-    if s.scan_folder(&folder, &mut root).await.is_err() {
+    let start = Instant::now();
+    let res = s.scan_folder(&folder, &mut root).await;
+    trace_scanner("ScanBucketDrive", &path_join_buf(&[&s.root, &folder.name]), start, res.as_ref().err()).await;
+    if res.is_err() {
         close_disk().await;
     }
     s.new_cache.force_compact(DATA_SCANNER_COMPACT_AT_CHILDREN as usize);
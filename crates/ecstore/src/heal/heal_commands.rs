@@ -21,17 +21,20 @@ use std::{
 use crate::{
     config::storageclass::{RRS, STANDARD},
     disk::{BUCKET_META_PREFIX, DeleteOptions, DiskAPI, DiskStore, RUSTFS_META_BUCKET, error::DiskError, fs::read_file},
-    global::GLOBAL_BackgroundHealState,
+    global::{GLOBAL_BackgroundHealState, GLOBAL_MRFState},
     heal::heal_ops::HEALING_TRACKER_FILENAME,
     new_object_layer_fn,
+    notification_sys::get_global_notification_sys,
     store_api::{BucketInfo, StorageAPI},
 };
 use crate::{disk, error::Result};
 use chrono::{DateTime, Utc};
 use lazy_static::lazy_static;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tokio::sync::RwLock;
+use tracing::warn;
 
 use super::{background_heal_ops::get_local_disks_to_heal, heal_ops::BG_HEALING_UUID};
 
@@ -447,61 +450,40 @@ pub async fn healing(derive_path: &str) -> disk::error::Result<Option<HealingTra
     Ok(Some(healing_tracker))
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct MRFStatus {
-    bytes_healed: u64,
-    items_healed: u64,
-}
+pub use rustfs_madmin::heal_commands::{BgHealState, MRFStatus, SetStatus};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct SetStatus {
-    pub id: String,
-    pub pool_index: i32,
-    pub set_index: i32,
-    pub heal_status: String,
-    pub heal_priority: String,
-    pub total_objects: usize,
-    pub disks: Vec<rustfs_madmin::Disk>,
-}
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct BgHealState {
-    offline_endpoints: Vec<String>,
-    scanned_items_count: u64,
-    heal_disks: Vec<String>,
-    sets: Vec<SetStatus>,
-    mrf: HashMap<String, MRFStatus>,
-    scparity: HashMap<String, usize>,
-}
-
-pub async fn get_local_background_heal_status() -> (BgHealState, bool) {
-    let (bg_seq, ok) = GLOBAL_BackgroundHealState.get_heal_sequence_by_token(BG_HEALING_UUID).await;
-    if !ok {
-        return (BgHealState::default(), false);
-    }
-    let bg_seq = bg_seq.unwrap();
+/// Returns the background heal state of the drives of this node.
+pub async fn get_local_background_heal_status() -> BgHealState {
+    let (bg_seq, _) = GLOBAL_BackgroundHealState.get_heal_sequence_by_token(BG_HEALING_UUID).await;
+    let scanned_items_count = match bg_seq {
+        Some(bg_seq) => bg_seq.get_scanned_items_count().await as u64,
+        None => 0,
+    };
     let mut status = BgHealState {
-        scanned_items_count: bg_seq.get_scanned_items_count().await as u64,
+        scanned_items_count,
         ..Default::default()
     };
+    status
+        .mrf
+        .insert(GLOBAL_Local_Node_Name.read().await.clone(), GLOBAL_MRFState.status());
+
     let mut heal_disks_map = HashSet::new();
     for ep in get_local_disks_to_heal().await.iter() {
         heal_disks_map.insert(ep.to_string());
     }
+    let healing = GLOBAL_BackgroundHealState.get_local_healing_disks().await;
 
     let Some(store) = new_object_layer_fn() else {
-        let healing = GLOBAL_BackgroundHealState.get_local_healing_disks().await;
         for disk in healing.values() {
             status.heal_disks.push(disk.endpoint.clone());
         }
-        return (status, true);
+        return status;
     };
 
     let si = store.local_storage_info().await;
     let mut indexed = HashMap::new();
     for disk in si.disks.iter() {
         let set_idx = format!("{}-{}", disk.pool_index, disk.set_index);
-        // indexed.insert(set_idx, disk);
         indexed.entry(set_idx).or_insert(Vec::new()).push(disk);
     }
 
@@ -513,12 +495,15 @@ pub async fn get_local_background_heal_status() -> (BgHealState, bool) {
             ..Default::default()
         };
         for disk in disks {
-            ss.disks.push(disk.clone());
-            if disk.healing {
+            let mut disk = disk.clone();
+            if disk.healing || heal_disks_map.contains(&disk.endpoint) {
+                disk.healing = true;
+                disk.heal_info = healing.get(&disk.endpoint).cloned();
                 ss.heal_status = "healing".to_string();
                 ss.heal_priority = "high".to_string();
                 status.heal_disks.push(disk.endpoint.clone());
             }
+            ss.disks.push(disk);
         }
         ss.disks.sort_by(|a, b| {
             if a.pool_index != b.pool_index {
@@ -540,5 +525,26 @@ pub async fn get_local_background_heal_status() -> (BgHealState, bool) {
         .scparity
         .insert(RRS.to_string(), backend_info.rr_sc_parity.unwrap_or_default());
 
-    (status, true)
+    status
+}
+
+/// Collects the background heal state of all nodes, nodes that do not answer are reported as
+/// offline.
+pub async fn get_aggregated_background_heal_state() -> BgHealState {
+    let mut status = get_local_background_heal_status().await;
+
+    if let Some(notification_sys) = get_global_notification_sys() {
+        for (host, state) in notification_sys.background_heal_status().await {
+            match state {
+                Ok(state) => status.merge(state),
+                Err(err) => {
+                    warn!("get background heal status from {} failed: {:?}", host, err);
+                    status.offline_endpoints.push(host);
+                }
+            }
+        }
+    }
+
+    status.sort();
+    status
 }
@@ -13,14 +13,16 @@
 // limitations under the License.
 
 use crate::disk::{BUCKET_META_PREFIX, RUSTFS_META_BUCKET};
+use crate::error::Result;
 use crate::heal::background_heal_ops::{heal_bucket, heal_object};
 use crate::heal::heal_commands::{HEAL_DEEP_SCAN, HEAL_NORMAL_SCAN};
 use chrono::{DateTime, Utc};
 use lazy_static::lazy_static;
 use regex::Regex;
+use rustfs_madmin::heal_commands::MRFStatus;
 use rustfs_utils::path::SLASH_SEPARATOR;
 use std::ops::Sub;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::sync::mpsc::{Receiver, Sender};
@@ -62,6 +64,9 @@ pub struct MRFState {
     rx: RwLock<Receiver<PartialOperation>>,
     closed: AtomicBool,
     closing: AtomicBool,
+    queued: AtomicU64,
+    items_healed: AtomicU64,
+    items_failed: AtomicU64,
 }
 
 impl Default for MRFState {
@@ -78,14 +83,35 @@ impl MRFState {
             rx: RwLock::new(rx),
             closed: Default::default(),
             closing: Default::default(),
+            queued: Default::default(),
+            items_healed: Default::default(),
+            items_failed: Default::default(),
         }
     }
 
+    pub fn status(&self) -> MRFStatus {
+        MRFStatus {
+            items_healed: self.items_healed.load(Ordering::Relaxed),
+            items_failed: self.items_failed.load(Ordering::Relaxed),
+            total_items: self.queued.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+
+    fn record(&self, result: &Result<()>) {
+        match result {
+            Ok(()) => self.items_healed.fetch_add(1, Ordering::Relaxed),
+            Err(_) => self.items_failed.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
     pub async fn add_partial(&self, op: PartialOperation) {
         if self.closed.load(Ordering::SeqCst) || self.closing.load(Ordering::SeqCst) {
             return;
         }
-        let _ = self.tx.send(op).await;
+        if self.tx.send(op).await.is_ok() {
+            self.queued.fetch_add(1, Ordering::Relaxed);
+        }
     }
 
     /// Enhanced heal routine with cancellation support
@@ -107,6 +133,7 @@ impl MRFState {
                     rx_guard.recv().await
                 } => {
                     if let Some(op) = op_result {
+                        self.queued.fetch_sub(1, Ordering::Relaxed);
                         // Special path filtering (original logic)
                         if op.bucket == RUSTFS_META_BUCKET {
                             for pattern in &*PATTERNS {
@@ -133,17 +160,21 @@ impl MRFState {
 
                         if op.object.is_empty() {
                             // Heal bucket (original logic)
-                            if let Err(err) = heal_bucket(&op.bucket).await {
+                            let result = heal_bucket(&op.bucket).await;
+                            self.record(&result);
+                            if let Err(err) = result {
                                 error!("heal bucket failed, bucket: {}, err: {:?}", op.bucket, err);
                             }
                         } else if op.versions.is_empty() {
                             // Heal single object (original logic)
-                            if let Err(err) = heal_object(
+                            let result = heal_object(
                                 &op.bucket,
                                 &op.object,
                                 &op.version_id.clone().unwrap_or_default(),
                                 scan_mode
-                            ).await {
+                            ).await;
+                            self.record(&result);
+                            if let Err(err) = result {
                                 error!("heal object failed, bucket: {}, object: {}, err: {:?}", op.bucket, op.object, err);
                             }
                         } else {
@@ -159,12 +190,14 @@ impl MRFState {
 
                                     let start = i * 16;
                                     let end = start + 16;
-                                    if let Err(err) = heal_object(
+                                    let result = heal_object(
                                         &op.bucket,
                                         &op.object,
                                         &Uuid::from_slice(&op.versions[start..end]).expect("").to_string(),
                                         scan_mode,
-                                    ).await {
+                                    ).await;
+                                    self.record(&result);
+                                    if let Err(err) = result {
                                         error!("heal object failed, bucket: {}, object: {}, err: {:?}", op.bucket, op.object, err);
                                     }
                                 }
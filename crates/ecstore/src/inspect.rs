@@ -0,0 +1,200 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Collection of the raw files of an object for support diagnostics.
+//!
+//! [`inspect_object`] reads `xl.meta` and, optionally, the part files of an object from every
+//! drive of the erasure set the object hashes to in each pool, without any quorum or healing.
+//! Part files are only located, they are streamed with [`InspectDrive::open_part`] while the
+//! archive is written. [`encrypt_archive`] seals the archive for the holder of an RSA private key.
+
+use crate::disk::{DiskAPI, DiskStore, FileReader, STORAGE_FORMAT_FILE, error::DiskError};
+use crate::error::{Error, Result};
+use crate::new_object_layer_fn;
+use bytes::Bytes;
+use futures::future::join_all;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::rand_core::{OsRng, RngCore};
+use rsa::{Oaep, RsaPublicKey};
+use rustfs_filemeta::FileMeta;
+use rustfs_rio::{EncryptReader, Reader};
+use rustfs_utils::path::path_join_buf;
+use sha2::Sha256;
+use std::collections::BTreeSet;
+use std::io::Cursor;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const ARCHIVE_FORMAT_VERSION: u8 = 1;
+
+/// The files of an object found on one drive.
+#[derive(Debug, Clone, Default)]
+pub struct InspectDrive {
+    pub endpoint: String,
+    pub pool_index: usize,
+    pub set_index: usize,
+    pub disk_index: usize,
+    pub disk: Option<DiskStore>,
+    /// Content of `xl.meta`, `None` when the drive doesn't have the object.
+    pub meta: Option<Bytes>,
+    /// Paths of the part files referenced by `meta`, relative to the bucket.
+    pub parts: Vec<String>,
+    /// Read errors other than missing files, missing files are expected on some drives.
+    pub errors: Vec<String>,
+}
+
+impl InspectDrive {
+    /// Opens the part file at `path` of `bucket`, returns `None` when the drive doesn't have it.
+    pub async fn open_part(&self, bucket: &str, path: &str) -> Result<Option<FileReader>> {
+        let Some(disk) = &self.disk else {
+            return Ok(None);
+        };
+        match disk.read_file(bucket, path).await {
+            Ok(reader) => Ok(Some(reader)),
+            Err(DiskError::FileNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+pub async fn inspect_object(bucket: &str, object: &str, include_parts: bool) -> Result<Vec<InspectDrive>> {
+    let Some(store) = new_object_layer_fn() else {
+        return Err(Error::other("errServerNotInitialized"));
+    };
+
+    let mut futures = Vec::new();
+    for pool in store.pools.iter() {
+        let set = pool.get_disks_by_key(object);
+        let disks = set.disks.read().await.clone();
+        for (disk_index, disk) in disks.into_iter().enumerate() {
+            let drive = InspectDrive {
+                endpoint: set.set_endpoints.get(disk_index).map(|ep| ep.to_string()).unwrap_or_default(),
+                pool_index: set.pool_index,
+                set_index: set.set_index,
+                disk_index,
+                disk,
+                ..Default::default()
+            };
+            futures.push(inspect_drive(drive, bucket, object, include_parts));
+        }
+    }
+
+    Ok(join_all(futures).await)
+}
+
+async fn inspect_drive(mut drive: InspectDrive, bucket: &str, object: &str, include_parts: bool) -> InspectDrive {
+    let Some(disk) = &drive.disk else {
+        drive.errors.push(DiskError::DiskNotFound.to_string());
+        return drive;
+    };
+
+    let meta_path = path_join_buf(&[object, STORAGE_FORMAT_FILE]);
+    let meta = match disk.read_all(bucket, &meta_path).await {
+        Ok(data) => data,
+        Err(err) => {
+            if err != DiskError::FileNotFound && err != DiskError::VolumeNotFound {
+                drive.errors.push(format!("{meta_path}: {err}"));
+            }
+            return drive;
+        }
+    };
+
+    if include_parts {
+        match part_paths(&meta, bucket, object) {
+            Ok(paths) => drive.parts = paths.into_iter().collect(),
+            Err(err) => drive.errors.push(format!("{STORAGE_FORMAT_FILE}: {err}")),
+        }
+    }
+    drive.meta = Some(meta);
+
+    drive
+}
+
+/// Paths of the part files referenced by the versions in `meta`, inlined data has no part files.
+fn part_paths(meta: &[u8], bucket: &str, object: &str) -> Result<BTreeSet<String>> {
+    let versions = FileMeta::load(meta)?.into_file_info_versions(bucket, object, true)?;
+
+    let mut paths = BTreeSet::new();
+    for fi in versions.versions.iter() {
+        let Some(data_dir) = fi.data_dir else {
+            continue;
+        };
+        if fi.inline_data() {
+            continue;
+        }
+        for part in fi.parts.iter() {
+            paths.insert(path_join_buf(&[object, &data_dir.to_string(), &format!("part.{}", part.number)]));
+        }
+    }
+    Ok(paths)
+}
+
+/// Encrypts the `archive` stream for the holder of the RSA private key matching the PEM encoded `public_key`.
+///
+/// The archive is sealed with [`EncryptReader`] by a random key, which is encrypted with
+/// RSA-OAEP (SHA-256) and stored in front of it:
+/// `[version: u8][key length: u16 BE][encrypted key][sealed packages]`.
+pub fn encrypt_archive<R: Reader>(public_key: &str, archive: R) -> Result<impl AsyncRead + Unpin + Send + Sync> {
+    let public_key = RsaPublicKey::from_public_key_pem(public_key)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(public_key))
+        .map_err(|e| Error::other(format!("invalid public key: {e}")))?;
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let sealed_key = public_key
+        .encrypt(&mut OsRng, Oaep::new::<Sha256>(), &key)
+        .map_err(|e| Error::other(format!("encrypt archive key: {e}")))?;
+
+    let mut header = Vec::with_capacity(3 + sealed_key.len());
+    header.push(ARCHIVE_FORMAT_VERSION);
+    header.extend_from_slice(&(sealed_key.len() as u16).to_be_bytes());
+    header.extend_from_slice(&sealed_key);
+    Ok(Cursor::new(header).chain(EncryptReader::new(archive, key, nonce)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::RsaPrivateKey;
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+    use rustfs_rio::{DecryptReader, WarpReader};
+
+    #[tokio::test]
+    async fn test_encrypt_archive() {
+        let private_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+        let public_key = private_key.to_public_key().to_public_key_pem(LineEnding::LF).unwrap();
+
+        let archive = vec![7u8; 200_000];
+        let mut sealed = Vec::new();
+        encrypt_archive(&public_key, WarpReader::new(Cursor::new(archive.clone())))
+            .unwrap()
+            .read_to_end(&mut sealed)
+            .await
+            .unwrap();
+        assert_eq!(sealed[0], ARCHIVE_FORMAT_VERSION);
+
+        let key_len = u16::from_be_bytes([sealed[1], sealed[2]]) as usize;
+        let key = private_key.decrypt(Oaep::new::<Sha256>(), &sealed[3..3 + key_len]).unwrap();
+        let mut data = Vec::new();
+        DecryptReader::new(WarpReader::new(Cursor::new(sealed[3 + key_len..].to_vec())), key.try_into().unwrap())
+            .read_to_end(&mut data)
+            .await
+            .unwrap();
+        assert_eq!(data, archive);
+
+        assert!(encrypt_archive("not a key", WarpReader::new(Cursor::new(Vec::new()))).is_err());
+    }
+}
@@ -0,0 +1,450 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{DataKey, Kms, KmsStatus, MasterKey, check_key_id};
+use crate::config::com::{CONFIG_PREFIX, read_config, save_config};
+use crate::disk::RUSTFS_META_BUCKET;
+use crate::error::{Error, Result};
+use crate::new_object_layer_fn;
+use crate::store::ECStore;
+use crate::store_api::StorageAPI;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::RngCore;
+use rustfs_utils::path::SLASH_SEPARATOR;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+const KEY_FILE_SUFFIX: &str = ".key";
+
+/// Prefix of the keys stored by [`ClusterKeyStore`] in the metadata bucket.
+const KMS_KEYS_PREFIX: &str = "kms/keys";
+
+/// Persistent storage of the keys created by [`LocalKms`].
+///
+/// The store only ever sees key material encrypted by the root key.
+#[async_trait::async_trait]
+pub trait KeyStore: Debug + Send + Sync + 'static {
+    /// Returns the sealed key `key_id`, or `None` if it does not exist.
+    async fn read(&self, key_id: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn write(&self, key_id: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Lists the ids of all stored keys.
+    async fn list(&self) -> Result<Vec<String>>;
+
+    fn endpoint(&self) -> String;
+}
+
+/// Stores the keys one file per key in a local directory, only suitable for single node deployments.
+#[derive(Debug)]
+pub struct DirKeyStore {
+    dir: PathBuf,
+}
+
+impl DirKeyStore {
+    pub async fn new(dir: PathBuf) -> Result<Self> {
+        tokio::fs::create_dir_all(&dir).await?;
+        Ok(Self { dir })
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyStore for DirKeyStore {
+    async fn read(&self, key_id: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.dir.join(format!("{key_id}{KEY_FILE_SUFFIX}"))).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write(&self, key_id: &str, data: Vec<u8>) -> Result<()> {
+        // Write to a temporary file first so a crash never leaves a truncated key behind.
+        let path = self.dir.join(format!("{key_id}{KEY_FILE_SUFFIX}"));
+        let tmp = self.dir.join(format!(".{key_id}{KEY_FILE_SUFFIX}.tmp"));
+        tokio::fs::write(&tmp, &data).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file_name = entry.file_name();
+            if let Some(key_id) = file_name.to_str().and_then(|name| name.strip_suffix(KEY_FILE_SUFFIX)) {
+                ids.push(key_id.to_owned());
+            }
+        }
+        Ok(ids)
+    }
+
+    fn endpoint(&self) -> String {
+        self.dir.display().to_string()
+    }
+}
+
+/// Stores the keys in the cluster config, so that every node can use the keys created on any of them.
+#[derive(Debug, Default)]
+pub struct ClusterKeyStore;
+
+impl ClusterKeyStore {
+    fn store() -> Result<Arc<ECStore>> {
+        new_object_layer_fn().ok_or_else(|| Error::other("errServerNotInitialized"))
+    }
+
+    fn key_file(key_id: &str) -> String {
+        format!("{CONFIG_PREFIX}{SLASH_SEPARATOR}{KMS_KEYS_PREFIX}{SLASH_SEPARATOR}{key_id}{KEY_FILE_SUFFIX}")
+    }
+}
+
+#[async_trait::async_trait]
+impl KeyStore for ClusterKeyStore {
+    async fn read(&self, key_id: &str) -> Result<Option<Vec<u8>>> {
+        match read_config(Self::store()?, &Self::key_file(key_id)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(Error::ConfigNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn write(&self, key_id: &str, data: Vec<u8>) -> Result<()> {
+        save_config(Self::store()?, &Self::key_file(key_id), data).await
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        let store = Self::store()?;
+        let prefix = format!("{CONFIG_PREFIX}{SLASH_SEPARATOR}{KMS_KEYS_PREFIX}{SLASH_SEPARATOR}");
+
+        let mut ids = Vec::new();
+        let mut token = None;
+        loop {
+            let page = store
+                .clone()
+                .list_objects_v2(RUSTFS_META_BUCKET, &prefix, token, None, 1000, false, None)
+                .await?;
+            ids.extend(
+                page.objects
+                    .iter()
+                    .filter_map(|obj| obj.name.strip_prefix(&prefix)?.strip_suffix(KEY_FILE_SUFFIX))
+                    .map(str::to_owned),
+            );
+            if !page.is_truncated || page.next_continuation_token.is_none() {
+                break;
+            }
+            token = page.next_continuation_token;
+        }
+        Ok(ids)
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{RUSTFS_META_BUCKET}{SLASH_SEPARATOR}{CONFIG_PREFIX}{SLASH_SEPARATOR}{KMS_KEYS_PREFIX}")
+    }
+}
+
+/// A KMS keeping its master keys in memory and in a [`KeyStore`].
+///
+/// The root key is the default master key. Keys created through [`Kms::create_key`] are encrypted by
+/// the root key with [`rustfs_crypto::encrypt_data`] and written to the store. Keys missing from the
+/// memory cache are looked up in the store, which picks up the keys created by other nodes sharing it.
+/// Without a store only the root key is available.
+pub struct LocalKms {
+    root: MasterKey,
+    store: Option<Arc<dyn KeyStore>>,
+    keys: RwLock<HashMap<String, [u8; 32]>>,
+}
+
+impl std::fmt::Debug for LocalKms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalKms")
+            .field("root", &self.root)
+            .field("store", &self.store)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalKms {
+    /// Opens the key store, loading the keys already stored in it.
+    pub async fn new(root: MasterKey, store: Option<Arc<dyn KeyStore>>) -> Result<Self> {
+        let kms = Self {
+            root,
+            store,
+            keys: RwLock::new(HashMap::new()),
+        };
+
+        if let Some(store) = &kms.store {
+            let mut keys = HashMap::new();
+            for key_id in store.list().await? {
+                if check_key_id(&key_id).is_err() || key_id == kms.root.id {
+                    continue;
+                }
+                if let Some(key) = kms.load_key(store.as_ref(), &key_id).await? {
+                    keys.insert(key_id, key);
+                }
+            }
+
+            info!("loaded {} KMS keys from {}", keys.len(), store.endpoint());
+            *kms.keys.write().await = keys;
+        }
+
+        Ok(kms)
+    }
+
+    /// Reads and unseals the key `key_id` from `store`.
+    async fn load_key(&self, store: &dyn KeyStore, key_id: &str) -> Result<Option<[u8; 32]>> {
+        let Some(data) = store.read(key_id).await? else {
+            return Ok(None);
+        };
+        let key = rustfs_crypto::decrypt_data(&self.root.key, &data)
+            .map_err(|e| Error::other(format!("failed to decrypt KMS key {key_id}, is the root key correct? {e}")))?;
+        let key: [u8; 32] = key
+            .try_into()
+            .map_err(|_| Error::other(format!("KMS key {key_id} is corrupted")))?;
+        Ok(Some(key))
+    }
+
+    /// Looks up a key created by another node and caches it.
+    async fn fetch_key(&self, key_id: &str) -> Result<Option<[u8; 32]>> {
+        let Some(store) = &self.store else {
+            return Ok(None);
+        };
+        if check_key_id(key_id).is_err() {
+            return Ok(None);
+        }
+
+        let key = self.load_key(store.as_ref(), key_id).await?;
+        if let Some(key) = key {
+            self.keys.write().await.entry(key_id.to_owned()).or_insert(key);
+        }
+        Ok(key)
+    }
+
+    async fn master_key(&self, key_id: &str) -> Result<[u8; 32]> {
+        if key_id == self.root.id {
+            return Ok(self.root.key);
+        }
+        if let Some(key) = self.keys.read().await.get(key_id) {
+            return Ok(*key);
+        }
+        self.fetch_key(key_id)
+            .await?
+            .ok_or_else(|| Error::KmsKeyNotFound(key_id.to_owned()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Kms for LocalKms {
+    fn status(&self) -> KmsStatus {
+        KmsStatus {
+            name: "local".to_owned(),
+            default_key_id: self.root.id.clone(),
+            endpoints: self.store.iter().map(|store| store.endpoint()).collect(),
+        }
+    }
+
+    fn default_key_id(&self) -> &str {
+        &self.root.id
+    }
+
+    async fn create_key(&self, key_id: &str) -> Result<()> {
+        check_key_id(key_id)?;
+        let Some(store) = &self.store else {
+            return Err(Error::other("KMS key store is not configured"));
+        };
+
+        let mut keys = self.keys.write().await;
+        if key_id == self.root.id || keys.contains_key(key_id) || store.read(key_id).await?.is_some() {
+            return Err(Error::KmsKeyExists(key_id.to_owned()));
+        }
+
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+        let data = rustfs_crypto::encrypt_data(&self.root.key, &key).map_err(Error::other)?;
+        store.write(key_id, data).await?;
+
+        keys.insert(key_id.to_owned(), key);
+        Ok(())
+    }
+
+    async fn key_exists(&self, key_id: &str) -> Result<bool> {
+        if key_id == self.root.id || self.keys.read().await.contains_key(key_id) {
+            return Ok(true);
+        }
+        Ok(self.fetch_key(key_id).await?.is_some())
+    }
+
+    async fn list_keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let pattern = if pattern.is_empty() { "*" } else { pattern };
+        let pattern = glob::Pattern::new(pattern).map_err(Error::other)?;
+
+        let mut ids = match &self.store {
+            Some(store) => store.list().await?,
+            None => Vec::new(),
+        };
+        ids.retain(|id| check_key_id(id).is_ok() && *id != self.root.id);
+        ids.push(self.root.id.clone());
+        ids.retain(|id| pattern.matches(id));
+        ids.sort();
+        Ok(ids)
+    }
+
+    async fn generate_key(&self, key_id: &str, context: &[u8]) -> Result<DataKey> {
+        let master = self.master_key(key_id).await?;
+
+        let mut plaintext = [0u8; 32];
+        rand::rng().fill_bytes(&mut plaintext);
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+
+        let cipher = Aes256Gcm::new_from_slice(&master).map_err(Error::other)?;
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: context,
+                },
+            )
+            .map_err(|e| Error::other(format!("seal data key: {e}")))?;
+
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.extend_from_slice(&sealed);
+
+        Ok(DataKey {
+            key_id: key_id.to_owned(),
+            plaintext,
+            ciphertext,
+        })
+    }
+
+    async fn decrypt_key(&self, key_id: &str, ciphertext: &[u8], context: &[u8]) -> Result<[u8; 32]> {
+        let master = self.master_key(key_id).await?;
+        if ciphertext.len() <= 12 {
+            return Err(Error::other("invalid data key ciphertext"));
+        }
+
+        let cipher = Aes256Gcm::new_from_slice(&master).map_err(Error::other)?;
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&ciphertext[..12]),
+                Payload {
+                    msg: &ciphertext[12..],
+                    aad: context,
+                },
+            )
+            .map_err(|_| Error::other(format!("failed to decrypt data key with KMS key {key_id}")))?;
+
+        plaintext.try_into().map_err(|_| Error::other("invalid data key length"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// A store shared by several [`LocalKms`] instances, standing in for the cluster config.
+    #[derive(Debug, Default)]
+    struct MemKeyStore(Mutex<HashMap<String, Vec<u8>>>);
+
+    #[async_trait::async_trait]
+    impl KeyStore for MemKeyStore {
+        async fn read(&self, key_id: &str) -> Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(key_id).cloned())
+        }
+
+        async fn write(&self, key_id: &str, data: Vec<u8>) -> Result<()> {
+            self.0.lock().unwrap().insert(key_id.to_owned(), data);
+            Ok(())
+        }
+
+        async fn list(&self) -> Result<Vec<String>> {
+            Ok(self.0.lock().unwrap().keys().cloned().collect())
+        }
+
+        fn endpoint(&self) -> String {
+            "memory".to_owned()
+        }
+    }
+
+    async fn dir_store(dir: &std::path::Path) -> Option<Arc<dyn KeyStore>> {
+        Some(Arc::new(DirKeyStore::new(dir.to_owned()).await.unwrap()))
+    }
+
+    fn root() -> MasterKey {
+        MasterKey::parse("root-key:AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_local_kms_data_keys() {
+        let kms = LocalKms::new(root(), None).await.unwrap();
+        assert_eq!(kms.default_key_id(), "root-key");
+
+        let key = kms.generate_key("root-key", b"ctx").await.unwrap();
+        let plaintext = kms.decrypt_key("root-key", &key.ciphertext, b"ctx").await.unwrap();
+        assert_eq!(plaintext, key.plaintext);
+
+        assert!(kms.decrypt_key("root-key", &key.ciphertext, b"other").await.is_err());
+        assert!(matches!(kms.generate_key("missing", b"ctx").await, Err(Error::KmsKeyNotFound(_))));
+        assert!(kms.create_key("new-key").await.is_err());
+
+        let status = kms.key_status("root-key").await.unwrap();
+        assert!(status.encryption_err.is_none() && status.decryption_err.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_local_kms_persists_keys() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let kms = LocalKms::new(root(), dir_store(dir.path()).await).await.unwrap();
+        kms.create_key("app-1").await.unwrap();
+        assert!(matches!(kms.create_key("app-1").await, Err(Error::KmsKeyExists(_))));
+        assert!(matches!(kms.create_key("root-key").await, Err(Error::KmsKeyExists(_))));
+        assert!(kms.create_key("../escape").await.is_err());
+        let key = kms.generate_key("app-1", b"ctx").await.unwrap();
+
+        let reopened = LocalKms::new(root(), dir_store(dir.path()).await).await.unwrap();
+        assert_eq!(reopened.list_keys("*").await.unwrap(), vec!["app-1", "root-key"]);
+        assert_eq!(reopened.list_keys("app-*").await.unwrap(), vec!["app-1"]);
+        let plaintext = reopened.decrypt_key("app-1", &key.ciphertext, b"ctx").await.unwrap();
+        assert_eq!(plaintext, key.plaintext);
+
+        let other_root = MasterKey::parse("root-key:HxwdHh8AAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRo=").unwrap();
+        assert!(LocalKms::new(other_root, dir_store(dir.path()).await).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_kms_shares_keys_between_nodes() {
+        let store: Arc<dyn KeyStore> = Arc::new(MemKeyStore::default());
+        let node1 = LocalKms::new(root(), Some(store.clone())).await.unwrap();
+        let node2 = LocalKms::new(root(), Some(store.clone())).await.unwrap();
+
+        // The key is created on node1 after node2 loaded the store.
+        node1.create_key("app-1").await.unwrap();
+        assert!(store.read("app-1").await.unwrap().is_some_and(|data| data.len() > 32));
+        let key = node1.generate_key("app-1", b"ctx").await.unwrap();
+
+        assert!(node2.key_exists("app-1").await.unwrap());
+        assert_eq!(node2.list_keys("*").await.unwrap(), vec!["app-1", "root-key"]);
+        let plaintext = node2.decrypt_key("app-1", &key.ciphertext, b"ctx").await.unwrap();
+        assert_eq!(plaintext, key.plaintext);
+        assert!(matches!(node2.create_key("app-1").await, Err(Error::KmsKeyExists(_))));
+        assert!(!node2.key_exists("missing").await.unwrap());
+    }
+}
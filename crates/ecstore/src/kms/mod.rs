@@ -0,0 +1,168 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key management for server-side encryption.
+//!
+//! A [`Kms`] owns named master keys and uses them to generate and unseal data encryption keys,
+//! the master keys themselves never leave the KMS. [`LocalKms`] is the built-in backend, other
+//! backends only have to implement the trait and be installed with [`init_global_kms`].
+
+mod local;
+
+pub use local::{ClusterKeyStore, DirKeyStore, KeyStore, LocalKms};
+
+use crate::error::{Error, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+/// A 256-bit master key, e.g. the root key of [`LocalKms`].
+pub struct MasterKey {
+    id: String,
+    key: [u8; 32],
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MasterKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+impl MasterKey {
+    /// Parses a master key in the `<key-id>:<base64 encoded 32 bytes>` form.
+    pub fn parse(s: &str) -> Result<Self> {
+        let Some((id, key)) = s.split_once(':') else {
+            return Err(Error::other("invalid master key: expected <key-id>:<base64-key>"));
+        };
+        check_key_id(id)?;
+        let key = general_purpose::STANDARD
+            .decode(key.trim())
+            .map_err(|e| Error::other(format!("invalid master key: {e}")))?;
+        let key: [u8; 32] = key
+            .try_into()
+            .map_err(|_| Error::other("invalid master key: key must be 32 bytes"))?;
+
+        Ok(Self { id: id.to_owned(), key })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// A data encryption key generated by a [`Kms`].
+pub struct DataKey {
+    pub key_id: String,
+    pub plaintext: [u8; 32],
+    /// The key sealed by the master key `key_id`, to be stored next to the encrypted data.
+    pub ciphertext: Vec<u8>,
+}
+
+impl std::fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataKey")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KmsStatus {
+    pub name: String,
+    #[serde(rename = "defaultKeyID")]
+    pub default_key_id: String,
+    pub endpoints: Vec<String>,
+}
+
+/// Result of a generate/decrypt round trip with a master key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyStatus {
+    #[serde(rename = "key-id")]
+    pub key_id: String,
+    #[serde(rename = "encryption-error", default, skip_serializing_if = "Option::is_none")]
+    pub encryption_err: Option<String>,
+    #[serde(rename = "decryption-error", default, skip_serializing_if = "Option::is_none")]
+    pub decryption_err: Option<String>,
+}
+
+#[async_trait::async_trait]
+pub trait Kms: Debug + Send + Sync + 'static {
+    fn status(&self) -> KmsStatus;
+
+    /// The master key used when a request does not name one.
+    fn default_key_id(&self) -> &str;
+
+    /// Creates a new master key, failing with [`Error::KmsKeyExists`] if `key_id` is taken.
+    async fn create_key(&self, key_id: &str) -> Result<()>;
+
+    async fn key_exists(&self, key_id: &str) -> Result<bool>;
+
+    /// Lists the master key ids matching the glob `pattern`.
+    async fn list_keys(&self, pattern: &str) -> Result<Vec<String>>;
+
+    /// Generates a data key sealed by the master key `key_id`. The same `context` has to be
+    /// passed to [`Kms::decrypt_key`] to unseal it.
+    async fn generate_key(&self, key_id: &str, context: &[u8]) -> Result<DataKey>;
+
+    async fn decrypt_key(&self, key_id: &str, ciphertext: &[u8], context: &[u8]) -> Result<[u8; 32]>;
+
+    async fn key_status(&self, key_id: &str) -> Result<KeyStatus> {
+        if !self.key_exists(key_id).await? {
+            return Err(Error::KmsKeyNotFound(key_id.to_owned()));
+        }
+
+        let mut status = KeyStatus {
+            key_id: key_id.to_owned(),
+            ..Default::default()
+        };
+        let context = b"key-status";
+        match self.generate_key(key_id, context).await {
+            Ok(key) => match self.decrypt_key(key_id, &key.ciphertext, context).await {
+                Ok(plaintext) if plaintext == key.plaintext => {}
+                Ok(_) => status.decryption_err = Some("decrypted key does not match the generated key".to_owned()),
+                Err(e) => status.decryption_err = Some(e.to_string()),
+            },
+            Err(e) => status.encryption_err = Some(e.to_string()),
+        }
+        Ok(status)
+    }
+}
+
+/// Key ids are used as file names by [`LocalKms`], so they are restricted to a safe alphabet.
+pub fn check_key_id(key_id: &str) -> Result<()> {
+    let valid = !key_id.is_empty()
+        && key_id.len() <= 128
+        && !key_id.starts_with('.')
+        && key_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(Error::other(format!("invalid KMS key id: {key_id:?}")));
+    }
+    Ok(())
+}
+
+static GLOBAL_KMS: OnceLock<Arc<dyn Kms>> = OnceLock::new();
+
+pub fn init_global_kms(kms: Arc<dyn Kms>) {
+    if GLOBAL_KMS.set(kms).is_err() {
+        tracing::warn!("KMS is already initialized");
+    }
+}
+
+pub fn get_global_kms() -> Option<Arc<dyn Kms>> {
+    GLOBAL_KMS.get().cloned()
+}
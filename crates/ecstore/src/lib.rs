@@ -25,16 +25,20 @@ pub mod compress;
 pub mod config;
 pub mod disk;
 pub mod disks_layout;
+pub mod encryption;
 pub mod endpoints;
 pub mod erasure_coding;
 pub mod error;
 pub mod global;
 pub mod heal;
+pub mod inspect;
+pub mod kms;
 pub mod metrics_realtime;
 pub mod notification_sys;
 pub mod pools;
 pub mod rebalance;
 pub mod rpc;
+pub mod service_signal;
 pub mod set_disk;
 mod sets;
 pub mod store;
@@ -48,6 +52,7 @@ pub mod client;
 pub mod event;
 pub mod event_notification;
 pub mod tier;
+pub mod trace;
 
 pub use global::new_object_layer_fn;
 pub use global::set_global_endpoints;
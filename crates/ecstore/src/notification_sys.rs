@@ -16,7 +16,9 @@ use crate::StorageAPI;
 use crate::admin_server_info::get_commit_id;
 use crate::error::{Error, Result};
 use crate::global::{GLOBAL_BOOT_TIME, get_global_endpoints};
+use crate::heal::heal_commands::BgHealState;
 use crate::rpc::PeerRestClient;
+use crate::service_signal::ServiceSignal;
 use crate::{endpoints::EndpointServerPools, new_object_layer_fn};
 use futures::future::join_all;
 use lazy_static::lazy_static;
@@ -154,6 +156,20 @@ impl NotificationSys {
         }
     }
 
+    pub async fn reload_site_replication_config(&self) {
+        let mut futures = Vec::with_capacity(self.peer_clients.len());
+        for client in self.peer_clients.iter().flatten() {
+            futures.push(client.reload_site_replication_config());
+        }
+
+        let results = join_all(futures).await;
+        for result in results {
+            if let Err(err) = result {
+                error!("notification reload_site_replication_config err {:?}", err);
+            }
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn load_rebalance_meta(&self, start: bool) {
         let mut futures = Vec::with_capacity(self.peer_clients.len());
@@ -202,6 +218,48 @@ impl NotificationSys {
         let _ = store.stop_rebalance().await;
         warn!("notification stop_rebalance stop_rebalance done");
     }
+
+    /// Returns the background heal state reported by every peer, keyed by the peer host.
+    pub async fn background_heal_status(&self) -> Vec<(String, Result<BgHealState>)> {
+        let mut futures = Vec::with_capacity(self.peer_clients.len());
+        for client in self.peer_clients.iter().flatten() {
+            futures.push(async move { (client.host.to_string(), client.background_heal_status().await) });
+        }
+
+        join_all(futures).await
+    }
+
+    /// Sends a service signal to every peer, the local node is not signalled.
+    pub async fn signal_service(&self, sig: ServiceSignal, dry_run: bool) -> Vec<NotificationPeerErr> {
+        self.signal_service_sub_sys(sig, "", dry_run).await
+    }
+
+    /// Asks every peer to reload `sub_sys` from the stored server config.
+    pub async fn reload_dynamic(&self, sub_sys: &str) -> Vec<NotificationPeerErr> {
+        self.signal_service_sub_sys(ServiceSignal::ReloadDynamic, sub_sys, false)
+            .await
+    }
+
+    async fn signal_service_sub_sys(&self, sig: ServiceSignal, sub_sys: &str, dry_run: bool) -> Vec<NotificationPeerErr> {
+        let mut futures = Vec::with_capacity(self.peer_clients.len());
+        for client in self.peer_clients.iter().flatten() {
+            futures.push(async move {
+                let err = client
+                    .signal_service(sig as u64, sub_sys, dry_run, SystemTime::now())
+                    .await
+                    .err();
+                if let Some(err) = &err {
+                    error!("notification signal_service {:?} to {} err {:?}", sig, client.host, err);
+                }
+                NotificationPeerErr {
+                    host: client.host.to_string(),
+                    err,
+                }
+            });
+        }
+
+        join_all(futures).await
+    }
 }
 
 fn get_offline_disks(offline_host: &str, endpoints: &EndpointServerPools) -> Vec<rustfs_madmin::Disk> {
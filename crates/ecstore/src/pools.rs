@@ -1264,6 +1264,7 @@ impl ECStore {
                 parts[i] = CompletePart {
                     part_num: pi.part_num,
                     etag: pi.etag,
+                    ..Default::default()
                 };
             }
 
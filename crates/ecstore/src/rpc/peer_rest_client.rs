@@ -25,6 +25,8 @@ use rustfs_madmin::{
     health::{Cpus, MemInfo, OsInfo, Partitions, ProcInfo, SysConfig, SysErrors, SysService},
     metrics::RealtimeMetrics,
     net::NetInfo,
+    service_commands::ServiceTraceOpts,
+    trace::TraceInfo,
 };
 use rustfs_protos::{
     node_service_time_out_client,
@@ -35,12 +37,13 @@ use rustfs_protos::{
         LoadBucketMetadataRequest, LoadGroupRequest, LoadPolicyMappingRequest, LoadPolicyRequest, LoadRebalanceMetaRequest,
         LoadServiceAccountRequest, LoadTransitionTierConfigRequest, LoadUserRequest, LocalStorageInfoRequest, Mss,
         ReloadPoolMetaRequest, ReloadSiteReplicationConfigRequest, ServerInfoRequest, SignalServiceRequest,
-        StartProfilingRequest, StopRebalanceRequest,
+        StartProfilingRequest, StopRebalanceRequest, TraceRequest,
     },
 };
 use rustfs_utils::XHost;
 use serde::{Deserialize, Serialize as _};
 use std::{collections::HashMap, io::Cursor, time::SystemTime};
+use tokio::sync::mpsc;
 use tonic::Request;
 use tracing::warn;
 
@@ -622,6 +625,35 @@ impl PeerRestClient {
         Ok(bg_heal_state)
     }
 
+    /// Forwards the trace events of the peer matching `opts` to `tx` until either side goes away.
+    pub async fn trace(&self, opts: &ServiceTraceOpts, tx: mpsc::Sender<TraceInfo>) -> Result<()> {
+        let mut client = node_service_time_out_client(&self.grid_host)
+            .await
+            .map_err(|err| Error::other(err.to_string()))?;
+        let request = Request::new(TraceRequest {
+            opts: serde_json::to_vec(opts)?.into(),
+        });
+
+        let mut response = client.trace(request).await?.into_inner();
+        loop {
+            let resp = tokio::select! {
+                _ = tx.closed() => return Ok(()),
+                resp = response.message() => resp?,
+            };
+            let Some(resp) = resp else {
+                return Ok(());
+            };
+            if !resp.success {
+                return Err(Error::other(resp.error_info.unwrap_or_default()));
+            }
+
+            let info: TraceInfo = serde_json::from_str(&resp.trace_info)?;
+            if tx.send(info).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
     pub async fn get_metacache_listing(&self) -> Result<()> {
         let _client = node_service_time_out_client(&self.grid_host)
             .await
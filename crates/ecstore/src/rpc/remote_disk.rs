@@ -34,7 +34,7 @@ use crate::disk::{
 };
 use crate::{
     disk::error::{Error, Result},
-    rpc::build_auth_headers,
+    rpc::{build_auth_headers, build_streaming_auth_headers},
 };
 use crate::{
     disk::{FileReader, FileWriter},
@@ -47,11 +47,8 @@ use crate::{
 use rustfs_filemeta::{FileInfo, ObjectPartInfo, RawFileInfo};
 use rustfs_protos::proto_gen::node_service::RenamePartRequest;
 use rustfs_rio::{HttpReader, HttpWriter};
-use tokio::{
-    io::AsyncWrite,
-    sync::mpsc::{self, Sender},
-};
-use tokio_stream::{StreamExt, wrappers::ReceiverStream};
+use tokio::{io::AsyncWrite, sync::mpsc::Sender};
+use tokio_stream::StreamExt;
 use tonic::Request;
 use tracing::info;
 use uuid::Uuid;
@@ -594,7 +591,7 @@ impl DiskAPI for RemoteDisk {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        build_auth_headers(&url, &Method::GET, &mut headers);
+        build_auth_headers(&url, &Method::GET, &mut headers, &opts)?;
 
         let mut reader = HttpReader::new(url, Method::GET, headers, Some(opts)).await?;
 
@@ -619,7 +616,7 @@ impl DiskAPI for RemoteDisk {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        build_auth_headers(&url, &Method::GET, &mut headers);
+        build_auth_headers(&url, &Method::GET, &mut headers, &[])?;
         Ok(Box::new(HttpReader::new(url, Method::GET, headers, None).await?))
     }
 
@@ -645,7 +642,7 @@ impl DiskAPI for RemoteDisk {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        build_auth_headers(&url, &Method::GET, &mut headers);
+        build_auth_headers(&url, &Method::GET, &mut headers, &[])?;
         Ok(Box::new(HttpReader::new(url, Method::GET, headers, None).await?))
     }
 
@@ -665,8 +662,11 @@ impl DiskAPI for RemoteDisk {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        build_auth_headers(&url, &Method::PUT, &mut headers);
-        Ok(Box::new(HttpWriter::new(url, Method::PUT, headers).await?))
+        // The writability probe is a request of its own and needs its own nonce.
+        let mut probe_headers = headers.clone();
+        build_streaming_auth_headers(&url, &Method::PUT, &mut probe_headers)?;
+        build_streaming_auth_headers(&url, &Method::PUT, &mut headers)?;
+        Ok(Box::new(HttpWriter::with_probe_headers(url, Method::PUT, probe_headers, headers).await?))
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
@@ -691,8 +691,11 @@ impl DiskAPI for RemoteDisk {
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        build_auth_headers(&url, &Method::PUT, &mut headers);
-        Ok(Box::new(HttpWriter::new(url, Method::PUT, headers).await?))
+        // The writability probe is a request of its own and needs its own nonce.
+        let mut probe_headers = headers.clone();
+        build_streaming_auth_headers(&url, &Method::PUT, &mut probe_headers)?;
+        build_streaming_auth_headers(&url, &Method::PUT, &mut headers)?;
+        Ok(Box::new(HttpWriter::with_probe_headers(url, Method::PUT, probe_headers, headers).await?))
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
@@ -942,17 +945,14 @@ impl DiskAPI for RemoteDisk {
             .await
             .map_err(|err| Error::other(format!("can not get client, err: {err}")))?;
 
-        let (tx, rx) = mpsc::channel(10);
-        let in_stream = ReceiverStream::new(rx);
-        let mut response = client.ns_scanner(in_stream).await?.into_inner();
         let request = NsScannerRequest {
             disk: self.endpoint.to_string(),
             cache,
             scan_mode: scan_mode as u64,
         };
-        tx.send(request)
-            .await
-            .map_err(|err| Error::other(format!("can not send request, err: {err}")))?;
+        // The request stream is complete before the call, internode calls are signed over the whole body.
+        let in_stream = tokio_stream::once(request);
+        let mut response = client.ns_scanner(in_stream).await?.into_inner();
 
         loop {
             match response.next().await {
@@ -18,6 +18,8 @@ use std::{collections::HashMap, io::Cursor, pin::Pin};
 use crate::{
     admin_server_info::get_local_server_property,
     bucket::{metadata::load_bucket_metadata, metadata_sys},
+    cmd::site_replication::GLOBAL_SITE_REPLICATION_SYS,
+    config::GLOBAL_ConfigSys,
     disk::{
         DeleteOptions, DiskAPI, DiskInfoOptions, DiskStore, FileInfoVersions, ReadMultipleReq, ReadOptions, UpdateMetadataOpts,
         error::DiskError,
@@ -28,9 +30,14 @@ use crate::{
     },
     metrics_realtime::{CollectMetricsOpts, MetricType, collect_local_metrics},
     new_object_layer_fn,
-    rpc::{LocalPeerS3Client, PeerS3Client},
+    rpc::{
+        LocalPeerS3Client, PeerS3Client,
+        peer_rest_client::{PEER_RESTDRY_RUN, PEER_RESTSIGNAL, PEER_RESTSUB_SYS},
+    },
+    service_signal::{ServiceSignal, send_service_signal},
     store::{all_local_disk_path, find_local_disk},
     store_api::{BucketOptions, DeleteBucketOptions, MakeBucketOptions, StorageAPI},
+    trace::GLOBAL_TRACE,
 };
 use futures::{Stream, StreamExt};
 use futures_util::future::join_all;
@@ -45,6 +52,7 @@ use rustfs_madmin::health::{
     get_cpus, get_mem_info, get_os_info, get_partitions, get_proc_info, get_sys_config, get_sys_errors, get_sys_services,
 };
 use rustfs_madmin::net::get_net_info;
+use rustfs_madmin::service_commands::ServiceTraceOpts;
 use rustfs_protos::{
     models::{PingBody, PingBodyBuilder},
     proto_gen::node_service::{node_service_server::NodeService as Node, *},
@@ -2135,39 +2143,83 @@ impl Node for NodeService {
         &self,
         _request: Request<ReloadSiteReplicationConfigRequest>,
     ) -> Result<Response<ReloadSiteReplicationConfigResponse>, Status> {
-        let Some(_store) = new_object_layer_fn() else {
+        let Some(store) = new_object_layer_fn() else {
             return Ok(tonic::Response::new(ReloadSiteReplicationConfigResponse {
                 success: false,
                 error_info: Some("errServerNotInitialized".to_string()),
             }));
         };
-        todo!()
+
+        match GLOBAL_SITE_REPLICATION_SYS.reload(store).await {
+            Ok(()) => Ok(tonic::Response::new(ReloadSiteReplicationConfigResponse {
+                success: true,
+                error_info: None,
+            })),
+            Err(err) => Ok(tonic::Response::new(ReloadSiteReplicationConfigResponse {
+                success: false,
+                error_info: Some(err.to_string()),
+            })),
+        }
     }
 
     async fn signal_service(&self, request: Request<SignalServiceRequest>) -> Result<Response<SignalServiceResponse>, Status> {
         let request = request.into_inner();
-        let _vars = match request.vars {
+        let vars = match request.vars {
             Some(vars) => vars.value,
             None => HashMap::new(),
         };
-        todo!()
+
+        let Some(signal) = vars
+            .get(PEER_RESTSIGNAL)
+            .and_then(|sig| sig.parse::<u64>().ok())
+            .and_then(ServiceSignal::from_u64)
+        else {
+            return Ok(tonic::Response::new(SignalServiceResponse {
+                success: false,
+                error_info: Some(format!("unsupported service signal: {:?}", vars.get(PEER_RESTSIGNAL))),
+            }));
+        };
+
+        if vars.get(PEER_RESTDRY_RUN).is_some_and(|v| v == "true") {
+            return Ok(tonic::Response::new(SignalServiceResponse {
+                success: true,
+                error_info: None,
+            }));
+        }
+
+        let res = if signal == ServiceSignal::ReloadDynamic {
+            let Some(store) = new_object_layer_fn() else {
+                return Ok(tonic::Response::new(SignalServiceResponse {
+                    success: false,
+                    error_info: Some("errServerNotInitialized".to_string()),
+                }));
+            };
+            let sub_sys = vars.get(PEER_RESTSUB_SYS).map(String::as_str).unwrap_or_default();
+            GLOBAL_ConfigSys.reload_dynamic(store, sub_sys).await
+        } else {
+            send_service_signal(signal)
+        };
+
+        match res {
+            Ok(()) => Ok(tonic::Response::new(SignalServiceResponse {
+                success: true,
+                error_info: None,
+            })),
+            Err(err) => Ok(tonic::Response::new(SignalServiceResponse {
+                success: false,
+                error_info: Some(err.to_string()),
+            })),
+        }
     }
 
     async fn background_heal_status(
         &self,
         _request: Request<BackgroundHealStatusRequest>,
     ) -> Result<Response<BackgroundHealStatusResponse>, Status> {
-        let (state, ok) = get_local_background_heal_status().await;
-        if !ok {
-            return Ok(tonic::Response::new(BackgroundHealStatusResponse {
-                success: false,
-                bg_heal_state: Bytes::new(),
-                error_info: Some("errServerNotInitialized".to_string()),
-            }));
-        }
+        let state = get_local_background_heal_status().await;
 
         let mut buf = Vec::new();
-        if let Err(err) = state.serialize(&mut Serializer::new(&mut buf)) {
+        if let Err(err) = state.serialize(&mut Serializer::new(&mut buf).with_struct_map()) {
             return Ok(tonic::Response::new(BackgroundHealStatusResponse {
                 success: false,
                 bg_heal_state: Bytes::new(),
@@ -2275,6 +2327,31 @@ impl Node for NodeService {
     ) -> Result<Response<LoadTransitionTierConfigResponse>, Status> {
         todo!()
     }
+
+    type TraceStream = ResponseStream<TraceResponse>;
+    async fn trace(&self, request: Request<TraceRequest>) -> Result<Response<Self::TraceStream>, Status> {
+        let request = request.into_inner();
+        let opts: ServiceTraceOpts =
+            serde_json::from_slice(&request.opts).map_err(|_| Status::invalid_argument("invalid ServiceTraceOpts"))?;
+
+        let rx = GLOBAL_TRACE.subscribe(opts);
+        let out_stream = ReceiverStream::new(rx)
+            .map(|info| match serde_json::to_string(&info) {
+                Ok(trace_info) => TraceResponse {
+                    success: true,
+                    trace_info,
+                    error_info: None,
+                },
+                Err(err) => TraceResponse {
+                    success: false,
+                    trace_info: String::new(),
+                    error_info: Some(err.to_string()),
+                },
+            })
+            .map(Ok);
+
+        Ok(Response::new(Box::pin(out_stream)))
+    }
 }
 
 #[cfg(test)]
@@ -3627,7 +3704,20 @@ mod tests {
         assert!(reload_response.error_info.is_some());
     }
 
-    // Note: signal_service test is skipped because it contains todo!() and would panic
+    #[tokio::test]
+    async fn test_signal_service_invalid_signal() {
+        let service = create_test_node_service();
+
+        let mut vars = HashMap::new();
+        vars.insert(PEER_RESTSIGNAL.to_string(), "0".to_string());
+        let request = Request::new(SignalServiceRequest {
+            vars: Some(Mss { value: vars }),
+        });
+
+        let response = service.signal_service(request).await.unwrap().into_inner();
+        assert!(!response.success);
+        assert!(response.error_info.is_some());
+    }
 
     #[test]
     fn test_node_service_debug() {
@@ -0,0 +1,88 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Service signals sent by the admin service API.
+//!
+//! The admin handler and the `SignalService` node RPC only publish a [`ServiceSignal`] here, the
+//! server process subscribes with [`subscribe_service_signal`] and performs the restart, stop,
+//! freeze or unfreeze itself. [`ServiceSignal::ReloadDynamic`] is only sent between nodes, the
+//! receiving node reloads the named config subsystem instead of publishing the signal.
+
+use crate::error::{Error, Result};
+use rustfs_madmin::service_commands::ServiceAction;
+use std::sync::LazyLock;
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum ServiceSignal {
+    Restart = 1,
+    Stop = 2,
+    Freeze = 3,
+    Unfreeze = 4,
+    ReloadDynamic = 5,
+}
+
+impl ServiceSignal {
+    pub fn from_u64(sig: u64) -> Option<Self> {
+        match sig {
+            1 => Some(ServiceSignal::Restart),
+            2 => Some(ServiceSignal::Stop),
+            3 => Some(ServiceSignal::Freeze),
+            4 => Some(ServiceSignal::Unfreeze),
+            5 => Some(ServiceSignal::ReloadDynamic),
+            _ => None,
+        }
+    }
+}
+
+impl From<ServiceAction> for ServiceSignal {
+    fn from(action: ServiceAction) -> Self {
+        match action {
+            ServiceAction::Restart => ServiceSignal::Restart,
+            ServiceAction::Stop => ServiceSignal::Stop,
+            ServiceAction::Freeze => ServiceSignal::Freeze,
+            ServiceAction::Unfreeze => ServiceSignal::Unfreeze,
+        }
+    }
+}
+
+static GLOBAL_SERVICE_SIGNAL: LazyLock<broadcast::Sender<ServiceSignal>> = LazyLock::new(|| broadcast::channel(16).0);
+
+pub fn subscribe_service_signal() -> broadcast::Receiver<ServiceSignal> {
+    GLOBAL_SERVICE_SIGNAL.subscribe()
+}
+
+/// Delivers `sig` to the local server, failing if nothing is listening for service signals.
+pub fn send_service_signal(sig: ServiceSignal) -> Result<()> {
+    GLOBAL_SERVICE_SIGNAL
+        .send(sig)
+        .map(|_| ())
+        .map_err(|_| Error::other("service signals are not handled by this server"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_service_signal() {
+        assert_eq!(ServiceSignal::from_u64(ServiceSignal::Freeze as u64), Some(ServiceSignal::Freeze));
+        assert_eq!(ServiceSignal::from_u64(0), None);
+
+        let mut rx = subscribe_service_signal();
+        send_service_signal(ServiceAction::Unfreeze.into()).unwrap();
+        assert_eq!(rx.recv().await.unwrap(), ServiceSignal::Unfreeze);
+    }
+}
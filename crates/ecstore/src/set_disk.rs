@@ -25,16 +25,17 @@ use crate::disk::{
 use crate::erasure_coding;
 use crate::erasure_coding::bitrot_verify;
 use crate::error::{Error, Result};
-use crate::error::{ObjectApiError, is_err_object_not_found};
+use crate::error::{ObjectApiError, is_err_object_not_found, is_err_version_not_found};
 use crate::global::GLOBAL_MRFState;
 use crate::global::{GLOBAL_LocalNodeName, GLOBAL_TierConfigMgr};
 use crate::heal::data_usage_cache::DataUsageCache;
 use crate::heal::heal_ops::{HealEntryFn, HealSequence};
 use crate::store_api::{ListPartsInfo, ObjectToDelete};
+use crate::tier::warm_backend::WarmBackendGetOpts;
 use crate::{
-    bucket::lifecycle::bucket_lifecycle_ops::{gen_transition_objname, get_transitioned_object_reader, put_restore_opts},
+    bucket::lifecycle::bucket_lifecycle_ops::{gen_transition_objname, put_restore_opts},
     cache_value::metacache_set::{ListPathRawOptions, list_path_raw},
-    config::{GLOBAL_StorageClass, storageclass},
+    config::{get_global_storage_class, storageclass},
     disk::{
         CheckPartsResp, DeleteOptions, DiskAPI, DiskInfo, DiskInfoOptions, DiskOption, DiskStore, FileInfoVersions,
         RUSTFS_META_BUCKET, RUSTFS_META_MULTIPART_BUCKET, RUSTFS_META_TMP_BUCKET, ReadMultipleReq, ReadMultipleResp, ReadOptions,
@@ -59,7 +60,7 @@ use crate::{
     store_api::{
         BucketInfo, BucketOptions, CompletePart, DeleteBucketOptions, DeletedObject, GetObjectReader, HTTPRangeSpec,
         ListMultipartsInfo, ListObjectsV2Info, MakeBucketOptions, MultipartInfo, MultipartUploadResult, ObjectIO, ObjectInfo,
-        ObjectOptions, PartInfo, PutObjReader, StorageAPI,
+        ObjectOptions, PartInfo, PutObjReader, StorageAPI, WritePreconditions,
     },
     store_init::load_format_erasure,
 };
@@ -80,12 +81,16 @@ use rustfs_filemeta::headers::RESERVED_METADATA_PREFIX_LOWER;
 use rustfs_filemeta::{
     FileInfo, FileMeta, FileMetaShallowVersion, MetaCacheEntries, MetaCacheEntry, MetadataResolutionParams, ObjectPartInfo,
     RawFileInfo, file_info_from_raw,
-    headers::{AMZ_OBJECT_TAGGING, AMZ_STORAGE_CLASS},
+    headers::{AMZ_OBJECT_TAGGING, AMZ_STORAGE_CLASS, RUSTFS_MULTIPART_CHECKSUM, RUSTFS_MULTIPART_CHECKSUM_TYPE},
     merge_file_meta_versions,
 };
-use rustfs_lock::{LockApi, namespace_lock::NsLockMap};
+use rustfs_lock::{
+    LockApi,
+    drwmutex::Options as LockOptions,
+    namespace_lock::{NsLockMap, WrapperLocker, new_nslock},
+};
 use rustfs_madmin::heal_commands::{HealDriveInfo, HealResultItem};
-use rustfs_rio::{EtagResolvable, HashReader, TryGetIndex as _, WarpReader};
+use rustfs_rio::{ChecksumMismatch, ChecksumType, EtagResolvable, HashReader, TryGetIndex as _, WarpReader};
 use rustfs_utils::{
     HashAlgorithm,
     crypto::{base64_decode, base64_encode, hex},
@@ -317,10 +322,25 @@ impl SetDisks {
             .filter(|v| v.as_ref().is_some_and(|d| d.is_local()))
             .collect()
     }
-    fn default_read_quorum(&self) -> usize {
+    /// Counts the online drives of the set, the local drives are left out when `exclude_local` is set.
+    pub async fn online_drive_count(&self, exclude_local: bool) -> usize {
+        let disks = self.get_disks_internal().await;
+        let mut online = 0;
+        for disk in disks.iter().flatten() {
+            if exclude_local && disk.is_local() {
+                continue;
+            }
+            if disk.is_online().await {
+                online += 1;
+            }
+        }
+        online
+    }
+
+    pub fn default_read_quorum(&self) -> usize {
         self.set_drive_count - self.default_parity_count
     }
-    fn default_write_quorum(&self) -> usize {
+    pub fn default_write_quorum(&self) -> usize {
         let mut data_count = self.set_drive_count - self.default_parity_count;
         if data_count == self.default_parity_count {
             data_count += 1
@@ -1996,6 +2016,70 @@ impl SetDisks {
         shuffled_disks
     }
 
+    /// Takes the namespace write lock on `bucket/object`; the lock is released when the guard is dropped.
+    async fn lock_object_for_write(&self, bucket: &str, object: &str) -> Result<WrapperLocker> {
+        let ns_lock = new_nslock(
+            Arc::clone(&self.ns_mutex),
+            self.locker_owner.clone(),
+            bucket.to_string(),
+            vec![object.to_string()],
+            self.lockers.clone(),
+        )
+        .await;
+        let locked = ns_lock
+            .0
+            .write()
+            .await
+            .get_lock(&LockOptions {
+                timeout: Duration::from_secs(5),
+                retry_interval: Duration::from_secs(1),
+            })
+            .await
+            .map_err(|err| Error::other(err.to_string()))?;
+        if !locked {
+            return Err(Error::SlowDown);
+        }
+
+        Ok(ns_lock)
+    }
+
+    /// Evaluates conditional write headers against the latest visible version of the object.
+    async fn check_write_preconditions(
+        &self,
+        bucket: &str,
+        object: &str,
+        preconditions: &WritePreconditions,
+        opts: &ObjectOptions,
+    ) -> Result<()> {
+        let current_opts = ObjectOptions {
+            versioned: opts.versioned,
+            version_suspended: opts.version_suspended,
+            no_lock: true,
+            ..Default::default()
+        };
+        let current = match self.get_object_info(bucket, object, &current_opts).await {
+            Ok(info) if !info.delete_marker => Some(info),
+            Ok(_) => None,
+            Err(err) if is_err_object_not_found(&err) || is_err_version_not_found(&err) => None,
+            Err(err) => return Err(err),
+        };
+
+        preconditions.check(bucket, object, current.as_ref())
+    }
+
+    /// Locks the object and re-checks the conditional write headers, so the check and the commit are atomic.
+    async fn lock_for_conditional_write(
+        &self,
+        bucket: &str,
+        object: &str,
+        preconditions: &WritePreconditions,
+        opts: &ObjectOptions,
+    ) -> Result<WrapperLocker> {
+        let lock = self.lock_object_for_write(bucket, object).await?;
+        self.check_write_preconditions(bucket, object, preconditions, opts).await?;
+        Ok(lock)
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     async fn get_object_fileinfo(
         &self,
@@ -2754,7 +2838,7 @@ impl SetDisks {
                                 }
 
                                 let is_inline_buffer = {
-                                    if let Some(sc) = GLOBAL_StorageClass.get() {
+                                    if let Some(sc) = get_global_storage_class() {
                                         sc.should_inline(erasure.shard_file_size(latest_meta.size), false)
                                     } else {
                                         false
@@ -3947,6 +4031,53 @@ impl SetDisks {
         Ok(())
     }
 
+    /// Copies the data of a transitioned version back from its tier into the same version.
+    async fn restore_from_tier(&self, bucket: &str, object: &str, oi: &ObjectInfo, opts: &ObjectOptions) -> Result<()> {
+        let reader = {
+            let mut tier_config_mgr = GLOBAL_TierConfigMgr.write().await;
+            let driver = tier_config_mgr
+                .get_driver(&oi.transitioned_object.tier)
+                .await
+                .map_err(|err| Error::other(err.to_string()))?;
+            driver
+                .get(
+                    &oi.transitioned_object.name,
+                    &oi.transitioned_object.version_id,
+                    WarmBackendGetOpts::default(),
+                )
+                .await
+                .map_err(StorageError::Io)?
+        };
+
+        let hr = HashReader::new(Box::new(WarpReader::new(reader)), oi.size, oi.size, None, false)?;
+        let mut data = PutObjReader::new(hr);
+        let mut ropts = put_restore_opts(bucket, object, &opts.transition.restore_request, oi);
+        ropts.versioned = opts.versioned;
+        ropts.version_suspended = opts.version_suspended;
+        self.put_object(bucket, object, &mut data, &ropts).await?;
+        Ok(())
+    }
+
+    /// Drops the local copy of a restored object; the transitioned version and its tier data stay.
+    async fn expire_restored_object(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        let _lock = self.lock_object_for_write(bucket, object).await?;
+        let (mut fi, _, _) = self
+            .get_object_fileinfo(bucket, object, opts, false)
+            .await
+            .map_err(|err| to_object_err(err, vec![bucket, object]))?;
+        if fi.transition_status != TRANSITION_COMPLETE {
+            return Err(Error::InvalidObjectState(bucket.to_owned(), object.to_owned()));
+        }
+
+        fi.expire_restored = true;
+        self.delete_object_version(bucket, object, &fi, false)
+            .await
+            .map_err(|err| to_object_err(err, vec![bucket, object]))?;
+
+        fi.metadata.remove(X_AMZ_RESTORE.as_str());
+        Ok(ObjectInfo::from_file_info(&fi, bucket, object, opts.versioned || opts.version_suspended))
+    }
+
     pub async fn update_restore_metadata(
         &self,
         bucket: &str,
@@ -3958,6 +4089,9 @@ impl SetDisks {
         oi.metadata_only = true;
 
         oi.user_defined.remove(X_AMZ_RESTORE.as_str());
+        if !oi.user_tags.is_empty() {
+            oi.user_defined.insert(AMZ_OBJECT_TAGGING.to_owned(), oi.user_tags.clone());
+        }
 
         let version_id = oi.version_id.map(|v| v.to_string());
         let obj = self
@@ -3969,10 +4103,13 @@ impl SetDisks {
                 &mut oi,
                 &ObjectOptions {
                     version_id: version_id.clone(),
+                    versioned: opts.versioned,
+                    version_suspended: opts.version_suspended,
                     ..Default::default()
                 },
                 &ObjectOptions {
                     version_id,
+                    mod_time: obj_info.mod_time,
                     ..Default::default()
                 },
             )
@@ -4006,6 +4143,11 @@ impl ObjectIO for SetDisks {
             return Err(to_object_err(Error::MethodNotAllowed, vec![bucket, object]));
         }
 
+        // Transitioned data has to be restored with RestoreObject before it can be read.
+        if fi.is_remote() {
+            return Err(Error::InvalidObjectState(bucket.to_owned(), object.to_owned()));
+        }
+
         // if object_info.size == 0 {
         //     let empty_rd: Box<dyn AsyncRead> = Box::new(Bytes::new());
 
@@ -4027,11 +4169,9 @@ impl ObjectIO for SetDisks {
             return Ok(reader);
         }
 
-        // TODO: remote
-
         let (rd, wd) = tokio::io::duplex(DEFAULT_READ_BUFFER_SIZE);
 
-        let (reader, offset, length) = GetObjectReader::new(Box::new(rd), range, &object_info, opts, &h)?;
+        let (reader, offset, length) = GetObjectReader::new(Box::new(rd), range, &object_info, opts, &h).await?;
 
         // let disks = disks.clone();
         let bucket = bucket.to_owned();
@@ -4064,6 +4204,11 @@ impl ObjectIO for SetDisks {
 
     #[tracing::instrument(level = "debug", skip(self, data,))]
     async fn put_object(&self, bucket: &str, object: &str, data: &mut PutObjReader, opts: &ObjectOptions) -> Result<ObjectInfo> {
+        // Fail fast before reading the body; the check is repeated under the lock before the commit.
+        if let Some(preconditions) = &opts.write_preconditions {
+            self.check_write_preconditions(bucket, object, preconditions, opts).await?;
+        }
+
         let disks = self.disks.read().await;
 
         // let mut _ns = None;
@@ -4097,7 +4242,7 @@ impl ObjectIO for SetDisks {
         let mut user_defined = opts.user_defined.clone();
 
         let sc_parity_drives = {
-            if let Some(sc) = GLOBAL_StorageClass.get() {
+            if let Some(sc) = get_global_storage_class() {
                 sc.get_parity_for_sc(user_defined.get(AMZ_STORAGE_CLASS).cloned().unwrap_or_default().as_str())
             } else {
                 None
@@ -4142,7 +4287,7 @@ impl ObjectIO for SetDisks {
         let erasure = erasure_coding::Erasure::new(fi.erasure.data_blocks, fi.erasure.parity_blocks, fi.erasure.block_size);
 
         let is_inline_buffer = {
-            if let Some(sc) = GLOBAL_StorageClass.get() {
+            if let Some(sc) = get_global_storage_class() {
                 sc.should_inline(erasure.shard_file_size(data.size()), opts.versioned)
             } else {
                 false
@@ -4234,7 +4379,10 @@ impl ObjectIO for SetDisks {
 
         //TODO: userDefined
 
-        let etag = data.stream.try_resolve_etag().unwrap_or_default();
+        let etag = match &opts.preserve_etag {
+            Some(etag) => etag.clone(),
+            None => data.stream.try_resolve_etag().unwrap_or_default(),
+        };
 
         user_defined.insert("etag".to_owned(), etag.clone());
 
@@ -4256,7 +4404,7 @@ impl ObjectIO for SetDisks {
             }
         }
 
-        let now = OffsetDateTime::now_utc();
+        let now = opts.mod_time.unwrap_or_else(OffsetDateTime::now_utc);
 
         for (i, fi) in parts_metadatas.iter_mut().enumerate() {
             if is_inline_buffer {
@@ -4272,6 +4420,7 @@ impl ObjectIO for SetDisks {
             fi.size = w_size as i64;
             fi.versioned = opts.versioned || opts.version_suspended;
             fi.add_object_part(1, etag.clone(), w_size, fi.mod_time, actual_size, index_op.clone());
+            fi.checksum = opts.checksum().map(|c| c.to_bytes().into());
 
             if opts.data_movement {
                 fi.set_data_moved();
@@ -4280,6 +4429,17 @@ impl ObjectIO for SetDisks {
 
         drop(writers); // drop writers to close all files, this is to prevent FileAccessDenied errors when renaming data
 
+        let _write_lock = match &opts.write_preconditions {
+            Some(preconditions) => match self.lock_for_conditional_write(bucket, object, preconditions, opts).await {
+                Ok(lock) => Some(lock),
+                Err(err) => {
+                    let _ = self.delete_all(RUSTFS_META_TMP_BUCKET, &tmp_dir).await;
+                    return Err(err);
+                }
+            },
+            None => None,
+        };
+
         let (online_disks, _, op_old_dir) = Self::rename_data(
             &shuffle_disks,
             RUSTFS_META_TMP_BUCKET,
@@ -4438,7 +4598,7 @@ impl StorageAPI for SetDisks {
             fi.metadata.insert("etag".to_owned(), etag.clone());
         }
 
-        let mod_time = OffsetDateTime::now_utc();
+        let mod_time = dst_opts.mod_time.unwrap_or_else(OffsetDateTime::now_utc);
 
         for fi in metas.iter_mut() {
             if fi.is_valid() {
@@ -4631,6 +4791,11 @@ impl StorageAPI for SetDisks {
 
             return Ok(ObjectInfo::default());
         }
+
+        if opts.transition.expire_restored {
+            return self.expire_restored_object(bucket, object, &opts).await;
+        }
+
         unimplemented!()
     }
 
@@ -4942,75 +5107,20 @@ impl StorageAPI for SetDisks {
 
     #[tracing::instrument(level = "debug", skip(self))]
     async fn restore_transitioned_object(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<()> {
-        let set_restore_header_fn = async move |oi: &mut ObjectInfo, rerr: Option<Error>| -> Result<()> {
-            if rerr.is_none() {
-                return Ok(());
-            }
-            self.update_restore_metadata(bucket, object, oi, opts).await?;
-            Err(rerr.unwrap())
-        };
-        let mut oi = ObjectInfo::default();
-        let fi = self.get_object_fileinfo(bucket, object, opts, true).await;
-        if let Err(err) = fi {
-            return set_restore_header_fn(&mut oi, Some(to_object_err(err, vec![bucket, object]))).await;
-        }
-        let (actual_fi, _, _) = fi.unwrap();
-
-        oi = ObjectInfo::from_file_info(&actual_fi, bucket, object, opts.versioned || opts.version_suspended);
-        let ropts = put_restore_opts(bucket, object, &opts.transition.restore_request, &oi);
-        /*if oi.parts.len() == 1 {
-            let mut rs: HTTPRangeSpec;
-            let gr = get_transitioned_object_reader(bucket, object, rs, HeaderMap::new(), oi, opts);
-            //if err != nil {
-            //    return set_restore_header_fn(&mut oi, Some(toObjectErr(err, bucket, object)));
-            //}
-            //defer gr.Close()
-            let hash_reader = HashReader::new(gr, gr.obj_info.size, "", "", gr.obj_info.size);
-            let p_reader = PutObjReader::new(StreamingBlob::from(Box::pin(hash_reader)), hash_reader.size());
-            if let Err(err) = self.put_object(bucket, object, &mut p_reader, &ropts).await {
-                return set_restore_header_fn(&mut oi, Some(to_object_err(err, vec![bucket, object])));
-            } else {
-                return Ok(());
-            }
+        let (fi, _, _) = self
+            .get_object_fileinfo(bucket, object, opts, false)
+            .await
+            .map_err(|err| to_object_err(err, vec![bucket, object]))?;
+        let oi = ObjectInfo::from_file_info(&fi, bucket, object, opts.versioned || opts.version_suspended);
+        if oi.transitioned_object.status != TRANSITION_COMPLETE {
+            return Err(Error::InvalidObjectState(bucket.to_owned(), object.to_owned()));
         }
 
-        let res = self.new_multipart_upload(bucket, object, &ropts).await?;
-        //if err != nil {
-        //    return set_restore_header_fn(&mut oi, err);
-        //}
-
-        let mut uploaded_parts: Vec<CompletePart> = vec![];
-        let mut rs: HTTPRangeSpec;
-        let gr = get_transitioned_object_reader(bucket, object, rs, HeaderMap::new(), oi, opts).await?;
-        //if err != nil {
-        //    return set_restore_header_fn(&mut oi, err);
-        //}
-
-        for part_info in oi.parts {
-            //let hr = HashReader::new(LimitReader(gr, part_info.size), part_info.size, "", "", part_info.size);
-            let hr = HashReader::new(gr, part_info.size as i64, part_info.size as i64, None, false);
-            //if err != nil {
-            //    return set_restore_header_fn(&mut oi, err);
-            //}
-            let mut p_reader = PutObjReader::new(hr, hr.size());
-            let p_info = self.put_object_part(bucket, object, &res.upload_id, part_info.number, &mut p_reader, &ObjectOptions::default()).await?;
-            //if let Err(err) = p_info {
-            //    return set_restore_header_fn(&mut oi, err);
-            //}
-            if p_info.size != part_info.size {
-                return set_restore_header_fn(&mut oi, Some(Error::from(ObjectApiError::InvalidObjectState(GenericError{bucket: bucket.to_string(), object: object.to_string(), ..Default::default()}))));
-            }
-            uploaded_parts.push(CompletePart {
-                part_num: p_info.part_num,
-                etag: p_info.etag,
-            });
+        if let Err(err) = self.restore_from_tier(bucket, object, &oi, opts).await {
+            // Drop the ongoing-request marker so the restore can be requested again.
+            self.update_restore_metadata(bucket, object, &oi, opts).await?;
+            return Err(to_object_err(err, vec![bucket, object]));
         }
-        if let Err(err) = self.complete_multipart_upload(bucket, object, &res.upload_id, uploaded_parts, &ObjectOptions {
-            mod_time: oi.mod_time,
-            ..Default::default()
-        }).await {
-            set_restore_header_fn(&mut oi, Some(err));
-        }*/
         Ok(())
     }
 
@@ -5036,19 +5146,27 @@ impl StorageAPI for SetDisks {
     #[tracing::instrument(skip(self))]
     async fn copy_object_part(
         &self,
-        _src_bucket: &str,
-        _src_object: &str,
-        _dst_bucket: &str,
-        _dst_object: &str,
-        _upload_id: &str,
-        _part_id: usize,
-        _start_offset: i64,
-        _length: i64,
-        _src_info: &ObjectInfo,
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        upload_id: &str,
+        part_id: usize,
+        src_info: &mut ObjectInfo,
         _src_opts: &ObjectOptions,
-        _dst_opts: &ObjectOptions,
-    ) -> Result<()> {
-        unimplemented!()
+        dst_opts: &ObjectOptions,
+    ) -> Result<PartInfo> {
+        // The caller has already positioned the source reader at the requested range.
+        let Some(data) = src_info.put_object_reader.as_mut() else {
+            return Err(StorageError::InvalidArgument(
+                src_bucket.to_owned(),
+                src_object.to_owned(),
+                "missing source reader".to_owned(),
+            ));
+        };
+
+        self.put_object_part(dst_bucket, dst_object, upload_id, part_id, data, dst_opts)
+            .await
     }
 
     #[tracing::instrument(level = "debug", skip(self, data, opts))]
@@ -5211,6 +5329,9 @@ impl StorageAPI for SetDisks {
             mod_time: Some(OffsetDateTime::now_utc()),
             actual_size,
             index: index_op,
+            checksums: opts
+                .checksum()
+                .map(|c| HashMap::from([(c.checksum_type.as_str().to_string(), c.encoded)])),
             ..Default::default()
         };
 
@@ -5528,7 +5649,7 @@ impl StorageAPI for SetDisks {
         }
 
         let sc_parity_drives = {
-            if let Some(sc) = GLOBAL_StorageClass.get() {
+            if let Some(sc) = get_global_storage_class() {
                 sc.get_parity_for_sc(user_defined.get(AMZ_STORAGE_CLASS).cloned().unwrap_or_default().as_str())
             } else {
                 None
@@ -5647,6 +5768,11 @@ impl StorageAPI for SetDisks {
         uploaded_parts: Vec<CompletePart>,
         opts: &ObjectOptions,
     ) -> Result<ObjectInfo> {
+        let _write_lock = match &opts.write_preconditions {
+            Some(preconditions) => Some(self.lock_for_conditional_write(bucket, object, preconditions, opts).await?),
+            None => None,
+        };
+
         let (mut fi, files_metas) = self.check_upload_id_exists(bucket, object, upload_id, true).await?;
         let upload_id_path = Self::get_upload_id_dir(bucket, object, upload_id);
 
@@ -5705,6 +5831,12 @@ impl StorageAPI for SetDisks {
         let mut object_size: usize = 0;
         let mut object_actual_size: i64 = 0;
 
+        let checksum_type = fi
+            .metadata
+            .get(RUSTFS_MULTIPART_CHECKSUM)
+            .and_then(|v| ChecksumType::from_algorithm(v));
+        let mut part_checksums = Vec::new();
+
         for (i, p) in uploaded_parts.iter().enumerate() {
             let has_part = curr_fi.parts.iter().find(|v| v.number == p.part_num);
             if has_part.is_none() {
@@ -5725,6 +5857,20 @@ impl StorageAPI for SetDisks {
                 return Err(Error::InvalidPart(p.part_num, ext_part.etag.clone(), p.etag.clone().unwrap_or_default()));
             }
 
+            if let Some(checksum_type) = checksum_type {
+                let part_checksum = object_parts[i].checksums.as_ref().and_then(|v| v.get(checksum_type.as_str()));
+                let Some(part_checksum) = part_checksum.filter(|v| p.checksum(checksum_type).is_none_or(|want| want == *v))
+                else {
+                    error!(
+                        "complete_multipart_upload checksum err {:?}, part_id={}, bucket={}, object={}",
+                        part_checksum, p.part_num, bucket, object
+                    );
+                    return Err(Error::InvalidPart(p.part_num, ext_part.etag.clone(), p.etag.clone().unwrap_or_default()));
+                };
+
+                part_checksums.push((part_checksum.clone(), ext_part.actual_size));
+            }
+
             // TODO: crypto
 
             if (i < uploaded_parts.len() - 1) && !is_min_allowed_part_size(ext_part.actual_size) {
@@ -5745,6 +5891,7 @@ impl StorageAPI for SetDisks {
                 mod_time: ext_part.mod_time,
                 actual_size: ext_part.actual_size,
                 index: ext_part.index.clone(),
+                checksums: object_parts[i].checksums.clone(),
                 ..Default::default()
             });
         }
@@ -5766,6 +5913,28 @@ impl StorageAPI for SetDisks {
 
         fi.metadata.insert("etag".to_owned(), etag);
 
+        if let Some(checksum_type) = checksum_type {
+            let full_object = fi
+                .metadata
+                .get(RUSTFS_MULTIPART_CHECKSUM_TYPE)
+                .is_some_and(|v| v == "FULL_OBJECT");
+            let checksum = rustfs_rio::Checksum::multipart(checksum_type, full_object, &part_checksums)?;
+
+            if let Some(want) = &opts.want_checksum {
+                if want.checksum_type != checksum_type || want.encoded != checksum.encoded {
+                    let err = ChecksumMismatch {
+                        want: want.encoded.clone(),
+                        got: checksum.encoded,
+                    };
+                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err).into());
+                }
+            }
+
+            fi.checksum = Some(checksum.to_bytes().into());
+        }
+        fi.metadata.remove(RUSTFS_MULTIPART_CHECKSUM);
+        fi.metadata.remove(RUSTFS_MULTIPART_CHECKSUM_TYPE);
+
         fi.metadata
             .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}actual-size"), object_actual_size.to_string());
 
@@ -5788,8 +5957,7 @@ impl StorageAPI for SetDisks {
                 meta.parts.clone_from(&fi.parts);
                 meta.metadata = fi.metadata.clone();
                 meta.versioned = opts.versioned || opts.version_suspended;
-
-                // TODO: Checksum
+                meta.checksum = fi.checksum.clone();
             }
         }
 
@@ -6519,10 +6687,12 @@ mod tests {
             CompletePart {
                 part_num: 1,
                 etag: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+                ..Default::default()
             },
             CompletePart {
                 part_num: 2,
                 etag: Some("098f6bcd4621d373cade4e832627b4f6".to_string()),
+                ..Default::default()
             },
         ];
 
@@ -6539,6 +6709,7 @@ mod tests {
         let single_part = vec![CompletePart {
             part_num: 1,
             etag: Some("d41d8cd98f00b204e9800998ecf8427e".to_string()),
+            ..Default::default()
         }];
         let single_result = get_complete_multipart_md5(&single_part);
         assert!(single_result.ends_with("-1"));
@@ -17,7 +17,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::disk::error_reduce::count_errs;
 use crate::error::{Error, Result};
-use crate::store_api::ListPartsInfo;
+use crate::store_api::{ListPartsInfo, SetHealth};
 use crate::{
     disk::{
         DiskAPI, DiskInfo, DiskOption, DiskStore,
@@ -284,6 +284,20 @@ impl Sets {
         // debug!("done connect_disks ...");
     }
 
+    /// Quorum state of every erasure set of the pool, with the local drives counted as offline in `maintenance`.
+    pub async fn health(&self, maintenance: bool) -> Vec<SetHealth> {
+        let futures = self.disk_set.iter().map(|set| async move {
+            SetHealth {
+                pool_index: self.pool_idx,
+                set_index: set.set_index,
+                online_drives: set.online_drive_count(maintenance).await,
+                read_quorum: set.default_read_quorum(),
+                write_quorum: set.default_write_quorum(),
+            }
+        });
+        join_all(futures).await
+    }
+
     pub fn get_disks(&self, set_idx: usize) -> Arc<SetDisks> {
         self.disk_set[set_idx].clone()
     }
@@ -673,19 +687,21 @@ impl StorageAPI for Sets {
     #[tracing::instrument(skip(self))]
     async fn copy_object_part(
         &self,
-        _src_bucket: &str,
-        _src_object: &str,
-        _dst_bucket: &str,
-        _dst_object: &str,
-        _upload_id: &str,
-        _part_id: usize,
-        _start_offset: i64,
-        _length: i64,
-        _src_info: &ObjectInfo,
-        _src_opts: &ObjectOptions,
-        _dst_opts: &ObjectOptions,
-    ) -> Result<()> {
-        unimplemented!()
+        src_bucket: &str,
+        src_object: &str,
+        dst_bucket: &str,
+        dst_object: &str,
+        upload_id: &str,
+        part_id: usize,
+        src_info: &mut ObjectInfo,
+        src_opts: &ObjectOptions,
+        dst_opts: &ObjectOptions,
+    ) -> Result<PartInfo> {
+        self.get_disks_by_key(dst_object)
+            .copy_object_part(
+                src_bucket, src_object, dst_bucket, dst_object, upload_id, part_id, src_info, src_opts, dst_opts,
+            )
+            .await
     }
 
     #[tracing::instrument(skip(self))]
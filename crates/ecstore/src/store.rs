@@ -16,7 +16,7 @@
 use crate::bucket::lifecycle::bucket_lifecycle_ops::init_background_expiry;
 use crate::bucket::metadata_sys::{self, set_bucket_metadata};
 use crate::bucket::utils::{check_valid_bucket_name, check_valid_bucket_name_strict, is_meta_bucketname};
-use crate::config::GLOBAL_StorageClass;
+use crate::config::get_global_storage_class;
 use crate::config::storageclass;
 use crate::disk::endpoint::{Endpoint, EndpointType};
 use crate::disk::{DiskAPI, DiskInfo, DiskInfoOptions};
@@ -38,7 +38,9 @@ use crate::new_object_layer_fn;
 use crate::notification_sys::get_global_notification_sys;
 use crate::pools::PoolMeta;
 use crate::rebalance::RebalanceMeta;
-use crate::store_api::{ListMultipartsInfo, ListObjectVersionsInfo, ListPartsInfo, MultipartInfo, ObjectIO};
+use crate::store_api::{
+    HealthResult, ListMultipartsInfo, ListObjectVersionsInfo, ListPartsInfo, MultipartInfo, ObjectIO, SetHealth,
+};
 use crate::store_init::{check_disk_fatal_errs, ec_drives_no_config};
 use crate::{
     bucket::{lifecycle::bucket_lifecycle_ops::TransitionState, metadata::BucketMetadata},
@@ -365,6 +367,24 @@ impl ECStore {
         self.pools.len() == 1
     }
 
+    /// Checks that every erasure set has read and write quorum. In `maintenance` the drives of this
+    /// node are counted as offline, which tells whether the node can be taken down.
+    pub async fn health(&self, maintenance: bool) -> HealthResult {
+        let sets = join_all(self.pools.iter().map(|pool| pool.health(maintenance)))
+            .await
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        HealthResult {
+            healthy: sets.iter().all(SetHealth::has_write_quorum),
+            read_healthy: sets.iter().all(SetHealth::has_read_quorum),
+            maintenance,
+            write_quorum: sets.iter().map(|s| s.write_quorum).max().unwrap_or_default(),
+            unhealthy_sets: sets.into_iter().filter(|s| !s.has_write_quorum()).collect(),
+        }
+    }
+
     // define in store_list_objects.rs
     // pub async fn list_path(&self, opts: &ListPathOptions, delimiter: &str) -> Result<ListObjectsInfo> {
     //     // if opts.prefix.ends_with(SLASH_SEPARATOR) {
@@ -1291,7 +1311,7 @@ impl StorageAPI for ECStore {
     #[tracing::instrument(skip(self))]
     async fn backend_info(&self) -> rustfs_madmin::BackendInfo {
         let (standard_sc_parity, rr_sc_parity) = {
-            if let Some(sc) = GLOBAL_StorageClass.get() {
+            if let Some(sc) = get_global_storage_class() {
                 let sc_parity = sc
                     .get_parity_for_sc(storageclass::CLASS_STANDARD)
                     .or(Some(self.pools[0].default_parity_count));
@@ -1983,22 +2003,26 @@ impl StorageAPI for ECStore {
         &self,
         src_bucket: &str,
         src_object: &str,
-        _dst_bucket: &str,
-        _dst_object: &str,
-        _upload_id: &str,
-        _part_id: usize,
-        _start_offset: i64,
-        _length: i64,
-        _src_info: &ObjectInfo,
+        dst_bucket: &str,
+        dst_object: &str,
+        upload_id: &str,
+        part_id: usize,
+        src_info: &mut ObjectInfo,
         _src_opts: &ObjectOptions,
-        _dst_opts: &ObjectOptions,
-    ) -> Result<()> {
+        dst_opts: &ObjectOptions,
+    ) -> Result<PartInfo> {
         check_new_multipart_args(src_bucket, src_object)?;
 
-        // TODO: PutObjectReader
-        // self.put_object_part(dst_bucket, dst_object, upload_id, part_id, data, opts)
+        let Some(data) = src_info.put_object_reader.as_mut() else {
+            return Err(StorageError::InvalidArgument(
+                src_bucket.to_owned(),
+                src_object.to_owned(),
+                "missing source reader".to_owned(),
+            ));
+        };
 
-        unimplemented!()
+        self.put_object_part(dst_bucket, dst_object, upload_id, part_id, data, dst_opts)
+            .await
     }
     #[tracing::instrument(skip(self, data))]
     async fn put_object_part(
@@ -15,6 +15,7 @@
 use crate::bucket::metadata_sys::get_versioning_config;
 use crate::bucket::versioning::VersioningApi as _;
 use crate::cmd::bucket_replication::{ReplicationStatusType, VersionPurgeStatusType};
+use crate::encryption;
 use crate::error::{Error, Result};
 use crate::heal::heal_ops::HealSequence;
 use crate::store_utils::clean_metadata;
@@ -26,11 +27,11 @@ use crate::{
 use crate::{disk::DiskStore, heal::heal_commands::HealOpts};
 use http::{HeaderMap, HeaderValue};
 use rustfs_filemeta::headers::RESERVED_METADATA_PREFIX_LOWER;
-use rustfs_filemeta::{FileInfo, MetaCacheEntriesSorted, ObjectPartInfo, headers::AMZ_OBJECT_TAGGING};
+use rustfs_filemeta::{FileInfo, MetaCacheEntriesSorted, ObjectPartInfo, RestoreStatus, headers::AMZ_OBJECT_TAGGING};
 use rustfs_madmin::heal_commands::HealResultItem;
-use rustfs_rio::{DecompressReader, HashReader, LimitReader, WarpReader};
+use rustfs_rio::{ChecksumType, DecompressReader, DecryptReader, HashReader, LimitReader, WarpReader};
 use rustfs_utils::CompressionAlgorithm;
-use rustfs_utils::path::decode_dir_object;
+use rustfs_utils::path::{decode_dir_object, trim_etag};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -109,12 +110,12 @@ pub struct GetObjectReader {
 
 impl GetObjectReader {
     #[tracing::instrument(level = "debug", skip(reader))]
-    pub fn new(
+    pub async fn new(
         reader: Box<dyn AsyncRead + Unpin + Send + Sync>,
         rs: Option<HTTPRangeSpec>,
         oi: &ObjectInfo,
         opts: &ObjectOptions,
-        _h: &HeaderMap<HeaderValue>,
+        h: &HeaderMap<HeaderValue>,
     ) -> Result<(Self, usize, i64)> {
         let mut rs = rs;
 
@@ -124,10 +125,57 @@ impl GetObjectReader {
             }
         }
 
-        // TODO:Encrypted
-
         let (algo, is_compressed) = oi.is_compressed_ok()?;
 
+        if let Some(key) = encryption::object_key(&oi.bucket, &oi.name, &oi.user_defined, h).await? {
+            let actual_size = oi.get_actual_size()?;
+
+            let mut oi = oi.clone();
+            oi.size = actual_size;
+
+            if is_compressed {
+                // Compressed data is encrypted as a whole, so every part is read from its start and a
+                // range is served by decompressing up to it and skipping the leading bytes.
+                let (off, length) = match rs {
+                    Some(rs) => rs.get_offset_length(actual_size)?,
+                    None => (0, actual_size),
+                };
+
+                let parts = oi.parts.iter().map(|part| part.size).collect();
+                let dec_reader = DecryptReader::with_range(reader, key.as_bytes(), 0, parts, 0);
+                let dec_reader = DecompressReader::new(dec_reader, algo);
+                let dec_reader = LimitReader::with_skip(dec_reader, off, length as usize);
+
+                let length = oi.parts.iter().map(|part| part.size as i64).sum();
+                return Ok((
+                    GetObjectReader {
+                        stream: Box::new(dec_reader),
+                        object_info: oi,
+                    },
+                    0,
+                    length,
+                ));
+            }
+
+            let (off, length) = match rs {
+                Some(rs) => rs.get_offset_length(actual_size)?,
+                None => (0, actual_size),
+            };
+
+            let range = encryption::encrypted_range(&oi.parts, off, length)?;
+            let dec_reader = DecryptReader::with_range(reader, key.as_bytes(), range.sequence, range.parts, range.skip);
+            let dec_reader = LimitReader::new(dec_reader, length as usize);
+
+            return Ok((
+                GetObjectReader {
+                    stream: Box::new(dec_reader),
+                    object_info: oi,
+                },
+                range.offset,
+                range.length,
+            ));
+        }
+
         // TODO: check TRANSITION
 
         if is_compressed {
@@ -214,11 +262,17 @@ impl HTTPRangeSpec {
             return None;
         }
 
+        let is_encrypted = oi.is_encrypted();
         let mut start = 0i64;
         let mut end = -1i64;
         for i in 0..oi.parts.len().min(part_number) {
             start = end + 1;
-            end = start + (oi.parts[i].size as i64) - 1
+            let size = if is_encrypted {
+                oi.parts[i].actual_size
+            } else {
+                oi.parts[i].size as i64
+            };
+            end = start + size - 1
         }
 
         Some(HTTPRangeSpec {
@@ -283,6 +337,36 @@ impl HTTPRangeSpec {
     }
 }
 
+/// Conditions a write must satisfy against the current object, evaluated under the object lock.
+#[derive(Debug, Default, Clone)]
+pub struct WritePreconditions {
+    /// `If-Match`: comma separated ETags, or `*`; the object must exist and match one of them.
+    pub if_match: Option<String>,
+    /// `If-None-Match: *`: the object must not exist.
+    pub if_none_match: bool,
+}
+
+impl WritePreconditions {
+    /// Checks the conditions against `current`, the latest visible version or `None` if there is none.
+    pub fn check(&self, bucket: &str, object: &str, current: Option<&ObjectInfo>) -> Result<()> {
+        if let Some(if_match) = &self.if_match {
+            let Some(info) = current else {
+                return Err(Error::ObjectNotFound(bucket.to_owned(), object.to_owned()));
+            };
+            let etag = info.etag.as_deref().map(trim_etag).unwrap_or_default();
+            if !if_match.split(',').map(str::trim).any(|v| v == "*" || trim_etag(v) == etag) {
+                return Err(Error::PreconditionFailed(bucket.to_owned(), object.to_owned()));
+            }
+        }
+
+        if self.if_none_match && current.is_some() {
+            return Err(Error::PreconditionFailed(bucket.to_owned(), object.to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct ObjectOptions {
     // Use the maximum parity (N/2), used when saving server configuration files
@@ -315,6 +399,24 @@ pub struct ObjectOptions {
     pub lifecycle_audit_event: LcAuditEvent,
 
     pub eval_metadata: Option<HashMap<String, String>>,
+
+    /// Checksum sent by the client, verified while the data is read and stored with the object or part.
+    pub want_checksum: Option<rustfs_rio::Checksum>,
+
+    /// Checksum sent in the trailer of an aws-chunked body, set once the data was read and verified.
+    pub trailing_checksum: Option<rustfs_rio::TrailingChecksum>,
+
+    /// Conditional write checks (`If-Match` / `If-None-Match`), evaluated atomically with the write.
+    pub write_preconditions: Option<WritePreconditions>,
+}
+
+impl ObjectOptions {
+    /// The verified checksum of the data, sent either as a header or in the trailer of the body.
+    pub fn checksum(&self) -> Option<rustfs_rio::Checksum> {
+        self.want_checksum
+            .clone()
+            .or_else(|| self.trailing_checksum.as_ref().and_then(|t| t.get()))
+    }
 }
 
 // impl Default for ObjectOptions {
@@ -361,6 +463,24 @@ pub struct PartInfo {
 pub struct CompletePart {
     pub part_num: usize,
     pub etag: Option<String>,
+    pub checksum_crc32: Option<String>,
+    pub checksum_crc32c: Option<String>,
+    pub checksum_sha1: Option<String>,
+    pub checksum_sha256: Option<String>,
+    pub checksum_crc64nvme: Option<String>,
+}
+
+impl CompletePart {
+    /// Part checksum of the given type sent by the client.
+    pub fn checksum(&self, checksum_type: ChecksumType) -> Option<&String> {
+        match checksum_type {
+            ChecksumType::Crc32 => self.checksum_crc32.as_ref(),
+            ChecksumType::Crc32c => self.checksum_crc32c.as_ref(),
+            ChecksumType::Sha1 => self.checksum_sha1.as_ref(),
+            ChecksumType::Sha256 => self.checksum_sha256.as_ref(),
+            ChecksumType::Crc64Nvme => self.checksum_crc64nvme.as_ref(),
+        }
+    }
 }
 
 impl From<s3s::dto::CompletedPart> for CompletePart {
@@ -368,6 +488,11 @@ impl From<s3s::dto::CompletedPart> for CompletePart {
         Self {
             part_num: value.part_number.unwrap_or_default() as usize,
             etag: value.e_tag,
+            checksum_crc32: value.checksum_crc32,
+            checksum_crc32c: value.checksum_crc32c,
+            checksum_sha1: value.checksum_sha1,
+            checksum_sha256: value.checksum_sha256,
+            checksum_crc64nvme: value.checksum_crc64nvme,
         }
     }
 }
@@ -387,6 +512,8 @@ pub struct ObjectInfo {
     pub version_id: Option<Uuid>,
     pub delete_marker: bool,
     pub transitioned_object: TransitionedObject,
+    pub restore_ongoing: bool,
+    pub restore_expires: Option<OffsetDateTime>,
     pub user_tags: String,
     pub parts: Vec<ObjectPartInfo>,
     pub is_latest: bool,
@@ -421,6 +548,8 @@ impl Clone for ObjectInfo {
             version_id: self.version_id,
             delete_marker: self.delete_marker,
             transitioned_object: self.transitioned_object.clone(),
+            restore_ongoing: self.restore_ongoing,
+            restore_expires: self.restore_expires,
             user_tags: self.user_tags.clone(),
             parts: self.parts.clone(),
             is_latest: self.is_latest,
@@ -437,7 +566,7 @@ impl Clone for ObjectInfo {
             replication_status: self.replication_status.clone(),
             version_purge_status_internal: self.version_purge_status_internal.clone(),
             version_purge_status: self.version_purge_status.clone(),
-            checksum: Default::default(),
+            checksum: self.checksum.clone(),
         }
     }
 }
@@ -466,12 +595,16 @@ impl ObjectInfo {
         self.etag.as_ref().is_some_and(|v| v.len() != 32)
     }
 
+    pub fn is_encrypted(&self) -> bool {
+        encryption::is_encrypted(&self.user_defined)
+    }
+
     pub fn get_actual_size(&self) -> std::io::Result<i64> {
         if self.actual_size > 0 {
             return Ok(self.actual_size);
         }
 
-        if self.is_compressed() {
+        if self.is_compressed() || self.is_encrypted() {
             if let Some(size_str) = self.user_defined.get(&format!("{RESERVED_METADATA_PREFIX_LOWER}actual-size")) {
                 if !size_str.is_empty() {
                     // Todo: deal with error
@@ -490,8 +623,6 @@ impl ObjectInfo {
             return Ok(actual_size);
         }
 
-        // TODO: IsEncrypted
-
         Ok(self.size)
     }
 
@@ -533,6 +664,8 @@ impl ObjectInfo {
             tier: fi.transition_tier.clone(),
         };
 
+        let restore = RestoreStatus::from_metadata(&fi.metadata);
+
         let metadata = {
             let mut v = fi.metadata.clone();
             clean_metadata(&mut v);
@@ -576,10 +709,18 @@ impl ObjectInfo {
             inlined,
             user_defined: metadata,
             transitioned_object,
+            restore_ongoing: restore.is_some_and(|s| s.is_ongoing()),
+            restore_expires: restore.and_then(|s| s.expiry()),
+            checksum: fi.checksum.as_ref().map(|v| v.to_vec()).unwrap_or_default(),
             ..Default::default()
         }
     }
 
+    /// Checksum stored with the object, if it was uploaded with one.
+    pub fn decoded_checksum(&self) -> Option<rustfs_rio::Checksum> {
+        rustfs_rio::Checksum::from_bytes(&self.checksum)
+    }
+
     pub async fn from_meta_cache_entries_sorted_versions(
         entries: &MetaCacheEntriesSorted,
         bucket: &str,
@@ -916,6 +1057,49 @@ pub struct ListObjectVersionsInfo {
     pub prefixes: Vec<String>,
 }
 
+/// Quorum state of one erasure set.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SetHealth {
+    #[serde(rename = "poolIndex")]
+    pub pool_index: usize,
+    #[serde(rename = "setIndex")]
+    pub set_index: usize,
+    #[serde(rename = "onlineDrives")]
+    pub online_drives: usize,
+    #[serde(rename = "readQuorum")]
+    pub read_quorum: usize,
+    #[serde(rename = "writeQuorum")]
+    pub write_quorum: usize,
+}
+
+impl SetHealth {
+    pub fn has_read_quorum(&self) -> bool {
+        self.online_drives >= self.read_quorum
+    }
+
+    pub fn has_write_quorum(&self) -> bool {
+        self.online_drives >= self.write_quorum
+    }
+}
+
+/// Quorum state of the whole cluster, see [`crate::store::ECStore::health`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HealthResult {
+    /// Every erasure set has write quorum.
+    pub healthy: bool,
+    /// Every erasure set has read quorum.
+    #[serde(rename = "readHealthy")]
+    pub read_healthy: bool,
+    /// The drives of this node were counted as offline.
+    pub maintenance: bool,
+    /// The largest write quorum of all erasure sets.
+    #[serde(rename = "writeQuorum")]
+    pub write_quorum: usize,
+    /// The erasure sets without write quorum.
+    #[serde(rename = "unhealthySets")]
+    pub unhealthy_sets: Vec<SetHealth>,
+}
+
 #[async_trait::async_trait]
 pub trait ObjectIO: Send + Sync + 'static {
     // GetObjectNInfo FIXME:
@@ -1002,6 +1186,7 @@ pub trait StorageAPI: ObjectIO {
         max_uploads: usize,
     ) -> Result<ListMultipartsInfo>;
     async fn new_multipart_upload(&self, bucket: &str, object: &str, opts: &ObjectOptions) -> Result<MultipartUploadResult>;
+    /// Writes a part from `src_info.put_object_reader`, which the caller positions at the copied range.
     async fn copy_object_part(
         &self,
         src_bucket: &str,
@@ -1010,12 +1195,10 @@ pub trait StorageAPI: ObjectIO {
         dst_object: &str,
         upload_id: &str,
         part_id: usize,
-        start_offset: i64,
-        length: i64,
-        src_info: &ObjectInfo,
+        src_info: &mut ObjectInfo,
         src_opts: &ObjectOptions,
         dst_opts: &ObjectOptions,
-    ) -> Result<()>;
+    ) -> Result<PartInfo>;
     async fn put_object_part(
         &self,
         bucket: &str,
@@ -0,0 +1,130 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local publishing of trace events.
+//!
+//! Every node publishes its own S3, internal, storage and scanner events to [`GLOBAL_TRACE`]. The
+//! admin trace API subscribes to the local node and, through the `Trace` node RPC, to its peers.
+
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_madmin::service_commands::ServiceTraceOpts;
+use rustfs_madmin::trace::{TraceInfo, TraceType};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tokio::sync::mpsc;
+
+/// Events buffered per subscriber, newer events are dropped while a subscriber is behind.
+const SUBSCRIBER_BUFFER: usize = 4096;
+
+pub static GLOBAL_TRACE: LazyLock<TracePubSub> = LazyLock::new(TracePubSub::default);
+
+struct Subscriber {
+    opts: ServiceTraceOpts,
+    tx: mpsc::Sender<TraceInfo>,
+}
+
+#[derive(Default)]
+pub struct TracePubSub {
+    subscribers: Mutex<Vec<Subscriber>>,
+    /// Union of the trace types of all subscribers.
+    types: AtomicU64,
+}
+
+impl TracePubSub {
+    /// Subscribes to the events matching `opts`, the subscription ends when the receiver is dropped.
+    pub fn subscribe(&self, opts: ServiceTraceOpts) -> mpsc::Receiver<TraceInfo> {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.push(Subscriber { opts, tx });
+        self.update_types(&subscribers);
+        rx
+    }
+
+    /// Whether anybody listens to events of type `t`. Publishers check this first so that events
+    /// are only built while a trace is running.
+    pub fn subscribed(&self, t: TraceType) -> bool {
+        TraceType::new(self.types.load(Ordering::Relaxed)).overlaps(&t)
+    }
+
+    pub fn publish(&self, info: TraceInfo) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        let count = subscribers.len();
+        subscribers.retain(|s| !s.tx.is_closed());
+        if subscribers.len() != count {
+            self.update_types(&subscribers);
+        }
+
+        for s in subscribers.iter().filter(|s| s.opts.matches(&info)) {
+            let _ = s.tx.try_send(info.clone());
+        }
+    }
+
+    fn update_types(&self, subscribers: &[Subscriber]) {
+        let mut types = TraceType::default();
+        for s in subscribers {
+            types.merge(&s.opts.trace_types());
+        }
+        self.types.store(types.mask(), Ordering::Relaxed);
+    }
+}
+
+pub fn subscribed(t: TraceType) -> bool {
+    GLOBAL_TRACE.subscribed(t)
+}
+
+/// Publishes `info` as an event of the local node.
+pub async fn publish(mut info: TraceInfo) {
+    if info.node_name.is_empty() {
+        info.node_name = GLOBAL_Local_Node_Name.read().await.clone();
+    }
+    GLOBAL_TRACE.publish(info);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Uri;
+
+    fn opts(query: &str) -> ServiceTraceOpts {
+        let mut opts = ServiceTraceOpts::default();
+        opts.parse_params(&format!("/trace?{query}").parse::<Uri>().unwrap()).unwrap();
+        opts
+    }
+
+    #[test]
+    fn test_trace_pub_sub() {
+        let ps = TracePubSub::default();
+        assert!(!ps.subscribed(TraceType::S3));
+
+        let mut s3 = ps.subscribe(opts("s3=true"));
+        let mut errors = ps.subscribe(opts("storage=true&err=true"));
+        assert!(ps.subscribed(TraceType::S3) && ps.subscribed(TraceType::STORAGE));
+        assert!(!ps.subscribed(TraceType::SCANNER));
+
+        ps.publish(TraceInfo::new(TraceType::S3, "node", "s3.GetObject"));
+        ps.publish(TraceInfo::new(TraceType::STORAGE, "node", "storage.ReadAll"));
+        let mut failed = TraceInfo::new(TraceType::STORAGE, "node", "storage.WriteAll");
+        failed.error = Some("disk full".to_owned());
+        ps.publish(failed);
+
+        assert_eq!(s3.try_recv().unwrap().func_name, "s3.GetObject");
+        assert!(s3.try_recv().is_err());
+        assert_eq!(errors.try_recv().unwrap().func_name, "storage.WriteAll");
+        assert!(errors.try_recv().is_err());
+
+        drop(s3);
+        ps.publish(TraceInfo::new(TraceType::STORAGE, "node", "storage.ReadAll"));
+        assert!(!ps.subscribed(TraceType::S3));
+    }
+}
@@ -15,6 +15,7 @@
 use crate::error::{Error, Result};
 use crate::headers::RESERVED_METADATA_PREFIX_LOWER;
 use crate::headers::RUSTFS_HEALING;
+use crate::{RestoreStatus, TRANSITION_COMPLETE};
 use bytes::Bytes;
 use rmp_serde::Serializer;
 use rustfs_utils::HashAlgorithm;
@@ -390,9 +391,12 @@ impl FileInfo {
             .contains_key(&format!("{RESERVED_METADATA_PREFIX_LOWER}compression"))
     }
 
-    /// Check if the object is remote (transitioned to another tier)
+    /// Check if the object is remote (transitioned to another tier and not restored locally)
     pub fn is_remote(&self) -> bool {
-        !self.transition_tier.is_empty()
+        if self.transition_status != TRANSITION_COMPLETE {
+            return false;
+        }
+        !RestoreStatus::from_metadata(&self.metadata).is_some_and(|s| s.on_disk(OffsetDateTime::now_utc()))
     }
 
     /// Get the data directory for this object
@@ -17,8 +17,9 @@ use crate::fileinfo::{ErasureAlgo, ErasureInfo, FileInfo, FileInfoVersions, Obje
 use crate::filemeta_inline::InlineData;
 use crate::headers::{
     self, AMZ_META_UNENCRYPTED_CONTENT_LENGTH, AMZ_META_UNENCRYPTED_CONTENT_MD5, AMZ_STORAGE_CLASS, RESERVED_METADATA_PREFIX,
-    RESERVED_METADATA_PREFIX_LOWER, VERSION_PURGE_STATUS_KEY,
+    RESERVED_METADATA_PREFIX_LOWER, RUSTFS_CHECKSUM, VERSION_PURGE_STATUS_KEY,
 };
+use crate::restore::{X_AMZ_RESTORE_EXPIRY_DAYS, X_AMZ_RESTORE_REQUEST_DATE};
 use byteorder::ByteOrder;
 use bytes::Bytes;
 use s3s::header::X_AMZ_RESTORE;
@@ -62,9 +63,6 @@ pub const TRANSITIONED_OBJECTNAME: &str = "transitioned-object";
 pub const TRANSITIONED_VERSION_ID: &str = "transitioned-versionID";
 pub const TRANSITION_TIER: &str = "transition-tier";
 
-const X_AMZ_RESTORE_EXPIRY_DAYS: &str = "X-Amz-Restore-Expiry-Days";
-const X_AMZ_RESTORE_REQUEST_DATE: &str = "X-Amz-Restore-Request-Date";
-
 // type ScanHeaderVersionFn = Box<dyn Fn(usize, &[u8], &[u8]) -> Result<()>>;
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -555,6 +553,8 @@ impl FileMeta {
             match ver.header.version_type {
                 VersionType::Invalid | VersionType::Legacy => return Err(Error::other("invalid file meta version")),
                 VersionType::Delete => return Ok(None),
+                // Transitioning or expiring a restored copy keeps the version; handled below.
+                VersionType::Object if fi.expire_restored || fi.transition_status == TRANSITION_COMPLETE => break,
                 VersionType::Object => {
                     let v = self.get_idx(i)?;
 
@@ -1463,6 +1463,10 @@ impl MetaObject {
                 continue;
             }
 
+            if k == RUSTFS_CHECKSUM {
+                continue;
+            }
+
             if k.starts_with(RESERVED_METADATA_PREFIX)
                 || k.starts_with(RESERVED_METADATA_PREFIX_LOWER)
                 || k == VERSION_PURGE_STATUS_KEY
@@ -1493,10 +1497,22 @@ impl MetaObject {
             volume: volume.to_string(),
             parts,
             metadata,
+            checksum: self.meta_sys.get(RUSTFS_CHECKSUM).map(|v| Bytes::copy_from_slice(v)),
+            transition_status: self.meta_sys_str(TRANSITION_STATUS),
+            transitioned_objname: self.meta_sys_str(TRANSITIONED_OBJECTNAME),
+            transition_tier: self.meta_sys_str(TRANSITION_TIER),
+            transition_version_id: Uuid::parse_str(&self.meta_sys_str(TRANSITIONED_VERSION_ID)).ok(),
             ..Default::default()
         }
     }
 
+    fn meta_sys_str(&self, key: &str) -> String {
+        self.meta_sys
+            .get(&format!("{RESERVED_METADATA_PREFIX_LOWER}{key}"))
+            .map(|v| String::from_utf8_lossy(v).into_owned())
+            .unwrap_or_default()
+    }
+
     pub fn set_transition(&mut self, fi: &FileInfo) {
         self.meta_sys.insert(
             format!("{RESERVED_METADATA_PREFIX_LOWER}{TRANSITION_STATUS}"),
@@ -1508,7 +1524,10 @@ impl MetaObject {
         );
         self.meta_sys.insert(
             format!("{RESERVED_METADATA_PREFIX_LOWER}{TRANSITIONED_VERSION_ID}"),
-            fi.transition_version_id.unwrap().as_bytes().to_vec(),
+            fi.transition_version_id
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+                .into_bytes(),
         );
         self.meta_sys.insert(
             format!("{RESERVED_METADATA_PREFIX_LOWER}{TRANSITION_TIER}"),
@@ -1643,6 +1662,10 @@ impl From<FileInfo> for MetaObject {
             }
         }
 
+        if let Some(checksum) = &value.checksum {
+            meta_sys.insert(RUSTFS_CHECKSUM.to_owned(), checksum.to_vec());
+        }
+
         Self {
             version_id: value.version_id,
             data_dir: value.data_dir,
@@ -3013,6 +3036,57 @@ mod test {
             assert_eq!(obj2.meta_user.get(key), Some(&expected_value.to_string()));
         }
     }
+
+    #[test]
+    fn test_delete_version_keeps_transitioned_and_restored_versions() {
+        let mut fm = FileMeta::new();
+        let vid = Uuid::new_v4();
+        let data_dir = Uuid::new_v4();
+
+        let mut fi = FileInfo::new("bucket/object", 2, 2);
+        fi.version_id = Some(vid);
+        fi.data_dir = Some(data_dir);
+        fi.mod_time = Some(OffsetDateTime::now_utc());
+        fm.add_version(fi.clone()).unwrap();
+
+        let mut transitioned = fi.clone();
+        transitioned.transition_status = TRANSITION_COMPLETE.to_string();
+        transitioned.transition_tier = "WARM".to_string();
+        transitioned.transitioned_objname = "tier/object".to_string();
+        transitioned.transition_version_id = Some(Uuid::new_v4());
+        assert_eq!(fm.delete_version(&transitioned).unwrap(), Some(data_dir));
+        assert_eq!(fm.versions.len(), 1);
+
+        let read = fm.into_fileinfo("bucket", "object", &vid.to_string(), false, false).unwrap();
+        assert_eq!(read.transition_status, TRANSITION_COMPLETE);
+        assert_eq!(read.transition_tier, "WARM");
+        assert_eq!(read.transitioned_objname, "tier/object");
+        assert_eq!(read.transition_version_id, transitioned.transition_version_id);
+        assert!(read.is_remote());
+
+        let mut restored = read.clone();
+        restored.metadata = HashMap::from([(
+            X_AMZ_RESTORE.as_str().to_string(),
+            crate::RestoreStatus::completed(OffsetDateTime::now_utc() + time::Duration::days(1)).to_string(),
+        )]);
+        fm.update_object_version(restored).unwrap();
+        let read = fm.into_fileinfo("bucket", "object", &vid.to_string(), false, false).unwrap();
+        assert!(!read.is_remote());
+
+        let expire = FileInfo {
+            version_id: Some(vid),
+            expire_restored: true,
+            ..Default::default()
+        };
+        assert_eq!(fm.delete_version(&expire).unwrap(), Some(data_dir));
+        assert_eq!(fm.versions.len(), 1);
+        let read = fm.into_fileinfo("bucket", "object", &vid.to_string(), false, false).unwrap();
+        assert!(!read.metadata.contains_key(X_AMZ_RESTORE.as_str()));
+        assert!(read.is_remote());
+
+        assert_eq!(fm.delete_version(&fi).unwrap(), Some(data_dir));
+        assert!(fm.versions.is_empty());
+    }
 }
 
 #[tokio::test]
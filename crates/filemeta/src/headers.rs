@@ -35,3 +35,9 @@ pub const AMZ_BUCKET_REPLICATION_STATUS: &str = "X-Amz-Replication-Status";
 pub const AMZ_DECODED_CONTENT_LENGTH: &str = "X-Amz-Decoded-Content-Length";
 
 pub const RUSTFS_DATA_MOVE: &str = "X-Rustfs-Internal-data-mov";
+
+/// Internal metadata holding the encoded `FileInfo::checksum` of an object version.
+pub const RUSTFS_CHECKSUM: &str = "x-rustfs-internal-crc";
+/// Checksum algorithm and type requested when a multipart upload was created.
+pub const RUSTFS_MULTIPART_CHECKSUM: &str = "x-rustfs-internal-multipart-checksum";
+pub const RUSTFS_MULTIPART_CHECKSUM_TYPE: &str = "x-rustfs-internal-multipart-checksum-type";
@@ -18,6 +18,7 @@ mod filemeta;
 mod filemeta_inline;
 pub mod headers;
 mod metacache;
+mod restore;
 
 pub mod test_data;
 
@@ -26,3 +27,4 @@ pub use fileinfo::*;
 pub use filemeta::*;
 pub use filemeta_inline::*;
 pub use metacache::*;
+pub use restore::*;
@@ -0,0 +1,147 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restore state of transitioned objects, stored in the `x-amz-restore` metadata entry.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use s3s::header::X_AMZ_RESTORE;
+use time::{OffsetDateTime, PrimitiveDateTime, macros::format_description};
+
+pub const X_AMZ_RESTORE_EXPIRY_DAYS: &str = "X-Amz-Restore-Expiry-Days";
+pub const X_AMZ_RESTORE_REQUEST_DATE: &str = "X-Amz-Restore-Request-Date";
+
+const HTTP_DATE_FORMAT: &[time::format_description::FormatItem<'static>] =
+    format_description!("[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT");
+
+/// Whether a restore of a transitioned object is running or has completed, and when the restored copy expires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestoreStatus {
+    ongoing: bool,
+    expiry: Option<OffsetDateTime>,
+}
+
+impl RestoreStatus {
+    pub fn ongoing() -> Self {
+        Self {
+            ongoing: true,
+            expiry: None,
+        }
+    }
+
+    pub fn completed(expiry: OffsetDateTime) -> Self {
+        Self {
+            ongoing: false,
+            expiry: Some(expiry),
+        }
+    }
+
+    /// Reads the restore status from object metadata, if a restore was ever requested.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        metadata.get(X_AMZ_RESTORE.as_str()).and_then(|v| Self::parse(v))
+    }
+
+    /// Parses an `x-amz-restore` value such as `ongoing-request="false", expiry-date="Fri, 21 Dec 2012 00:00:00 GMT"`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (progress, rest) = match value.split_once(',') {
+            Some((progress, rest)) => (progress, Some(rest)),
+            None => (value, None),
+        };
+
+        let (key, ongoing) = progress.split_once('=')?;
+        if key.trim() != "ongoing-request" {
+            return None;
+        }
+
+        match (ongoing.trim().trim_matches('"'), rest) {
+            ("true", None) => Some(Self::ongoing()),
+            ("false", Some(rest)) => {
+                let (key, expiry) = rest.split_once('=')?;
+                if key.trim() != "expiry-date" {
+                    return None;
+                }
+                let expiry = PrimitiveDateTime::parse(expiry.trim().trim_matches('"'), HTTP_DATE_FORMAT).ok()?;
+                Some(Self::completed(expiry.assume_utc()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_ongoing(&self) -> bool {
+        self.ongoing
+    }
+
+    pub fn expiry(&self) -> Option<OffsetDateTime> {
+        self.expiry
+    }
+
+    pub fn expired(&self, now: OffsetDateTime) -> bool {
+        !self.ongoing && self.expiry.is_some_and(|expiry| now >= expiry)
+    }
+
+    /// Reports whether a readable restored copy of the object is stored locally.
+    pub fn on_disk(&self, now: OffsetDateTime) -> bool {
+        !self.ongoing && !self.expired(now)
+    }
+}
+
+impl fmt::Display for RestoreStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.ongoing, self.expiry) {
+            (false, Some(expiry)) => {
+                let expiry = expiry
+                    .to_offset(time::UtcOffset::UTC)
+                    .format(HTTP_DATE_FORMAT)
+                    .map_err(|_| fmt::Error)?;
+                write!(f, "ongoing-request=\"false\", expiry-date=\"{expiry}\"")
+            }
+            _ => write!(f, "ongoing-request=\"true\""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_restore_status_round_trip() {
+        let ongoing = RestoreStatus::ongoing();
+        assert_eq!(ongoing.to_string(), "ongoing-request=\"true\"");
+        assert_eq!(RestoreStatus::parse(&ongoing.to_string()), Some(ongoing));
+
+        let completed = RestoreStatus::completed(datetime!(2012-12-21 0:00 UTC));
+        assert_eq!(
+            completed.to_string(),
+            "ongoing-request=\"false\", expiry-date=\"Fri, 21 Dec 2012 00:00:00 GMT\""
+        );
+        assert_eq!(RestoreStatus::parse(&completed.to_string()), Some(completed));
+
+        assert_eq!(RestoreStatus::parse("ongoing-request=\"false\""), None);
+        assert_eq!(RestoreStatus::parse("expiry-date=\"Fri, 21 Dec 2012 00:00:00 GMT\""), None);
+    }
+
+    #[test]
+    fn test_restore_status_on_disk() {
+        let expiry = datetime!(2012-12-21 0:00 UTC);
+        let completed = RestoreStatus::completed(expiry);
+        assert!(completed.on_disk(expiry - time::Duration::hours(1)));
+        assert!(!completed.on_disk(expiry));
+        assert!(completed.expired(expiry));
+        assert!(!RestoreStatus::ongoing().on_disk(expiry));
+        assert!(!RestoreStatus::ongoing().expired(expiry));
+    }
+}
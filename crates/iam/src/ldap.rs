@@ -0,0 +1,397 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! LDAP identity provider.
+//!
+//! Users of the directory get STS credentials with `AssumeRoleWithLDAPIdentity`. The server binds with
+//! a lookup account to find the DN of the user, checks the password by binding as the user and searches
+//! the groups of the user. Policies are attached to the DNs of users and groups with `policy_db_set`.
+
+use crate::error::{Error, Result};
+use ldap3::{LdapConnAsync, LdapConnSettings, Scope, SearchEntry, ldap_escape};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::warn;
+
+/// Claim carrying the DN of the LDAP user of temporary credentials.
+pub const LDAP_USER_CLAIM: &str = "ldapUser";
+/// Claim carrying the username the LDAP user logged in with.
+pub const LDAP_USERNAME_CLAIM: &str = "ldapUsername";
+/// Claim carrying the DNs of the groups of the LDAP user.
+pub const LDAP_GROUPS_CLAIM: &str = "ldapGroups";
+
+const LDAP_TIMEOUT: Duration = Duration::from_secs(30);
+
+static LDAP_PROVIDER: OnceLock<Arc<LdapProvider>> = OnceLock::new();
+
+pub fn init_ldap_provider(provider: Arc<LdapProvider>) {
+    if LDAP_PROVIDER.set(provider).is_err() {
+        warn!("LDAP provider is already initialized");
+    }
+}
+
+pub fn get_ldap_provider() -> Option<Arc<LdapProvider>> {
+    LDAP_PROVIDER.get().cloned()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LdapConfig {
+    /// URL of the server, `ldap://host:389` or `ldaps://host:636`.
+    pub server_addr: String,
+    /// Account the server binds with to search users and groups.
+    pub lookup_bind_dn: String,
+    pub lookup_bind_password: String,
+    /// Base DN and filter of the user search, `%s` in the filter is replaced by the username.
+    pub user_dn_search_base_dn: String,
+    pub user_dn_search_filter: String,
+    /// Base DN and filter of the group search, `%s` in the filter is replaced by the username and `%d`
+    /// by the DN of the user. Groups are not searched when unset.
+    pub group_search_base_dn: String,
+    pub group_search_filter: String,
+    /// Upgrade `ldap://` connections with StartTLS.
+    pub start_tls: bool,
+    pub tls_skip_verify: bool,
+}
+
+/// Directory the provider binds to, implemented over the LDAP protocol by [`LdapServer`].
+#[async_trait::async_trait]
+pub trait LdapDirectory: Send + Sync {
+    /// Opens a session bound as `dn`, failing when the password is wrong.
+    async fn bind(&self, dn: &str, password: &str) -> Result<Box<dyn LdapSession>>;
+}
+
+#[async_trait::async_trait]
+pub trait LdapSession: Send {
+    /// DNs of the entries below `base_dn` matching `filter`.
+    async fn search(&mut self, base_dn: &str, filter: &str) -> Result<Vec<String>>;
+}
+
+/// An LDAP server
+pub struct LdapServer {
+    url: String,
+    start_tls: bool,
+    tls_skip_verify: bool,
+}
+
+impl LdapServer {
+    pub fn new(url: &str, start_tls: bool, tls_skip_verify: bool) -> Self {
+        Self {
+            url: url.to_string(),
+            start_tls,
+            tls_skip_verify,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl LdapDirectory for LdapServer {
+    async fn bind(&self, dn: &str, password: &str) -> Result<Box<dyn LdapSession>> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(LDAP_TIMEOUT)
+            .set_starttls(self.start_tls)
+            .set_no_tls_verify(self.tls_skip_verify);
+        let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &self.url)
+            .await
+            .map_err(|e| Error::StringError(format!("ldap: connect to {} failed: {e}", self.url)))?;
+        ldap3::drive!(conn);
+
+        ldap.with_timeout(LDAP_TIMEOUT)
+            .simple_bind(dn, password)
+            .await
+            .and_then(|res| res.success())
+            .map_err(|e| Error::StringError(format!("ldap: bind as {dn} failed: {e}")))?;
+
+        Ok(Box::new(LdapServerSession { ldap }))
+    }
+}
+
+struct LdapServerSession {
+    ldap: ldap3::Ldap,
+}
+
+#[async_trait::async_trait]
+impl LdapSession for LdapServerSession {
+    async fn search(&mut self, base_dn: &str, filter: &str) -> Result<Vec<String>> {
+        // "1.1" requests no attributes, only the DNs are used
+        let (entries, _) = self
+            .ldap
+            .with_timeout(LDAP_TIMEOUT)
+            .search(base_dn, Scope::Subtree, filter, vec!["1.1"])
+            .await
+            .and_then(|res| res.success())
+            .map_err(|e| Error::StringError(format!("ldap: search {filter} in {base_dn} failed: {e}")))?;
+
+        Ok(entries.into_iter().map(|entry| SearchEntry::construct(entry).dn).collect())
+    }
+}
+
+/// A user authenticated by the directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdapIdentity {
+    pub username: String,
+    pub user_dn: String,
+    pub group_dns: Vec<String>,
+}
+
+pub struct LdapProvider {
+    config: LdapConfig,
+    directory: Box<dyn LdapDirectory>,
+}
+
+impl LdapProvider {
+    pub fn new(config: LdapConfig) -> Result<Self> {
+        let directory = LdapServer::new(&config.server_addr, config.start_tls, config.tls_skip_verify);
+        Self::with_directory(config, Box::new(directory))
+    }
+
+    pub fn with_directory(config: LdapConfig, directory: Box<dyn LdapDirectory>) -> Result<Self> {
+        if config.server_addr.is_empty() {
+            return Err(Error::StringError("ldap: server address is required".to_string()));
+        }
+        if config.lookup_bind_dn.is_empty() {
+            return Err(Error::StringError("ldap: lookup bind dn is required".to_string()));
+        }
+        if config.user_dn_search_base_dn.is_empty() || !config.user_dn_search_filter.contains("%s") {
+            return Err(Error::StringError(
+                "ldap: user dn search base dn and a filter with %s are required".to_string(),
+            ));
+        }
+
+        Ok(Self { config, directory })
+    }
+
+    /// Checks the password of the user and finds the user's DN and groups.
+    pub async fn authenticate(&self, username: &str, password: &str) -> Result<LdapIdentity> {
+        // A bind with an empty password is an unauthenticated bind, which servers accept
+        if username.is_empty() || password.is_empty() {
+            return Err(Error::StringError("ldap: username and password are required".to_string()));
+        }
+
+        let mut lookup = self.lookup_bind().await?;
+        let user_dn = self.search_user_dn(lookup.as_mut(), username).await?;
+
+        self.directory
+            .bind(&user_dn, password)
+            .await
+            .map_err(|_| Error::StringError("ldap: invalid username or password".to_string()))?;
+
+        let group_dns = self.search_group_dns(lookup.as_mut(), username, &user_dn).await?;
+
+        Ok(LdapIdentity {
+            username: username.to_string(),
+            user_dn,
+            group_dns,
+        })
+    }
+
+    /// The DN of the user with the username.
+    pub async fn lookup_user_dn(&self, username: &str) -> Result<String> {
+        let mut lookup = self.lookup_bind().await?;
+        self.search_user_dn(lookup.as_mut(), username).await
+    }
+
+    async fn lookup_bind(&self) -> Result<Box<dyn LdapSession>> {
+        self.directory
+            .bind(&self.config.lookup_bind_dn, &self.config.lookup_bind_password)
+            .await
+    }
+
+    async fn search_user_dn(&self, session: &mut dyn LdapSession, username: &str) -> Result<String> {
+        let filter = format_filter(&self.config.user_dn_search_filter, username, "");
+        let mut dns = session.search(&self.config.user_dn_search_base_dn, &filter).await?;
+
+        match dns.len() {
+            0 => Err(Error::NoSuchUser(username.to_string())),
+            1 => Ok(normalize_dn(&dns.remove(0))),
+            _ => Err(Error::StringError(format!("ldap: multiple users match {username}"))),
+        }
+    }
+
+    async fn search_group_dns(&self, session: &mut dyn LdapSession, username: &str, user_dn: &str) -> Result<Vec<String>> {
+        if self.config.group_search_base_dn.is_empty() || self.config.group_search_filter.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let filter = format_filter(&self.config.group_search_filter, username, user_dn);
+        let mut dns: Vec<String> = session
+            .search(&self.config.group_search_base_dn, &filter)
+            .await?
+            .iter()
+            .map(|dn| normalize_dn(dn))
+            .collect();
+        dns.sort();
+        dns.dedup();
+
+        Ok(dns)
+    }
+}
+
+/// DNs are compared, and policies attached to them, case insensitively.
+pub fn normalize_dn(dn: &str) -> String {
+    dn.trim().to_lowercase()
+}
+
+/// Replaces `%s` by the username and `%d` by the DN in a search filter, both escaped.
+fn format_filter(template: &str, username: &str, dn: &str) -> String {
+    let mut filter = String::with_capacity(template.len() + username.len() + dn.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('%', Some('s')) => {
+                filter.push_str(&ldap_escape(username));
+                chars.next();
+            }
+            ('%', Some('d')) => {
+                filter.push_str(&ldap_escape(dn));
+                chars.next();
+            }
+            _ => filter.push(c),
+        }
+    }
+    filter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// In-process stand-in for an LDAP server, answering searches from a table of filters
+    #[derive(Default)]
+    struct MemoryDirectory {
+        passwords: HashMap<String, String>,
+        searches: HashMap<(String, String), Vec<String>>,
+        filters: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct MemorySession {
+        searches: HashMap<(String, String), Vec<String>>,
+        filters: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LdapDirectory for MemoryDirectory {
+        async fn bind(&self, dn: &str, password: &str) -> Result<Box<dyn LdapSession>> {
+            if self.passwords.get(dn).map(String::as_str) != Some(password) {
+                return Err(Error::StringError("invalid credentials".to_string()));
+            }
+            Ok(Box::new(MemorySession {
+                searches: self.searches.clone(),
+                filters: self.filters.clone(),
+            }))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LdapSession for MemorySession {
+        async fn search(&mut self, base_dn: &str, filter: &str) -> Result<Vec<String>> {
+            self.filters.lock().unwrap().push(filter.to_string());
+            Ok(self
+                .searches
+                .get(&(base_dn.to_string(), filter.to_string()))
+                .cloned()
+                .unwrap_or_default())
+        }
+    }
+
+    const LOOKUP_DN: &str = "cn=admin,dc=example,dc=com";
+    const ALICE_DN: &str = "uid=alice,ou=people,dc=example,dc=com";
+
+    fn config() -> LdapConfig {
+        LdapConfig {
+            server_addr: "ldap://localhost:389".to_string(),
+            lookup_bind_dn: LOOKUP_DN.to_string(),
+            lookup_bind_password: "admin".to_string(),
+            user_dn_search_base_dn: "ou=people,dc=example,dc=com".to_string(),
+            user_dn_search_filter: "(uid=%s)".to_string(),
+            group_search_base_dn: "ou=groups,dc=example,dc=com".to_string(),
+            group_search_filter: "(&(objectclass=groupOfNames)(member=%d))".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn provider() -> (LdapProvider, Arc<Mutex<Vec<String>>>) {
+        let mut directory = MemoryDirectory::default();
+        directory.passwords.insert(LOOKUP_DN.to_string(), "admin".to_string());
+        directory.passwords.insert(ALICE_DN.to_string(), "secret".to_string());
+        directory.searches.insert(
+            ("ou=people,dc=example,dc=com".to_string(), "(uid=alice)".to_string()),
+            vec!["uid=alice,ou=People,dc=example,dc=com".to_string()],
+        );
+        directory.searches.insert(
+            (
+                "ou=groups,dc=example,dc=com".to_string(),
+                format!("(&(objectclass=groupOfNames)(member={ALICE_DN}))"),
+            ),
+            vec![
+                "cn=Dev,ou=groups,dc=example,dc=com".to_string(),
+                "cn=ops,ou=groups,dc=example,dc=com".to_string(),
+            ],
+        );
+        let filters = directory.filters.clone();
+
+        (LdapProvider::with_directory(config(), Box::new(directory)).unwrap(), filters)
+    }
+
+    #[tokio::test]
+    async fn test_authenticate() {
+        let (provider, _) = provider();
+
+        let identity = provider.authenticate("alice", "secret").await.unwrap();
+        assert_eq!(
+            identity,
+            LdapIdentity {
+                username: "alice".to_string(),
+                user_dn: ALICE_DN.to_string(),
+                group_dns: vec![
+                    "cn=dev,ou=groups,dc=example,dc=com".to_string(),
+                    "cn=ops,ou=groups,dc=example,dc=com".to_string(),
+                ],
+            }
+        );
+
+        assert_eq!(provider.lookup_user_dn("alice").await.unwrap(), ALICE_DN);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_invalid_credentials() {
+        let (provider, filters) = provider();
+
+        assert!(provider.authenticate("alice", "wrong").await.is_err());
+        assert!(provider.authenticate("alice", "").await.is_err());
+        assert_eq!(provider.authenticate("bob", "secret").await, Err(Error::NoSuchUser("bob".to_string())));
+
+        // Filter metacharacters in the username are escaped
+        assert!(provider.authenticate("*)(uid=*", "secret").await.is_err());
+        assert_eq!(filters.lock().unwrap().last().unwrap(), "(uid=\\2a\\29\\28uid=\\2a)");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = config();
+        config.user_dn_search_filter = "(uid=alice)".to_string();
+        assert!(LdapProvider::new(config).is_err());
+        assert!(LdapProvider::new(LdapConfig::default()).is_err());
+        assert!(LdapProvider::new(self::config()).is_ok());
+    }
+
+    #[test]
+    fn test_format_filter() {
+        assert_eq!(
+            format_filter("(&(uid=%s)(member=%d)(x=%%))", "a%d", "cn=a,dc=b"),
+            "(&(uid=a%d)(member=cn=a,dc=b)(x=%%))"
+        );
+    }
+}
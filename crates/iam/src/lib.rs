@@ -22,7 +22,9 @@ use tracing::{debug, instrument};
 
 pub mod cache;
 pub mod error;
+pub mod ldap;
 pub mod manager;
+pub mod openid;
 pub mod store;
 pub mod utils;
 
@@ -798,6 +798,33 @@ where
         Ok(OffsetDateTime::now_utc())
     }
 
+    /// Policies attached to an identity of an external directory, which has no user or group entry in IAM.
+    pub async fn policy_db_get_external(&self, name: &str, is_group: bool) -> Result<Vec<String>> {
+        let cache = if is_group {
+            &self.cache.group_policies
+        } else {
+            &self.cache.sts_policies
+        };
+
+        if let Some(p) = cache.load().get(name) {
+            return Ok(p.to_slice());
+        }
+
+        let mut m = HashMap::new();
+        if let Err(err) = self.api.load_mapped_policy(name, UserType::Sts, is_group, &mut m).await {
+            if !is_err_no_such_policy(&err) {
+                return Err(err);
+            }
+        }
+
+        let Some(p) = m.get(name) else {
+            return Ok(Vec::new());
+        };
+        Cache::add_or_update(cache, name, p, OffsetDateTime::now_utc());
+
+        Ok(p.to_slice())
+    }
+
     pub async fn set_temp_user(&self, access_key: &str, cred: &Credentials, policy_name: Option<&str>) -> Result<OffsetDateTime> {
         if access_key.is_empty() || !cred.is_temp() || cred.is_expired() || cred.parent_user.is_empty() {
             error!(
@@ -16,6 +16,7 @@ use crate::error::Error as IamError;
 use crate::error::is_err_no_such_account;
 use crate::error::is_err_no_such_temp_account;
 use crate::error::{Error, Result};
+use crate::ldap::{LDAP_GROUPS_CLAIM, LDAP_USER_CLAIM};
 use crate::manager::IamCache;
 use crate::manager::extract_jwt_claims;
 use crate::manager::get_default_policyes;
@@ -34,7 +35,9 @@ use rustfs_policy::auth::{
     is_access_key_valid, is_secret_key_valid,
 };
 use rustfs_policy::policy::Args;
-use rustfs_policy::policy::{EMBEDDED_POLICY_TYPE, INHERITED_POLICY_TYPE, Policy, PolicyDoc, iam_policy_claim_name_sa};
+use rustfs_policy::policy::{
+    EMBEDDED_POLICY_TYPE, INHERITED_POLICY_TYPE, Policy, PolicyDoc, get_policies_from_claims, iam_policy_claim_name_sa,
+};
 use rustfs_utils::crypto::{base64_decode, base64_encode};
 use serde_json::Value;
 use serde_json::json;
@@ -525,6 +528,53 @@ impl<T: Store> IamSys<T> {
         self.store.policy_db_get(name, groups).await
     }
 
+    /// Policies attached to the DN of an LDAP user and the DNs of its groups.
+    pub async fn policy_db_get_ldap(&self, user_dn: &str, group_dns: &[String]) -> Result<Vec<String>> {
+        let mut policies = self.store.policy_db_get_external(user_dn, false).await?;
+        for group_dn in group_dns {
+            for policy in self.store.policy_db_get_external(group_dn, true).await? {
+                if !policies.contains(&policy) {
+                    policies.push(policy);
+                }
+            }
+        }
+
+        Ok(policies)
+    }
+
+    /// Attaches policies to, or detaches them from, the DN of an LDAP user or group and returns the
+    /// policies that changed.
+    pub async fn ldap_policy_db_update(
+        &self,
+        dn: &str,
+        is_group: bool,
+        policies: &[String],
+        attach: bool,
+    ) -> Result<Vec<String>> {
+        let mut mapped = self.store.policy_db_get_external(dn, is_group).await?;
+
+        let mut changed = Vec::new();
+        for policy in policies {
+            if attach {
+                if !mapped.contains(policy) {
+                    mapped.push(policy.clone());
+                    changed.push(policy.clone());
+                }
+            } else if let Some(i) = mapped.iter().position(|v| v == policy) {
+                mapped.remove(i);
+                changed.push(policy.clone());
+            }
+        }
+
+        if !changed.is_empty() {
+            self.store
+                .policy_db_set(dn, UserType::Sts, is_group, &mapped.join(","))
+                .await?;
+        }
+
+        Ok(changed)
+    }
+
     pub async fn is_allowed_sts(&self, args: &Args<'_>, parent_user: &str) -> bool {
         let is_owner = parent_user == get_global_action_cred().unwrap().access_key;
         let role_arn = args.get_role_arn();
@@ -535,11 +585,27 @@ impl<T: Store> IamSys<T> {
                 let Ok(arn) = ARN::parse(role_arn.unwrap_or_default()) else { return false };
 
                 MappedPolicy::new(self.roles_map.get(&arn).map_or_else(String::default, |v| v.clone()).as_str()).to_slice()
+            } else if let Some(user_dn) = args.claims.get(LDAP_USER_CLAIM).and_then(Value::as_str) {
+                // Identities of an LDAP directory get the policies attached to their DN and groups
+                let group_dns: Vec<String> = args
+                    .claims
+                    .get(LDAP_GROUPS_CLAIM)
+                    .and_then(Value::as_array)
+                    .map(|v| v.iter().filter_map(|g| g.as_str().map(str::to_string)).collect())
+                    .unwrap_or_default();
+
+                let Ok(p) = self.policy_db_get_ldap(user_dn, &group_dns).await else { return false };
+
+                p
             } else {
                 let Ok(p) = self.policy_db_get(parent_user, args.groups).await else { return false };
 
-                p
-                //TODO: FROM JWT
+                if p.is_empty() {
+                    // Identities of an OpenID provider carry their policies in the session claims
+                    get_policies_from_claims(args.claims, POLICYNAME).0.into_iter().collect()
+                } else {
+                    p
+                }
             }
         };
 
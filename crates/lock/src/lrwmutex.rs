@@ -60,6 +60,11 @@ impl LRWMutex {
         self.look_loop(id, source, timeout, is_write).await
     }
 
+    /// Tries to take the lock once without waiting.
+    pub async fn try_lock(&self, id: &str, source: &str, is_write: bool) -> bool {
+        self.inner_lock(id, source, is_write).await
+    }
+
     async fn inner_lock(&self, id: &str, source: &str, is_write: bool) -> bool {
         *self.id.write().await = id.to_string();
         *self.source.write().await = source.to_string();
@@ -13,8 +13,14 @@
 // limitations under the License.
 
 use async_trait::async_trait;
-use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
-use tokio::sync::RwLock;
+use rand::Rng;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::RwLock, time::sleep};
 use uuid::Uuid;
 
 use crate::{
@@ -54,8 +60,11 @@ impl NsLockMap {
         }
     }
 
+    /// Locks `volume/path`, retrying until `timeout` elapses.
+    ///
+    /// The lock map is only held for a single attempt so the current holder can unlock while others wait.
     async fn lock(
-        &mut self,
+        &self,
         volume: &String,
         path: &String,
         lock_source: &str,
@@ -64,30 +73,34 @@ impl NsLockMap {
         timeout: Duration,
     ) -> bool {
         let resource = Path::new(volume).join(path).to_str().unwrap().to_string();
-        let mut w_lock_map = self.lock_map.write().await;
-        let nslk = w_lock_map.entry(resource.clone()).or_insert(NsLock {
-            reference: 0,
-            lock: LRWMutex::default(),
-        });
-        nslk.reference += 1;
+        let start = Instant::now();
+        loop {
+            {
+                let mut w_lock_map = self.lock_map.write().await;
+                let nslk = w_lock_map.entry(resource.clone()).or_insert(NsLock {
+                    reference: 0,
+                    lock: LRWMutex::default(),
+                });
+
+                if nslk.lock.try_lock(ops_id, lock_source, !read_lock).await {
+                    nslk.reference += 1;
+                    return true;
+                }
 
-        let locked = if read_lock {
-            nslk.lock.get_r_lock(ops_id, lock_source, &timeout).await
-        } else {
-            nslk.lock.get_lock(ops_id, lock_source, &timeout).await
-        };
+                if nslk.reference == 0 {
+                    w_lock_map.remove(&resource);
+                }
+            }
 
-        if !locked {
-            nslk.reference -= 1;
-            if nslk.reference == 0 {
-                w_lock_map.remove(&resource);
+            if start.elapsed() > timeout {
+                return false;
             }
+            let sleep_time = rand::rng().random_range(10..=50);
+            sleep(Duration::from_millis(sleep_time)).await;
         }
-
-        locked
     }
 
-    async fn un_lock(&mut self, volume: &String, path: &String, read_lock: bool) {
+    async fn un_lock(&self, volume: &String, path: &String, read_lock: bool) {
         let resource = Path::new(volume).join(path).to_str().unwrap().to_string();
         let mut w_lock_map = self.lock_map.write().await;
         if let Some(nslk) = w_lock_map.get_mut(&resource) {
@@ -180,6 +193,8 @@ struct LocalLockInstance {
     volume: String,
     paths: Vec<String>,
     ops_id: String,
+    // Only release what this instance holds, so dropping a failed attempt cannot unlock another holder.
+    locked: bool,
 }
 
 impl LocalLockInstance {
@@ -190,6 +205,7 @@ impl LocalLockInstance {
             volume,
             paths,
             ops_id,
+            locked: false,
         }
     }
 }
@@ -203,14 +219,14 @@ impl RWLocker for LocalLockInstance {
         for (idx, path) in self.paths.iter().enumerate() {
             if !self
                 .ns
-                .write()
+                .read()
                 .await
                 .lock(&self.volume, path, &source, &self.ops_id, read_lock, opts.timeout)
                 .await
             {
                 for (i, x) in success.iter().enumerate() {
                     if *x {
-                        self.ns.write().await.un_lock(&self.volume, &self.paths[i], read_lock).await;
+                        self.ns.read().await.un_lock(&self.volume, &self.paths[i], read_lock).await;
                     }
                 }
 
@@ -219,13 +235,17 @@ impl RWLocker for LocalLockInstance {
 
             success[idx] = true;
         }
+        self.locked = true;
         Ok(true)
     }
 
     async fn un_lock(&mut self) -> Result<()> {
+        if !std::mem::take(&mut self.locked) {
+            return Ok(());
+        }
         let read_lock = false;
         for path in self.paths.iter() {
-            self.ns.write().await.un_lock(&self.volume, path, read_lock).await;
+            self.ns.read().await.un_lock(&self.volume, path, read_lock).await;
         }
 
         Ok(())
@@ -234,18 +254,18 @@ impl RWLocker for LocalLockInstance {
     async fn get_u_lock(&mut self, opts: &Options) -> Result<bool> {
         let source = "".to_string();
         let read_lock = true;
-        let mut success = Vec::with_capacity(self.paths.len());
+        let mut success = vec![false; self.paths.len()];
         for (idx, path) in self.paths.iter().enumerate() {
             if !self
                 .ns
-                .write()
+                .read()
                 .await
                 .lock(&self.volume, path, &source, &self.ops_id, read_lock, opts.timeout)
                 .await
             {
                 for (i, x) in success.iter().enumerate() {
                     if *x {
-                        self.ns.write().await.un_lock(&self.volume, &self.paths[i], read_lock).await;
+                        self.ns.read().await.un_lock(&self.volume, &self.paths[i], read_lock).await;
                     }
                 }
 
@@ -254,13 +274,17 @@ impl RWLocker for LocalLockInstance {
 
             success[idx] = true;
         }
+        self.locked = true;
         Ok(true)
     }
 
     async fn un_r_lock(&mut self) -> Result<()> {
+        if !std::mem::take(&mut self.locked) {
+            return Ok(());
+        }
         let read_lock = true;
         for path in self.paths.iter() {
-            self.ns.write().await.un_lock(&self.volume, path, read_lock).await;
+            self.ns.read().await.un_lock(&self.volume, path, read_lock).await;
         }
 
         Ok(())
@@ -276,7 +300,7 @@ mod test {
 
     use crate::{
         drwmutex::Options,
-        namespace_lock::{NsLockMap, new_nslock},
+        namespace_lock::{NsLockMap, WrapperLocker, new_nslock},
     };
 
     #[tokio::test]
@@ -303,4 +327,139 @@ mod test {
         assert!(result);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_local_instance_waits_for_unlock() -> Result<()> {
+        let ns_lock_map = Arc::new(RwLock::new(NsLockMap::default()));
+        let opts = Options {
+            timeout: Duration::from_secs(5),
+            retry_interval: Duration::from_secs(1),
+        };
+        let first = new_nslock(
+            Arc::clone(&ns_lock_map),
+            "local".to_string(),
+            "test".to_string(),
+            vec!["foo".to_string()],
+            Vec::new(),
+        )
+        .await;
+        let second = new_nslock(
+            Arc::clone(&ns_lock_map),
+            "local".to_string(),
+            "test".to_string(),
+            vec!["foo".to_string()],
+            Vec::new(),
+        )
+        .await;
+
+        assert!(first.0.write().await.get_lock(&opts).await?);
+
+        let holder = Arc::clone(&first.0);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            holder.write().await.un_lock().await
+        });
+
+        assert!(second.0.write().await.get_lock(&opts).await?);
+
+        let short = Options {
+            timeout: Duration::from_millis(100),
+            retry_interval: Duration::from_millis(10),
+        };
+        assert!(!first.0.write().await.get_lock(&short).await?);
+        first.0.write().await.un_lock().await?;
+        assert!(!first.0.write().await.get_lock(&short).await?);
+        Ok(())
+    }
+
+    async fn local_lock(ns_lock_map: &Arc<RwLock<NsLockMap>>, paths: &[&str]) -> WrapperLocker {
+        new_nslock(
+            Arc::clone(ns_lock_map),
+            "local".to_string(),
+            "test".to_string(),
+            paths.iter().map(|path| path.to_string()).collect(),
+            Vec::new(),
+        )
+        .await
+    }
+
+    async fn locked_resources(ns_lock_map: &Arc<RwLock<NsLockMap>>) -> Vec<String> {
+        let mut resources: Vec<String> = ns_lock_map.read().await.lock_map.read().await.keys().cloned().collect();
+        resources.sort();
+        resources
+    }
+
+    #[tokio::test]
+    async fn test_local_instance_read_write_contention() -> Result<()> {
+        let ns_lock_map = Arc::new(RwLock::new(NsLockMap::default()));
+        let opts = Options {
+            timeout: Duration::from_secs(5),
+            retry_interval: Duration::from_secs(1),
+        };
+        let short = Options {
+            timeout: Duration::from_millis(100),
+            retry_interval: Duration::from_millis(10),
+        };
+        let first_reader = local_lock(&ns_lock_map, &["foo"]).await;
+        let second_reader = local_lock(&ns_lock_map, &["foo"]).await;
+        let writer = local_lock(&ns_lock_map, &["foo"]).await;
+
+        // Readers share the lock and keep writers out until the last one leaves.
+        assert!(first_reader.0.write().await.get_u_lock(&short).await?);
+        assert!(second_reader.0.write().await.get_u_lock(&short).await?);
+        assert!(!writer.0.write().await.get_lock(&short).await?);
+        first_reader.0.write().await.un_r_lock().await?;
+        assert!(!writer.0.write().await.get_lock(&short).await?);
+
+        let reader = Arc::clone(&second_reader.0);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            reader.write().await.un_r_lock().await
+        });
+        assert!(writer.0.write().await.get_lock(&opts).await?);
+
+        // A writer keeps readers out until it unlocks.
+        assert!(!first_reader.0.write().await.get_u_lock(&short).await?);
+        let holder = Arc::clone(&writer.0);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            holder.write().await.un_lock().await
+        });
+        assert!(first_reader.0.write().await.get_u_lock(&opts).await?);
+
+        first_reader.0.write().await.un_r_lock().await?;
+        assert!(locked_resources(&ns_lock_map).await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_local_instance_rolls_back_partial_lock() -> Result<()> {
+        let ns_lock_map = Arc::new(RwLock::new(NsLockMap::default()));
+        let short = Options {
+            timeout: Duration::from_millis(100),
+            retry_interval: Duration::from_millis(10),
+        };
+        let holder = local_lock(&ns_lock_map, &["bar"]).await;
+        let multi = local_lock(&ns_lock_map, &["foo", "bar"]).await;
+        let other = local_lock(&ns_lock_map, &["bar"]).await;
+
+        assert!(holder.0.write().await.get_lock(&short).await?);
+
+        // Failing on bar releases foo again, without touching the lock on bar.
+        assert!(!multi.0.write().await.get_lock(&short).await?);
+        assert!(!multi.0.write().await.get_u_lock(&short).await?);
+        assert_eq!(locked_resources(&ns_lock_map).await, vec!["test/bar".to_string()]);
+
+        // Unlocking after a failed attempt is a no-op, bar stays with its holder.
+        multi.0.write().await.un_lock().await?;
+        multi.0.write().await.un_r_lock().await?;
+        assert!(!other.0.write().await.get_lock(&short).await?);
+
+        holder.0.write().await.un_lock().await?;
+        assert!(multi.0.write().await.get_lock(&short).await?);
+        assert_eq!(locked_resources(&ns_lock_map).await, vec!["test/bar".to_string(), "test/foo".to_string()]);
+        multi.0.write().await.un_lock().await?;
+        assert!(locked_resources(&ns_lock_map).await.is_empty());
+        Ok(())
+    }
 }
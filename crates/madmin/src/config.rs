@@ -0,0 +1,51 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Help text of a single key of a configuration subsystem.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct HelpKV {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub description: String,
+    pub optional: bool,
+    #[serde(rename = "multipleTargets")]
+    pub multiple_targets: bool,
+}
+
+/// Help text of a configuration subsystem, returned by `help-config-kv`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Help {
+    #[serde(rename = "subSys")]
+    pub sub_sys: String,
+    pub description: String,
+    #[serde(rename = "multipleTargets")]
+    pub multiple_targets: bool,
+    #[serde(rename = "keysHelp")]
+    pub keys_help: Vec<HelpKV>,
+}
+
+/// The exported configuration as it was before a change, restorable by its id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigHistoryEntry {
+    #[serde(rename = "restoreId")]
+    pub restore_id: String,
+    #[serde(rename = "createTime", with = "time::serde::rfc3339")]
+    pub create_time: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub data: String,
+}
@@ -12,8 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::Disk;
+
 pub type HealItemType = String;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -58,3 +62,124 @@ pub struct HealResultItem {
     #[serde(rename = "objectSize")]
     pub object_size: usize,
 }
+
+/// Progress of the MRF (most recently failed) heal queue of a node.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MRFStatus {
+    pub bytes_healed: u64,
+    pub items_healed: u64,
+    pub items_failed: u64,
+    /// Operations waiting in the queue.
+    pub total_items: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SetStatus {
+    pub id: String,
+    pub pool_index: i32,
+    pub set_index: i32,
+    pub heal_status: String,
+    pub heal_priority: String,
+    pub total_objects: usize,
+    pub disks: Vec<Disk>,
+}
+
+/// Background heal state of one node, or of the whole cluster once aggregated with [`BgHealState::merge`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BgHealState {
+    /// Nodes that did not report their heal state.
+    #[serde(rename = "offline_nodes")]
+    pub offline_endpoints: Vec<String>,
+    #[serde(rename = "ScannedItemsCount")]
+    pub scanned_items_count: u64,
+    /// Drives currently being healed.
+    #[serde(rename = "HealDisks")]
+    pub heal_disks: Vec<String>,
+    pub sets: Vec<SetStatus>,
+    /// MRF status per node.
+    pub mrf: HashMap<String, MRFStatus>,
+    #[serde(rename = "sc_parity")]
+    pub scparity: HashMap<String, usize>,
+}
+
+impl BgHealState {
+    /// Merges the state reported by another node into this one.
+    pub fn merge(&mut self, other: BgHealState) {
+        self.offline_endpoints.extend(other.offline_endpoints);
+        self.scanned_items_count += other.scanned_items_count;
+        self.heal_disks.extend(other.heal_disks);
+        // Every node only reports its local drives, so the same set shows up once per node.
+        for set in other.sets {
+            match self.sets.iter_mut().find(|s| s.id == set.id) {
+                Some(existing) => {
+                    if existing.heal_status.is_empty() {
+                        existing.heal_status = set.heal_status;
+                        existing.heal_priority = set.heal_priority;
+                    }
+                    existing.total_objects = existing.total_objects.max(set.total_objects);
+                    existing.disks.extend(set.disks);
+                }
+                None => self.sets.push(set),
+            }
+        }
+        self.mrf.extend(other.mrf);
+        if self.scparity.is_empty() {
+            self.scparity = other.scparity;
+        }
+    }
+
+    /// Sorts the sets and heal disks, the order of node replies is random.
+    pub fn sort(&mut self) {
+        self.heal_disks.sort();
+        self.offline_endpoints.sort();
+        self.sets.sort_by_key(|s| (s.pool_index, s.set_index));
+        for set in self.sets.iter_mut() {
+            set.disks.sort_by_key(|d| d.disk_index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_state(node: &str, disk_index: i32, healing: bool) -> BgHealState {
+        let disk = Disk {
+            endpoint: format!("{node}/disk{disk_index}"),
+            healing,
+            disk_index,
+            ..Default::default()
+        };
+        BgHealState {
+            scanned_items_count: 10,
+            heal_disks: if healing { vec![disk.endpoint.clone()] } else { Vec::new() },
+            sets: vec![SetStatus {
+                id: "0-0".to_string(),
+                heal_status: if healing { "healing".to_string() } else { String::new() },
+                disks: vec![disk],
+                ..Default::default()
+            }],
+            mrf: HashMap::from([(node.to_string(), MRFStatus::default())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bg_heal_state_merge() {
+        let mut state = node_state("node2", 1, false);
+        state.merge(node_state("node1", 0, true));
+        state.sort();
+
+        assert_eq!(state.scanned_items_count, 20);
+        assert_eq!(state.heal_disks, vec!["node1/disk0"]);
+        assert_eq!(state.sets.len(), 1);
+        assert_eq!(state.sets[0].heal_status, "healing");
+        assert_eq!(state.sets[0].disks.len(), 2);
+        assert_eq!(state.sets[0].disks[0].disk_index, 0);
+        assert_eq!(state.mrf.len(), 2);
+
+        let json = serde_json::to_value(&state).unwrap();
+        assert!(json.get("HealDisks").is_some());
+        assert!(json.get("offline_nodes").is_some());
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod config;
 pub mod group;
 pub mod heal_commands;
 pub mod health;
@@ -19,12 +20,16 @@ pub mod info_commands;
 pub mod metrics;
 pub mod net;
 pub mod policy;
+pub mod quota;
 pub mod service_commands;
+pub mod site_replication;
 pub mod trace;
 pub mod user;
 pub mod utils;
 
+pub use config::*;
 pub use group::*;
 pub use info_commands::*;
 pub use policy::*;
+pub use quota::*;
 pub use user::*;
@@ -26,3 +26,22 @@ pub struct PolicyInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub update_date: Option<OffsetDateTime>,
 }
+
+/// Policies to attach to, or detach from, a user or a group of an identity provider
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PolicyAssociationReq {
+    pub policies: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub user: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub group: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyAssociationResp {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policies_attached: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policies_detached: Vec<String>,
+}
@@ -0,0 +1,80 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum QuotaType {
+    /// Writes which would take the bucket over the quota are rejected.
+    #[default]
+    #[serde(rename = "hard")]
+    Hard,
+}
+
+/// Quota configuration of a bucket, stored as `quota.json` in the bucket metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BucketQuota {
+    /// Deprecated, kept for configurations written before `size` existed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<u64>,
+    /// Maximum bucket size in bytes.
+    pub size: u64,
+    pub rate: u64,
+    pub requests: u64,
+    #[serde(rename = "quotatype", alias = "quota_type", skip_serializing_if = "Option::is_none")]
+    pub quota_type: Option<QuotaType>,
+}
+
+impl BucketQuota {
+    pub fn hard(size: u64) -> Self {
+        Self {
+            size,
+            quota_type: Some(QuotaType::Hard),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the hard limit in bytes, if one is configured.
+    pub fn hard_limit(&self) -> Option<u64> {
+        if self.quota_type.unwrap_or_default() != QuotaType::Hard {
+            return None;
+        }
+        match (self.size, self.quota) {
+            (0, Some(quota)) if quota > 0 => Some(quota),
+            (0, _) => None,
+            (size, _) => Some(size),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_quota_json() {
+        let quota: BucketQuota = serde_json::from_str(r#"{"quota":0,"size":1024,"quotatype":"hard"}"#).unwrap();
+        assert_eq!(quota.hard_limit(), Some(1024));
+        assert_eq!(
+            serde_json::to_string(&BucketQuota::hard(10)).unwrap(),
+            r#"{"size":10,"rate":0,"requests":0,"quotatype":"hard"}"#
+        );
+
+        let legacy: BucketQuota = serde_json::from_str(r#"{"quota":2048}"#).unwrap();
+        assert_eq!(legacy.hard_limit(), Some(2048));
+
+        assert_eq!(BucketQuota::default().hard_limit(), None);
+    }
+}
@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
 
 use hyper::Uri;
 
-use crate::{trace::TraceType, utils::parse_duration};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default)]
-#[allow(dead_code)]
+use crate::{
+    trace::{TraceInfo, TraceType},
+    utils::parse_duration,
+};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ServiceTraceOpts {
     s3: bool,
     internal: bool,
@@ -41,9 +45,8 @@ pub struct ServiceTraceOpts {
     threshold: Duration,
 }
 
-#[allow(dead_code)]
 impl ServiceTraceOpts {
-    fn trace_types(&self) -> TraceType {
+    pub fn trace_types(&self) -> TraceType {
         let mut tt = TraceType::default();
         tt.set_if(self.s3, &TraceType::S3);
         tt.set_if(self.internal, &TraceType::INTERNAL);
@@ -93,13 +96,6 @@ impl ServiceTraceOpts {
         self.batch_replication = query_pairs.get("batch-replication").is_some_and(|v| v == "true");
         self.batch_key_rotation = query_pairs.get("batch-keyrotation").is_some_and(|v| v == "true");
         self.batch_expire = query_pairs.get("batch-expire").is_some_and(|v| v == "true");
-        if query_pairs.get("all").is_some_and(|v| v == "true") {
-            self.s3 = true;
-            self.internal = true;
-            self.storage = true;
-            self.os = true;
-        }
-
         self.rebalance = query_pairs.get("rebalance").is_some_and(|v| v == "true");
         self.storage = query_pairs.get("storage").is_some_and(|v| v == "true");
         self.internal = query_pairs.get("internal").is_some_and(|v| v == "true");
@@ -108,6 +104,12 @@ impl ServiceTraceOpts {
         self.bootstrap = query_pairs.get("bootstrap").is_some_and(|v| v == "true");
         self.ftp = query_pairs.get("ftp").is_some_and(|v| v == "true");
         self.ilm = query_pairs.get("ilm").is_some_and(|v| v == "true");
+        if query_pairs.get("all").is_some_and(|v| v == "true") {
+            self.s3 = true;
+            self.internal = true;
+            self.storage = true;
+            self.os = true;
+        }
 
         if let Some(threshold) = query_pairs.get("threshold") {
             let duration = parse_duration(threshold)?;
@@ -116,4 +118,76 @@ impl ServiceTraceOpts {
 
         Ok(())
     }
+
+    /// Whether `info` passes the type, error and latency filters of these options.
+    pub fn matches(&self, info: &TraceInfo) -> bool {
+        if !self.trace_types().overlaps(&TraceType::new(info.trace_type)) {
+            return false;
+        }
+        if self.only_errors && !info.is_error() {
+            return false;
+        }
+        if !self.threshold.is_zero() && info.duration < self.threshold {
+            return false;
+        }
+        true
+    }
+}
+
+/// Action requested through the admin service API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceAction {
+    Restart,
+    Stop,
+    Freeze,
+    Unfreeze,
+}
+
+impl ServiceAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceAction::Restart => "restart",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Freeze => "freeze",
+            ServiceAction::Unfreeze => "unfreeze",
+        }
+    }
+}
+
+impl fmt::Display for ServiceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ServiceAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "restart" => Ok(ServiceAction::Restart),
+            "stop" => Ok(ServiceAction::Stop),
+            "freeze" => Ok(ServiceAction::Freeze),
+            "unfreeze" => Ok(ServiceAction::Unfreeze),
+            _ => Err(format!("unknown service action: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ServiceActionPeerResult {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub err: String,
+}
+
+/// Response of the admin service API, one result per node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceActionResult {
+    pub action: ServiceAction,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<ServiceActionPeerResult>,
 }
@@ -0,0 +1,206 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+/// A site given to the site replication add API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerSite {
+    pub name: String,
+    /// Endpoint of the site, such as `http://127.0.0.1:9000`.
+    pub endpoint: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// A site linked by site replication.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub name: String,
+    pub endpoint: String,
+    pub deployment_id: String,
+}
+
+/// Sites linked by site replication, as seen by one site.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteReplicationInfo {
+    pub enabled: bool,
+    /// Name of the local site
+    pub name: String,
+    pub sites: Vec<PeerInfo>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicateAddStatus {
+    pub success: bool,
+    pub status: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub err_detail: String,
+    /// Items which could not be synced to the peers when the sites were linked
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub initial_sync_errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SRRemoveReq {
+    pub site_names: Vec<String>,
+    pub remove_all: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicateRemoveStatus {
+    pub status: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub err_detail: String,
+}
+
+/// Local state a site reports to its peers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SRPeerState {
+    pub deployment_id: String,
+    pub buckets: Vec<String>,
+}
+
+/// Replication status of a peer site.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SRSiteStatus {
+    pub name: String,
+    pub endpoint: String,
+    pub deployment_id: String,
+    pub online: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub error: String,
+    /// Local buckets the peer does not have
+    pub missing_buckets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SRStatusInfo {
+    pub enabled: bool,
+    pub name: String,
+    pub buckets: usize,
+    pub sites: Vec<SRSiteStatus>,
+}
+
+/// Operation on a bucket replicated to the peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SRBucketOp {
+    /// Create the bucket with versioning enabled.
+    MakeWithVersioning,
+    /// Replicate the objects of the bucket to the other sites.
+    ConfigureReplication,
+    DeleteBucket,
+}
+
+/// A bucket metadata config file replicated to the peers, a config without data is deleted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SRBucketMeta {
+    pub bucket: String,
+    pub config_file: String,
+    pub data: Option<String>,
+}
+
+/// An STS credential replicated to the peers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SRSTSCredential {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: String,
+    pub parent_user: String,
+    pub expiration: Option<OffsetDateTime>,
+    pub groups: Option<Vec<String>>,
+    pub claims: Option<HashMap<String, Value>>,
+    /// Policy mapped to the credential
+    pub policy: Option<String>,
+}
+
+/// An IAM change replicated to the peers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SRIAMItem {
+    /// A canned policy, a policy without document is deleted.
+    #[serde(rename_all = "camelCase")]
+    Policy { name: String, policy: Option<String> },
+    /// A user, a user without secret key is deleted.
+    #[serde(rename_all = "camelCase")]
+    User {
+        access_key: String,
+        secret_key: Option<String>,
+        status: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    UserStatus { access_key: String, status: String },
+    #[serde(rename_all = "camelCase")]
+    GroupMembers {
+        group: String,
+        members: Vec<String>,
+        is_remove: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    GroupStatus { group: String, enabled: bool },
+    #[serde(rename_all = "camelCase")]
+    PolicyMapping {
+        user_or_group: String,
+        is_group: bool,
+        policy: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    StsCredential(SRSTSCredential),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sr_iam_item_serde() {
+        let item = SRIAMItem::PolicyMapping {
+            user_or_group: "dev".to_string(),
+            is_group: true,
+            policy: "readwrite".to_string(),
+        };
+        let data = serde_json::to_string(&item).unwrap();
+        assert_eq!(
+            data,
+            r#"{"type":"policy-mapping","userOrGroup":"dev","isGroup":true,"policy":"readwrite"}"#
+        );
+
+        let item: SRIAMItem = serde_json::from_str(r#"{"type":"group-status","group":"dev","enabled":false}"#).unwrap();
+        assert!(matches!(item, SRIAMItem::GroupStatus { ref group, enabled: false } if group == "dev"));
+
+        let item = SRIAMItem::StsCredential(SRSTSCredential {
+            access_key: "ak".to_string(),
+            parent_user: "user".to_string(),
+            ..Default::default()
+        });
+        let data = serde_json::to_string(&item).unwrap();
+        let item: SRIAMItem = serde_json::from_str(&data).unwrap();
+        assert!(matches!(item, SRIAMItem::StsCredential(ref c) if c.access_key == "ak" && c.parent_user == "user"));
+    }
+}
@@ -58,7 +58,7 @@ impl TraceType {
     }
 
     pub fn single_type(&self) -> bool {
-        todo!()
+        self.0.count_ones() == 1
     }
 
     pub fn merge(&mut self, other: &TraceType) {
@@ -79,102 +79,122 @@ impl TraceType {
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceInfo {
     #[serde(rename = "type")]
-    trace_type: u64,
+    pub trace_type: u64,
     #[serde(rename = "nodename")]
-    node_name: String,
+    pub node_name: String,
     #[serde(rename = "funcname")]
-    func_name: String,
+    pub func_name: String,
     #[serde(rename = "time")]
-    time: DateTime<Utc>,
+    pub time: DateTime<Utc>,
     #[serde(rename = "path")]
-    path: String,
+    pub path: String,
     #[serde(rename = "dur")]
-    duration: Duration,
+    pub duration: Duration,
     #[serde(rename = "bytes", skip_serializing_if = "Option::is_none")]
-    bytes: Option<i64>,
+    pub bytes: Option<i64>,
     #[serde(rename = "msg", skip_serializing_if = "Option::is_none")]
-    message: Option<String>,
+    pub message: Option<String>,
     #[serde(rename = "error", skip_serializing_if = "Option::is_none")]
-    error: Option<String>,
+    pub error: Option<String>,
     #[serde(rename = "custom", skip_serializing_if = "Option::is_none")]
-    custom: Option<HashMap<String, String>>,
+    pub custom: Option<HashMap<String, String>>,
     #[serde(rename = "http", skip_serializing_if = "Option::is_none")]
-    http: Option<TraceHTTPStats>,
+    pub http: Option<TraceHTTPStats>,
     #[serde(rename = "healResult", skip_serializing_if = "Option::is_none")]
-    heal_result: Option<HealResultItem>,
+    pub heal_result: Option<HealResultItem>,
 }
 
 impl TraceInfo {
+    pub fn new(trace_type: TraceType, node_name: impl Into<String>, func_name: impl Into<String>) -> Self {
+        Self {
+            trace_type: trace_type.mask(),
+            node_name: node_name.into(),
+            func_name: func_name.into(),
+            time: Utc::now(),
+            ..Default::default()
+        }
+    }
+
     pub fn mask(&self) -> u64 {
         TraceType::new(self.trace_type).mask()
     }
+
+    /// Whether the traced call failed, either with an error or an HTTP error status.
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+            || self
+                .http
+                .as_ref()
+                .and_then(|http| http.resp_info.status_code)
+                .is_some_and(|code| code >= 400)
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceInfoLegacy {
-    trace_info: TraceInfo,
+    pub trace_info: TraceInfo,
     #[serde(rename = "request")]
-    req_info: Option<TraceRequestInfo>,
+    pub req_info: Option<TraceRequestInfo>,
     #[serde(rename = "response")]
-    resp_info: Option<TraceResponseInfo>,
+    pub resp_info: Option<TraceResponseInfo>,
     #[serde(rename = "stats")]
-    call_stats: Option<TraceCallStats>,
+    pub call_stats: Option<TraceCallStats>,
     #[serde(rename = "storageStats")]
-    storage_stats: Option<StorageStats>,
+    pub storage_stats: Option<StorageStats>,
     #[serde(rename = "osStats")]
-    os_stats: Option<OSStats>,
+    pub os_stats: Option<OSStats>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct StorageStats {
-    path: String,
-    duration: Duration,
+    pub path: String,
+    pub duration: Duration,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct OSStats {
-    path: String,
-    duration: Duration,
+    pub path: String,
+    pub duration: Duration,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceHTTPStats {
-    req_info: TraceRequestInfo,
-    resp_info: TraceResponseInfo,
-    call_stats: TraceCallStats,
+    pub req_info: TraceRequestInfo,
+    pub resp_info: TraceResponseInfo,
+    pub call_stats: TraceCallStats,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceCallStats {
-    input_bytes: i32,
-    output_bytes: i32,
-    latency: Duration,
-    time_to_first_byte: Duration,
+    pub input_bytes: i32,
+    pub output_bytes: i32,
+    pub latency: Duration,
+    pub time_to_first_byte: Duration,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceRequestInfo {
-    time: DateTime<Utc>,
-    proto: String,
-    method: String,
+    pub time: DateTime<Utc>,
+    pub proto: String,
+    pub method: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    path: Option<String>,
+    pub path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    raw_query: Option<String>,
+    pub raw_query: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    headers: Option<HashMap<String, String>>,
+    pub headers: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    body: Option<Vec<u8>>,
-    client: String,
+    pub body: Option<Vec<u8>>,
+    pub client: String,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TraceResponseInfo {
-    time: DateTime<Utc>,
+    pub time: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    headers: Option<HashMap<String, String>>,
+    pub headers: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    body: Option<Vec<u8>>,
+    pub body: Option<Vec<u8>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    status_code: Option<i32>,
+    pub status_code: Option<i32>,
 }
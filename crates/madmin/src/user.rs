@@ -17,7 +17,7 @@ use serde_json::value::RawValue;
 use std::collections::HashMap;
 use time::OffsetDateTime;
 
-use crate::BackendInfo;
+use crate::{BackendInfo, BucketQuota};
 
 #[derive(Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub enum AccountStatus {
@@ -268,6 +268,8 @@ pub struct BucketDetails {
     pub versioning_suspended: bool,
     pub locking: bool,
     pub replication: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<BucketQuota>,
     // pub tagging: Option<Tagging>,
 }
 
@@ -845,6 +847,7 @@ mod tests {
                 versioning_suspended: false,
                 locking: true,
                 replication: false,
+                quota: None,
             }),
             prefix_usage,
             created: Some(now),
@@ -873,6 +876,7 @@ mod tests {
             versioning_suspended: false,
             locking: true,
             replication: true,
+            quota: None,
         };
 
         assert!(details.versioning);
@@ -48,7 +48,7 @@ mod config;
 mod entry;
 mod global;
 mod logger;
-mod metrics;
+pub mod metrics;
 mod sinks;
 mod system;
 mod telemetry;
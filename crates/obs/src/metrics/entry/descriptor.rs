@@ -54,11 +54,15 @@ impl MetricDescriptor {
     /// Get the full metric name, including the prefix and formatting path
     #[allow(dead_code)]
     pub fn get_full_metric_name(&self) -> String {
-        let prefix = self.metric_type.as_prom();
+        format!("{}{}", self.metric_type.as_prom(), self.get_metric_name())
+    }
+
+    /// Get the metric name as exposed to Prometheus, without the metric type prefix
+    pub fn get_metric_name(&self) -> String {
         let namespace = self.namespace.as_str();
         let formatted_subsystem = self.subsystem.as_str();
 
-        format!("{}{}_{}_{}", prefix, namespace, formatted_subsystem, self.name.as_str())
+        format!("{}_{}_{}", namespace, formatted_subsystem, self.name.as_str())
     }
 
     /// check whether the label is in the label set
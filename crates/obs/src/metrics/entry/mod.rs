@@ -19,6 +19,7 @@ pub(crate) mod metric_name;
 pub(crate) mod metric_type;
 pub(crate) mod namespace;
 mod path_utils;
+pub(crate) mod prometheus;
 pub(crate) mod subsystem;
 
 /// Create a new counter metric descriptor
@@ -0,0 +1,152 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::metrics::{MetricDescriptor, MetricType};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Samples of one metric, rendered together under a single HELP/TYPE header
+#[derive(Debug)]
+struct MetricFamily {
+    help: String,
+    metric_type: MetricType,
+    samples: Vec<String>,
+}
+
+/// PrometheusEncoder - Collects metric samples and renders them in the Prometheus text exposition format
+#[derive(Debug, Default)]
+pub struct PrometheusEncoder {
+    families: BTreeMap<String, MetricFamily>,
+}
+
+impl PrometheusEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample of the metric, the label values are matched to the variable labels of the descriptor in order
+    pub fn add(&mut self, md: &MetricDescriptor, label_values: &[&str], value: f64) {
+        debug_assert_eq!(md.variable_labels.len(), label_values.len(), "label values of {}", md.name.as_str());
+
+        let name = md.get_metric_name();
+        let mut sample = name.clone();
+        if !label_values.is_empty() {
+            sample.push('{');
+            for (i, (label, value)) in md.variable_labels.iter().zip(label_values).enumerate() {
+                if i > 0 {
+                    sample.push(',');
+                }
+                let _ = write!(sample, "{}=\"{}\"", label, escape_label_value(value));
+            }
+            sample.push('}');
+        }
+        sample.push(' ');
+        sample.push_str(&format_value(value));
+
+        self.families
+            .entry(name)
+            .or_insert_with(|| MetricFamily {
+                help: md.help.clone(),
+                metric_type: md.metric_type,
+                samples: Vec::new(),
+            })
+            .samples
+            .push(sample);
+    }
+
+    /// Whether no sample has been added
+    pub fn is_empty(&self) -> bool {
+        self.families.is_empty()
+    }
+
+    /// Render all samples, grouped by metric
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        for (name, family) in self.families.iter() {
+            let _ = writeln!(out, "# HELP {} {}", name, escape_help(&family.help));
+            let _ = writeln!(out, "# TYPE {} {}", name, family.metric_type.as_str());
+            for sample in family.samples.iter() {
+                out.push_str(sample);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{MetricName, new_counter_md, new_gauge_md, subsystems};
+
+    #[test]
+    fn test_prometheus_encoder() {
+        let drive_md = new_gauge_md(
+            MetricName::DriveFreeBytes,
+            "Total storage free on a drive in bytes",
+            &["drive", "pool_index"],
+            subsystems::SYSTEM_DRIVE,
+        );
+        let scans_md = new_counter_md(
+            MetricName::ScannerObjectsScanned,
+            "Total number of unique objects scanned since server start",
+            &[],
+            subsystems::SCANNER,
+        );
+
+        let mut encoder = PrometheusEncoder::new();
+        assert!(encoder.is_empty());
+        encoder.add(&drive_md, &["/data/rustfs0", "0"], 1024.0);
+        encoder.add(&scans_md, &[], 42.0);
+        encoder.add(&drive_md, &["/data/\"rustfs1\"", "0"], 0.5);
+
+        assert_eq!(
+            encoder.encode(),
+            "# HELP rustfs_scanner_objects_scanned Total number of unique objects scanned since server start\n\
+             # TYPE rustfs_scanner_objects_scanned counter\n\
+             rustfs_scanner_objects_scanned 42\n\
+             # HELP rustfs_system_drive_free_bytes Total storage free on a drive in bytes\n\
+             # TYPE rustfs_system_drive_free_bytes gauge\n\
+             rustfs_system_drive_free_bytes{drive=\"/data/rustfs0\",pool_index=\"0\"} 1024\n\
+             rustfs_system_drive_free_bytes{drive=\"/data/\\\"rustfs1\\\"\",pool_index=\"0\"} 0.5\n"
+        );
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(f64::INFINITY), "+Inf");
+        assert_eq!(format_value(f64::NAN), "NaN");
+        assert_eq!(format_value(3.0), "3");
+    }
+}
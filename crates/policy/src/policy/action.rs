@@ -182,6 +182,20 @@ pub enum S3Action {
     PutBucketPolicyAction,
     #[strum(serialize = "s3:PutBucketCors")]
     PutBucketCorsAction,
+    #[strum(serialize = "s3:GetBucketWebsite")]
+    GetBucketWebsiteAction,
+    #[strum(serialize = "s3:PutBucketWebsite")]
+    PutBucketWebsiteAction,
+    #[strum(serialize = "s3:DeleteBucketWebsite")]
+    DeleteBucketWebsiteAction,
+    #[strum(serialize = "s3:GetBucketLogging")]
+    GetBucketLoggingAction,
+    #[strum(serialize = "s3:PutBucketLogging")]
+    PutBucketLoggingAction,
+    #[strum(serialize = "s3:GetInventoryConfiguration")]
+    GetInventoryConfigurationAction,
+    #[strum(serialize = "s3:PutInventoryConfiguration")]
+    PutInventoryConfigurationAction,
     #[strum(serialize = "s3:PutObject")]
     PutObjectAction,
     #[strum(serialize = "s3:DeleteObjectVersion")]
@@ -0,0 +1,600 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Authentication of internode `NodeService` calls.
+//!
+//! Every call carries an HMAC-SHA256 signature over the call target, a timestamp, a random nonce
+//! and the SHA-256 digest of the request body sent in [`CONTENT_SHA256_METADATA`], keyed by a
+//! secret derived from the cluster credentials. The headers are verified before the body is read,
+//! so only authenticated callers get their body buffered, and the body is then checked against the
+//! signed digest. [`SignedChannel`] signs outgoing gRPC calls and [`VerifyService`] verifies incoming
+//! ones. The internode HTTP streams use the same scheme through [`sign_http_request`] and
+//! [`verify_http_request`]. When a CA certificate is configured, nodes additionally talk to each
+//! other over mutual TLS.
+
+use std::collections::{BTreeMap, HashSet};
+use std::convert::Infallible;
+use std::future::Future;
+use std::hash::{BuildHasher, RandomState};
+use std::pin::Pin;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use base64::engine::general_purpose;
+use bytes::{Bytes, BytesMut};
+use hmac::{Hmac, Mac};
+use http::{HeaderMap, HeaderValue, Method};
+use http_body::{Frame, SizeHint};
+use sha2::{Digest, Sha256};
+use tonic::{
+    Status,
+    transport::{Certificate, Channel, ClientTlsConfig, Identity},
+};
+use tower::Service;
+use tracing::warn;
+
+type HmacSha256 = Hmac<Sha256>;
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+pub const SIGNATURE_METADATA: &str = "x-rustfs-rpc-signature";
+pub const TIMESTAMP_METADATA: &str = "x-rustfs-rpc-timestamp";
+pub const NONCE_METADATA: &str = "x-rustfs-rpc-nonce";
+pub const CONTENT_SHA256_METADATA: &str = "x-rustfs-rpc-content-sha256";
+
+/// Body digest of HTTP calls whose body is streamed and cannot be hashed before it is sent.
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// How far a call's timestamp may drift from the server clock, in seconds.
+pub const TOKEN_VALID_DURATION: i64 = 300;
+
+const SECRET_CONTEXT: &str = "rustfs-internode-rpc";
+
+/// Largest request body buffered for verification, one message plus its gRPC frame header.
+const MAX_RPC_BODY_SIZE: usize = crate::DEFAULT_GRPC_SERVER_MESSAGE_LEN + 5;
+
+/// Number of independently locked parts of the nonce cache.
+const NONCE_SHARDS: usize = 16;
+/// Nonces kept per shard, a full shard forgets its oldest interval.
+const MAX_NONCES_PER_SHARD: usize = 256 * 1024;
+/// Nonces are grouped by the interval their timestamp falls in and expire a whole interval at a time.
+const NONCE_BUCKET_SECONDS: i64 = 10;
+
+static RPC_SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+static RPC_TLS: OnceLock<ClientTlsConfig> = OnceLock::new();
+static SEEN_NONCES: LazyLock<NonceCache> = LazyLock::new(NonceCache::default);
+
+/// Marks a server-side request that arrived over a connection with a verified client certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedPeer;
+
+/// Derives the internode signing key from the cluster credentials.
+///
+/// Must be called once at startup, before any internode call is made or served.
+pub fn init_rpc_secret(access_key: &str, secret_key: &str) {
+    let _ = RPC_SECRET.set(derive_secret(access_key, secret_key));
+}
+
+/// Enables mutual TLS for outgoing internode connections.
+///
+/// `ca` verifies the certificates presented by other nodes; `cert` and `key` identify this node.
+pub fn init_rpc_tls(ca: &[u8], cert: &[u8], key: &[u8]) {
+    let config = ClientTlsConfig::new()
+        .ca_certificate(Certificate::from_pem(ca))
+        .identity(Identity::from_pem(cert, key));
+    let _ = RPC_TLS.set(config);
+}
+
+/// Reports whether internode calls must come from peers authenticated with mutual TLS.
+pub fn mtls_enabled() -> bool {
+    RPC_TLS.get().is_some()
+}
+
+pub(crate) fn client_tls_config() -> Option<ClientTlsConfig> {
+    RPC_TLS.get().cloned()
+}
+
+fn derive_secret(access_key: &str, secret_key: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(secret_key.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(format!("{SECRET_CONTEXT}|{access_key}").as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_mac(secret: &[u8], target: &str, timestamp: i64, nonce: &str, body_digest: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC can take key of any size");
+    mac.update(format!("{target}|{timestamp}|{nonce}|{body_digest}").as_bytes());
+    mac
+}
+
+/// The digest of a request body as sent in [`CONTENT_SHA256_METADATA`].
+pub fn body_digest(body: &[u8]) -> String {
+    general_purpose::STANDARD.encode(Sha256::digest(body))
+}
+
+/// HTTP calls are signed over their method and path with query, gRPC calls over their path.
+fn http_target(method: &Method, path_and_query: &str) -> String {
+    format!("{method} {path_and_query}")
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Reads a whole request body into memory, failing when it is larger than `limit`.
+#[allow(clippy::result_large_err)]
+async fn read_body<B>(body: B, limit: usize) -> Result<Bytes, Status>
+where
+    B: http_body::Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let mut body = std::pin::pin!(body);
+    let mut buf = BytesMut::new();
+    while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+        let frame = frame.map_err(|e| Status::internal(format!("read request body failed: {}", e.into())))?;
+        if let Ok(data) = frame.into_data() {
+            if buf.len() + data.len() > limit {
+                return Err(Status::resource_exhausted("request body too large"));
+            }
+            buf.extend_from_slice(&data);
+        }
+    }
+    Ok(buf.freeze())
+}
+
+/// Checks a body read after its headers were verified against the signed digest.
+#[allow(clippy::result_large_err)]
+fn check_body_digest(signed_digest: &str, body: &[u8]) -> Result<(), Status> {
+    if signed_digest != body_digest(body) {
+        return Err(Status::unauthenticated("request body does not match the signed digest"));
+    }
+    Ok(())
+}
+
+/// A request body read into memory to be signed or verified.
+#[derive(Debug, Default)]
+pub struct SignedBody(Option<Bytes>);
+
+impl http_body::Body for SignedBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(self.get_mut().0.take().map(|data| Ok(Frame::data(data))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.0.as_ref().map_or(0, |data| data.len() as u64))
+    }
+}
+
+/// Internode channel that signs every outgoing `NodeService` call.
+///
+/// The request body is buffered to be signed, so client-streaming calls must close their
+/// request stream before the response is awaited.
+#[derive(Debug, Clone)]
+pub struct SignedChannel {
+    inner: Channel,
+}
+
+impl SignedChannel {
+    pub fn new(inner: Channel) -> Self {
+        Self { inner }
+    }
+}
+
+impl Service<http::Request<tonic::body::Body>> for SignedChannel {
+    type Response = http::Response<tonic::body::Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: http::Request<tonic::body::Body>) -> Self::Future {
+        // Take the channel that was polled ready and leave a clone behind.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let secret = RPC_SECRET
+                .get()
+                .ok_or_else(|| Status::unauthenticated("internode authentication is not configured"))?;
+            let (mut parts, body) = req.into_parts();
+            let body = read_body(body, usize::MAX).await?;
+            sign_request_with(secret, now_unix(), parts.uri.path(), &mut parts.headers, &body_digest(&body))?;
+            let req = http::Request::from_parts(parts, tonic::body::Body::new(SignedBody(Some(body))));
+            inner.call(req).await.map_err(Into::into)
+        })
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn sign_request_with(
+    secret: &[u8],
+    timestamp: i64,
+    target: &str,
+    headers: &mut HeaderMap,
+    body_digest: &str,
+) -> Result<(), Status> {
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let signature = general_purpose::STANDARD.encode(
+        signing_mac(secret, target, timestamp, &nonce, body_digest)
+            .finalize()
+            .into_bytes(),
+    );
+
+    headers.insert(SIGNATURE_METADATA, header_value(&signature)?);
+    headers.insert(TIMESTAMP_METADATA, header_value(&timestamp.to_string())?);
+    headers.insert(NONCE_METADATA, header_value(&nonce)?);
+    headers.insert(CONTENT_SHA256_METADATA, header_value(body_digest)?);
+    Ok(())
+}
+
+/// Signs an internode HTTP call. `body_digest` is the [`body_digest`] of the body, or
+/// [`UNSIGNED_PAYLOAD`] for a body streamed after the headers.
+pub fn sign_http_request(
+    method: &Method,
+    path_and_query: &str,
+    headers: &mut HeaderMap,
+    body_digest: &str,
+) -> std::io::Result<()> {
+    let secret = RPC_SECRET
+        .get()
+        .ok_or_else(|| std::io::Error::other("internode authentication is not configured"))?;
+    sign_request_with(secret, now_unix(), &http_target(method, path_and_query), headers, body_digest)
+        .map_err(|status| std::io::Error::other(status.message().to_owned()))
+}
+
+/// Verifies the headers of an internode HTTP call and returns the signed body digest, which the
+/// handler checks with [`verify_http_body`] once it has read the body.
+pub fn verify_http_request(method: &Method, path_and_query: &str, headers: &HeaderMap) -> std::io::Result<String> {
+    let secret = RPC_SECRET
+        .get()
+        .ok_or_else(|| std::io::Error::other("internode authentication is not configured"))?;
+    verify_headers_with(secret, now_unix(), &SEEN_NONCES, &http_target(method, path_and_query), headers)
+        .map_err(|status| std::io::Error::other(status.message().to_owned()))
+}
+
+/// Checks the body of an internode HTTP call against the digest returned by [`verify_http_request`].
+pub fn verify_http_body(signed_digest: &str, body: &[u8]) -> std::io::Result<()> {
+    check_body_digest(signed_digest, body).map_err(|status| std::io::Error::other(status.message().to_owned()))
+}
+
+#[allow(clippy::result_large_err)]
+fn header_value(value: &str) -> Result<HeaderValue, Status> {
+    value.parse().map_err(|_| Status::internal("invalid auth metadata"))
+}
+
+/// Server middleware: serves a `NodeService` call only if it carries a fresh, valid signature
+/// over its body and, when mutual TLS is enabled, arrived from a peer with a verified certificate.
+#[derive(Debug, Clone)]
+pub struct VerifyService<S> {
+    inner: S,
+}
+
+impl<S> VerifyService<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, B, ResBody> Service<http::Request<B>> for VerifyService<S>
+where
+    S: Service<http::Request<SignedBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    ResBody: Default + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            match verify_request(&parts, body).await {
+                Ok(body) => inner.call(http::Request::from_parts(parts, SignedBody(Some(body)))).await,
+                Err(status) => {
+                    warn!("rejected internode rpc call: {}", status.message());
+                    Ok(status.into_http())
+                }
+            }
+        })
+    }
+}
+
+#[allow(clippy::result_large_err)]
+async fn verify_request<B>(parts: &http::request::Parts, body: B) -> Result<Bytes, Status>
+where
+    B: http_body::Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    if mtls_enabled() && parts.extensions.get::<VerifiedPeer>().is_none() {
+        return Err(Status::unauthenticated("client certificate required"));
+    }
+    let secret = RPC_SECRET
+        .get()
+        .ok_or_else(|| Status::unauthenticated("internode authentication is not configured"))?;
+    // The body is only read for callers holding the secret.
+    let signed_digest = verify_headers_with(secret, now_unix(), &SEEN_NONCES, parts.uri.path(), &parts.headers)?;
+    let body = read_body(body, MAX_RPC_BODY_SIZE).await?;
+    check_body_digest(&signed_digest, &body)?;
+    Ok(body)
+}
+
+/// Verifies the signature, timestamp and nonce of a call, returns the signed body digest.
+#[allow(clippy::result_large_err)]
+fn verify_headers_with(secret: &[u8], now: i64, seen: &NonceCache, target: &str, headers: &HeaderMap) -> Result<String, Status> {
+    let header = |key: &str| {
+        headers
+            .get(key)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Status::unauthenticated(format!("missing {key}")))
+    };
+    let signature = header(SIGNATURE_METADATA)?;
+    let nonce_str = header(NONCE_METADATA)?;
+    let digest = header(CONTENT_SHA256_METADATA)?;
+    let timestamp: i64 = header(TIMESTAMP_METADATA)?
+        .parse()
+        .map_err(|_| Status::unauthenticated("invalid timestamp"))?;
+    if nonce_str.len() != 32 {
+        return Err(Status::unauthenticated("invalid nonce"));
+    }
+    let nonce = u128::from_str_radix(nonce_str, 16).map_err(|_| Status::unauthenticated("invalid nonce"))?;
+
+    if now.abs_diff(timestamp) > TOKEN_VALID_DURATION as u64 {
+        return Err(Status::unauthenticated("request timestamp outside the allowed window"));
+    }
+
+    let signature = general_purpose::STANDARD
+        .decode(signature)
+        .map_err(|_| Status::unauthenticated("invalid signature"))?;
+    signing_mac(secret, target, timestamp, nonce_str, digest)
+        .verify_slice(&signature)
+        .map_err(|_| Status::unauthenticated("invalid signature"))?;
+
+    // Only signed calls reach the cache, so it cannot be filled by unauthenticated clients.
+    seen.insert(nonce, timestamp, now)?;
+    Ok(digest.to_owned())
+}
+
+/// Nonces of recently accepted calls, kept until their timestamps fall out of the replay window.
+///
+/// The cache is split into shards with their own lock and size cap. Each shard groups its nonces
+/// by the interval of their timestamp, so expiring them drops whole sets instead of single entries.
+/// A full shard forgets its oldest interval and from then on rejects timestamps in it, which keeps
+/// replays out without failing fresh calls under load.
+struct NonceCache {
+    hasher: RandomState,
+    shards: Vec<Mutex<NonceShard>>,
+    max_per_shard: usize,
+}
+
+#[derive(Default)]
+struct NonceShard {
+    /// Nonces by the start of the interval of their timestamp.
+    buckets: BTreeMap<i64, HashSet<u128>>,
+    len: usize,
+    /// Timestamps before this were evicted to make room and can no longer be checked.
+    floor: i64,
+}
+
+impl Default for NonceCache {
+    fn default() -> Self {
+        Self::new(NONCE_SHARDS, MAX_NONCES_PER_SHARD)
+    }
+}
+
+impl NonceCache {
+    fn new(shards: usize, max_per_shard: usize) -> Self {
+        Self {
+            hasher: RandomState::new(),
+            shards: (0..shards).map(|_| Mutex::default()).collect(),
+            max_per_shard,
+        }
+    }
+
+    /// Records `nonce` of a call signed at `timestamp`, failing if it was already seen.
+    #[allow(clippy::result_large_err)]
+    fn insert(&self, nonce: u128, timestamp: i64, now: i64) -> Result<(), Status> {
+        let shard = &self.shards[self.hasher.hash_one(nonce) as usize % self.shards.len()];
+        let mut shard = shard.lock().unwrap_or_else(|e| e.into_inner());
+
+        // A timestamp is accepted until it is more than the window behind the clock.
+        while let Some(entry) = shard.buckets.first_entry() {
+            if *entry.key() + NONCE_BUCKET_SECONDS + TOKEN_VALID_DURATION >= now {
+                break;
+            }
+            let expired = entry.remove().len();
+            shard.len -= expired;
+        }
+
+        while shard.len >= self.max_per_shard {
+            let Some((start, nonces)) = shard.buckets.pop_first() else {
+                break;
+            };
+            shard.len -= nonces.len();
+            shard.floor = shard.floor.max(start + NONCE_BUCKET_SECONDS);
+            warn!("internode nonce cache is full, dropping nonces of timestamps before {}", shard.floor);
+        }
+
+        if timestamp < shard.floor {
+            return Err(Status::unauthenticated("request timestamp outside the replay window"));
+        }
+
+        let bucket = timestamp - timestamp.rem_euclid(NONCE_BUCKET_SECONDS);
+        if !shard.buckets.entry(bucket).or_default().insert(nonce) {
+            return Err(Status::unauthenticated("replayed request"));
+        }
+        shard.len += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATH: &str = "/node_service.NodeService/Ping";
+    const BODY: &[u8] = b"\0\0\0\0\x02\x08\x01";
+
+    fn signed(secret: &[u8], timestamp: i64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        sign_request_with(secret, timestamp, PATH, &mut headers, &body_digest(BODY)).unwrap();
+        headers
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn verify(secret: &[u8], now: i64, cache: &NonceCache, target: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), Status> {
+        let digest = verify_headers_with(secret, now, cache, target, headers)?;
+        check_body_digest(&digest, body)
+    }
+
+    #[test]
+    fn test_verify_signed_request() {
+        let secret = derive_secret("access", "secret");
+        let cache = NonceCache::default();
+        let now = 1_700_000_000;
+
+        assert!(verify(&secret, now, &cache, PATH, &signed(&secret, now), BODY).is_ok());
+        let old = signed(&secret, now - TOKEN_VALID_DURATION);
+        assert!(verify(&secret, now, &cache, PATH, &old, BODY).is_ok());
+    }
+
+    #[test]
+    fn test_reject_wrong_secret_path_body_and_stale_timestamp() {
+        let secret = derive_secret("access", "secret");
+        let cache = NonceCache::default();
+        let now = 1_700_000_000;
+
+        let other = derive_secret("other", "secret");
+        assert!(verify(&other, now, &cache, PATH, &signed(&secret, now), BODY).is_err());
+
+        let headers = signed(&secret, now);
+        assert!(verify(&secret, now, &cache, "/node_service.NodeService/DeleteVolume", &headers, BODY).is_err());
+
+        // A captured signature does not cover another message.
+        let headers = signed(&secret, now);
+        assert!(verify(&secret, now, &cache, PATH, &headers, b"\0\0\0\0\x02\x08\x02").is_err());
+
+        // Nor another digest header.
+        let mut headers = signed(&secret, now);
+        let forged = body_digest(b"\0\0\0\0\x02\x08\x02");
+        headers.insert(CONTENT_SHA256_METADATA, HeaderValue::from_str(&forged).unwrap());
+        assert!(verify_headers_with(&secret, now, &cache, PATH, &headers).is_err());
+
+        let stale = signed(&secret, now - TOKEN_VALID_DURATION - 1);
+        assert!(verify(&secret, now, &cache, PATH, &stale, BODY).is_err());
+
+        assert!(verify(&secret, now, &cache, PATH, &HeaderMap::new(), BODY).is_err());
+    }
+
+    #[test]
+    fn test_reject_replayed_request() {
+        let secret = derive_secret("access", "secret");
+        let cache = NonceCache::default();
+        let now = 1_700_000_000;
+
+        let headers = signed(&secret, now);
+        assert!(verify(&secret, now, &cache, PATH, &headers, BODY).is_ok());
+        assert!(verify(&secret, now + 1, &cache, PATH, &headers, BODY).is_err());
+    }
+
+    #[test]
+    fn test_http_target_covers_method() {
+        let secret = derive_secret("access", "secret");
+        let cache = NonceCache::default();
+        let now = 1_700_000_000;
+        let path = "/rustfs/rpc/read_file_stream?disk=d1&volume=v&path=p";
+
+        let mut headers = HeaderMap::new();
+        let target = http_target(&Method::GET, path);
+        sign_request_with(&secret, now, &target, &mut headers, UNSIGNED_PAYLOAD).unwrap();
+        assert!(verify_headers_with(&secret, now, &cache, &http_target(&Method::PUT, path), &headers).is_err());
+        assert_eq!(verify_headers_with(&secret, now, &cache, &target, &headers).unwrap(), UNSIGNED_PAYLOAD);
+    }
+
+    #[test]
+    fn test_nonce_cache_expires_old_entries() {
+        let cache = NonceCache::default();
+        assert!(cache.insert(1, 0, 0).is_ok());
+        assert!(cache.insert(1, 0, TOKEN_VALID_DURATION).is_err());
+        assert!(
+            cache
+                .insert(2, TOKEN_VALID_DURATION, TOKEN_VALID_DURATION + NONCE_BUCKET_SECONDS + 1)
+                .is_ok()
+        );
+
+        let len: usize = cache.shards.iter().map(|s| s.lock().unwrap().len).sum();
+        assert_eq!(len, 1);
+    }
+
+    #[test]
+    fn test_nonce_cache_sustained_load() {
+        // Sized for the replay window, a steady stream of calls never fills the cache.
+        let per_second = 200;
+        let cache = NonceCache::new(1, per_second * (TOKEN_VALID_DURATION + 6 * NONCE_BUCKET_SECONDS) as usize);
+        let start = 1_700_000_000;
+        let mut nonce = 0u128;
+        for now in start..start + 4 * TOKEN_VALID_DURATION {
+            for i in 0..per_second as i64 {
+                nonce += 1;
+                // Calls arrive with some clock skew between the nodes.
+                assert!(cache.insert(nonce, now - i % 30, now).is_ok());
+            }
+            assert!(cache.insert(nonce, now - (per_second as i64 - 1) % 30, now).is_err());
+        }
+        assert_eq!(cache.shards[0].lock().unwrap().floor, 0);
+    }
+
+    #[test]
+    fn test_nonce_cache_full_evicts_oldest_interval() {
+        let per_second = 200;
+        let cache = NonceCache::new(1, per_second * 50);
+        let start = 1_700_000_000;
+        let mut nonce = 0u128;
+        for now in start..start + 2 * TOKEN_VALID_DURATION {
+            for _ in 0..per_second {
+                nonce += 1;
+                // Fresh calls are never failed by a full cache.
+                assert!(cache.insert(nonce, now, now).is_ok());
+            }
+        }
+
+        let now = start + 2 * TOKEN_VALID_DURATION;
+        let shard = cache.shards[0].lock().unwrap();
+        assert!(shard.len <= per_second * 50);
+        assert!(shard.floor > now - TOKEN_VALID_DURATION);
+        drop(shard);
+        // Timestamps whose nonces were dropped cannot be replayed, recent ones are still checked.
+        assert!(cache.insert(1, now - TOKEN_VALID_DURATION, now).is_err());
+        assert!(cache.insert(nonce, now - 1, now).is_err());
+        assert!(cache.insert(nonce + 1, now, now).is_ok());
+    }
+}
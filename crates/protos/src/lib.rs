@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod auth;
 #[allow(unsafe_code)]
 mod generated;
 
@@ -20,32 +21,23 @@ use std::{error::Error, time::Duration};
 pub use generated::*;
 use proto_gen::node_service::node_service_client::NodeServiceClient;
 use rustfs_common::globals::GLOBAL_Conn_Map;
-use tonic::{
-    Request, Status,
-    metadata::MetadataValue,
-    service::interceptor::InterceptedService,
-    transport::{Channel, Endpoint},
-};
+use tonic::transport::Endpoint;
 
 // Default 100 MB
 pub const DEFAULT_GRPC_SERVER_MESSAGE_LEN: usize = 100 * 1024 * 1024;
 
-pub async fn node_service_time_out_client(
-    addr: &String,
-) -> Result<
-    NodeServiceClient<
-        InterceptedService<Channel, Box<dyn Fn(Request<()>) -> Result<Request<()>, Status> + Send + Sync + 'static>>,
-    >,
-    Box<dyn Error>,
-> {
-    let token: MetadataValue<_> = "rustfs rpc".parse()?;
-
+pub async fn node_service_time_out_client(addr: &String) -> Result<NodeServiceClient<auth::SignedChannel>, Box<dyn Error>> {
     let channel = { GLOBAL_Conn_Map.read().await.get(addr).cloned() };
 
     let channel = match channel {
         Some(channel) => channel,
         None => {
-            let connector = Endpoint::from_shared(addr.to_string())?.connect_timeout(Duration::from_secs(60));
+            let mut connector = Endpoint::from_shared(addr.to_string())?.connect_timeout(Duration::from_secs(60));
+            if addr.starts_with("https://") {
+                if let Some(tls) = auth::client_tls_config() {
+                    connector = connector.tls_config(tls)?;
+                }
+            }
             let channel = connector.connect().await?;
 
             {
@@ -56,11 +48,5 @@ pub async fn node_service_time_out_client(
     };
 
     // let timeout_channel = Timeout::new(channel, Duration::from_secs(60));
-    Ok(NodeServiceClient::with_interceptor(
-        channel,
-        Box::new(move |mut req: Request<()>| {
-            req.metadata_mut().insert("authorization", token.clone());
-            Ok(req)
-        }),
-    ))
+    Ok(NodeServiceClient::new(auth::SignedChannel::new(channel)))
 }
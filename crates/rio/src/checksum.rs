@@ -0,0 +1,577 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! S3 additional checksums (`x-amz-checksum-*`).
+//!
+//! [`ChecksumReader`] verifies the checksum a client sent along with an upload while the body is read,
+//! the checksum comes from a header or, for aws-chunked uploads, from the trailer after the body.
+//! [`Checksum::multipart`] derives the checksum of a multipart object from the checksums of its parts,
+//! either as a checksum over the part checksums (`COMPOSITE`) or, for the CRC types, as the CRC of the
+//! whole object (`FULL_OBJECT`).
+
+use crate::compress_index::{Index, TryGetIndex};
+use crate::{EtagResolvable, HashReaderDetector, HashReaderMut, Reader};
+use pin_project_lite::pin_project;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Checksum algorithms supported by S3
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumType {
+    #[serde(rename = "CRC32")]
+    Crc32,
+    #[serde(rename = "CRC32C")]
+    Crc32c,
+    #[serde(rename = "SHA1")]
+    Sha1,
+    #[serde(rename = "SHA256")]
+    Sha256,
+    #[serde(rename = "CRC64NVME")]
+    Crc64Nvme,
+}
+
+impl ChecksumType {
+    pub const ALL: [ChecksumType; 5] = [
+        ChecksumType::Crc32,
+        ChecksumType::Crc32c,
+        ChecksumType::Sha1,
+        ChecksumType::Sha256,
+        ChecksumType::Crc64Nvme,
+    ];
+
+    /// Parses an algorithm name such as `CRC32C`, as sent in `x-amz-checksum-algorithm`.
+    pub fn from_algorithm(algorithm: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str().eq_ignore_ascii_case(algorithm))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumType::Crc32 => "CRC32",
+            ChecksumType::Crc32c => "CRC32C",
+            ChecksumType::Sha1 => "SHA1",
+            ChecksumType::Sha256 => "SHA256",
+            ChecksumType::Crc64Nvme => "CRC64NVME",
+        }
+    }
+
+    /// Header carrying a checksum of this type.
+    pub fn header(&self) -> &'static str {
+        match self {
+            ChecksumType::Crc32 => "x-amz-checksum-crc32",
+            ChecksumType::Crc32c => "x-amz-checksum-crc32c",
+            ChecksumType::Sha1 => "x-amz-checksum-sha1",
+            ChecksumType::Sha256 => "x-amz-checksum-sha256",
+            ChecksumType::Crc64Nvme => "x-amz-checksum-crc64nvme",
+        }
+    }
+
+    /// Length of the raw checksum in bytes.
+    pub fn raw_len(&self) -> usize {
+        match self {
+            ChecksumType::Crc32 | ChecksumType::Crc32c => 4,
+            ChecksumType::Sha1 => 20,
+            ChecksumType::Sha256 => 32,
+            ChecksumType::Crc64Nvme => 8,
+        }
+    }
+
+    /// Whether the checksums of consecutive parts can be merged into the checksum of the whole object.
+    pub fn can_merge(&self) -> bool {
+        self.crc_params().is_some()
+    }
+
+    pub fn hasher(&self) -> ChecksumHasher {
+        match self {
+            ChecksumType::Crc32 => ChecksumHasher::Crc32(crc32fast::Hasher::new()),
+            ChecksumType::Crc32c => ChecksumHasher::Crc32c(0),
+            ChecksumType::Sha1 => ChecksumHasher::Sha1(sha1::Sha1::new()),
+            ChecksumType::Sha256 => ChecksumHasher::Sha256(sha2::Sha256::new()),
+            ChecksumType::Crc64Nvme => ChecksumHasher::Crc64Nvme(crc64fast_nvme::Digest::new()),
+        }
+    }
+
+    /// Reflected polynomial and width of the CRC types.
+    fn crc_params(&self) -> Option<(u64, usize)> {
+        match self {
+            ChecksumType::Crc32 => Some((0xedb8_8320, 32)),
+            ChecksumType::Crc32c => Some((0x82f6_3b78, 32)),
+            ChecksumType::Crc64Nvme => Some((0xad93_d235_94c9_3659u64.reverse_bits(), 64)),
+            ChecksumType::Sha1 | ChecksumType::Sha256 => None,
+        }
+    }
+}
+
+/// Running checksum of one [`ChecksumType`]
+pub enum ChecksumHasher {
+    Crc32(crc32fast::Hasher),
+    Crc32c(u32),
+    Sha1(sha1::Sha1),
+    Sha256(sha2::Sha256),
+    Crc64Nvme(crc64fast_nvme::Digest),
+}
+
+impl ChecksumHasher {
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            ChecksumHasher::Crc32(h) => h.update(data),
+            ChecksumHasher::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            ChecksumHasher::Sha1(h) => h.update(data),
+            ChecksumHasher::Sha256(h) => h.update(data),
+            ChecksumHasher::Crc64Nvme(h) => h.write(data),
+        }
+    }
+
+    /// Raw checksum, CRCs are big endian.
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            ChecksumHasher::Crc32(h) => h.finalize().to_be_bytes().to_vec(),
+            ChecksumHasher::Crc32c(crc) => crc.to_be_bytes().to_vec(),
+            ChecksumHasher::Sha1(h) => h.finalize().to_vec(),
+            ChecksumHasher::Sha256(h) => h.finalize().to_vec(),
+            ChecksumHasher::Crc64Nvme(h) => h.sum64().to_be_bytes().to_vec(),
+        }
+    }
+}
+
+/// Checksum of an object or of a part
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub checksum_type: ChecksumType,
+    /// Set when `encoded` covers the object data rather than the part checksums.
+    pub full_object: bool,
+    /// Base64 encoded raw checksum.
+    pub encoded: String,
+    /// Base64 encoded checksums of the parts of a multipart object.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<String>,
+}
+
+impl Checksum {
+    /// Checksum of a single upload, `None` if `encoded` is not a base64 checksum of the given type.
+    pub fn new(checksum_type: ChecksumType, encoded: &str) -> Option<Self> {
+        let raw = base64_simd::STANDARD.decode_to_vec(encoded).ok()?;
+        if raw.len() != checksum_type.raw_len() {
+            return None;
+        }
+
+        Some(Self {
+            checksum_type,
+            full_object: true,
+            encoded: encoded.to_string(),
+            parts: Vec::new(),
+        })
+    }
+
+    pub fn compute(checksum_type: ChecksumType, data: &[u8]) -> Self {
+        let mut hasher = checksum_type.hasher();
+        hasher.update(data);
+        Self {
+            checksum_type,
+            full_object: true,
+            encoded: base64_simd::STANDARD.encode_to_string(hasher.finalize()),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Checksum of a multipart object from the checksums and sizes of its parts.
+    pub fn multipart(checksum_type: ChecksumType, full_object: bool, parts: &[(String, i64)]) -> std::io::Result<Self> {
+        let mut raw_parts = Vec::with_capacity(parts.len());
+        for (encoded, _) in parts {
+            match base64_simd::STANDARD.decode_to_vec(encoded) {
+                Ok(raw) if raw.len() == checksum_type.raw_len() => raw_parts.push(raw),
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid {} part checksum {}", checksum_type.as_str(), encoded),
+                    ));
+                }
+            }
+        }
+
+        let raw = if full_object {
+            let Some((poly, width)) = checksum_type.crc_params() else {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("{} does not support full object checksums", checksum_type.as_str()),
+                ));
+            };
+
+            let mut crc = 0u64;
+            for (i, (raw, (_, size))) in raw_parts.iter().zip(parts).enumerate() {
+                let part_crc = raw.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+                crc = if i == 0 {
+                    part_crc
+                } else {
+                    crc_combine(poly, width, crc, part_crc, *size as u64)
+                };
+            }
+            crc.to_be_bytes()[8 - width / 8..].to_vec()
+        } else {
+            let mut hasher = checksum_type.hasher();
+            raw_parts.iter().for_each(|raw| hasher.update(raw));
+            hasher.finalize()
+        };
+
+        Ok(Self {
+            checksum_type,
+            full_object,
+            encoded: base64_simd::STANDARD.encode_to_string(raw),
+            parts: parts.iter().map(|(encoded, _)| encoded.clone()).collect(),
+        })
+    }
+
+    /// Value returned to clients, composite checksums end with the number of parts.
+    pub fn value(&self) -> String {
+        if self.full_object || self.parts.is_empty() {
+            self.encoded.clone()
+        } else {
+            format!("{}-{}", self.encoded, self.parts.len())
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        serde_json::from_slice(buf).ok()
+    }
+}
+
+/// Combines `crc1` of a first block of data with `crc2` of the `len2` bytes following it, for reflected
+/// CRCs whose initial value and final xor are all ones.
+fn crc_combine(poly: u64, width: usize, mut crc1: u64, crc2: u64, mut len2: u64) -> u64 {
+    fn times(mat: &[u64], mut vec: u64) -> u64 {
+        let mut sum = 0;
+        let mut i = 0;
+        while vec != 0 {
+            if vec & 1 != 0 {
+                sum ^= mat[i];
+            }
+            vec >>= 1;
+            i += 1;
+        }
+        sum
+    }
+
+    fn square(square: &mut [u64], mat: &[u64]) {
+        for (sq, &row) in square.iter_mut().zip(mat) {
+            *sq = times(mat, row);
+        }
+    }
+
+    if len2 == 0 {
+        return crc1;
+    }
+
+    // Operator advancing the CRC over one zero bit
+    let mut odd = vec![0u64; width];
+    odd[0] = poly;
+    for (n, row) in odd.iter_mut().enumerate().skip(1) {
+        *row = 1 << (n - 1);
+    }
+
+    let mut even = vec![0u64; width];
+    square(&mut even, &odd);
+    square(&mut odd, &even);
+
+    // Apply len2 zero bytes to crc1, squaring the operator for each bit of len2
+    loop {
+        square(&mut even, &odd);
+        if len2 & 1 != 0 {
+            crc1 = times(&even, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+
+        square(&mut odd, &even);
+        if len2 & 1 != 0 {
+            crc1 = times(&odd, crc1);
+        }
+        len2 >>= 1;
+        if len2 == 0 {
+            break;
+        }
+    }
+
+    crc1 ^ crc2
+}
+
+/// Error returned when uploaded data does not match the checksum sent with it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub want: String,
+    pub got: String,
+}
+
+impl std::fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "checksum mismatch: want {}, got {}", self.want, self.got)
+    }
+}
+
+impl std::error::Error for ChecksumMismatch {}
+
+/// A checksum sent after the body, e.g. in the trailer of an aws-chunked upload, or computed by the
+/// server for data it produces itself, e.g. a copied part.
+///
+/// The clones share the checksum [`ChecksumReader`] verified or computed once the whole body was read.
+#[derive(Debug, Clone)]
+pub struct TrailingChecksum {
+    pub checksum_type: ChecksumType,
+    verified: Arc<OnceLock<Checksum>>,
+}
+
+impl TrailingChecksum {
+    pub fn new(checksum_type: ChecksumType) -> Self {
+        Self {
+            checksum_type,
+            verified: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// The checksum of the body, `None` until it was read and matched the trailer, if there is one.
+    pub fn get(&self) -> Option<Checksum> {
+        self.verified.get().cloned()
+    }
+}
+
+/// Reads the encoded checksum from the trailer once the body is complete.
+type TrailerValue = Box<dyn Fn() -> Option<String> + Send + Sync>;
+
+pin_project! {
+    /// Verifies the checksum of the data read through it when the inner reader reaches EOF.
+    pub struct ChecksumReader {
+        #[pin]
+        pub inner: Box<dyn Reader>,
+        hasher: Option<ChecksumHasher>,
+        want: Option<Checksum>,
+        trailer: Option<(TrailingChecksum, Option<TrailerValue>)>,
+    }
+}
+
+impl ChecksumReader {
+    pub fn new(inner: Box<dyn Reader>, want: Checksum) -> Self {
+        Self {
+            inner,
+            hasher: Some(want.checksum_type.hasher()),
+            want: Some(want),
+            trailer: None,
+        }
+    }
+
+    /// Verifies the data against a trailing checksum, `value` is called at EOF to get the value the
+    /// client sent. A missing value fails the read like a mismatch.
+    pub fn with_trailer(
+        inner: Box<dyn Reader>,
+        trailer: TrailingChecksum,
+        value: impl Fn() -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner,
+            hasher: Some(trailer.checksum_type.hasher()),
+            want: None,
+            trailer: Some((trailer, Some(Box::new(value)))),
+        }
+    }
+
+    /// Computes the checksum of data the server produces itself, there is nothing to verify it against.
+    pub fn computing(inner: Box<dyn Reader>, into: TrailingChecksum) -> Self {
+        Self {
+            inner,
+            hasher: Some(into.checksum_type.hasher()),
+            want: None,
+            trailer: Some((into, None)),
+        }
+    }
+}
+
+impl AsyncRead for ChecksumReader {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.project();
+        let orig_filled = buf.filled().len();
+        let poll = this.inner.poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = &poll {
+            let filled = &buf.filled()[orig_filled..];
+            if !filled.is_empty() {
+                if let Some(hasher) = this.hasher.as_mut() {
+                    hasher.update(filled);
+                }
+            } else if let Some(hasher) = this.hasher.take() {
+                // EOF
+                let got = base64_simd::STANDARD.encode_to_string(hasher.finalize());
+                let want = match (&*this.want, &*this.trailer) {
+                    (Some(want), _) => Some(want.encoded.clone()),
+                    (None, Some((_, Some(value)))) => value(),
+                    (None, Some((_, None))) => Some(got.clone()),
+                    (None, None) => None,
+                };
+                if want.as_deref() != Some(got.as_str()) {
+                    let err = ChecksumMismatch {
+                        want: want.unwrap_or_default(),
+                        got,
+                    };
+                    return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, err)));
+                }
+                if let Some((trailer, _)) = this.trailer.as_ref() {
+                    let _ = trailer.verified.set(Checksum {
+                        checksum_type: trailer.checksum_type,
+                        full_object: true,
+                        encoded: got,
+                        parts: Vec::new(),
+                    });
+                }
+            }
+        }
+        poll
+    }
+}
+
+impl EtagResolvable for ChecksumReader {
+    fn try_resolve_etag(&mut self) -> Option<String> {
+        self.inner.try_resolve_etag()
+    }
+}
+
+impl HashReaderDetector for ChecksumReader {
+    fn is_hash_reader(&self) -> bool {
+        self.inner.is_hash_reader()
+    }
+
+    fn as_hash_reader_mut(&mut self) -> Option<&mut dyn HashReaderMut> {
+        self.inner.as_hash_reader_mut()
+    }
+}
+
+impl TryGetIndex for ChecksumReader {
+    fn try_get_index(&self) -> Option<&Index> {
+        self.inner.try_get_index()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WarpReader;
+    use tokio::io::{AsyncReadExt, BufReader};
+
+    fn reader(data: &'static [u8]) -> Box<dyn Reader> {
+        Box::new(WarpReader::new(BufReader::new(data)))
+    }
+
+    #[test]
+    fn test_checksum_check_values() {
+        let raw = |t| {
+            let c = Checksum::compute(t, b"123456789");
+            base64_simd::STANDARD.decode_to_vec(c.encoded).unwrap()
+        };
+
+        assert_eq!(raw(ChecksumType::Crc32), 0xcbf4_3926u32.to_be_bytes());
+        assert_eq!(raw(ChecksumType::Crc32c), 0xe306_9283u32.to_be_bytes());
+        assert_eq!(raw(ChecksumType::Crc64Nvme), 0xae8b_1486_0a79_9888u64.to_be_bytes());
+        assert_eq!(raw(ChecksumType::Sha1).len(), 20);
+        assert_eq!(raw(ChecksumType::Sha256).len(), 32);
+    }
+
+    #[test]
+    fn test_checksum_new_rejects_wrong_length() {
+        let crc32 = Checksum::compute(ChecksumType::Crc32, b"data").encoded;
+        assert!(Checksum::new(ChecksumType::Crc32, &crc32).is_some());
+        assert!(Checksum::new(ChecksumType::Sha256, &crc32).is_none());
+        assert!(Checksum::new(ChecksumType::Crc32, "not base64").is_none());
+    }
+
+    #[test]
+    fn test_full_object_checksum_matches_whole_data() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let (a, b, c) = (&data[..4096], &data[4096..4097], &data[4097..]);
+
+        for t in [ChecksumType::Crc32, ChecksumType::Crc32c, ChecksumType::Crc64Nvme] {
+            let parts: Vec<(String, i64)> = [a, b, c]
+                .iter()
+                .map(|p| (Checksum::compute(t, p).encoded, p.len() as i64))
+                .collect();
+            let merged = Checksum::multipart(t, true, &parts).unwrap();
+            assert_eq!(merged.encoded, Checksum::compute(t, &data).encoded, "{}", t.as_str());
+            assert_eq!(merged.value(), merged.encoded);
+        }
+
+        assert!(Checksum::multipart(ChecksumType::Sha256, true, &[]).is_err());
+    }
+
+    #[test]
+    fn test_composite_checksum() {
+        let parts: Vec<(String, i64)> = [&b"part one"[..], &b"part two"[..]]
+            .iter()
+            .map(|p| (Checksum::compute(ChecksumType::Sha256, p).encoded, p.len() as i64))
+            .collect();
+        let composite = Checksum::multipart(ChecksumType::Sha256, false, &parts).unwrap();
+
+        let mut raw = Vec::new();
+        for (encoded, _) in &parts {
+            raw.extend(base64_simd::STANDARD.decode_to_vec(encoded).unwrap());
+        }
+        assert_eq!(composite.encoded, Checksum::compute(ChecksumType::Sha256, &raw).encoded);
+        assert_eq!(composite.value(), format!("{}-2", composite.encoded));
+        assert_eq!(Checksum::from_bytes(&composite.to_bytes()), Some(composite));
+    }
+
+    #[tokio::test]
+    async fn test_checksum_reader() {
+        let want = Checksum::compute(ChecksumType::Crc32c, b"hello world");
+        let mut r = ChecksumReader::new(reader(b"hello world"), want.clone());
+        let mut buf = Vec::new();
+        r.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(buf, b"hello world");
+
+        let mut r = ChecksumReader::new(reader(b"hello there"), want);
+        let err = r.read_to_end(&mut Vec::new()).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.get_ref().is_some_and(|e| e.is::<ChecksumMismatch>()));
+    }
+
+    #[tokio::test]
+    async fn test_checksum_reader_trailer() {
+        let encoded = Checksum::compute(ChecksumType::Crc32, b"hello world").encoded;
+
+        let trailer = TrailingChecksum::new(ChecksumType::Crc32);
+        let value = encoded.clone();
+        let mut r = ChecksumReader::with_trailer(reader(b"hello world"), trailer.clone(), move || Some(value.clone()));
+        assert!(trailer.get().is_none());
+        r.read_to_end(&mut Vec::new()).await.unwrap();
+        assert_eq!(trailer.get().map(|c| c.encoded), Some(encoded.clone()));
+
+        let trailer = TrailingChecksum::new(ChecksumType::Crc32);
+        let mut r = ChecksumReader::with_trailer(reader(b"hello there"), trailer.clone(), move || Some(encoded.clone()));
+        let err = r.read_to_end(&mut Vec::new()).await.unwrap_err();
+        assert!(err.get_ref().is_some_and(|e| e.is::<ChecksumMismatch>()));
+        assert!(trailer.get().is_none());
+
+        // The client announced a trailer but did not send it
+        let trailer = TrailingChecksum::new(ChecksumType::Crc32);
+        let mut r = ChecksumReader::with_trailer(reader(b"hello world"), trailer.clone(), || None);
+        assert!(r.read_to_end(&mut Vec::new()).await.is_err());
+
+        let computed = TrailingChecksum::new(ChecksumType::Sha256);
+        let mut r = ChecksumReader::computing(reader(b"hello world"), computed.clone());
+        r.read_to_end(&mut Vec::new()).await.unwrap();
+        assert_eq!(computed.get(), Some(Checksum::compute(ChecksumType::Sha256, b"hello world")));
+    }
+}
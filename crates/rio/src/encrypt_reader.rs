@@ -12,29 +12,78 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Streaming AES-256-GCM encryption in fixed-size packages.
+//!
+//! The plaintext is split into packages of [`ENCRYPT_PACKAGE_SIZE`] bytes (the last one may be
+//! shorter). Every package is sealed independently and written as `nonce || ciphertext || tag`.
+//! The nonce of package `n` is the stream nonce with `n` XORed into its last four bytes. The
+//! associated data is `n` followed by a flag byte that marks the final package of the stream, so
+//! packages can neither be reordered nor dropped from the end of a stream. Because the package
+//! size is fixed, the encrypted offset of any plaintext offset can be computed without reading
+//! the stream, which is what allows ranged reads to start decrypting in the middle of an object.
+
 use crate::HashReaderDetector;
 use crate::HashReaderMut;
 use crate::compress_index::{Index, TryGetIndex};
 use crate::{EtagResolvable, Reader};
-use aes_gcm::aead::Aead;
+use aes_gcm::aead::{Aead, Payload};
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
 use pin_project_lite::pin_project;
-use rustfs_utils::{put_uvarint, put_uvarint_len};
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, ReadBuf};
 
+/// Plaintext size of a single encrypted package.
+pub const ENCRYPT_PACKAGE_SIZE: usize = 64 * 1024;
+
+/// Bytes added to every package: a 96-bit nonce and a 128-bit GCM tag.
+pub const ENCRYPT_PACKAGE_OVERHEAD: usize = NONCE_SIZE + TAG_SIZE;
+
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const SEALED_PACKAGE_SIZE: usize = ENCRYPT_PACKAGE_SIZE + ENCRYPT_PACKAGE_OVERHEAD;
+const FINAL_PACKAGE_FLAG: u8 = 0x80;
+
+/// Returns the size of the encrypted stream produced for `size` bytes of plaintext.
+pub fn encrypted_size(size: usize) -> usize {
+    size + size.div_ceil(ENCRYPT_PACKAGE_SIZE) * ENCRYPT_PACKAGE_OVERHEAD
+}
+
+fn package_nonce(base: &[u8; NONCE_SIZE], sequence: u32) -> [u8; NONCE_SIZE] {
+    let mut nonce = *base;
+    for (b, s) in nonce[NONCE_SIZE - 4..].iter_mut().zip(sequence.to_be_bytes()) {
+        *b ^= s;
+    }
+    nonce
+}
+
+fn package_aad(sequence: u32, last: bool) -> [u8; 5] {
+    let mut aad = [0u8; 5];
+    aad[..4].copy_from_slice(&sequence.to_be_bytes());
+    if last {
+        aad[4] = FINAL_PACKAGE_FLAG;
+    }
+    aad
+}
+
 pin_project! {
     /// A reader wrapper that encrypts data on the fly using AES-256-GCM.
-    /// This is a demonstration. For production, use a secure and audited crypto library.
+    ///
+    /// `nonce` must be unique for every stream encrypted with the same key.
     #[derive(Debug)]
     pub struct EncryptReader<R> {
         #[pin]
         pub inner: R,
         key: [u8; 32],   // AES-256-GCM key
-        nonce: [u8; 12], // 96-bit nonce for GCM
+        nonce: [u8; 12], // base nonce of the stream
+        sequence: u32,
+        plaintext: Vec<u8>,
+        plaintext_len: usize,
         buffer: Vec<u8>,
         buffer_pos: usize,
+        eof: bool,
         finished: bool,
     }
 }
@@ -48,8 +97,13 @@ where
             inner,
             key,
             nonce,
+            sequence: 0,
+            // One byte of look-ahead tells whether a full package is the last one.
+            plaintext: vec![0u8; ENCRYPT_PACKAGE_SIZE + 1],
+            plaintext_len: 0,
             buffer: Vec::new(),
             buffer_pos: 0,
+            eof: false,
             finished: false,
         }
     }
@@ -61,79 +115,70 @@ where
 {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
         let mut this = self.project();
-        // Serve from buffer if any
-        if *this.buffer_pos < this.buffer.len() {
-            let to_copy = std::cmp::min(buf.remaining(), this.buffer.len() - *this.buffer_pos);
-            buf.put_slice(&this.buffer[*this.buffer_pos..*this.buffer_pos + to_copy]);
-            *this.buffer_pos += to_copy;
-            if *this.buffer_pos == this.buffer.len() {
-                this.buffer.clear();
-                *this.buffer_pos = 0;
+        loop {
+            // Serve from buffer if any
+            if *this.buffer_pos < this.buffer.len() {
+                let to_copy = std::cmp::min(buf.remaining(), this.buffer.len() - *this.buffer_pos);
+                buf.put_slice(&this.buffer[*this.buffer_pos..*this.buffer_pos + to_copy]);
+                *this.buffer_pos += to_copy;
+                return Poll::Ready(Ok(()));
             }
-            return Poll::Ready(Ok(()));
-        }
-        if *this.finished {
-            return Poll::Ready(Ok(()));
-        }
-        // Read a fixed block size from inner
-        let block_size = 8 * 1024;
-        let mut temp = vec![0u8; block_size];
-        let mut temp_buf = ReadBuf::new(&mut temp);
-        match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(Ok(())) => {
-                let n = temp_buf.filled().len();
-                if n == 0 {
-                    // EOF, write end header
-                    let mut header = [0u8; 8];
-                    header[0] = 0xFF; // type: end
-                    *this.buffer = header.to_vec();
-                    *this.buffer_pos = 0;
-                    *this.finished = true;
-                    let to_copy = std::cmp::min(buf.remaining(), this.buffer.len());
-                    buf.put_slice(&this.buffer[..to_copy]);
-                    *this.buffer_pos += to_copy;
-                    Poll::Ready(Ok(()))
-                } else {
-                    // Encrypt the chunk
-                    let cipher = Aes256Gcm::new_from_slice(this.key).expect("key");
-                    let nonce = Nonce::from_slice(this.nonce);
-                    let plaintext = &temp_buf.filled()[..n];
-                    let plaintext_len = plaintext.len();
-                    let crc = crc32fast::hash(plaintext);
-                    let ciphertext = cipher
-                        .encrypt(nonce, plaintext)
-                        .map_err(|e| std::io::Error::other(format!("encrypt error: {e}")))?;
-                    let int_len = put_uvarint_len(plaintext_len as u64);
-                    let clen = int_len + ciphertext.len() + 4;
-                    // Header: 8 bytes
-                    // 0: type (0 = encrypted, 0xFF = end)
-                    // 1-3: length (little endian u24, ciphertext length)
-                    // 4-7: CRC32 of ciphertext (little endian u32)
-                    let mut header = [0u8; 8];
-                    header[0] = 0x00; // 0 = encrypted
-                    header[1] = (clen & 0xFF) as u8;
-                    header[2] = ((clen >> 8) & 0xFF) as u8;
-                    header[3] = ((clen >> 16) & 0xFF) as u8;
-                    header[4] = (crc & 0xFF) as u8;
-                    header[5] = ((crc >> 8) & 0xFF) as u8;
-                    header[6] = ((crc >> 16) & 0xFF) as u8;
-                    header[7] = ((crc >> 24) & 0xFF) as u8;
-                    let mut out = Vec::with_capacity(8 + int_len + ciphertext.len());
-                    out.extend_from_slice(&header);
-                    let mut plaintext_len_buf = vec![0u8; int_len];
-                    put_uvarint(&mut plaintext_len_buf, plaintext_len as u64);
-                    out.extend_from_slice(&plaintext_len_buf);
-                    out.extend_from_slice(&ciphertext);
-                    *this.buffer = out;
-                    *this.buffer_pos = 0;
-                    let to_copy = std::cmp::min(buf.remaining(), this.buffer.len());
-                    buf.put_slice(&this.buffer[..to_copy]);
-                    *this.buffer_pos += to_copy;
-                    Poll::Ready(Ok(()))
+            if *this.finished {
+                return Poll::Ready(Ok(()));
+            }
+
+            // Fill a whole package before sealing it, so every package but the last has the same size.
+            while !*this.eof && *this.plaintext_len <= ENCRYPT_PACKAGE_SIZE {
+                let mut temp_buf = ReadBuf::new(&mut this.plaintext[*this.plaintext_len..]);
+                match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(())) => {
+                        let n = temp_buf.filled().len();
+                        if n == 0 {
+                            *this.eof = true;
+                        }
+                        *this.plaintext_len += n;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
                 }
             }
-            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+
+            if *this.plaintext_len == 0 {
+                *this.finished = true;
+                continue;
+            }
+
+            let last = *this.plaintext_len <= ENCRYPT_PACKAGE_SIZE;
+            let len = std::cmp::min(*this.plaintext_len, ENCRYPT_PACKAGE_SIZE);
+
+            let cipher = Aes256Gcm::new_from_slice(this.key).map_err(|e| Error::other(format!("invalid key: {e}")))?;
+            let nonce = package_nonce(this.nonce, *this.sequence);
+            let ciphertext = cipher
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &this.plaintext[..len],
+                        aad: &package_aad(*this.sequence, last),
+                    },
+                )
+                .map_err(|e| Error::other(format!("encrypt error: {e}")))?;
+
+            this.buffer.clear();
+            this.buffer.extend_from_slice(&nonce);
+            this.buffer.extend_from_slice(&ciphertext);
+            *this.buffer_pos = 0;
+            if last {
+                *this.plaintext_len = 0;
+                *this.finished = true;
+            } else {
+                // Carry the look-ahead byte over into the next package.
+                this.plaintext[0] = this.plaintext[ENCRYPT_PACKAGE_SIZE];
+                *this.plaintext_len = 1;
+                *this.sequence = this
+                    .sequence
+                    .checked_add(1)
+                    .ok_or_else(|| Error::other("too many packages in encrypted stream"))?;
+            }
         }
     }
 }
@@ -170,45 +215,63 @@ where
 }
 
 pin_project! {
-    /// A reader wrapper that decrypts data on the fly using AES-256-GCM.
-    /// This is a demonstration. For production, use a secure and audited crypto library.
-#[derive(Debug)]
+    /// A reader wrapper that decrypts data produced by [`EncryptReader`].
+    ///
+    /// The input may also be a sequence of independently encrypted streams (one per part of a
+    /// multipart object), and may start at any package boundary, see [`DecryptReader::with_range`].
+    #[derive(Debug)]
     pub struct DecryptReader<R> {
         #[pin]
         pub inner: R,
-        key: [u8; 32],   // AES-256-GCM key
-        nonce: [u8; 12], // 96-bit nonce for GCM
+        key: [u8; 32], // AES-256-GCM key
+        nonce: Option<[u8; 12]>, // base nonce of the current stream, learned from its first package
+        sequence: u32,
+        last: bool, // the final package of the current stream has been read
+        // Encrypted bytes left in each stream; empty when the input is a single unbounded stream.
+        parts: VecDeque<usize>,
+        bounded: bool,
+        skip: usize,
+        package: Vec<u8>,
+        package_len: usize,
         buffer: Vec<u8>,
         buffer_pos: usize,
         finished: bool,
-        // For block framing
-        header_buf: [u8; 8],
-        header_read: usize,
-        header_done: bool,
-        ciphertext_buf: Option<Vec<u8>>,
-        ciphertext_read: usize,
-        ciphertext_len: usize,
     }
 }
 
 impl<R> DecryptReader<R>
 where
-    R: Reader,
+    R: AsyncRead + Unpin + Send + Sync,
 {
-    pub fn new(inner: R, key: [u8; 32], nonce: [u8; 12]) -> Self {
+    /// Decrypts a single stream from its beginning until EOF.
+    pub fn new(inner: R, key: [u8; 32]) -> Self {
+        Self::build(inner, key, 0, VecDeque::new(), false, 0)
+    }
+
+    /// Decrypts a sub-range of one or more consecutive streams.
+    ///
+    /// `sequence` is the package number the input starts at within the first stream, `parts` holds
+    /// the number of encrypted bytes to consume from each stream (later streams start at package
+    /// zero), and `skip` is the number of leading plaintext bytes to drop.
+    pub fn with_range(inner: R, key: [u8; 32], sequence: u32, parts: Vec<usize>, skip: usize) -> Self {
+        Self::build(inner, key, sequence, parts.into(), true, skip)
+    }
+
+    fn build(inner: R, key: [u8; 32], sequence: u32, parts: VecDeque<usize>, bounded: bool, skip: usize) -> Self {
         Self {
             inner,
             key,
-            nonce,
+            nonce: None,
+            sequence,
+            last: false,
+            parts,
+            bounded,
+            skip,
+            package: vec![0u8; SEALED_PACKAGE_SIZE],
+            package_len: 0,
             buffer: Vec::new(),
             buffer_pos: 0,
             finished: false,
-            header_buf: [0u8; 8],
-            header_read: 0,
-            header_done: false,
-            ciphertext_buf: None,
-            ciphertext_read: 0,
-            ciphertext_len: 0,
         }
     }
 }
@@ -219,121 +282,133 @@ where
 {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
         let mut this = self.project();
-        // Serve from buffer if any
-        if *this.buffer_pos < this.buffer.len() {
-            let to_copy = std::cmp::min(buf.remaining(), this.buffer.len() - *this.buffer_pos);
-            buf.put_slice(&this.buffer[*this.buffer_pos..*this.buffer_pos + to_copy]);
-            *this.buffer_pos += to_copy;
-            if *this.buffer_pos == this.buffer.len() {
-                this.buffer.clear();
-                *this.buffer_pos = 0;
+        loop {
+            // Serve from buffer if any
+            if *this.buffer_pos < this.buffer.len() {
+                let to_copy = std::cmp::min(buf.remaining(), this.buffer.len() - *this.buffer_pos);
+                buf.put_slice(&this.buffer[*this.buffer_pos..*this.buffer_pos + to_copy]);
+                *this.buffer_pos += to_copy;
+                return Poll::Ready(Ok(()));
             }
-            return Poll::Ready(Ok(()));
-        }
-        if *this.finished {
-            return Poll::Ready(Ok(()));
-        }
-        // Read header (8 bytes), support partial header read
-        while !*this.header_done && *this.header_read < 8 {
-            let mut temp = [0u8; 8];
-            let mut temp_buf = ReadBuf::new(&mut temp[0..8 - *this.header_read]);
-            match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
-                Poll::Pending => return Poll::Pending,
-                Poll::Ready(Ok(())) => {
-                    let n = temp_buf.filled().len();
-                    if n == 0 {
-                        break;
+            if *this.finished {
+                return Poll::Ready(Ok(()));
+            }
+
+            let want = if *this.bounded {
+                match this.parts.front() {
+                    Some(0) => {
+                        // The next stream starts over with its own nonce.
+                        this.parts.pop_front();
+                        *this.nonce = None;
+                        *this.sequence = 0;
+                        *this.last = false;
+                        continue;
+                    }
+                    Some(_) if *this.last => {
+                        return Poll::Ready(Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "encrypted stream continues after its final package",
+                        )));
+                    }
+                    Some(remaining) => std::cmp::min(*remaining, SEALED_PACKAGE_SIZE),
+                    None => {
+                        *this.finished = true;
+                        continue;
                     }
-                    this.header_buf[*this.header_read..*this.header_read + n].copy_from_slice(&temp_buf.filled()[..n]);
-                    *this.header_read += n;
                 }
-                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
-            }
-            if *this.header_read < 8 {
-                return Poll::Pending;
+            } else {
+                SEALED_PACKAGE_SIZE
+            };
+
+            let mut eof = false;
+            while *this.package_len < want {
+                let mut temp_buf = ReadBuf::new(&mut this.package[*this.package_len..want]);
+                match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(())) => {
+                        let n = temp_buf.filled().len();
+                        if n == 0 {
+                            eof = true;
+                            break;
+                        }
+                        *this.package_len += n;
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                }
             }
-        }
-        if !*this.header_done && *this.header_read == 8 {
-            *this.header_done = true;
-        }
-        if !*this.header_done {
-            return Poll::Pending;
-        }
-        let typ = this.header_buf[0];
-        let len = (this.header_buf[1] as usize) | ((this.header_buf[2] as usize) << 8) | ((this.header_buf[3] as usize) << 16);
-        let crc = (this.header_buf[4] as u32)
-            | ((this.header_buf[5] as u32) << 8)
-            | ((this.header_buf[6] as u32) << 16)
-            | ((this.header_buf[7] as u32) << 24);
-        *this.header_read = 0;
-        *this.header_done = false;
-        if typ == 0xFF {
-            *this.finished = true;
-            return Poll::Ready(Ok(()));
-        }
-        // Read ciphertext block (len bytes), support partial read
-        if this.ciphertext_buf.is_none() {
-            *this.ciphertext_len = len - 4; // 4 bytes for CRC32
-            *this.ciphertext_buf = Some(vec![0u8; *this.ciphertext_len]);
-            *this.ciphertext_read = 0;
-        }
-        let ciphertext_buf = this.ciphertext_buf.as_mut().unwrap();
-        while *this.ciphertext_read < *this.ciphertext_len {
-            let mut temp_buf = ReadBuf::new(&mut ciphertext_buf[*this.ciphertext_read..]);
-            match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
-                Poll::Pending => return Poll::Pending,
-                Poll::Ready(Ok(())) => {
-                    let n = temp_buf.filled().len();
-                    if n == 0 {
-                        break;
+
+            if eof {
+                if *this.bounded {
+                    return Poll::Ready(Err(Error::new(ErrorKind::UnexpectedEof, "encrypted stream is truncated")));
+                }
+                if *this.package_len == 0 {
+                    // A stream that ends without its final package has been truncated.
+                    if !*this.last && this.nonce.is_some() {
+                        return Poll::Ready(Err(Error::new(ErrorKind::UnexpectedEof, "encrypted stream is truncated")));
                     }
-                    *this.ciphertext_read += n;
+                    *this.finished = true;
+                    continue;
                 }
-                Poll::Ready(Err(e)) => {
-                    this.ciphertext_buf.take();
-                    *this.ciphertext_read = 0;
-                    *this.ciphertext_len = 0;
-                    return Poll::Ready(Err(e));
+            }
+            if !*this.bounded && *this.last {
+                return Poll::Ready(Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "encrypted stream continues after its final package",
+                )));
+            }
+
+            let len = *this.package_len;
+            *this.package_len = 0;
+            if len <= ENCRYPT_PACKAGE_OVERHEAD {
+                return Poll::Ready(Err(Error::new(ErrorKind::InvalidData, "encrypted package is too short")));
+            }
+
+            let mut nonce = [0u8; NONCE_SIZE];
+            nonce.copy_from_slice(&this.package[..NONCE_SIZE]);
+            match this.nonce {
+                Some(base) if package_nonce(base, *this.sequence) != nonce => {
+                    return Poll::Ready(Err(Error::new(ErrorKind::InvalidData, "encrypted package is out of order")));
                 }
+                Some(_) => {}
+                None => *this.nonce = Some(package_nonce(&nonce, *this.sequence)),
             }
+
+            // Only a full-size package can be followed by another one, so a short package must be
+            // the final one; a full-size package may be either.
+            let cipher = Aes256Gcm::new_from_slice(this.key).map_err(|e| Error::other(format!("invalid key: {e}")))?;
+            let decrypt = |last: bool| {
+                cipher.decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &this.package[NONCE_SIZE..len],
+                        aad: &package_aad(*this.sequence, last),
+                    },
+                )
+            };
+            let result = if len < SEALED_PACKAGE_SIZE {
+                decrypt(true).map(|plaintext| (plaintext, true))
+            } else {
+                decrypt(false)
+                    .map(|plaintext| (plaintext, false))
+                    .or_else(|_| decrypt(true).map(|plaintext| (plaintext, true)))
+            };
+            let (mut plaintext, last) = result.map_err(|e| Error::new(ErrorKind::InvalidData, format!("decrypt error: {e}")))?;
+            *this.last = last;
+
+            if let Some(remaining) = this.parts.front_mut() {
+                *remaining -= len;
+            }
+            *this.sequence = this.sequence.wrapping_add(1);
+
+            if *this.skip > 0 {
+                let n = std::cmp::min(*this.skip, plaintext.len());
+                plaintext.drain(..n);
+                *this.skip -= n;
+            }
+
+            *this.buffer = plaintext;
+            *this.buffer_pos = 0;
         }
-        if *this.ciphertext_read < *this.ciphertext_len {
-            return Poll::Pending;
-        }
-        // Parse uvarint for plaintext length
-        let (plaintext_len, uvarint_len) = rustfs_utils::uvarint(&ciphertext_buf[0..16]);
-        let ciphertext = &ciphertext_buf[uvarint_len as usize..];
-
-        // Decrypt
-        let cipher = Aes256Gcm::new_from_slice(this.key).expect("key");
-        let nonce = Nonce::from_slice(this.nonce);
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| std::io::Error::other(format!("decrypt error: {e}")))?;
-        if plaintext.len() != plaintext_len as usize {
-            this.ciphertext_buf.take();
-            *this.ciphertext_read = 0;
-            *this.ciphertext_len = 0;
-            return Poll::Ready(Err(std::io::Error::other("Plaintext length mismatch")));
-        }
-        // CRC32 check
-        let actual_crc = crc32fast::hash(&plaintext);
-        if actual_crc != crc {
-            this.ciphertext_buf.take();
-            *this.ciphertext_read = 0;
-            *this.ciphertext_len = 0;
-            return Poll::Ready(Err(std::io::Error::other("CRC32 mismatch")));
-        }
-        *this.buffer = plaintext;
-        *this.buffer_pos = 0;
-        // Clear block state for next block
-        this.ciphertext_buf.take();
-        *this.ciphertext_read = 0;
-        *this.ciphertext_len = 0;
-        let to_copy = std::cmp::min(buf.remaining(), this.buffer.len());
-        buf.put_slice(&this.buffer[..to_copy]);
-        *this.buffer_pos += to_copy;
-        Poll::Ready(Ok(()))
     }
 }
 
@@ -369,6 +444,15 @@ mod tests {
     use rand::RngCore;
     use tokio::io::{AsyncReadExt, BufReader};
 
+    async fn encrypt(data: &[u8], key: [u8; 32]) -> Vec<u8> {
+        let mut nonce = [0u8; 12];
+        rand::rng().fill_bytes(&mut nonce);
+        let mut encrypt_reader = EncryptReader::new(WarpReader::new(Cursor::new(data.to_vec())), key, nonce);
+        let mut encrypted = Vec::new();
+        encrypt_reader.read_to_end(&mut encrypted).await.unwrap();
+        encrypted
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt_reader_aes256gcm() {
         let data = b"hello sse encrypt";
@@ -387,7 +471,7 @@ mod tests {
 
         // Decrypt using DecryptReader
         let reader = Cursor::new(encrypted.clone());
-        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key, nonce);
+        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key);
         let mut decrypt_reader = decrypt_reader;
         let mut decrypted = Vec::new();
         decrypt_reader.read_to_end(&mut decrypted).await.unwrap();
@@ -414,7 +498,7 @@ mod tests {
         // Now test DecryptReader
 
         let reader = Cursor::new(encrypted.clone());
-        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key, nonce);
+        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key);
         let mut decrypt_reader = decrypt_reader;
         let mut decrypted = Vec::new();
         decrypt_reader.read_to_end(&mut decrypted).await.unwrap();
@@ -438,13 +522,116 @@ mod tests {
         let mut encrypt_reader = encrypt_reader;
         let mut encrypted = Vec::new();
         encrypt_reader.read_to_end(&mut encrypted).await.unwrap();
+        assert_eq!(encrypted.len(), encrypted_size(size));
 
         let reader = std::io::Cursor::new(encrypted.clone());
-        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key, nonce);
+        let decrypt_reader = DecryptReader::new(WarpReader::new(reader), key);
         let mut decrypt_reader = decrypt_reader;
         let mut decrypted = Vec::new();
         decrypt_reader.read_to_end(&mut decrypted).await.unwrap();
 
         assert_eq!(&decrypted, &data);
     }
+
+    #[tokio::test]
+    async fn test_decrypt_reader_range_across_packages() {
+        use rand::Rng;
+        let size = 3 * ENCRYPT_PACKAGE_SIZE + 100;
+        let mut data = vec![0u8; size];
+        rand::rng().fill(&mut data[..]);
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+
+        let encrypted = encrypt(&data, key).await;
+
+        // Plaintext range starting inside package 1 and ending inside package 3.
+        let (start, end) = (ENCRYPT_PACKAGE_SIZE + 10, 3 * ENCRYPT_PACKAGE_SIZE + 50);
+        let first = start / ENCRYPT_PACKAGE_SIZE;
+        let enc_start = first * SEALED_PACKAGE_SIZE;
+        let enc_end = encrypted.len();
+
+        let reader = Cursor::new(encrypted[enc_start..enc_end].to_vec());
+        let mut decrypt_reader = DecryptReader::with_range(
+            WarpReader::new(reader),
+            key,
+            first as u32,
+            vec![enc_end - enc_start],
+            start - first * ENCRYPT_PACKAGE_SIZE,
+        );
+        let mut decrypted = Vec::new();
+        decrypt_reader.read_to_end(&mut decrypted).await.unwrap();
+
+        assert_eq!(&decrypted[..end - start + 1], &data[start..=end]);
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_reader_multiple_streams() {
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+
+        let part1 = vec![1u8; ENCRYPT_PACKAGE_SIZE + 7];
+        let part2 = vec![2u8; 33];
+        let enc1 = encrypt(&part1, key).await;
+        let enc2 = encrypt(&part2, key).await;
+
+        // Start at the second package of the first stream and read through the second stream.
+        let mut input = enc1[SEALED_PACKAGE_SIZE..].to_vec();
+        input.extend_from_slice(&enc2);
+        let mut decrypt_reader = DecryptReader::with_range(
+            WarpReader::new(Cursor::new(input)),
+            key,
+            1,
+            vec![enc1.len() - SEALED_PACKAGE_SIZE, enc2.len()],
+            0,
+        );
+        let mut decrypted = Vec::new();
+        decrypt_reader.read_to_end(&mut decrypted).await.unwrap();
+
+        let mut expected = part1[ENCRYPT_PACKAGE_SIZE..].to_vec();
+        expected.extend_from_slice(&part2);
+        assert_eq!(decrypted, expected);
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_reader_rejects_truncated_stream() {
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+
+        let data = vec![9u8; 2 * ENCRYPT_PACKAGE_SIZE];
+        let encrypted = encrypt(&data, key).await;
+        assert_eq!(encrypted.len(), 2 * SEALED_PACKAGE_SIZE);
+
+        // Dropping the final package at a package boundary must be detected.
+        let truncated = encrypted[..SEALED_PACKAGE_SIZE].to_vec();
+        let mut decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(truncated.clone())), key);
+        let mut decrypted = Vec::new();
+        assert!(decrypt_reader.read_to_end(&mut decrypted).await.is_err());
+
+        // Appending a package after the final one must be detected as well.
+        let mut extended = encrypted.clone();
+        extended.extend_from_slice(&truncated);
+        let mut decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(extended)), key);
+        let mut decrypted = Vec::new();
+        assert!(decrypt_reader.read_to_end(&mut decrypted).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_reader_rejects_reordered_packages() {
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+
+        let data = vec![7u8; 2 * ENCRYPT_PACKAGE_SIZE];
+        let encrypted = encrypt(&data, key).await;
+
+        let mut swapped = encrypted[SEALED_PACKAGE_SIZE..].to_vec();
+        swapped.extend_from_slice(&encrypted[..SEALED_PACKAGE_SIZE]);
+        let mut decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(swapped)), key);
+        let mut decrypted = Vec::new();
+        assert!(decrypt_reader.read_to_end(&mut decrypted).await.is_err());
+
+        // A wrong key must not decrypt either.
+        let mut decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(encrypted)), [0u8; 32]);
+        let mut decrypted = Vec::new();
+        assert!(decrypt_reader.read_to_end(&mut decrypted).await.is_err());
+    }
 }
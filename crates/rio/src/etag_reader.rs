@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::compress_index::{Index, TryGetIndex};
-use crate::{EtagResolvable, HashReaderDetector, HashReaderMut, Reader};
+use crate::{ChecksumMismatch, EtagResolvable, HashReaderDetector, HashReaderMut, Reader};
 use md5::{Digest, Md5};
 use pin_project_lite::pin_project;
 use std::pin::Pin;
@@ -62,7 +62,11 @@ impl AsyncRead for EtagReader {
                 if let Some(checksum) = this.checksum {
                     let etag = format!("{:x}", this.md5.clone().finalize());
                     if *checksum != etag {
-                        return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Checksum mismatch")));
+                        let mismatch = ChecksumMismatch {
+                            want: checksum.clone(),
+                            got: etag,
+                        };
+                        return Poll::Ready(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, mismatch)));
                     }
                 }
             }
@@ -239,5 +243,6 @@ mod tests {
         // 校验失败，应该返回InvalidData错误
         let err = etag_reader.read_to_end(&mut buf).await.unwrap_err();
         assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.get_ref().is_some_and(|e| e.is::<ChecksumMismatch>()));
     }
 }
@@ -166,7 +166,10 @@ impl HashReader {
                 ));
             }
 
-            existing_hash_reader.set_checksum(md5.clone());
+            // Keep the md5 the inner reader verifies unless a new one is given
+            if md5.is_some() {
+                existing_hash_reader.set_checksum(md5.clone());
+            }
 
             if existing_hash_reader.size() < 0 && size >= 0 {
                 existing_hash_reader.set_size(size);
@@ -458,7 +461,7 @@ mod tests {
             println!("Encrypted size: {}", encrypted_data.len());
 
             // 解密数据
-            let decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(encrypted_data)), key, nonce);
+            let decrypt_reader = DecryptReader::new(WarpReader::new(Cursor::new(encrypted_data)), key);
             let mut decrypt_reader = decrypt_reader;
             let mut decrypted_data = Vec::new();
             decrypt_reader.read_to_end(&mut decrypted_data).await.unwrap();
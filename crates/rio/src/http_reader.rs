@@ -21,7 +21,7 @@ use std::error::Error as _;
 use std::io::{self, Error};
 use std::ops::Not as _;
 use std::pin::Pin;
-use std::sync::LazyLock;
+use std::sync::OnceLock;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::sync::mpsc;
@@ -29,11 +29,26 @@ use tokio_util::io::StreamReader;
 
 use crate::{EtagResolvable, HashReaderDetector, HashReaderMut};
 
+// Reuse the HTTP connection pool in the global `reqwest::Client` instance
+// TODO: interact with load balancing?
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
 fn get_http_client() -> Client {
-    // Reuse the HTTP connection pool in the global `reqwest::Client` instance
-    // TODO: interact with load balancing?
-    static CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-    CLIENT.clone()
+    CLIENT.get_or_init(Client::new).clone()
+}
+
+/// Makes internode streams trust `ca` and present this node's certificate for mutual TLS.
+///
+/// Must be called before the first request; later calls are ignored.
+pub fn init_http_client_tls(ca: &[u8], cert: &[u8], key: &[u8]) -> io::Result<()> {
+    let identity = reqwest::Identity::from_pem(&[cert, b"\n", key].concat()).map_err(Error::other)?;
+    let client = Client::builder()
+        .add_root_certificate(reqwest::Certificate::from_pem(ca).map_err(Error::other)?)
+        .identity(identity)
+        .build()
+        .map_err(Error::other)?;
+    let _ = CLIENT.set(client);
+    Ok(())
 }
 
 static HTTP_DEBUG_LOG: bool = false;
@@ -211,6 +226,17 @@ pin_project! {
 impl HttpWriter {
     /// Create a new HttpWriter for the given URL. The HTTP request is performed in the background.
     pub async fn new(url: String, method: Method, headers: HeaderMap) -> io::Result<Self> {
+        Self::with_probe_headers(url, method, headers.clone(), headers).await
+    }
+
+    /// Like [`HttpWriter::new`], but sends the empty request checking the URL is writable with
+    /// `probe_headers`, for servers that reject a request whose headers were already seen.
+    pub async fn with_probe_headers(
+        url: String,
+        method: Method,
+        probe_headers: HeaderMap,
+        headers: HeaderMap,
+    ) -> io::Result<Self> {
         // http_log!("[HttpWriter::new] url: {url}, method: {method:?}, headers: {headers:?}");
         let url_clone = url.clone();
         let method_clone = method.clone();
@@ -218,7 +244,7 @@ impl HttpWriter {
 
         // First, try to write empty data to check if writable
         let client = get_http_client();
-        let resp = client.put(&url).headers(headers.clone()).body(Vec::new()).send().await;
+        let resp = client.put(&url).headers(probe_headers).body(Vec::new()).send().await;
         match resp {
             Ok(resp) => {
                 // http_log!("[HttpWriter::new] empty PUT status: {}", resp.status());
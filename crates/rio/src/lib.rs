@@ -24,7 +24,7 @@ mod compress_reader;
 pub use compress_reader::{CompressReader, DecompressReader};
 
 mod encrypt_reader;
-pub use encrypt_reader::{DecryptReader, EncryptReader};
+pub use encrypt_reader::{DecryptReader, ENCRYPT_PACKAGE_OVERHEAD, ENCRYPT_PACKAGE_SIZE, EncryptReader, encrypted_size};
 
 mod hardlimit_reader;
 pub use hardlimit_reader::HardLimitReader;
@@ -32,6 +32,9 @@ pub use hardlimit_reader::HardLimitReader;
 mod hash_reader;
 pub use hash_reader::*;
 
+mod checksum;
+pub use checksum::{Checksum, ChecksumHasher, ChecksumMismatch, ChecksumReader, ChecksumType, TrailingChecksum};
+
 pub mod reader;
 pub use reader::WarpReader;
 
@@ -79,5 +82,6 @@ pub trait HashReaderDetector {
 impl Reader for crate::HashReader {}
 impl Reader for crate::HardLimitReader {}
 impl Reader for crate::EtagReader {}
+impl Reader for crate::ChecksumReader {}
 impl<R> Reader for crate::CompressReader<R> where R: Reader {}
 impl<R> Reader for crate::EncryptReader<R> where R: Reader {}
@@ -44,6 +44,7 @@ pin_project! {
     pub struct LimitReader<R> {
         #[pin]
         pub inner: R,
+        skip: usize,
         limit: usize,
         read: usize,
     }
@@ -56,7 +57,18 @@ where
 {
     /// Create a new LimitReader wrapping `inner`, with a total read limit of `limit` bytes.
     pub fn new(inner: R, limit: usize) -> Self {
-        Self { inner, limit, read: 0 }
+        Self::with_skip(inner, 0, limit)
+    }
+
+    /// Create a new LimitReader that discards the first `skip` bytes of `inner` and then reads at
+    /// most `limit` bytes.
+    pub fn with_skip(inner: R, skip: usize, limit: usize) -> Self {
+        Self {
+            inner,
+            skip,
+            limit,
+            read: 0,
+        }
     }
 }
 
@@ -66,6 +78,20 @@ where
 {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
         let mut this = self.project();
+        while *this.skip > 0 {
+            let mut temp = vec![0u8; (*this.skip).min(64 * 1024)];
+            let mut temp_buf = ReadBuf::new(&mut temp);
+            match this.inner.as_mut().poll_read(cx, &mut temp_buf) {
+                Poll::Ready(Ok(())) => {
+                    let n = temp_buf.filled().len();
+                    if n == 0 {
+                        return Poll::Ready(Ok(()));
+                    }
+                    *this.skip -= n;
+                }
+                other => return other,
+            }
+        }
         let remaining = this.limit.saturating_sub(*this.read);
         if remaining == 0 {
             return Poll::Ready(Ok(()));
@@ -125,6 +151,18 @@ mod tests {
     use super::*;
     use tokio::io::{AsyncReadExt, BufReader};
 
+    #[tokio::test]
+    async fn test_limit_reader_with_skip() {
+        let data = b"hello world";
+        let reader = BufReader::new(&data[..]);
+        let mut limit_reader = LimitReader::with_skip(reader, 6, 3);
+
+        let mut buf = Vec::new();
+        let n = limit_reader.read_to_end(&mut buf).await.unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(&buf, b"wor");
+    }
+
     #[tokio::test]
     async fn test_limit_reader_exact() {
         let data = b"hello world";
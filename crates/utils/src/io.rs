@@ -35,18 +35,9 @@ pub async fn write_all<W: AsyncWrite + Send + Sync + Unpin>(writer: &mut W, buf:
 pub async fn read_full<R: AsyncRead + Send + Sync + Unpin>(mut reader: R, mut buf: &mut [u8]) -> std::io::Result<usize> {
     let mut total = 0;
     while !buf.is_empty() {
-        let n = match reader.read(buf).await {
-            Ok(n) => n,
-            Err(e) => {
-                if total == 0 {
-                    return Err(e);
-                }
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    format!("read {total} bytes, error: {e}"),
-                ));
-            }
-        };
+        // Errors are returned as is, a reader failing after a partial read (e.g. on a checksum
+        // mismatch at EOF) must not look like a short read.
+        let n = reader.read(buf).await?;
         if n == 0 {
             if total > 0 {
                 return Ok(total);
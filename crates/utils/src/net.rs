@@ -154,6 +154,18 @@ pub fn is_custom_query_value(qs_key: &str) -> bool {
     qs_key.starts_with(ALLOWED_CUSTOM_QUERY_PREFIX)
 }
 
+/// Removes the `:port` suffix of a host header, IPv6 literals keep their brackets.
+pub fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port))
+            if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) && (!name.contains(':') || name.ends_with(']')) =>
+        {
+            name
+        }
+        _ => host,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct XHost {
     pub name: String,
@@ -369,6 +381,16 @@ mod test {
         assert!(get_host_ip(invalid_host).is_err());
     }
 
+    #[test]
+    fn test_strip_port() {
+        assert_eq!(strip_port("photos.s3.example.com:9000"), "photos.s3.example.com");
+        assert_eq!(strip_port("photos.s3.example.com"), "photos.s3.example.com");
+        assert_eq!(strip_port("127.0.0.1:9000"), "127.0.0.1");
+        assert_eq!(strip_port("[::1]:9000"), "[::1]");
+        assert_eq!(strip_port("[::1]"), "[::1]");
+        assert_eq!(strip_port("::1"), "::1");
+    }
+
     #[test]
     fn test_get_available_port() {
         let port1 = get_available_port();
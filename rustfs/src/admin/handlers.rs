@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::router::Operation;
+use super::utils::validate_admin_request;
 use crate::auth::check_key_valid;
 use crate::auth::get_condition_values;
 use crate::auth::get_session_token;
@@ -25,6 +26,7 @@ use matchit::Params;
 use percent_encoding::{AsciiSet, CONTROLS, percent_encode};
 use rustfs_ecstore::admin_server_info::get_server_info;
 use rustfs_ecstore::bucket::metadata_sys::{self, get_replication_config};
+use rustfs_ecstore::bucket::quota::BucketQuotaSys;
 use rustfs_ecstore::bucket::target::BucketTarget;
 use rustfs_ecstore::bucket::versioning_sys::BucketVersioningSys;
 use rustfs_ecstore::cmd::bucket_targets::{self, GLOBAL_Bucket_Target_Sys};
@@ -33,7 +35,7 @@ use rustfs_ecstore::global::GLOBAL_ALlHealState;
 use rustfs_ecstore::global::get_global_action_cred;
 // use rustfs_ecstore::heal::data_usage::load_data_usage_from_backend;
 use rustfs_ecstore::heal::data_usage::load_data_usage_from_backend;
-use rustfs_ecstore::heal::heal_commands::HealOpts;
+use rustfs_ecstore::heal::heal_commands::{HealOpts, get_aggregated_background_heal_state};
 use rustfs_ecstore::heal::heal_ops::new_heal_sequence;
 use rustfs_ecstore::metrics_realtime::{CollectMetricsOpts, MetricType, collect_local_metrics};
 use rustfs_ecstore::new_object_layer_fn;
@@ -48,6 +50,7 @@ use rustfs_madmin::utils::parse_duration;
 use rustfs_policy::policy::Args;
 use rustfs_policy::policy::BucketPolicy;
 use rustfs_policy::policy::action::Action;
+use rustfs_policy::policy::action::AdminAction;
 use rustfs_policy::policy::action::S3Action;
 use rustfs_policy::policy::default::DEFAULT_POLICIES;
 use rustfs_utils::path::path_join;
@@ -72,12 +75,21 @@ use tracing::{error, info, warn};
 // use url::UrlQuery;
 
 pub mod bucket_meta;
+pub mod config;
 pub mod event;
 pub mod group;
+pub mod health;
+pub mod inspect;
+pub mod kms;
+pub mod ldap;
 pub mod policies;
 pub mod pools;
+pub mod quota;
 pub mod rebalance;
+pub mod replication;
+pub mod service;
 pub mod service_account;
+pub mod site_replication;
 pub mod sts;
 pub mod tier;
 pub mod trace;
@@ -262,13 +274,13 @@ impl Operation for AccountInfoHandler {
         for bucket in buckets.iter() {
             let (rd, wr) = is_allow(bucket.name.clone()).await;
             if rd || wr {
-                // TODO: BucketQuotaSys
                 // TODO: other attributes
                 account_info.buckets.push(rustfs_madmin::BucketAccessInfo {
                     name: bucket.name.clone(),
                     details: Some(rustfs_madmin::BucketDetails {
                         versioning: BucketVersioningSys::enabled(bucket.name.as_str()).await,
                         versioning_suspended: BucketVersioningSys::suspended(bucket.name.as_str()).await,
+                        quota: BucketQuotaSys::get(bucket.name.as_str()).await.ok().flatten(),
                         ..Default::default()
                     }),
                     created: bucket.created,
@@ -288,16 +300,6 @@ impl Operation for AccountInfoHandler {
     }
 }
 
-pub struct ServiceHandle {}
-#[async_trait::async_trait]
-impl Operation for ServiceHandle {
-    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
-        warn!("handle ServiceHandle");
-
-        return Err(s3_error!(NotImplemented));
-    }
-}
-
 pub struct ServerInfoHandler {}
 
 #[async_trait::async_trait]
@@ -315,17 +317,6 @@ impl Operation for ServerInfoHandler {
     }
 }
 
-pub struct InspectDataHandler {}
-
-#[async_trait::async_trait]
-impl Operation for InspectDataHandler {
-    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
-        warn!("handle InspectDataHandler");
-
-        return Err(s3_error!(NotImplemented));
-    }
-}
-
 pub struct StorageInfoHandler {}
 
 #[async_trait::async_trait]
@@ -337,9 +328,23 @@ impl Operation for StorageInfoHandler {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        // TODO:getAggregatedBackgroundHealState
-
-        let info = store.storage_info().await;
+        let mut info = store.storage_info().await;
+
+        // Mark the drives healed anywhere in the cluster, storage info only knows about local healing.
+        let heal_state = get_aggregated_background_heal_state().await;
+        let healing: HashMap<&str, &rustfs_madmin::Disk> = heal_state
+            .sets
+            .iter()
+            .flat_map(|set| set.disks.iter())
+            .filter(|disk| disk.healing)
+            .map(|disk| (disk.endpoint.as_str(), disk))
+            .collect();
+        for disk in info.disks.iter_mut() {
+            if let Some(healing_disk) = healing.get(disk.endpoint.as_str()) {
+                disk.healing = true;
+                disk.heal_info = healing_disk.heal_info.clone();
+            }
+        }
 
         let data = serde_json::to_vec(&info)
             .map_err(|_e| S3Error::with_message(S3ErrorCode::InternalError, "parse accountInfo failed"))?;
@@ -766,10 +771,19 @@ pub struct BackgroundHealStatusHandler {}
 
 #[async_trait::async_trait]
 impl Operation for BackgroundHealStatusHandler {
-    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
-        warn!("handle BackgroundHealStatusHandler");
+    // POST <endpoint>/<admin-API>/background-heal/status
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::HealAdminAction).await?;
 
-        return Err(s3_error!(NotImplemented));
+        let state = get_aggregated_background_heal_state().await;
+
+        let data = serde_json::to_vec(&state)
+            .map_err(|_e| S3Error::with_message(S3ErrorCode::InternalError, "parse BgHealState failed"))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
     }
 }
 
@@ -20,6 +20,7 @@ use std::{
 use crate::{
     admin::router::Operation,
     auth::{check_key_valid, get_session_token},
+    error::ApiError,
 };
 
 use http::{HeaderMap, StatusCode};
@@ -28,8 +29,9 @@ use rustfs_ecstore::{
     StorageAPI,
     bucket::{
         metadata::{
-            BUCKET_LIFECYCLE_CONFIG, BUCKET_NOTIFICATION_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_QUOTA_CONFIG_FILE,
-            BUCKET_REPLICATION_CONFIG, BUCKET_SSECONFIG, BUCKET_TAGGING_CONFIG, BUCKET_TARGETS_FILE, BUCKET_VERSIONING_CONFIG,
+            BUCKET_CORS_CONFIG, BUCKET_INVENTORY_CONFIG, BUCKET_LIFECYCLE_CONFIG, BUCKET_LOGGING_CONFIG,
+            BUCKET_NOTIFICATION_CONFIG, BUCKET_POLICY_CONFIG, BUCKET_QUOTA_CONFIG_FILE, BUCKET_REPLICATION_CONFIG,
+            BUCKET_SSECONFIG, BUCKET_TAGGING_CONFIG, BUCKET_TARGETS_FILE, BUCKET_VERSIONING_CONFIG, BUCKET_WEBSITE_CONFIG,
             BucketMetadata, OBJECT_LOCK_CONFIG,
         },
         metadata_sys,
@@ -42,6 +44,7 @@ use rustfs_ecstore::{
 };
 use rustfs_ecstore::{
     bucket::utils::{deserialize, serialize},
+    cmd::site_replication::GLOBAL_SITE_REPLICATION_SYS,
     store_api::MakeBucketOptions,
 };
 use rustfs_policy::policy::BucketPolicy;
@@ -49,8 +52,9 @@ use rustfs_utils::path::{SLASH_SEPARATOR, path_join_buf};
 use s3s::{
     Body, S3Request, S3Response, S3Result,
     dto::{
-        BucketLifecycleConfiguration, ObjectLockConfiguration, ReplicationConfiguration, ServerSideEncryptionConfiguration,
-        Tagging, VersioningConfiguration,
+        BucketLifecycleConfiguration, BucketLoggingStatus, CORSConfiguration, ListBucketInventoryConfigurationsOutput,
+        ObjectLockConfiguration, ReplicationConfiguration, ServerSideEncryptionConfiguration, Tagging, VersioningConfiguration,
+        WebsiteConfiguration,
     },
     header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE},
     s3_error,
@@ -118,6 +122,10 @@ impl Operation for ExportBucketMetadata {
             BUCKET_VERSIONING_CONFIG,
             BUCKET_REPLICATION_CONFIG,
             BUCKET_TARGETS_FILE,
+            BUCKET_CORS_CONFIG,
+            BUCKET_WEBSITE_CONFIG,
+            BUCKET_LOGGING_CONFIG,
+            BUCKET_INVENTORY_CONFIG,
         ];
 
         for bucket in buckets {
@@ -328,6 +336,87 @@ impl Operation for ExportBucketMetadata {
                             .write_all(&config_json)
                             .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
                     }
+                    BUCKET_CORS_CONFIG => {
+                        let config: CORSConfiguration = match metadata_sys::get_cors_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_xml =
+                            serialize(&config).map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_xml)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_WEBSITE_CONFIG => {
+                        let config: WebsiteConfiguration = match metadata_sys::get_website_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_xml =
+                            serialize(&config).map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_xml)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_LOGGING_CONFIG => {
+                        let config: BucketLoggingStatus = match metadata_sys::get_logging_config(&bucket.name).await {
+                            Ok((res, _)) => res,
+                            Err(e) => {
+                                if e == StorageError::ConfigNotFound {
+                                    continue;
+                                }
+                                return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                            }
+                        };
+                        let config_xml =
+                            serialize(&config).map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_xml)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
+                    BUCKET_INVENTORY_CONFIG => {
+                        let config: ListBucketInventoryConfigurationsOutput =
+                            match metadata_sys::get_inventory_config(&bucket.name).await {
+                                Ok((res, _)) => res,
+                                Err(e) => {
+                                    if e == StorageError::ConfigNotFound {
+                                        continue;
+                                    }
+                                    return Err(s3_error!(InternalError, "get bucket metadata failed: {e}"));
+                                }
+                            };
+                        let config_xml =
+                            serialize(&config).map_err(|e| s3_error!(InternalError, "serialize config failed: {e}"))?;
+
+                        zip_writer
+                            .start_file(conf_path, SimpleFileOptions::default())
+                            .map_err(|e| s3_error!(InternalError, "start file failed: {e}"))?;
+                        zip_writer
+                            .write_all(&config_xml)
+                            .map_err(|e| s3_error!(InternalError, "write file failed: {e}"))?;
+                    }
                     _ => {}
                 }
             }
@@ -588,11 +677,60 @@ impl Operation for ImportBucketMetadata {
                     metadata.bucket_targets_config_updated_at = update_at;
                 }
 
+                BUCKET_CORS_CONFIG => {
+                    if let Err(e) = deserialize::<CORSConfiguration>(&content) {
+                        warn!("deserialize config failed: {e}");
+                        continue;
+                    }
+
+                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
+                    metadata.cors_config_xml = content;
+                    metadata.cors_config_updated_at = update_at;
+                }
+
+                BUCKET_WEBSITE_CONFIG => {
+                    if let Err(e) = deserialize::<WebsiteConfiguration>(&content) {
+                        warn!("deserialize config failed: {e}");
+                        continue;
+                    }
+
+                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
+                    metadata.website_config_xml = content;
+                    metadata.website_config_updated_at = update_at;
+                }
+
+                BUCKET_LOGGING_CONFIG => {
+                    if let Err(e) = deserialize::<BucketLoggingStatus>(&content) {
+                        warn!("deserialize config failed: {e}");
+                        continue;
+                    }
+
+                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
+                    metadata.logging_config_xml = content;
+                    metadata.logging_config_updated_at = update_at;
+                }
+
+                BUCKET_INVENTORY_CONFIG => {
+                    if let Err(e) = deserialize::<ListBucketInventoryConfigurationsOutput>(&content) {
+                        warn!("deserialize config failed: {e}");
+                        continue;
+                    }
+
+                    let metadata = bucket_metadatas.get_mut(bucket_name).unwrap();
+                    metadata.inventory_config_xml = content;
+                    metadata.inventory_config_updated_at = update_at;
+                }
+
                 _ => {}
             }
         }
 
-        // TODO: site replication notify
+        for metadata in bucket_metadatas.values() {
+            GLOBAL_SITE_REPLICATION_SYS
+                .replicate_bucket_metadata(metadata)
+                .await
+                .map_err(ApiError::from)?;
+        }
 
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
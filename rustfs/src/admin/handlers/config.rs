@@ -0,0 +1,317 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::config::com::{
+    DEFAULT_CONFIG_HISTORY_COUNT, is_dynamic_sub_sys, list_server_config_history, read_config_without_migrate,
+    read_server_config_history, save_server_config, save_server_config_history, validate_config,
+};
+use rustfs_ecstore::config::{Config, GLOBAL_ConfigSys, help};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::notification_sys::get_global_notification_sys;
+use rustfs_ecstore::store::ECStore;
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{
+    Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    s3_error,
+};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::{info, warn};
+
+use crate::{
+    admin::{router::Operation, utils::validate_admin_request},
+    error::ApiError,
+};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct ConfigKVQuery {
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct HelpConfigKVQuery {
+    #[serde(rename = "subSys")]
+    pub sub_sys: String,
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct ConfigHistoryQuery {
+    pub count: Option<usize>,
+    #[serde(rename = "restoreId")]
+    pub restore_id: String,
+}
+
+fn parse_query<T: for<'de> Deserialize<'de> + Default>(req: &S3Request<Body>) -> S3Result<T> {
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(T::default()),
+    }
+}
+
+fn object_layer() -> S3Result<Arc<ECStore>> {
+    new_object_layer_fn().ok_or_else(|| S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()))
+}
+
+async fn read_body(req: S3Request<Body>) -> S3Result<String> {
+    let mut input = req.input;
+    let body = match input.store_all_unlimited().await {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("get body failed, e: {:?}", e);
+            return Err(s3_error!(InvalidRequest, "get body failed"));
+        }
+    };
+
+    String::from_utf8(body.to_vec()).map_err(|_e| s3_error!(InvalidArgument, "config must be utf-8 text"))
+}
+
+/// Validates and saves `cfg`, keeping the current config in the history, then applies the changed
+/// subsystems on this node and on every peer.
+async fn apply_config(store: Arc<ECStore>, current: &Config, cfg: Config, sub_systems: &[String]) -> S3Result<()> {
+    validate_config(&cfg, store.as_ref()).map_err(|e| s3_error!(InvalidArgument, "invalid config: {}", e))?;
+
+    save_server_config_history(store.clone(), current.export().as_bytes())
+        .await
+        .map_err(ApiError::from)?;
+    save_server_config(store.clone(), &cfg).await.map_err(ApiError::from)?;
+
+    for sub_sys in sub_systems.iter().filter(|s| is_dynamic_sub_sys(s)) {
+        if let Err(err) = GLOBAL_ConfigSys.apply_dynamic(store.clone(), cfg.clone(), sub_sys).await {
+            warn!("apply config of {} failed, e: {:?}", sub_sys, err);
+        }
+
+        if let Some(notification_sys) = get_global_notification_sys() {
+            for peer in notification_sys.reload_dynamic(sub_sys).await {
+                if let Some(err) = peer.err {
+                    warn!("reload config of {} on {} failed, e: {:?}", sub_sys, peer.host, err);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn text_response(data: String) -> S3Response<(StatusCode, Body)> {
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "text/plain".parse().unwrap());
+    S3Response::with_headers((StatusCode::OK, Body::from(data)), header)
+}
+
+fn empty_response() -> S3Response<(StatusCode, Body)> {
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    header.insert(CONTENT_LENGTH, "0".parse().unwrap());
+    S3Response::with_headers((StatusCode::OK, Body::empty()), header)
+}
+
+fn json_response<T: serde::Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal response err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct GetConfigKV {}
+
+#[async_trait::async_trait]
+impl Operation for GetConfigKV {
+    // GET <endpoint>/<admin-API>/get-config-kv?key=<sub_sys[:target]>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let query: ConfigKVQuery = parse_query(&req)?;
+        if query.key.is_empty() {
+            return Err(s3_error!(InvalidArgument, "key is required"));
+        }
+
+        let cfg = read_config_without_migrate(object_layer()?).await.map_err(ApiError::from)?;
+        let lines = cfg.get_kvs(&query.key).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+
+        Ok(text_response(lines.join("\n")))
+    }
+}
+
+pub struct SetConfigKV {}
+
+#[async_trait::async_trait]
+impl Operation for SetConfigKV {
+    // PUT <endpoint>/<admin-API>/set-config-kv, body: `sub_sys[:target] key=value ...` lines
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let store = object_layer()?;
+        let body = read_body(req).await?;
+
+        let current = read_config_without_migrate(store.clone()).await.map_err(ApiError::from)?;
+        let mut cfg = current.clone();
+        let mut sub_systems = Vec::new();
+        for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let sub_sys = cfg.set_kvs(line).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+            if !sub_systems.contains(&sub_sys) {
+                sub_systems.push(sub_sys);
+            }
+        }
+        if sub_systems.is_empty() {
+            return Err(s3_error!(InvalidArgument, "no config specified"));
+        }
+
+        info!("set config of {:?}", sub_systems);
+        apply_config(store, &current, cfg, &sub_systems).await?;
+
+        Ok(empty_response())
+    }
+}
+
+pub struct DelConfigKV {}
+
+#[async_trait::async_trait]
+impl Operation for DelConfigKV {
+    // DELETE <endpoint>/<admin-API>/del-config-kv, body: `sub_sys[:target]`
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let store = object_layer()?;
+        let body = read_body(req).await?;
+
+        let current = read_config_without_migrate(store.clone()).await.map_err(ApiError::from)?;
+        let mut cfg = current.clone();
+        let sub_sys = cfg.del_kvs(body.trim()).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+
+        info!("delete config {}", body.trim());
+        apply_config(store, &current, cfg, &[sub_sys]).await?;
+
+        Ok(empty_response())
+    }
+}
+
+pub struct HelpConfigKV {}
+
+#[async_trait::async_trait]
+impl Operation for HelpConfigKV {
+    // GET <endpoint>/<admin-API>/help-config-kv?subSys=<sub_sys>&key=<key>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let query: HelpConfigKVQuery = parse_query(&req)?;
+
+        let Some(mut help) = help::lookup_help(&query.sub_sys) else {
+            return Err(s3_error!(InvalidArgument, "unknown sub-system '{}'", query.sub_sys));
+        };
+        if !query.key.is_empty() {
+            help.keys_help.retain(|kv| kv.key == query.key);
+            if help.keys_help.is_empty() {
+                return Err(s3_error!(InvalidArgument, "unknown key '{}' for sub-system {}", query.key, query.sub_sys));
+            }
+        }
+
+        json_response(&help)
+    }
+}
+
+pub struct GetConfig {}
+
+#[async_trait::async_trait]
+impl Operation for GetConfig {
+    // GET <endpoint>/<admin-API>/config
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+
+        let cfg = read_config_without_migrate(object_layer()?).await.map_err(ApiError::from)?;
+
+        Ok(text_response(cfg.export()))
+    }
+}
+
+pub struct SetConfig {}
+
+#[async_trait::async_trait]
+impl Operation for SetConfig {
+    // PUT <endpoint>/<admin-API>/config, body: the full config as exported by GET config
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let store = object_layer()?;
+        let body = read_body(req).await?;
+
+        let cfg = Config::read_from(&body).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+        let current = read_config_without_migrate(store.clone()).await.map_err(ApiError::from)?;
+
+        info!("import config");
+        let sub_systems: Vec<String> = help::sub_system_names().map(str::to_owned).collect();
+        apply_config(store, &current, cfg, &sub_systems).await?;
+
+        Ok(empty_response())
+    }
+}
+
+pub struct ListConfigHistoryKV {}
+
+#[async_trait::async_trait]
+impl Operation for ListConfigHistoryKV {
+    // GET <endpoint>/<admin-API>/list-config-history-kv?count=<count>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let query: ConfigHistoryQuery = parse_query(&req)?;
+
+        let count = query.count.unwrap_or(DEFAULT_CONFIG_HISTORY_COUNT);
+        let entries = list_server_config_history(object_layer()?, true, count)
+            .await
+            .map_err(ApiError::from)?;
+
+        json_response(&entries)
+    }
+}
+
+pub struct RestoreConfigHistoryKV {}
+
+#[async_trait::async_trait]
+impl Operation for RestoreConfigHistoryKV {
+    // PUT <endpoint>/<admin-API>/restore-config-history-kv?restoreId=<restore_id>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::ConfigUpdateAdminAction).await?;
+        let query: ConfigHistoryQuery = parse_query(&req)?;
+        if query.restore_id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "restoreId is required"));
+        }
+        let store = object_layer()?;
+
+        let data = read_server_config_history(store.clone(), &query.restore_id)
+            .await
+            .map_err(ApiError::from)?;
+        let data = String::from_utf8(data).map_err(|_e| s3_error!(InvalidArgument, "config history is not utf-8 text"))?;
+        let cfg = Config::read_from(&data).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+        let current = read_config_without_migrate(store.clone()).await.map_err(ApiError::from)?;
+
+        info!("restore config history {}", query.restore_id);
+        let sub_systems: Vec<String> = help::sub_system_names().map(str::to_owned).collect();
+        apply_config(store, &current, cfg, &sub_systems).await?;
+
+        Ok(empty_response())
+    }
+}
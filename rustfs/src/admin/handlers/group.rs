@@ -16,7 +16,7 @@ use http::{HeaderMap, StatusCode};
 use matchit::Params;
 use rustfs_ecstore::global::get_global_action_cred;
 use rustfs_iam::error::{is_err_no_such_group, is_err_no_such_user};
-use rustfs_madmin::GroupAddRemove;
+use rustfs_madmin::{GroupAddRemove, site_replication::SRIAMItem};
 use s3s::{
     Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
     header::{CONTENT_LENGTH, CONTENT_TYPE},
@@ -26,7 +26,7 @@ use serde::Deserialize;
 use serde_urlencoded::from_bytes;
 use tracing::warn;
 
-use crate::admin::{router::Operation, utils::has_space_be};
+use crate::admin::{handlers::site_replication::replicate_iam_change, router::Operation, utils::has_space_be};
 
 #[derive(Debug, Deserialize, Default)]
 pub struct GroupQuery {
@@ -109,8 +109,8 @@ impl Operation for SetGroupStatus {
 
         let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
 
-        if let Some(status) = query.status {
-            match status.as_str() {
+        if let Some(status) = query.status.as_deref() {
+            match status {
                 "enabled" => {
                     iam_store.set_group_status(&query.group, true).await.map_err(|e| {
                         warn!("enable group failed, e: {:?}", e);
@@ -131,6 +131,12 @@ impl Operation for SetGroupStatus {
             return Err(s3_error!(InvalidArgument, "status is required"));
         }
 
+        replicate_iam_change(SRIAMItem::GroupStatus {
+            group: query.group.clone(),
+            enabled: query.status.as_deref() == Some("enabled"),
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -192,6 +198,12 @@ impl Operation for UpdateGroupMembers {
             }
         }
 
+        let item = SRIAMItem::GroupMembers {
+            group: args.group.clone(),
+            members: args.members.clone(),
+            is_remove: args.is_remove,
+        };
+
         if args.is_remove {
             warn!("remove group members");
             iam_store
@@ -216,6 +228,8 @@ impl Operation for UpdateGroupMembers {
             })?;
         }
 
+        replicate_iam_change(item).await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
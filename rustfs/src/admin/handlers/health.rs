@@ -0,0 +1,125 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Unauthenticated health checks for probes and load balancers.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::new_object_layer_fn;
+use s3s::{Body, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+
+use crate::admin::router::Operation;
+
+/// Health routes are served without authentication.
+pub const HEALTH_PREFIX: &str = "/rustfs/health";
+
+/// Short aliases of the health routes, e.g. `/health/live`, for probes configured with the usual paths.
+pub const HEALTH_ALIAS_PREFIX: &str = "/health";
+
+/// Names of the health checks, served under both [`HEALTH_PREFIX`] and [`HEALTH_ALIAS_PREFIX`].
+pub const HEALTH_CHECKS: [&str; 3] = ["live", "ready", "cluster"];
+
+/// Whether the path is a health check. Only the exact alias paths are matched so that objects
+/// in a bucket named `health` stay reachable.
+pub fn is_health_path(path: &str) -> bool {
+    path.starts_with(HEALTH_PREFIX)
+        || path
+            .strip_prefix(HEALTH_ALIAS_PREFIX)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .is_some_and(|check| HEALTH_CHECKS.contains(&check))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct HealthQuery {
+    pub maintenance: bool,
+}
+
+pub struct HealthLiveHandler {}
+
+#[async_trait::async_trait]
+impl Operation for HealthLiveHandler {
+    // GET <endpoint>/rustfs/health/live or <endpoint>/health/live
+    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct HealthReadyHandler {}
+
+#[async_trait::async_trait]
+impl Operation for HealthReadyHandler {
+    // GET <endpoint>/rustfs/health/ready or <endpoint>/health/ready
+    async fn call(&self, _req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let status = if new_object_layer_fn().is_some() {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+        Ok(S3Response::new((status, Body::empty())))
+    }
+}
+
+pub struct HealthClusterHandler {}
+
+#[async_trait::async_trait]
+impl Operation for HealthClusterHandler {
+    // GET <endpoint>/rustfs/health/cluster or <endpoint>/health/cluster?maintenance=<bool>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query: HealthQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => HealthQuery::default(),
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Ok(S3Response::new((StatusCode::SERVICE_UNAVAILABLE, Body::empty())));
+        };
+
+        let result = store.health(query.maintenance).await;
+        let status = match (result.healthy, result.maintenance) {
+            (true, _) => StatusCode::OK,
+            // Taking this node down would lose quorum
+            (false, true) => StatusCode::PRECONDITION_FAILED,
+            (false, false) => StatusCode::SERVICE_UNAVAILABLE,
+        };
+
+        let data = serde_json::to_vec(&result).map_err(|e| s3_error!(InternalError, "marshal health result err {}", e))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((status, Body::from(data)), header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_health_path() {
+        assert!(is_health_path("/rustfs/health/live"));
+        assert!(is_health_path("/health/live"));
+        assert!(is_health_path("/health/ready"));
+        assert!(is_health_path("/health/cluster"));
+
+        // Other objects of a bucket named `health` are left to the S3 API.
+        assert!(!is_health_path("/health"));
+        assert!(!is_health_path("/health/"));
+        assert!(!is_health_path("/health/live/object"));
+        assert!(!is_health_path("/healthy/live"));
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::disk::STORAGE_FORMAT_FILE;
+use rustfs_ecstore::inspect::{InspectDrive, encrypt_archive, inspect_object};
+use rustfs_ecstore::set_disk::DEFAULT_READ_BUFFER_SIZE;
+use rustfs_policy::policy::action::AdminAction;
+use rustfs_rio::WarpReader;
+use s3s::dto::StreamingBlob;
+use s3s::{
+    Body, S3Request, S3Response, S3Result,
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    s3_error,
+};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+use tracing::warn;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::admin::{router::Operation, utils::validate_admin_request};
+
+/// Part files are cut off at this size in the archive.
+const MAX_INSPECT_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct InspectDataQuery {
+    pub volume: String,
+    pub file: String,
+    /// Also export the part files of every version.
+    pub parts: bool,
+}
+
+/// Describes the drives in the archive, one directory per drive.
+#[derive(Debug, Serialize)]
+struct InspectDriveInfo {
+    dir: String,
+    endpoint: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+/// Returns the object name for `file`, which may also point at the `xl.meta` of the object.
+fn object_name(file: &str) -> S3Result<String> {
+    let file = file.trim_start_matches('/');
+    let object = file.strip_suffix(STORAGE_FORMAT_FILE).unwrap_or(file).trim_end_matches('/');
+    if object.is_empty() || object.split('/').any(|p| p.is_empty() || p == "." || p == "..") {
+        return Err(s3_error!(InvalidArgument, "invalid file"));
+    }
+    Ok(object.to_owned())
+}
+
+pub struct InspectDataHandler {}
+
+#[async_trait::async_trait]
+impl Operation for InspectDataHandler {
+    // GET <endpoint>/<admin-API>/inspect-data?volume=<bucket>&file=<object>&parts=<bool>
+    // POST with a PEM encoded RSA public key as body to get the archive encrypted for that key.
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::InspectDataAction).await?;
+
+        let query: InspectDataQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => InspectDataQuery::default(),
+        };
+        if query.volume.is_empty() {
+            return Err(s3_error!(InvalidArgument, "volume is required"));
+        }
+        let object = object_name(&query.file)?;
+
+        let mut input = req.input;
+        let public_key = match input.store_all_unlimited().await {
+            Ok(b) => String::from_utf8(b.to_vec()).map_err(|_e| s3_error!(InvalidArgument, "invalid public key"))?,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let drives = inspect_object(&query.volume, &object, query.parts)
+            .await
+            .map_err(|e| s3_error!(InternalError, "inspect data failed: {e}"))?;
+
+        // The archive is written while the response is sent, so only one chunk of a part is held in memory.
+        let (rd, wd) = tokio::io::duplex(DEFAULT_READ_BUFFER_SIZE);
+        let (archive, file_name): (Box<dyn AsyncRead + Unpin + Send + Sync>, _) = if public_key.trim().is_empty() {
+            (Box::new(rd), "inspect-data.zip")
+        } else {
+            let archive =
+                encrypt_archive(public_key.trim(), WarpReader::new(rd)).map_err(|e| s3_error!(InvalidArgument, "{e}"))?;
+            (Box::new(archive), "inspect-data.enc")
+        };
+
+        let volume = query.volume;
+        tokio::spawn(async move {
+            if let Err(e) = write_archive(wd, &volume, &object, drives).await {
+                warn!("write inspect archive failed, e: {:?}", e);
+            }
+        });
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/octet-stream".parse().unwrap());
+        header.insert(CONTENT_DISPOSITION, format!("attachment; filename={file_name}").parse().unwrap());
+        let body = Body::from(StreamingBlob::wrap(ReaderStream::with_capacity(archive, DEFAULT_READ_BUFFER_SIZE)));
+        Ok(S3Response::with_headers((StatusCode::OK, body), header))
+    }
+}
+
+/// Buffer the zip writer writes to, drained into the response after every write.
+#[derive(Clone, Default)]
+struct ArchiveBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl ArchiveBuffer {
+    async fn flush_to<W: AsyncWrite + Unpin>(&self, out: &mut W) -> std::io::Result<()> {
+        let data = std::mem::take(&mut *self.0.lock().unwrap());
+        out.write_all(&data).await
+    }
+}
+
+impl Write for ArchiveBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes the zip archive of `drives` to `out`, one directory per drive plus `drives.json`.
+async fn write_archive<W: AsyncWrite + Unpin>(
+    mut out: W,
+    volume: &str,
+    object: &str,
+    drives: Vec<InspectDrive>,
+) -> std::io::Result<()> {
+    let buffer = ArchiveBuffer::default();
+    let mut zip_writer = ZipWriter::new_stream(buffer.clone());
+    let mut chunk = vec![0u8; DEFAULT_READ_BUFFER_SIZE];
+
+    let mut infos = Vec::with_capacity(drives.len());
+    for mut drive in drives {
+        let dir = format!("pool-{}-set-{}-disk-{}", drive.pool_index, drive.set_index, drive.disk_index);
+        if let Some(meta) = &drive.meta {
+            zip_writer
+                .start_file(format!("{dir}/{volume}/{object}/{STORAGE_FORMAT_FILE}"), SimpleFileOptions::default())
+                .map_err(std::io::Error::other)?;
+            zip_writer.write_all(meta)?;
+            buffer.flush_to(&mut out).await?;
+        }
+
+        for path in std::mem::take(&mut drive.parts) {
+            let reader = match drive.open_part(volume, &path).await {
+                Ok(Some(reader)) => reader,
+                Ok(None) => continue,
+                Err(err) => {
+                    drive.errors.push(format!("{path}: {err}"));
+                    continue;
+                }
+            };
+
+            zip_writer
+                .start_file(format!("{dir}/{volume}/{path}"), SimpleFileOptions::default())
+                .map_err(std::io::Error::other)?;
+            // Read one byte past the cap to tell whether the file was cut off.
+            let mut reader = reader.take(MAX_INSPECT_FILE_SIZE + 1);
+            let mut written = 0;
+            loop {
+                let n = match reader.read(&mut chunk).await {
+                    Ok(n) => n,
+                    Err(err) => {
+                        drive.errors.push(format!("{path}: {err}"));
+                        break;
+                    }
+                };
+                let n = std::cmp::min(n as u64, MAX_INSPECT_FILE_SIZE - written) as usize;
+                if n == 0 {
+                    break;
+                }
+                zip_writer.write_all(&chunk[..n])?;
+                buffer.flush_to(&mut out).await?;
+                written += n as u64;
+            }
+            if written == MAX_INSPECT_FILE_SIZE && reader.limit() == 0 {
+                drive
+                    .errors
+                    .push(format!("{path}: truncated to {MAX_INSPECT_FILE_SIZE} bytes"));
+            }
+        }
+
+        infos.push(InspectDriveInfo {
+            dir,
+            endpoint: drive.endpoint,
+            errors: drive.errors,
+        });
+    }
+
+    let drives_json = serde_json::to_vec_pretty(&infos).map_err(std::io::Error::other)?;
+    zip_writer
+        .start_file("drives.json", SimpleFileOptions::default())
+        .map_err(std::io::Error::other)?;
+    zip_writer.write_all(&drives_json)?;
+    zip_writer.finish().map_err(std::io::Error::other)?;
+    buffer.flush_to(&mut out).await?;
+    out.shutdown().await
+}
@@ -0,0 +1,152 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::kms::{Kms, get_global_kms};
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{
+    Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    s3_error,
+};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{router::Operation, utils::validate_admin_request},
+    error::ApiError,
+};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct KmsKeyQuery {
+    #[serde(rename = "key-id")]
+    pub key_id: String,
+    pub pattern: String,
+}
+
+#[derive(Debug, Serialize)]
+struct KmsKeyInfo {
+    name: String,
+}
+
+fn parse_query(req: &S3Request<Body>) -> S3Result<KmsKeyQuery> {
+    match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed")),
+        None => Ok(KmsKeyQuery::default()),
+    }
+}
+
+/// Checks that the requester may perform `action` and returns the configured KMS.
+async fn authorize(req: &S3Request<Body>, action: AdminAction) -> S3Result<Arc<dyn Kms>> {
+    validate_admin_request(req, action).await?;
+
+    get_global_kms().ok_or_else(|| ApiError::from(StorageError::KmsNotConfigured).into())
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal response err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct KmsStatus {}
+
+#[async_trait::async_trait]
+impl Operation for KmsStatus {
+    // GET <endpoint>/<admin-API>/kms/status
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let kms = authorize(&req, AdminAction::KMSKeyStatusAdminAction).await?;
+
+        json_response(&kms.status())
+    }
+}
+
+pub struct CreateKmsKey {}
+
+#[async_trait::async_trait]
+impl Operation for CreateKmsKey {
+    // POST <endpoint>/<admin-API>/kms/key/create?key-id=<key-id>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = parse_query(&req)?;
+        if query.key_id.is_empty() {
+            return Err(s3_error!(InvalidArgument, "key-id is required"));
+        }
+
+        let kms = authorize(&req, AdminAction::KMSCreateKeyAdminAction).await?;
+
+        kms.create_key(&query.key_id).await.map_err(|e| {
+            warn!("create kms key {} failed, e: {:?}", query.key_id, e);
+            ApiError::from(e)
+        })?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        header.insert(CONTENT_LENGTH, "0".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
+    }
+}
+
+pub struct ListKmsKeys {}
+
+#[async_trait::async_trait]
+impl Operation for ListKmsKeys {
+    // GET <endpoint>/<admin-API>/kms/key/list?pattern=<glob>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = parse_query(&req)?;
+        let kms = authorize(&req, AdminAction::KMSKeyStatusAdminAction).await?;
+
+        let keys: Vec<KmsKeyInfo> = kms
+            .list_keys(&query.pattern)
+            .await
+            .map_err(ApiError::from)?
+            .into_iter()
+            .map(|name| KmsKeyInfo { name })
+            .collect();
+
+        json_response(&keys)
+    }
+}
+
+pub struct KmsKeyStatus {}
+
+#[async_trait::async_trait]
+impl Operation for KmsKeyStatus {
+    // GET <endpoint>/<admin-API>/kms/key/status?key-id=<key-id>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query = parse_query(&req)?;
+        let kms = authorize(&req, AdminAction::KMSKeyStatusAdminAction).await?;
+
+        let key_id = if query.key_id.is_empty() {
+            kms.default_key_id().to_owned()
+        } else {
+            query.key_id
+        };
+        let status = kms.key_status(&key_id).await.map_err(ApiError::from)?;
+
+        json_response(&status)
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::admin::{router::Operation, utils::validate_admin_request};
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_iam::ldap::{get_ldap_provider, normalize_dn};
+use rustfs_madmin::{PolicyAssociationReq, PolicyAssociationResp};
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use tracing::warn;
+
+/// Attaches policies to an LDAP user or group
+pub struct AttachPolicyLdap {}
+
+#[async_trait::async_trait]
+impl Operation for AttachPolicyLdap {
+    // POST <endpoint>/<admin-API>/idp/ldap/policy/attach
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        update_policy_association(req, true).await
+    }
+}
+
+/// Detaches policies from an LDAP user or group
+pub struct DetachPolicyLdap {}
+
+#[async_trait::async_trait]
+impl Operation for DetachPolicyLdap {
+    // POST <endpoint>/<admin-API>/idp/ldap/policy/detach
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        update_policy_association(req, false).await
+    }
+}
+
+async fn update_policy_association(req: S3Request<Body>, attach: bool) -> S3Result<S3Response<(StatusCode, Body)>> {
+    validate_admin_request(&req, AdminAction::UpdatePolicyAssociationAction).await?;
+
+    let Some(provider) = get_ldap_provider() else {
+        return Err(s3_error!(NotImplemented, "ldap provider is not configured"));
+    };
+
+    let mut input = req.input;
+    let body = match input.store_all_unlimited().await {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("get body failed, e: {:?}", e);
+            return Err(s3_error!(InvalidRequest, "get body failed"));
+        }
+    };
+
+    let assoc: PolicyAssociationReq =
+        serde_json::from_slice(&body).map_err(|e| s3_error!(InvalidArgument, "invalid policy association: {}", e))?;
+
+    if assoc.policies.is_empty() {
+        return Err(s3_error!(InvalidArgument, "no policy is given"));
+    }
+    if assoc.user.is_empty() == assoc.group.is_empty() {
+        return Err(s3_error!(InvalidArgument, "exactly one of user and group is required"));
+    }
+
+    // Users are named by DN or by username, groups by DN
+    let (dn, is_group) = if !assoc.group.is_empty() {
+        (normalize_dn(&assoc.group), true)
+    } else if assoc.user.contains('=') {
+        (normalize_dn(&assoc.user), false)
+    } else {
+        let dn = provider.lookup_user_dn(&assoc.user).await.map_err(|e| {
+            warn!("lookup ldap user {} failed: {e}", assoc.user);
+            s3_error!(InvalidArgument, "ldap user not found")
+        })?;
+        (dn, false)
+    };
+
+    let Ok(iam_store) = rustfs_iam::get() else { return Err(s3_error!(InternalError, "iam not init")) };
+
+    let changed = iam_store
+        .ldap_policy_db_update(&dn, is_group, &assoc.policies, attach)
+        .await
+        .map_err(|e| {
+            warn!("update policies of {dn} failed, e: {:?}", e);
+            S3Error::with_message(S3ErrorCode::InvalidArgument, e.to_string())
+        })?;
+
+    let resp = if attach {
+        PolicyAssociationResp {
+            policies_attached: changed,
+            ..Default::default()
+        }
+    } else {
+        PolicyAssociationResp {
+            policies_detached: changed,
+            ..Default::default()
+        }
+    };
+
+    let data = serde_json::to_vec(&resp)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal policy association err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
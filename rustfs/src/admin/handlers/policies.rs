@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::admin::{router::Operation, utils::has_space_be};
+use crate::admin::{handlers::site_replication::replicate_iam_change, router::Operation, utils::has_space_be};
 use http::{HeaderMap, StatusCode};
 use matchit::Params;
 use rustfs_ecstore::global::get_global_action_cred;
 use rustfs_iam::error::is_err_no_such_user;
 use rustfs_iam::store::MappedPolicy;
+use rustfs_madmin::site_replication::SRIAMItem;
 use rustfs_policy::policy::Policy;
 use s3s::{
     Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
@@ -125,6 +126,12 @@ impl Operation for AddCannedPolicy {
             S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
         })?;
 
+        replicate_iam_change(SRIAMItem::Policy {
+            name: query.name.clone(),
+            policy: Some(String::from_utf8_lossy(&policy_bytes).into_owned()),
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -200,6 +207,12 @@ impl Operation for RemoveCannedPolicy {
             S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
         })?;
 
+        replicate_iam_change(SRIAMItem::Policy {
+            name: query.name.clone(),
+            policy: None,
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -286,6 +299,13 @@ impl Operation for SetPolicyForUserOrGroup {
                 S3Error::with_message(S3ErrorCode::InternalError, e.to_string())
             })?;
 
+        replicate_iam_change(SRIAMItem::PolicyMapping {
+            user_or_group: query.user_or_group.clone(),
+            is_group: query.is_group,
+            policy: query.policy_name.clone(),
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -0,0 +1,134 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::bucket::metadata::BUCKET_QUOTA_CONFIG_FILE;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::quota::{BucketQuota, BucketQuotaSys, QuotaType};
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{
+    Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    s3_error,
+};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::warn;
+
+use crate::{
+    admin::{router::Operation, utils::validate_admin_request},
+    error::ApiError,
+};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct BucketQuotaQuery {
+    pub bucket: String,
+}
+
+/// Parses the `bucket` query parameter and checks that the bucket exists.
+async fn query_bucket(req: &S3Request<Body>) -> S3Result<String> {
+    let query: BucketQuotaQuery = match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+        None => BucketQuotaQuery::default(),
+    };
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(query.bucket)
+}
+
+pub struct SetBucketQuota {}
+
+#[async_trait::async_trait]
+impl Operation for SetBucketQuota {
+    // PUT <endpoint>/<admin-API>/set-bucket-quota?bucket=<bucket>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SetBucketQuotaAdminAction).await?;
+        let bucket = query_bucket(&req).await?;
+
+        let mut input = req.input;
+        let body = match input.store_all_unlimited().await {
+            Ok(b) => b,
+            Err(e) => {
+                warn!("get body failed, e: {:?}", e);
+                return Err(s3_error!(InvalidRequest, "get body failed"));
+            }
+        };
+
+        let quota: BucketQuota =
+            serde_json::from_slice(&body).map_err(|e| s3_error!(InvalidArgument, "invalid bucket quota: {}", e))?;
+
+        // A quota without a limit clears the bucket quota.
+        if quota.hard_limit().is_none() {
+            if quota.quota_type.is_some_and(|t| t != QuotaType::Hard) {
+                return Err(s3_error!(InvalidArgument, "unsupported quota type"));
+            }
+            metadata_sys::delete(&bucket, BUCKET_QUOTA_CONFIG_FILE)
+                .await
+                .map_err(ApiError::from)?;
+        } else {
+            let data = serde_json::to_vec(&BucketQuota {
+                quota_type: Some(QuotaType::Hard),
+                ..quota
+            })
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal quota err {e}")))?;
+            metadata_sys::update(&bucket, BUCKET_QUOTA_CONFIG_FILE, data)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        header.insert(CONTENT_LENGTH, "0".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::empty()), header))
+    }
+}
+
+pub struct GetBucketQuota {}
+
+#[async_trait::async_trait]
+impl Operation for GetBucketQuota {
+    // GET <endpoint>/<admin-API>/get-bucket-quota?bucket=<bucket>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::GetBucketQuotaAdminAction).await?;
+        let bucket = query_bucket(&req).await?;
+
+        let quota = BucketQuotaSys::get(&bucket)
+            .await
+            .map_err(ApiError::from)?
+            .unwrap_or_default();
+
+        let data = serde_json::to_vec(&quota)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal quota err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::cmd::bucket_replication_resync::GLOBAL_REPLICATION_RESYNCER;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_ecstore::store_api::{BucketOptions, StorageAPI};
+use rustfs_madmin::utils::parse_duration;
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+
+use crate::{
+    admin::{router::Operation, utils::validate_admin_request},
+    error::ApiError,
+};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct ResyncQuery {
+    pub bucket: String,
+    pub arn: String,
+    #[serde(rename = "older-than")]
+    pub older_than: String,
+}
+
+/// Parses the resync query and checks that the bucket exists.
+async fn query_resync(req: &S3Request<Body>, require_arn: bool) -> S3Result<(Arc<ECStore>, ResyncQuery)> {
+    let query: ResyncQuery = match req.uri.query() {
+        Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+        None => ResyncQuery::default(),
+    };
+    if query.bucket.is_empty() {
+        return Err(s3_error!(InvalidArgument, "bucket is required"));
+    }
+    if require_arn && query.arn.is_empty() {
+        return Err(s3_error!(InvalidArgument, "arn is required"));
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+    store
+        .get_bucket_info(&query.bucket, &BucketOptions::default())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok((store, query))
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal resync status err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+pub struct ReplicationResyncStart {}
+
+#[async_trait::async_trait]
+impl Operation for ReplicationResyncStart {
+    // PUT <endpoint>/<admin-API>/replication/resync/start?bucket=<bucket>&arn=<arn>&older-than=<duration>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SetBucketTargetAction).await?;
+        let (store, query) = query_resync(&req, true).await?;
+
+        let older_than = if query.older_than.is_empty() {
+            None
+        } else {
+            Some(parse_duration(&query.older_than).map_err(|e| s3_error!(InvalidArgument, "invalid older-than: {}", e))?)
+        };
+
+        let status = GLOBAL_REPLICATION_RESYNCER
+            .start(store, &query.bucket, &query.arn, older_than)
+            .await
+            .map_err(|e| s3_error!(InvalidRequest, "start resync failed: {}", e))?;
+
+        json_response(&status)
+    }
+}
+
+pub struct ReplicationResyncCancel {}
+
+#[async_trait::async_trait]
+impl Operation for ReplicationResyncCancel {
+    // PUT <endpoint>/<admin-API>/replication/resync/cancel?bucket=<bucket>&arn=<arn>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SetBucketTargetAction).await?;
+        let (store, query) = query_resync(&req, true).await?;
+
+        let status = GLOBAL_REPLICATION_RESYNCER
+            .cancel(store, &query.bucket, &query.arn)
+            .await
+            .map_err(|e| s3_error!(InvalidRequest, "cancel resync failed: {}", e))?;
+
+        json_response(&status)
+    }
+}
+
+pub struct ReplicationResyncStatus {}
+
+#[async_trait::async_trait]
+impl Operation for ReplicationResyncStatus {
+    // GET <endpoint>/<admin-API>/replication/resync/status?bucket=<bucket>[&arn=<arn>]
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::GetBucketTargetAction).await?;
+        let (store, query) = query_resync(&req, false).await?;
+
+        let mut status = GLOBAL_REPLICATION_RESYNCER
+            .status(store, &query.bucket)
+            .await
+            .map_err(ApiError::from)?;
+        if !query.arn.is_empty() {
+            status.targets_map.retain(|arn, _| *arn == query.arn);
+        }
+
+        json_response(&status)
+    }
+}
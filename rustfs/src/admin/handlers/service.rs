@@ -0,0 +1,102 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_common::globals::GLOBAL_Local_Node_Name;
+use rustfs_ecstore::notification_sys::get_global_notification_sys;
+use rustfs_ecstore::service_signal::{ServiceSignal, send_service_signal};
+use rustfs_madmin::service_commands::{ServiceAction, ServiceActionPeerResult, ServiceActionResult};
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::Deserialize;
+use serde_urlencoded::from_bytes;
+use tracing::{info, warn};
+
+use crate::admin::{router::Operation, utils::validate_admin_request};
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct ServiceQuery {
+    pub action: String,
+    #[serde(rename = "dry-run")]
+    pub dry_run: bool,
+}
+
+pub struct ServiceHandle {}
+
+#[async_trait::async_trait]
+impl Operation for ServiceHandle {
+    // POST <endpoint>/<admin-API>/service?action=<restart|stop|freeze|unfreeze>&dry-run=<bool>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        let query: ServiceQuery = match req.uri.query() {
+            Some(query) => from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?,
+            None => ServiceQuery::default(),
+        };
+        let action: ServiceAction = query
+            .action
+            .parse()
+            .map_err(|e: String| S3Error::with_message(S3ErrorCode::InvalidArgument, e))?;
+
+        let admin_action = match action {
+            ServiceAction::Restart => AdminAction::ServiceRestartAdminAction,
+            ServiceAction::Stop => AdminAction::ServiceStopAdminAction,
+            ServiceAction::Freeze | ServiceAction::Unfreeze => AdminAction::ServiceFreezeAdminAction,
+        };
+        validate_admin_request(&req, admin_action).await?;
+
+        info!("service action {} requested, dry-run: {}", action, query.dry_run);
+        let signal = ServiceSignal::from(action);
+
+        // Peers are signalled first, a local restart or stop would otherwise cut the fan-out short.
+        let mut results: Vec<ServiceActionPeerResult> = match get_global_notification_sys() {
+            Some(notification_sys) => notification_sys
+                .signal_service(signal, query.dry_run)
+                .await
+                .into_iter()
+                .map(|peer| ServiceActionPeerResult {
+                    host: peer.host,
+                    err: peer.err.map(|e| e.to_string()).unwrap_or_default(),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let local_err = if query.dry_run {
+            None
+        } else {
+            send_service_signal(signal).err()
+        };
+        if let Some(err) = &local_err {
+            warn!("service action {} failed on the local node, e: {:?}", action, err);
+        }
+        results.push(ServiceActionPeerResult {
+            host: GLOBAL_Local_Node_Name.read().await.clone(),
+            err: local_err.map(|e| e.to_string()).unwrap_or_default(),
+        });
+
+        let result = ServiceActionResult {
+            action,
+            dry_run: query.dry_run,
+            results,
+        };
+        let data = serde_json::to_vec(&result)
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal response err {e}")))?;
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+    }
+}
@@ -0,0 +1,558 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use http::{HeaderMap, StatusCode};
+use matchit::Params;
+use rustfs_ecstore::cmd::site_replication::{
+    GLOBAL_SITE_REPLICATION_SYS, PeerCredentials, SITE_REPLICATOR_ACCESS_KEY, SiteReplicationState, open_peer_body,
+};
+use rustfs_ecstore::global::get_global_action_cred;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store::ECStore;
+use rustfs_iam::store::UserType;
+use rustfs_iam::sys::NewServiceAccountOpts;
+use rustfs_iam::utils::gen_secret_key;
+use rustfs_madmin::AccountStatus;
+use rustfs_madmin::AddOrUpdateUserReq;
+use rustfs_madmin::site_replication::{PeerSite, SRBucketMeta, SRBucketOp, SRIAMItem, SRRemoveReq, SRSTSCredential};
+use rustfs_policy::auth::Credentials;
+use rustfs_policy::policy::Policy;
+use rustfs_policy::policy::action::AdminAction;
+use rustfs_policy::policy::default::DEFAULT_POLICIES;
+use s3s::{Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result, header::CONTENT_TYPE, s3_error};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_urlencoded::from_bytes;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tracing::warn;
+
+use crate::{
+    admin::{router::Operation, utils::validate_admin_request},
+    error::ApiError,
+};
+
+fn object_layer() -> S3Result<Arc<ECStore>> {
+    new_object_layer_fn().ok_or_else(|| S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()))
+}
+
+async fn read_json<T: DeserializeOwned>(req: S3Request<Body>) -> S3Result<T> {
+    let mut input = req.input;
+    let body = match input.store_all_unlimited().await {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("get body failed, e: {:?}", e);
+            return Err(s3_error!(InvalidRequest, "get body failed"));
+        }
+    };
+    serde_json::from_slice(&body).map_err(|e| s3_error!(InvalidArgument, "unmarshal body err {}", e))
+}
+
+/// Reads the JSON body of a peer call, encrypted by the secret key of the calling site.
+async fn read_peer_json<T: DeserializeOwned>(req: S3Request<Body>) -> S3Result<T> {
+    let Some(cred) = req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+    let mut input = req.input;
+    let body = match input.store_all_unlimited().await {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("get body failed, e: {:?}", e);
+            return Err(s3_error!(InvalidRequest, "get body failed"));
+        }
+    };
+    let body = open_peer_body(cred.secret_key.expose(), &body).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+    serde_json::from_slice(&body).map_err(|e| s3_error!(InvalidArgument, "unmarshal body err {}", e))
+}
+
+fn json_response<T: Serialize>(value: &T) -> S3Result<S3Response<(StatusCode, Body)>> {
+    let data = serde_json::to_vec(value)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("marshal site replication err {e}")))?;
+
+    let mut header = HeaderMap::new();
+    header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    Ok(S3Response::with_headers((StatusCode::OK, Body::from(data)), header))
+}
+
+fn sr_error(e: impl std::fmt::Display) -> S3Error {
+    s3_error!(InvalidRequest, "site replication: {}", e)
+}
+
+/// The site replicator service account has to outlive the link between the sites, the IAM system
+/// requires service accounts to expire though.
+const SITE_REPLICATOR_EXPIRATION_DAYS: i64 = 100 * 365;
+
+/// Creates the service account the sites call each other with, replacing one left over by a failed link.
+async fn create_site_replicator(cred: &PeerCredentials) -> S3Result<()> {
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InternalError, "iam not init"));
+    };
+    let Some(sys_cred) = get_global_action_cred() else {
+        return Err(s3_error!(InternalError, "get_global_action_cred failed"));
+    };
+
+    iam_store
+        .delete_service_account(SITE_REPLICATOR_ACCESS_KEY, false)
+        .await
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?;
+
+    let opts = NewServiceAccountOpts {
+        access_key: cred.access_key.clone(),
+        secret_key: cred.secret_key.clone(),
+        name: Some("site-replicator".to_string()),
+        description: Some("Used by site replication to call the other sites".to_string()),
+        expiration: Some(OffsetDateTime::now_utc() + time::Duration::days(SITE_REPLICATOR_EXPIRATION_DAYS)),
+        allow_site_replicator_account: true,
+        ..Default::default()
+    };
+    iam_store
+        .new_service_account(&sys_cred.access_key, None, opts)
+        .await
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("create site replicator account failed: {e}")))?;
+    Ok(())
+}
+
+/// Deletes the site replicator service account once the site is no longer linked.
+async fn delete_site_replicator() {
+    if GLOBAL_SITE_REPLICATION_SYS.is_enabled().await {
+        return;
+    }
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return;
+    };
+    if let Err(e) = iam_store.delete_service_account(SITE_REPLICATOR_ACCESS_KEY, true).await {
+        warn!("site replication: delete site replicator account failed: {}", e);
+    }
+}
+
+/// Sends an IAM change to the peer sites.
+pub async fn replicate_iam_change(item: SRIAMItem) -> S3Result<()> {
+    GLOBAL_SITE_REPLICATION_SYS
+        .iam_change_hook(&item)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(())
+}
+
+/// Sends a new STS credential to the peer sites.
+pub async fn replicate_sts_credential(cred: &Credentials, policy: Option<&str>) -> S3Result<()> {
+    replicate_iam_change(SRIAMItem::StsCredential(SRSTSCredential {
+        access_key: cred.access_key.clone(),
+        secret_key: cred.secret_key.clone(),
+        session_token: cred.session_token.clone(),
+        parent_user: cred.parent_user.clone(),
+        expiration: cred.expiration,
+        groups: cred.groups.clone(),
+        claims: cred.claims.clone(),
+        policy: policy.map(str::to_string),
+    }))
+    .await
+}
+
+/// Applies an IAM change sent by a peer site.
+async fn apply_iam_item(item: SRIAMItem) -> S3Result<()> {
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InternalError, "iam not init"));
+    };
+
+    let res = match item {
+        SRIAMItem::Policy {
+            name,
+            policy: Some(policy),
+        } => {
+            let policy = Policy::parse_config(policy.as_bytes()).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+            iam_store.set_policy(&name, policy).await.map(|_| ())
+        }
+        SRIAMItem::Policy { name, policy: None } => iam_store.delete_policy(&name, true).await,
+        SRIAMItem::User {
+            access_key,
+            secret_key: Some(secret_key),
+            status,
+        } => {
+            let status = AccountStatus::try_from(status.as_str()).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+            let args = AddOrUpdateUserReq {
+                secret_key,
+                policy: None,
+                status,
+            };
+            iam_store.create_user(&access_key, &args).await.map(|_| ())
+        }
+        SRIAMItem::User {
+            access_key,
+            secret_key: None,
+            ..
+        } => iam_store.delete_user(&access_key, true).await,
+        SRIAMItem::UserStatus { access_key, status } => {
+            let status = AccountStatus::try_from(status.as_str()).map_err(|e| s3_error!(InvalidArgument, "{}", e))?;
+            iam_store.set_user_status(&access_key, status).await.map(|_| ())
+        }
+        SRIAMItem::GroupMembers {
+            group,
+            members,
+            is_remove,
+        } => {
+            if is_remove {
+                iam_store.remove_users_from_group(&group, members).await.map(|_| ())
+            } else {
+                iam_store.add_users_to_group(&group, members).await.map(|_| ())
+            }
+        }
+        SRIAMItem::GroupStatus { group, enabled } => iam_store.set_group_status(&group, enabled).await.map(|_| ()),
+        SRIAMItem::PolicyMapping {
+            user_or_group,
+            is_group,
+            policy,
+        } => iam_store
+            .policy_db_set(&user_or_group, UserType::Reg, is_group, &policy)
+            .await
+            .map(|_| ()),
+        SRIAMItem::StsCredential(c) => {
+            let cred = Credentials {
+                access_key: c.access_key,
+                secret_key: c.secret_key,
+                session_token: c.session_token,
+                expiration: c.expiration,
+                parent_user: c.parent_user,
+                groups: c.groups,
+                claims: c.claims,
+                ..Default::default()
+            };
+            iam_store
+                .set_temp_user(&cred.access_key, &cred, c.policy.as_deref())
+                .await
+                .map(|_| ())
+        }
+    };
+
+    res.map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))
+}
+
+/// Sends the local IAM entities to the peer sites, returns the items which failed.
+async fn sync_iam() -> Vec<String> {
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return vec!["iam not init".to_string()];
+    };
+    let mut items = Vec::new();
+
+    match iam_store.list_polices("").await {
+        Ok(policies) => {
+            for (name, policy) in policies {
+                if DEFAULT_POLICIES.iter().any(|(n, _)| *n == name) {
+                    continue;
+                }
+                match serde_json::to_string(&policy) {
+                    Ok(policy) => items.push(SRIAMItem::Policy {
+                        name,
+                        policy: Some(policy),
+                    }),
+                    Err(e) => warn!("site replication: marshal policy {} failed: {}", name, e),
+                }
+            }
+        }
+        Err(e) => return vec![format!("list policies: {e}")],
+    }
+
+    match iam_store.list_users().await {
+        Ok(users) => {
+            for (access_key, info) in users {
+                let Some(user) = iam_store.get_user(&access_key).await else {
+                    continue;
+                };
+                items.push(SRIAMItem::User {
+                    access_key: access_key.clone(),
+                    secret_key: Some(user.credentials.secret_key),
+                    status: info.status.as_ref().to_string(),
+                });
+                if let Some(policy) = info.policy_name.filter(|p| !p.is_empty()) {
+                    items.push(SRIAMItem::PolicyMapping {
+                        user_or_group: access_key,
+                        is_group: false,
+                        policy,
+                    });
+                }
+            }
+        }
+        Err(e) => return vec![format!("list users: {e}")],
+    }
+
+    match iam_store.list_groups().await {
+        Ok(groups) => {
+            for group in groups {
+                let desc = match iam_store.get_group_description(&group).await {
+                    Ok(desc) => desc,
+                    Err(e) => {
+                        warn!("site replication: get group {} failed: {}", group, e);
+                        continue;
+                    }
+                };
+                items.push(SRIAMItem::GroupMembers {
+                    group: group.clone(),
+                    members: desc.members,
+                    is_remove: false,
+                });
+                if desc.status == "disabled" {
+                    items.push(SRIAMItem::GroupStatus {
+                        group: group.clone(),
+                        enabled: false,
+                    });
+                }
+                if !desc.policy.is_empty() {
+                    items.push(SRIAMItem::PolicyMapping {
+                        user_or_group: group,
+                        is_group: true,
+                        policy: desc.policy,
+                    });
+                }
+            }
+        }
+        Err(e) => return vec![format!("list groups: {e}")],
+    }
+
+    let mut errs = Vec::new();
+    for item in items {
+        if let Err(e) = GLOBAL_SITE_REPLICATION_SYS.iam_change_hook(&item).await {
+            errs.push(e.to_string());
+        }
+    }
+    errs
+}
+
+pub struct SiteReplicationAdd {}
+
+#[async_trait::async_trait]
+impl Operation for SiteReplicationAdd {
+    // PUT <endpoint>/<admin-API>/site-replication/add
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationAddAction).await?;
+        let store = object_layer()?;
+        let sites: Vec<PeerSite> = read_json(req).await?;
+
+        // Sites added to an existing link get the account the linked sites share.
+        let linked_account = GLOBAL_SITE_REPLICATION_SYS.service_account().await;
+        let linked = linked_account.is_some();
+        let service_account = match linked_account {
+            Some(account) => account,
+            None => {
+                let account = PeerCredentials {
+                    access_key: SITE_REPLICATOR_ACCESS_KEY.to_string(),
+                    secret_key: gen_secret_key(40)
+                        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, e.to_string()))?,
+                };
+                create_site_replicator(&account).await?;
+                account
+            }
+        };
+
+        let mut status = match GLOBAL_SITE_REPLICATION_SYS
+            .add_peer_sites(store, sites, service_account)
+            .await
+        {
+            Ok(status) => status,
+            Err(e) => {
+                if !linked {
+                    delete_site_replicator().await;
+                }
+                return Err(sr_error(e));
+            }
+        };
+        status.initial_sync_errors.extend(sync_iam().await);
+
+        json_response(&status)
+    }
+}
+
+pub struct SiteReplicationRemove {}
+
+#[async_trait::async_trait]
+impl Operation for SiteReplicationRemove {
+    // PUT <endpoint>/<admin-API>/site-replication/remove
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationRemoveAction).await?;
+        let store = object_layer()?;
+        let remove_req: SRRemoveReq = read_json(req).await?;
+
+        let status = GLOBAL_SITE_REPLICATION_SYS
+            .remove_peer_sites(store, remove_req)
+            .await
+            .map_err(sr_error)?;
+        delete_site_replicator().await;
+
+        json_response(&status)
+    }
+}
+
+pub struct SiteReplicationInfo {}
+
+#[async_trait::async_trait]
+impl Operation for SiteReplicationInfo {
+    // GET <endpoint>/<admin-API>/site-replication/info
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationInfoAction).await?;
+
+        json_response(&GLOBAL_SITE_REPLICATION_SYS.info().await)
+    }
+}
+
+pub struct SiteReplicationStatus {}
+
+#[async_trait::async_trait]
+impl Operation for SiteReplicationStatus {
+    // GET <endpoint>/<admin-API>/site-replication/status
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationInfoAction).await?;
+        let store = object_layer()?;
+
+        let status = GLOBAL_SITE_REPLICATION_SYS.status(store).await.map_err(ApiError::from)?;
+
+        json_response(&status)
+    }
+}
+
+pub struct SRPeerState {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerState {
+    // GET <endpoint>/<admin-API>/site-replication/peer/state
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationOperationAction).await?;
+        let store = object_layer()?;
+
+        let state = GLOBAL_SITE_REPLICATION_SYS.peer_state(store).await.map_err(ApiError::from)?;
+
+        json_response(&state)
+    }
+}
+
+pub struct SRPeerJoin {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerJoin {
+    // PUT <endpoint>/<admin-API>/site-replication/peer/join
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationAddAction).await?;
+        let store = object_layer()?;
+        let state: SiteReplicationState = read_peer_json(req).await?;
+
+        if state.service_account.access_key != SITE_REPLICATOR_ACCESS_KEY {
+            return Err(sr_error("the site replicator service account is missing"));
+        }
+        // A linked site keeps its account and only learns about the added sites.
+        if GLOBAL_SITE_REPLICATION_SYS.is_enabled().await {
+            GLOBAL_SITE_REPLICATION_SYS.peer_join(store, state).await.map_err(sr_error)?;
+            return Ok(S3Response::new((StatusCode::OK, Body::empty())));
+        }
+        create_site_replicator(&state.service_account).await?;
+
+        if let Err(e) = GLOBAL_SITE_REPLICATION_SYS.peer_join(store, state).await {
+            delete_site_replicator().await;
+            return Err(sr_error(e));
+        }
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct SRPeerRemove {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerRemove {
+    // PUT <endpoint>/<admin-API>/site-replication/peer/remove
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationRemoveAction).await?;
+        let store = object_layer()?;
+        let remove_req: SRRemoveReq = read_peer_json(req).await?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .peer_remove(store, &remove_req)
+            .await
+            .map_err(sr_error)?;
+        delete_site_replicator().await;
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SRBucketOpQuery {
+    pub bucket: String,
+    pub operation: SRBucketOp,
+    #[serde(default)]
+    pub lock: bool,
+}
+
+pub struct SRPeerBucketOps {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerBucketOps {
+    // PUT <endpoint>/<admin-API>/site-replication/peer/bucket-ops?bucket=<bucket>&operation=<op>&lock=<bool>
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationOperationAction).await?;
+        let store = object_layer()?;
+
+        let Some(query) = req.uri.query() else {
+            return Err(s3_error!(InvalidArgument, "bucket and operation are required"));
+        };
+        let query: SRBucketOpQuery = from_bytes(query.as_bytes()).map_err(|_e| s3_error!(InvalidArgument, "get query failed"))?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .peer_bucket_op(store, &query.bucket, query.operation, query.lock)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct SRPeerBucketMeta {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerBucketMeta {
+    // PUT <endpoint>/<admin-API>/site-replication/peer/bucket-meta
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationOperationAction).await?;
+        let meta: SRBucketMeta = read_peer_json(req).await?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .peer_bucket_meta(meta)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
+
+pub struct SRPeerIAMItem {}
+
+#[async_trait::async_trait]
+impl Operation for SRPeerIAMItem {
+    // PUT <endpoint>/<admin-API>/site-replication/peer/iam-item
+    #[tracing::instrument(skip_all)]
+    async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
+        validate_admin_request(&req, AdminAction::SiteReplicationOperationAction).await?;
+        let item: SRIAMItem = read_peer_json(req).await?;
+
+        apply_iam_item(item).await?;
+
+        Ok(S3Response::new((StatusCode::OK, Body::empty())))
+    }
+}
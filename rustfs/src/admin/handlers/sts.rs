@@ -13,19 +13,26 @@
 // limitations under the License.
 
 use crate::{
-    admin::router::Operation,
+    admin::{handlers::site_replication::replicate_sts_credential, router::Operation},
     auth::{check_key_valid, get_session_token},
 };
-use http::StatusCode;
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use http::{HeaderMap, StatusCode, header::CONTENT_LENGTH};
 use matchit::Params;
 use rustfs_ecstore::bucket::utils::serialize;
-use rustfs_iam::{manager::get_token_signing_key, sys::SESSION_POLICY_NAME};
+use rustfs_iam::{
+    ldap::{LDAP_GROUPS_CLAIM, LDAP_USER_CLAIM, LDAP_USERNAME_CLAIM, get_ldap_provider},
+    manager::get_token_signing_key,
+    openid::get_openid_provider,
+    sys::{POLICYNAME, SESSION_POLICY_NAME},
+};
 use rustfs_policy::{auth::get_new_credentials_with_metadata, policy::Policy};
 use rustfs_utils::crypto::base64_encode;
 use s3s::{
     Body, S3Error, S3ErrorCode, S3Request, S3Response, S3Result,
     dto::{AssumeRoleOutput, Credentials, Timestamp},
-    s3_error,
+    s3_error, xml,
 };
 use serde::Deserialize;
 use serde_json::Value;
@@ -35,7 +42,26 @@ use time::{Duration, OffsetDateTime};
 use tracing::{info, warn};
 
 const ASSUME_ROLE_ACTION: &str = "AssumeRole";
+const ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION: &str = "AssumeRoleWithWebIdentity";
+const ASSUME_ROLE_WITH_CLIENT_GRANTS_ACTION: &str = "AssumeRoleWithClientGrants";
+const ASSUME_ROLE_WITH_LDAP_IDENTITY_ACTION: &str = "AssumeRoleWithLDAPIdentity";
 const ASSUME_ROLE_VERSION: &str = "2011-06-15";
+const STS_XMLNS: &str = "https://sts.amazonaws.com/doc/2011-06-15/";
+
+/// Valid range and default of `DurationSeconds`, in seconds.
+const MIN_DURATION_SECONDS: usize = 900;
+const MAX_DURATION_SECONDS: usize = 43200;
+const DEFAULT_DURATION_SECONDS: usize = 3600;
+
+/// STS requests are small forms, larger bodies are refused before they are read.
+const MAX_STS_BODY_SIZE: usize = 1 << 20;
+
+/// Actions served to anonymous callers, they authenticate with the token or LDAP password in the request.
+const ANONYMOUS_ACTIONS: &[&str] = &[
+    ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION,
+    ASSUME_ROLE_WITH_CLIENT_GRANTS_ACTION,
+    ASSUME_ROLE_WITH_LDAP_IDENTITY_ACTION,
+];
 
 #[derive(Deserialize, Debug, Default)]
 #[serde(rename_all = "PascalCase", default)]
@@ -47,6 +73,12 @@ pub struct AssumeRoleRequest {
     pub role_session_name: String,
     pub policy: String,
     pub external_id: String,
+    pub web_identity_token: String,
+    pub token: String,
+    #[serde(rename = "LDAPUsername")]
+    pub ldap_username: String,
+    #[serde(rename = "LDAPPassword")]
+    pub ldap_password: String,
 }
 
 pub struct AssumeRoleHandle {}
@@ -55,109 +87,295 @@ impl Operation for AssumeRoleHandle {
     async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
         warn!("handle AssumeRoleHandle");
 
-        let Some(user) = req.credentials else { return Err(s3_error!(InvalidRequest, "get cred failed")) };
-
-        let session_token = get_session_token(&req.uri, &req.headers);
-        if session_token.is_some() {
-            return Err(s3_error!(InvalidRequest, "AccessDenied1"));
-        }
+        let bytes = read_body(&req.headers, req.input).await?;
 
-        let (cred, _owner) =
-            check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &user.access_key).await?;
+        let body: AssumeRoleRequest = from_bytes(&bytes).map_err(|_e| s3_error!(InvalidRequest, "get body failed"))?;
 
-        // TODO: Check permissions, do not allow STS access
-        if cred.is_temp() || cred.is_service_account() {
-            return Err(s3_error!(InvalidRequest, "AccessDenied"));
+        if body.version.as_str() != ASSUME_ROLE_VERSION {
+            return Err(s3_error!(InvalidArgument, "not support version"));
         }
 
-        let mut input = req.input;
+        if req.credentials.is_none() && !ANONYMOUS_ACTIONS.contains(&body.action.as_str()) {
+            return Err(s3_error!(AccessDenied, "Signature is required"));
+        }
 
-        let bytes = match input.store_all_unlimited().await {
-            Ok(b) => b,
-            Err(e) => {
-                warn!("get body failed, e: {:?}", e);
-                return Err(s3_error!(InvalidRequest, "get body failed"));
+        let output = match body.action.as_str() {
+            ASSUME_ROLE_ACTION => {
+                let Some(user) = req.credentials else { return Err(s3_error!(InvalidRequest, "get cred failed")) };
+                let session_token = get_session_token(&req.uri, &req.headers).unwrap_or_default();
+                let resp = assume_role(&user.access_key, session_token, &body).await?;
+                serialize::<AssumeRoleOutput>(&resp).unwrap()
+            }
+            ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION => {
+                let resp = assume_role_with_openid(&body.web_identity_token, &body).await?;
+                serialize_sts_response(ASSUME_ROLE_WITH_WEB_IDENTITY_ACTION, &resp)?
             }
+            ASSUME_ROLE_WITH_CLIENT_GRANTS_ACTION => {
+                let resp = assume_role_with_openid(&body.token, &body).await?;
+                serialize_sts_response(ASSUME_ROLE_WITH_CLIENT_GRANTS_ACTION, &resp)?
+            }
+            ASSUME_ROLE_WITH_LDAP_IDENTITY_ACTION => {
+                let resp = assume_role_with_ldap(&body).await?;
+                serialize_sts_response(ASSUME_ROLE_WITH_LDAP_IDENTITY_ACTION, &resp)?
+            }
+            _ => return Err(s3_error!(InvalidArgument, "not support action")),
         };
 
-        let body: AssumeRoleRequest = from_bytes(&bytes).map_err(|_e| s3_error!(InvalidRequest, "get body failed"))?;
+        Ok(S3Response::new((StatusCode::OK, Body::from(output))))
+    }
+}
 
-        if body.action.as_str() != ASSUME_ROLE_ACTION {
-            return Err(s3_error!(InvalidArgument, "not support action"));
-        }
+/// Reads the form of an STS request, which may come from an anonymous caller.
+async fn read_body(headers: &HeaderMap, mut input: Body) -> S3Result<Bytes> {
+    let declared = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > MAX_STS_BODY_SIZE as u64) {
+        return Err(request_too_large());
+    }
 
-        if body.version.as_str() != ASSUME_ROLE_VERSION {
-            return Err(s3_error!(InvalidArgument, "not support version"));
+    let mut bytes = BytesMut::new();
+    while let Some(chunk) = input.next().await {
+        let chunk = chunk.map_err(|e| {
+            warn!("get body failed, e: {:?}", e);
+            s3_error!(InvalidRequest, "get body failed")
+        })?;
+        if bytes.len() + chunk.len() > MAX_STS_BODY_SIZE {
+            return Err(request_too_large());
         }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes.freeze())
+}
 
-        let mut claims = cred.claims.unwrap_or_default();
+fn request_too_large() -> S3Error {
+    let mut err = S3Error::with_message(
+        S3ErrorCode::Custom("RequestEntityTooLarge".into()),
+        "The request body is too large.".to_string(),
+    );
+    err.set_status_code(StatusCode::PAYLOAD_TOO_LARGE);
+    err
+}
 
-        populate_session_policy(&mut claims, &body.policy)?;
+async fn assume_role(access_key: &str, session_token: &str, body: &AssumeRoleRequest) -> S3Result<AssumeRoleOutput> {
+    if !session_token.is_empty() {
+        return Err(s3_error!(InvalidRequest, "AccessDenied1"));
+    }
 
-        let exp = {
-            if body.duration_seconds > 0 {
-                body.duration_seconds
-            } else {
-                3600
-            }
-        };
+    let (cred, _owner) = check_key_valid(session_token, access_key).await?;
 
-        claims.insert(
-            "exp".to_string(),
-            serde_json::Value::Number(serde_json::Number::from(OffsetDateTime::now_utc().unix_timestamp() + exp as i64)),
-        );
+    // TODO: Check permissions, do not allow STS access
+    if cred.is_temp() || cred.is_service_account() {
+        return Err(s3_error!(InvalidRequest, "AccessDenied"));
+    }
 
-        claims.insert("parent".to_string(), serde_json::Value::String(cred.access_key.clone()));
+    let mut claims = cred.claims.unwrap_or_default();
 
-        // warn!("AssumeRole get cred {:?}", &user);
-        // warn!("AssumeRole get body {:?}", &body);
+    populate_session_policy(&mut claims, &body.policy)?;
 
-        let Ok(iam_store) = rustfs_iam::get() else {
-            return Err(s3_error!(InvalidRequest, "iam not init"));
-        };
+    let exp = credentials_expiration(body.duration_seconds, None)?;
+    claims.insert("exp".to_string(), serde_json::Value::Number(serde_json::Number::from(exp)));
 
-        if let Err(_err) = iam_store.policy_db_get(&cred.access_key, &cred.groups).await {
-            return Err(s3_error!(InvalidArgument, "invalid policy arg"));
-        }
+    claims.insert("parent".to_string(), serde_json::Value::String(cred.access_key.clone()));
 
-        let Some(secret) = get_token_signing_key() else {
-            return Err(s3_error!(InvalidArgument, "global active sk not init"));
-        };
+    // warn!("AssumeRole get cred {:?}", &user);
+    // warn!("AssumeRole get body {:?}", &body);
+
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InvalidRequest, "iam not init"));
+    };
+
+    if let Err(_err) = iam_store.policy_db_get(&cred.access_key, &cred.groups).await {
+        return Err(s3_error!(InvalidArgument, "invalid policy arg"));
+    }
+
+    let Some(secret) = get_token_signing_key() else {
+        return Err(s3_error!(InvalidArgument, "global active sk not init"));
+    };
+
+    info!("AssumeRole get claims {:?}", &claims);
 
-        info!("AssumeRole get claims {:?}", &claims);
+    let mut new_cred = get_new_credentials_with_metadata(&claims, &secret)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get new cred failed {e}")))?;
 
-        let mut new_cred = get_new_credentials_with_metadata(&claims, &secret)
-            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get new cred failed {e}")))?;
+    new_cred.parent_user = cred.access_key.clone();
+
+    info!("AssumeRole get new_cred {:?}", &new_cred);
+
+    if let Err(_err) = iam_store.set_temp_user(&new_cred.access_key, &new_cred, None).await {
+        return Err(s3_error!(InternalError, "set_temp_user failed"));
+    }
 
-        new_cred.parent_user = cred.access_key.clone();
+    replicate_sts_credential(&new_cred, None).await?;
+
+    Ok(credentials_output(new_cred))
+}
+
+/// AssumeRoleWithWebIdentity and AssumeRoleWithClientGrants, the caller is authenticated by a token of
+/// the OpenID provider and gets the policies named in its claims.
+async fn assume_role_with_openid(token: &str, body: &AssumeRoleRequest) -> S3Result<AssumeRoleOutput> {
+    let Some(provider) = get_openid_provider() else {
+        return Err(s3_error!(NotImplemented, "openid provider is not configured"));
+    };
+
+    if token.is_empty() {
+        return Err(s3_error!(InvalidArgument, "token is required"));
+    }
 
-        info!("AssumeRole get new_cred {:?}", &new_cred);
+    let mut claims = provider.validate(token).await.map_err(|e| {
+        warn!("openid token validation failed: {e}");
+        s3_error!(AccessDenied, "invalid identity token")
+    })?;
 
-        if let Err(_err) = iam_store.set_temp_user(&new_cred.access_key, &new_cred, None).await {
-            return Err(s3_error!(InternalError, "set_temp_user failed"));
+    let Some(sub) = claims.get("sub").and_then(Value::as_str).filter(|v| !v.is_empty()) else {
+        return Err(s3_error!(AccessDenied, "identity token has no subject"));
+    };
+    let parent_user = format!("openid:{sub}");
+
+    let policies = provider.policies(&claims);
+    if policies.is_empty() {
+        return Err(s3_error!(AccessDenied, "no policy is mapped to the identity"));
+    }
+    let policy_name = policies.join(",");
+
+    // The credentials never outlive the token they are exchanged for
+    let token_exp = claims.get("exp").and_then(Value::as_i64);
+    let exp = credentials_expiration(body.duration_seconds, token_exp)?;
+    claims.insert("exp".to_string(), Value::Number(serde_json::Number::from(exp)));
+
+    claims.insert(POLICYNAME.to_string(), Value::String(policy_name.clone()));
+    claims.insert("parent".to_string(), Value::String(parent_user.clone()));
+
+    populate_session_policy(&mut claims, &body.policy)?;
+
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InvalidRequest, "iam not init"));
+    };
+
+    let Some(secret) = get_token_signing_key() else {
+        return Err(s3_error!(InvalidArgument, "global active sk not init"));
+    };
+
+    let mut new_cred = get_new_credentials_with_metadata(&claims, &secret)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get new cred failed {e}")))?;
+
+    new_cred.parent_user = parent_user;
+
+    if let Err(err) = iam_store
+        .set_temp_user(&new_cred.access_key, &new_cred, Some(&policy_name))
+        .await
+    {
+        warn!("set_temp_user for {} failed: {err}", new_cred.parent_user);
+        return Err(s3_error!(AccessDenied, "the mapped policies do not exist"));
+    }
+
+    replicate_sts_credential(&new_cred, Some(&policy_name)).await?;
+
+    Ok(credentials_output(new_cred))
+}
+
+/// AssumeRoleWithLDAPIdentity, the caller is authenticated by the LDAP directory and gets the policies
+/// attached to the DNs of the user and its groups.
+async fn assume_role_with_ldap(body: &AssumeRoleRequest) -> S3Result<AssumeRoleOutput> {
+    let Some(provider) = get_ldap_provider() else {
+        return Err(s3_error!(NotImplemented, "ldap provider is not configured"));
+    };
+
+    let exp = credentials_expiration(body.duration_seconds, None)?;
+
+    let identity = provider
+        .authenticate(&body.ldap_username, &body.ldap_password)
+        .await
+        .map_err(|e| {
+            warn!("ldap authentication of {} failed: {e}", body.ldap_username);
+            s3_error!(AccessDenied, "invalid ldap username or password")
+        })?;
+
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InvalidRequest, "iam not init"));
+    };
+
+    let policies = iam_store
+        .policy_db_get_ldap(&identity.user_dn, &identity.group_dns)
+        .await
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get ldap policies failed {e}")))?;
+    if policies.is_empty() {
+        return Err(s3_error!(AccessDenied, "no policy is attached to the identity"));
+    }
+
+    let mut claims = HashMap::new();
+    claims.insert("exp".to_string(), Value::Number(serde_json::Number::from(exp)));
+    claims.insert("parent".to_string(), Value::String(identity.user_dn.clone()));
+    claims.insert(LDAP_USER_CLAIM.to_string(), Value::String(identity.user_dn.clone()));
+    claims.insert(LDAP_USERNAME_CLAIM.to_string(), Value::String(identity.username));
+    claims.insert(
+        LDAP_GROUPS_CLAIM.to_string(),
+        Value::Array(identity.group_dns.into_iter().map(Value::String).collect()),
+    );
+
+    populate_session_policy(&mut claims, &body.policy)?;
+
+    let Some(secret) = get_token_signing_key() else {
+        return Err(s3_error!(InvalidArgument, "global active sk not init"));
+    };
+
+    let mut new_cred = get_new_credentials_with_metadata(&claims, &secret)
+        .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("get new cred failed {e}")))?;
+
+    new_cred.parent_user = identity.user_dn;
+
+    if let Err(_err) = iam_store.set_temp_user(&new_cred.access_key, &new_cred, None).await {
+        return Err(s3_error!(InternalError, "set_temp_user failed"));
+    }
+
+    replicate_sts_credential(&new_cred, None).await?;
+
+    Ok(credentials_output(new_cred))
+}
+
+/// Unix time the temporary credentials expire at. `DurationSeconds` defaults to an hour and must be
+/// within 900..=43200; the expiry is capped at `token_exp`, the expiry of the identity token if any.
+fn credentials_expiration(duration_seconds: usize, token_exp: Option<i64>) -> S3Result<i64> {
+    let duration = match duration_seconds {
+        0 => DEFAULT_DURATION_SECONDS,
+        MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS => duration_seconds,
+        _ => {
+            return Err(S3Error::with_message(
+                S3ErrorCode::Custom("InvalidParameterValue".into()),
+                format!("DurationSeconds must be between {MIN_DURATION_SECONDS} and {MAX_DURATION_SECONDS} seconds"),
+            ));
         }
+    };
 
-        // TODO: globalSiteReplicationSys
-
-        let resp = AssumeRoleOutput {
-            credentials: Some(Credentials {
-                access_key_id: new_cred.access_key,
-                expiration: Timestamp::from(
-                    new_cred
-                        .expiration
-                        .unwrap_or(OffsetDateTime::now_utc().saturating_add(Duration::seconds(3600))),
-                ),
-                secret_access_key: new_cred.secret_key,
-                session_token: new_cred.session_token,
-            }),
-            ..Default::default()
-        };
+    let exp = OffsetDateTime::now_utc().unix_timestamp() + duration as i64;
+    Ok(token_exp.map_or(exp, |token_exp| exp.min(token_exp)))
+}
 
-        // getAssumeRoleCredentials
-        let output = serialize::<AssumeRoleOutput>(&resp).unwrap();
+fn credentials_output(cred: rustfs_policy::auth::Credentials) -> AssumeRoleOutput {
+    AssumeRoleOutput {
+        credentials: Some(Credentials {
+            access_key_id: cred.access_key,
+            expiration: Timestamp::from(
+                cred.expiration
+                    .unwrap_or(OffsetDateTime::now_utc().saturating_add(Duration::seconds(3600))),
+            ),
+            secret_access_key: cred.secret_key,
+            session_token: cred.session_token,
+        }),
+        ..Default::default()
+    }
+}
 
-        Ok(S3Response::new((StatusCode::OK, Body::from(output))))
+/// Wraps the credentials in the `<Action>Response` element of the action
+fn serialize_sts_response(action: &str, output: &AssumeRoleOutput) -> S3Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(256);
+    {
+        let mut ser = xml::Serializer::new(&mut buf);
+        ser.element_with_ns(&format!("{action}Response"), STS_XMLNS, |s| s.content(&format!("{action}Result"), output))
+            .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("serialize response failed {e}")))?;
     }
+    Ok(buf)
 }
 
 pub fn populate_session_policy(claims: &mut HashMap<String, Value>, policy: &str) -> S3Result<()> {
@@ -180,3 +398,47 @@ pub fn populate_session_policy(claims: &mut HashMap<String, Value>, policy: &str
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_expiration() {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        let exp = credentials_expiration(0, None).unwrap();
+        assert!((now + 3600..now + 3610).contains(&exp));
+
+        let exp = credentials_expiration(900, None).unwrap();
+        assert!((now + 900..now + 910).contains(&exp));
+        assert!(credentials_expiration(43200, None).is_ok());
+
+        // Capped at the expiry of the identity token
+        assert_eq!(credentials_expiration(43200, Some(now + 1000)).unwrap(), now + 1000);
+        assert_eq!(credentials_expiration(0, Some(now + 60)).unwrap(), now + 60);
+
+        for invalid in [1, 899, 43201] {
+            let err = credentials_expiration(invalid, None).unwrap_err();
+            assert_eq!(err.code(), &S3ErrorCode::Custom("InvalidParameterValue".into()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_body_is_bounded() {
+        let form = "Action=AssumeRoleWithWebIdentity&Version=2011-06-15";
+        let bytes = read_body(&HeaderMap::new(), Body::from(form.to_string())).await.unwrap();
+        assert_eq!(bytes.as_ref(), form.as_bytes());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_LENGTH, (MAX_STS_BODY_SIZE + 1).to_string().parse().unwrap());
+        let err = read_body(&headers, Body::from(form.to_string())).await.unwrap_err();
+        assert_eq!(err.status_code(), Some(StatusCode::PAYLOAD_TOO_LARGE));
+
+        // A body without a declared length is cut off at the limit
+        let err = read_body(&HeaderMap::new(), Body::from(vec![b'a'; MAX_STS_BODY_SIZE + 1]))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), Some(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+}
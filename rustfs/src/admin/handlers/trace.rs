@@ -12,15 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use http::StatusCode;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use http::{HeaderMap, StatusCode};
 use hyper::Uri;
 use matchit::Params;
-use rustfs_ecstore::{GLOBAL_Endpoints, rpc::PeerRestClient};
+use rustfs_ecstore::{GLOBAL_Endpoints, rpc::PeerRestClient, trace::GLOBAL_TRACE};
 use rustfs_madmin::service_commands::ServiceTraceOpts;
-use s3s::{Body, S3Request, S3Response, S3Result, s3_error};
+use rustfs_madmin::trace::TraceInfo;
+use rustfs_policy::policy::action::AdminAction;
+use s3s::{
+    Body, S3Request, S3Response, S3Result, StdError,
+    header::CONTENT_TYPE,
+    s3_error,
+    stream::{ByteStream, DynByteStream},
+};
+use tokio::sync::mpsc;
+use tokio::time::interval;
+use tokio_stream::wrappers::ReceiverStream;
 use tracing::warn;
 
-use crate::admin::router::Operation;
+use crate::admin::{router::Operation, utils::validate_admin_request};
+
+/// Interval of the whitespace written to keep idle trace connections open.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
 
 fn extract_trace_options(uri: &Uri) -> S3Result<ServiceTraceOpts> {
     let mut st_opts = ServiceTraceOpts::default();
@@ -31,20 +50,97 @@ fn extract_trace_options(uri: &Uri) -> S3Result<ServiceTraceOpts> {
     Ok(st_opts)
 }
 
+struct TraceStream {
+    inner: ReceiverStream<Result<Bytes, StdError>>,
+}
+
+impl Stream for TraceStream {
+    type Item = Result<Bytes, StdError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::into_inner(self).inner.poll_next_unpin(cx)
+    }
+}
+
+impl ByteStream for TraceStream {}
+
+fn encode_trace(info: &TraceInfo) -> Option<Bytes> {
+    match serde_json::to_vec(info) {
+        Ok(mut data) => {
+            data.push(b'\n');
+            Some(data.into())
+        }
+        Err(e) => {
+            warn!("encode trace info failed, e: {:?}", e);
+            None
+        }
+    }
+}
+
 pub struct Trace {}
 
 #[async_trait::async_trait]
 impl Operation for Trace {
+    // GET <endpoint>/<admin-API>/trace?s3=true&internal=true&storage=true&scanner=true&err=true&threshold=<duration>
     async fn call(&self, req: S3Request<Body>, _params: Params<'_, '_>) -> S3Result<S3Response<(StatusCode, Body)>> {
-        warn!("handle Trace");
+        validate_admin_request(&req, AdminAction::TraceAdminAction).await?;
+
+        let trace_opts = extract_trace_options(&req.uri)?;
 
-        let _trace_opts = extract_trace_options(&req.uri)?;
+        let mut local = GLOBAL_TRACE.subscribe(trace_opts.clone());
 
-        // let (tx, rx) = mpsc::channel(10000);
-        let _perrs = match GLOBAL_Endpoints.get() {
-            Some(ep) => PeerRestClient::new_clients(ep.clone()).await,
-            None => (Vec::new(), Vec::new()),
+        // Events of the other nodes, forwarded until the caller goes away.
+        let (peer_tx, mut peer_rx) = mpsc::channel(1000);
+        let peers = match GLOBAL_Endpoints.get() {
+            Some(ep) => PeerRestClient::new_clients(ep.clone()).await.0,
+            None => Vec::new(),
         };
-        return Err(s3_error!(NotImplemented));
+        for peer in peers.into_iter().flatten() {
+            let opts = trace_opts.clone();
+            let tx = peer_tx.clone();
+            tokio::spawn(async move {
+                if let Err(e) = peer.trace(&opts, tx).await {
+                    warn!("trace from peer {} failed, e: {:?}", peer.host, e);
+                }
+            });
+        }
+        drop(peer_tx);
+
+        let (tx, rx) = mpsc::channel(100);
+        tokio::spawn(async move {
+            let mut keep_alive = interval(KEEP_ALIVE_INTERVAL);
+            let mut peers_done = false;
+            loop {
+                let data = tokio::select! {
+                    _ = tx.closed() => return,
+                    info = local.recv() => match info {
+                        Some(info) => encode_trace(&info),
+                        None => return,
+                    },
+                    info = peer_rx.recv(), if !peers_done => match info {
+                        Some(info) => encode_trace(&info),
+                        None => {
+                            peers_done = true;
+                            None
+                        }
+                    },
+                    _ = keep_alive.tick() => Some(Bytes::from_static(b" ")),
+                };
+
+                if let Some(data) = data {
+                    if tx.send(Ok(data)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let stream: DynByteStream = Box::pin(TraceStream {
+            inner: ReceiverStream::new(rx),
+        });
+
+        let mut header = HeaderMap::new();
+        header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        Ok(S3Response::with_headers((StatusCode::OK, Body::from(stream)), header))
     }
 }
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use crate::{
-    admin::{router::Operation, utils::has_space_be},
+    admin::{handlers::site_replication::replicate_iam_change, router::Operation, utils::has_space_be},
     auth::{check_key_valid, get_condition_values, get_session_token},
 };
 use http::{HeaderMap, StatusCode};
@@ -25,6 +25,7 @@ use rustfs_iam::{
 };
 use rustfs_madmin::{
     AccountStatus, AddOrUpdateUserReq, IAMEntities, IAMErrEntities, IAMErrEntity, IAMErrPolicyEntity,
+    site_replication::SRIAMItem,
     user::{ImportIAMResult, SRSessionPolicy, SRSvcAccCreate},
 };
 use rustfs_policy::policy::{
@@ -144,6 +145,13 @@ impl Operation for AddUser {
             .await
             .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("create_user err {e}")))?;
 
+        replicate_iam_change(SRIAMItem::User {
+            access_key: ak.to_string(),
+            secret_key: Some(args.secret_key.clone()),
+            status: args.status.as_ref().to_string(),
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -186,11 +194,18 @@ impl Operation for SetUserStatus {
             return Err(s3_error!(InvalidRequest, "iam not init"));
         };
 
+        let status_name = status.as_ref().to_string();
         iam_store
             .set_user_status(ak, status)
             .await
             .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("set_user_status err {e}")))?;
 
+        replicate_iam_change(SRIAMItem::UserStatus {
+            access_key: ak.to_string(),
+            status: status_name,
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -308,6 +323,13 @@ impl Operation for RemoveUser {
             .await
             .map_err(|e| S3Error::with_message(S3ErrorCode::InternalError, format!("delete_user err {e}")))?;
 
+        replicate_iam_change(SRIAMItem::User {
+            access_key: ak.to_string(),
+            secret_key: None,
+            status: String::new(),
+        })
+        .await?;
+
         let mut header = HeaderMap::new();
         header.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         header.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -20,9 +20,9 @@ pub mod utils;
 
 // use ecstore::global::{is_dist_erasure, is_erasure};
 use handlers::{
-    bucket_meta, group, policies, pools, rebalance,
+    bucket_meta, config, group, health, inspect, kms, ldap, policies, pools, quota, rebalance, replication, service,
     service_account::{AddServiceAccount, DeleteServiceAccount, InfoServiceAccount, ListServiceAccount, UpdateServiceAccount},
-    sts, tier, user,
+    site_replication, sts, tier, trace, user,
 };
 
 use handlers::{GetReplicationMetricsHandler, ListRemoteTargetHandler, RemoveRemoteTargetHandler, SetRemoteTargetHandler};
@@ -41,11 +41,12 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
 
     register_rpc_route(&mut r)?;
     register_user_route(&mut r)?;
+    register_health_route(&mut r)?;
 
     r.insert(
         Method::POST,
         format!("{}{}", ADMIN_PREFIX, "/v3/service").as_str(),
-        AdminOperation(&handlers::ServiceHandle {}),
+        AdminOperation(&service::ServiceHandle {}),
     )?;
     // 1
     r.insert(
@@ -56,12 +57,12 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/v3/inspect-data").as_str(),
-        AdminOperation(&handlers::InspectDataHandler {}),
+        AdminOperation(&inspect::InspectDataHandler {}),
     )?;
     r.insert(
         Method::POST,
         format!("{}{}", ADMIN_PREFIX, "/v3/inspect-data").as_str(),
-        AdminOperation(&handlers::InspectDataHandler {}),
+        AdminOperation(&inspect::InspectDataHandler {}),
     )?;
     // 1
     r.insert(
@@ -80,6 +81,11 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         format!("{}{}", ADMIN_PREFIX, "/v3/metrics").as_str(),
         AdminOperation(&handlers::MetricsHandler {}),
     )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/trace").as_str(),
+        AdminOperation(&trace::Trace {}),
+    )?;
 
     // 1
     r.insert(
@@ -173,6 +179,43 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&tier::ClearTier {}),
     )?;
 
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/status").as_str(),
+        AdminOperation(&kms::KmsStatus {}),
+    )?;
+    // ?key-id=xxx
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key/create").as_str(),
+        AdminOperation(&kms::CreateKmsKey {}),
+    )?;
+    // ?pattern=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key/list").as_str(),
+        AdminOperation(&kms::ListKmsKeys {}),
+    )?;
+    // ?key-id=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/kms/key/status").as_str(),
+        AdminOperation(&kms::KmsKeyStatus {}),
+    )?;
+
+    // ?bucket=xxx
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/set-bucket-quota").as_str(),
+        AdminOperation(&quota::SetBucketQuota {}),
+    )?;
+    // ?bucket=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/get-bucket-quota").as_str(),
+        AdminOperation(&quota::GetBucketQuota {}),
+    )?;
+
     r.insert(
         Method::GET,
         format!("{}{}", ADMIN_PREFIX, "/export-bucket-metadata").as_str(),
@@ -209,9 +252,149 @@ pub fn make_admin_route(console_enabled: bool) -> std::io::Result<impl S3Route>
         AdminOperation(&RemoveRemoteTargetHandler {}),
     )?;
 
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/add").as_str(),
+        AdminOperation(&site_replication::SiteReplicationAdd {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/remove").as_str(),
+        AdminOperation(&site_replication::SiteReplicationRemove {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/info").as_str(),
+        AdminOperation(&site_replication::SiteReplicationInfo {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/status").as_str(),
+        AdminOperation(&site_replication::SiteReplicationStatus {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/state").as_str(),
+        AdminOperation(&site_replication::SRPeerState {}),
+    )?;
+    // ?key=<sub_sys[:target]>
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/get-config-kv").as_str(),
+        AdminOperation(&config::GetConfigKV {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/set-config-kv").as_str(),
+        AdminOperation(&config::SetConfigKV {}),
+    )?;
+    r.insert(
+        Method::DELETE,
+        format!("{}{}", ADMIN_PREFIX, "/v3/del-config-kv").as_str(),
+        AdminOperation(&config::DelConfigKV {}),
+    )?;
+    // ?subSys=xxx&key=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/help-config-kv").as_str(),
+        AdminOperation(&config::HelpConfigKV {}),
+    )?;
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/config").as_str(),
+        AdminOperation(&config::GetConfig {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/config").as_str(),
+        AdminOperation(&config::SetConfig {}),
+    )?;
+    // ?count=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/list-config-history-kv").as_str(),
+        AdminOperation(&config::ListConfigHistoryKV {}),
+    )?;
+    // ?restoreId=xxx
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/restore-config-history-kv").as_str(),
+        AdminOperation(&config::RestoreConfigHistoryKV {}),
+    )?;
+
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/join").as_str(),
+        AdminOperation(&site_replication::SRPeerJoin {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/remove").as_str(),
+        AdminOperation(&site_replication::SRPeerRemove {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/bucket-ops").as_str(),
+        AdminOperation(&site_replication::SRPeerBucketOps {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/bucket-meta").as_str(),
+        AdminOperation(&site_replication::SRPeerBucketMeta {}),
+    )?;
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/site-replication/peer/iam-item").as_str(),
+        AdminOperation(&site_replication::SRPeerIAMItem {}),
+    )?;
+
+    // ?bucket=xxx&arn=xxx&older-than=xxx
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/replication/resync/start").as_str(),
+        AdminOperation(&replication::ReplicationResyncStart {}),
+    )?;
+    // ?bucket=xxx&arn=xxx
+    r.insert(
+        Method::PUT,
+        format!("{}{}", ADMIN_PREFIX, "/v3/replication/resync/cancel").as_str(),
+        AdminOperation(&replication::ReplicationResyncCancel {}),
+    )?;
+    // ?bucket=xxx&arn=xxx
+    r.insert(
+        Method::GET,
+        format!("{}{}", ADMIN_PREFIX, "/v3/replication/resync/status").as_str(),
+        AdminOperation(&replication::ReplicationResyncStatus {}),
+    )?;
+
     Ok(r)
 }
 
+/// health router, probes use both GET and HEAD
+fn register_health_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()> {
+    for prefix in [health::HEALTH_PREFIX, health::HEALTH_ALIAS_PREFIX] {
+        for method in [Method::GET, Method::HEAD] {
+            r.insert(
+                method.clone(),
+                format!("{}{}", prefix, "/live").as_str(),
+                AdminOperation(&health::HealthLiveHandler {}),
+            )?;
+            r.insert(
+                method.clone(),
+                format!("{}{}", prefix, "/ready").as_str(),
+                AdminOperation(&health::HealthReadyHandler {}),
+            )?;
+            r.insert(
+                method,
+                format!("{}{}", prefix, "/cluster").as_str(),
+                AdminOperation(&health::HealthClusterHandler {}),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 /// user router
 fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()> {
     // 1
@@ -364,5 +547,17 @@ fn register_user_route(r: &mut S3Router<AdminOperation>) -> std::io::Result<()>
         AdminOperation(&policies::SetPolicyForUserOrGroup {}),
     )?;
 
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/idp/ldap/policy/attach").as_str(),
+        AdminOperation(&ldap::AttachPolicyLdap {}),
+    )?;
+
+    r.insert(
+        Method::POST,
+        format!("{}{}", ADMIN_PREFIX, "/v3/idp/ldap/policy/detach").as_str(),
+        AdminOperation(&ldap::DetachPolicyLdap {}),
+    )?;
+
     Ok(())
 }
@@ -33,10 +33,16 @@ use tracing::error;
 
 use crate::admin::ADMIN_PREFIX;
 use crate::admin::console;
+use crate::admin::handlers::health::is_health_path;
 use crate::admin::rpc::RPC_PREFIX;
 
 const CONSOLE_PREFIX: &str = "/rustfs/console";
 
+/// Whether the path is served by the admin, rpc, console or health routes instead of the S3 API.
+pub fn is_admin_path(path: &str) -> bool {
+    path.starts_with(ADMIN_PREFIX) || path.starts_with(RPC_PREFIX) || path.starts_with(CONSOLE_PREFIX) || is_health_path(path)
+}
+
 pub struct S3Router<T> {
     router: Router<T>,
     console_enabled: bool,
@@ -101,7 +107,7 @@ where
             }
         }
 
-        uri.path().starts_with(ADMIN_PREFIX) || uri.path().starts_with(RPC_PREFIX) || uri.path().starts_with(CONSOLE_PREFIX)
+        is_admin_path(uri.path())
     }
 
     async fn call(&self, req: S3Request<Body>) -> S3Result<S3Response<Body>> {
@@ -138,6 +144,11 @@ where
             return Ok(());
         }
 
+        // Health checks are answered to anonymous probes
+        if is_health_path(req.uri.path()) {
+            return Ok(());
+        }
+
         // Check RPC signature verification
         if req.uri.path().starts_with(RPC_PREFIX) {
             // Skip signature verification for HEAD requests (health checks)
@@ -150,6 +161,12 @@ where
             return Ok(());
         }
 
+        // STS federation requests are anonymous, the handler only serves the federation actions to them
+        // and authenticates each one
+        if req.method == Method::POST && req.uri.path() == "/" {
+            return Ok(());
+        }
+
         // For non-RPC admin requests, check credentials
         match req.credentials {
             Some(_) => Ok(()),
@@ -21,6 +21,7 @@ use hyper::Method;
 use matchit::Params;
 use rustfs_ecstore::disk::DiskAPI;
 use rustfs_ecstore::disk::WalkDirOptions;
+use rustfs_ecstore::rpc::verify_rpc_body;
 use rustfs_ecstore::set_disk::DEFAULT_READ_BUFFER_SIZE;
 use rustfs_ecstore::store::find_local_disk;
 use rustfs_utils::net::bytes_stream;
@@ -148,6 +149,7 @@ impl Operation for WalkDir {
                 return Err(s3_error!(InvalidRequest, "get body failed"));
             }
         };
+        verify_rpc_body(&req.headers, &body).map_err(|e| s3_error!(AccessDenied, "{}", e))?;
 
         // let body_bytes = decrypt_data(input_cred.secret_key.expose().as_bytes(), &body)
         //     .map_err(|e| S3Error::with_message(S3ErrorCode::InvalidArgument, format!("decrypt_data err {}", e)))?;
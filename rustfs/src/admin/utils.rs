@@ -12,6 +12,48 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
+use rustfs_policy::policy::Args;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::{Body, S3Request, S3Result, s3_error};
+
+use crate::auth::{check_key_valid, get_condition_values, get_session_token};
+
 pub fn has_space_be(s: &str) -> bool {
     s.trim().len() != s.len()
 }
+
+/// Checks that the credentials of an admin request allow `action`.
+pub async fn validate_admin_request(req: &S3Request<Body>, action: AdminAction) -> S3Result<()> {
+    let Some(input_cred) = &req.credentials else {
+        return Err(s3_error!(InvalidRequest, "get cred failed"));
+    };
+
+    let (cred, owner) =
+        check_key_valid(get_session_token(&req.uri, &req.headers).unwrap_or_default(), &input_cred.access_key).await?;
+
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err(s3_error!(InvalidRequest, "iam not init"));
+    };
+
+    let conditions = get_condition_values(&req.headers, &cred);
+    if !iam_store
+        .is_allowed(&Args {
+            account: &cred.access_key,
+            groups: &cred.groups,
+            action: Action::AdminAction(action),
+            bucket: "",
+            conditions: &conditions,
+            is_owner: owner,
+            object: "",
+            claims: cred.claims.as_ref().unwrap_or(&HashMap::new()),
+            deny_only: false,
+        })
+        .await
+    {
+        return Err(s3_error!(AccessDenied, "access denied"));
+    }
+
+    Ok(())
+}
@@ -81,6 +81,77 @@ pub struct Opt {
 
     #[arg(long, env = "RUSTFS_REGION")]
     pub region: Option<String>,
+
+    /// Root key of the built-in KMS used for server-side encryption, in the form <key-id>:<base64 encoded 32 bytes>.
+    #[arg(long, env = "RUSTFS_KMS_SECRET_KEY")]
+    pub kms_secret_key: Option<String>,
+
+    /// Directory where the built-in KMS stores the keys it creates, encrypted by the root key.
+    /// Only for single node deployments, by default the keys are stored in the cluster config.
+    #[arg(long, env = "RUSTFS_KMS_KEY_DIR")]
+    pub kms_key_dir: Option<String>,
+
+    /// Authentication of the Prometheus scrape endpoints, "jwt" requires a bearer token and "public" none.
+    #[arg(long, default_value_t = String::from("jwt"), value_parser = ["jwt", "public"], env = "RUSTFS_PROMETHEUS_AUTH_TYPE")]
+    pub prometheus_auth_type: String,
+
+    /// Discovery document of the OpenID Connect provider used by STS AssumeRoleWithWebIdentity, e.g.
+    /// https://idp.example.com/.well-known/openid-configuration.
+    #[arg(long, env = "RUSTFS_IDENTITY_OPENID_CONFIG_URL")]
+    pub identity_openid_config_url: Option<String>,
+
+    /// Local JWKS file with the signing keys of the OpenID Connect provider, used instead of the published keys.
+    #[arg(long, env = "RUSTFS_IDENTITY_OPENID_JWKS_FILE")]
+    pub identity_openid_jwks_file: Option<String>,
+
+    /// Expected issuer of OpenID Connect tokens, defaults to the issuer of the discovery document. Required with a JWKS file.
+    #[arg(long, env = "RUSTFS_IDENTITY_OPENID_ISSUER")]
+    pub identity_openid_issuer: Option<String>,
+
+    /// Client ID OpenID Connect tokens must be issued to.
+    #[arg(long, env = "RUSTFS_IDENTITY_OPENID_CLIENT_ID")]
+    pub identity_openid_client_id: Option<String>,
+
+    /// Claim of OpenID Connect tokens naming the policies of the identity.
+    #[arg(long, default_value_t = rustfs_iam::openid::DEFAULT_CLAIM_NAME.to_string(), env = "RUSTFS_IDENTITY_OPENID_CLAIM_NAME")]
+    pub identity_openid_claim_name: String,
+
+    /// LDAP server used by STS AssumeRoleWithLDAPIdentity, e.g. ldap://ldap.example.com:389.
+    #[arg(long, env = "RUSTFS_IDENTITY_LDAP_SERVER_ADDR")]
+    pub identity_ldap_server_addr: Option<String>,
+
+    /// DN of the account the server binds with to search LDAP users and groups.
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_LOOKUP_BIND_DN")]
+    pub identity_ldap_lookup_bind_dn: String,
+
+    /// Password of the LDAP lookup account.
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_LOOKUP_BIND_PASSWORD")]
+    pub identity_ldap_lookup_bind_password: String,
+
+    /// Base DN of the LDAP user search.
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_USER_DN_SEARCH_BASE_DN")]
+    pub identity_ldap_user_dn_search_base_dn: String,
+
+    /// Filter of the LDAP user search, %s is replaced by the username, e.g. (uid=%s).
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_USER_DN_SEARCH_FILTER")]
+    pub identity_ldap_user_dn_search_filter: String,
+
+    /// Base DN of the LDAP group search.
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_GROUP_SEARCH_BASE_DN")]
+    pub identity_ldap_group_search_base_dn: String,
+
+    /// Filter of the LDAP group search, %s is replaced by the username and %d by the user DN, e.g.
+    /// (&(objectclass=groupOfNames)(member=%d)).
+    #[arg(long, default_value_t = String::new(), env = "RUSTFS_IDENTITY_LDAP_GROUP_SEARCH_FILTER")]
+    pub identity_ldap_group_search_filter: String,
+
+    /// Upgrade ldap:// connections with StartTLS.
+    #[arg(long, default_value_t = false, env = "RUSTFS_IDENTITY_LDAP_START_TLS")]
+    pub identity_ldap_start_tls: bool,
+
+    /// Do not verify the certificate of the LDAP server.
+    #[arg(long, default_value_t = false, env = "RUSTFS_IDENTITY_LDAP_TLS_SKIP_VERIFY")]
+    pub identity_ldap_tls_skip_verify: bool,
 }
 
 // lazy_static::lazy_static! {
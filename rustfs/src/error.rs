@@ -12,9 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use http::StatusCode;
 use rustfs_ecstore::error::StorageError;
 use s3s::{S3Error, S3ErrorCode};
 
+/// Error code returned when a write would exceed the hard quota of a bucket.
+pub const BUCKET_QUOTA_EXCEEDED: &str = "XMinioAdminBucketQuotaExceeded";
+
 #[derive(Debug)]
 pub struct ApiError {
     pub code: S3ErrorCode,
@@ -45,7 +49,11 @@ impl ApiError {
 
 impl From<ApiError> for S3Error {
     fn from(err: ApiError) -> Self {
+        let quota_exceeded = err.code == S3ErrorCode::Custom(BUCKET_QUOTA_EXCEEDED.into());
         let mut s3e = S3Error::with_message(err.code, err.message);
+        if quota_exceeded {
+            s3e.set_status_code(StatusCode::BAD_REQUEST);
+        }
         if let Some(source) = err.source {
             s3e.set_source(source);
         }
@@ -81,6 +89,16 @@ impl From<StorageError> for ApiError {
             StorageError::DataMovementOverwriteErr(_, _, _) => S3ErrorCode::InvalidArgument,
             StorageError::ObjectExistsAsDirectory(_, _) => S3ErrorCode::InvalidArgument,
             StorageError::InvalidPart(_, _, _) => S3ErrorCode::InvalidPart,
+            StorageError::KmsNotConfigured => S3ErrorCode::NotImplemented,
+            StorageError::InvalidEncryptionParameters(_) => S3ErrorCode::InvalidArgument,
+            StorageError::ObjectEncryptionKeyRequired(_, _) => S3ErrorCode::InvalidRequest,
+            StorageError::ObjectEncryptionKeyMismatch(_, _) => S3ErrorCode::AccessDenied,
+            StorageError::KmsKeyNotFound(_) => S3ErrorCode::InvalidArgument,
+            StorageError::KmsKeyExists(_) => S3ErrorCode::InvalidArgument,
+            StorageError::BucketQuotaExceeded(_) => S3ErrorCode::Custom(BUCKET_QUOTA_EXCEEDED.into()),
+            StorageError::PreconditionFailed(_, _) => S3ErrorCode::PreconditionFailed,
+            StorageError::InvalidObjectState(_, _) => S3ErrorCode::InvalidObjectState,
+            StorageError::Io(e) if e.get_ref().is_some_and(|e| e.is::<rustfs_rio::ChecksumMismatch>()) => S3ErrorCode::BadDigest,
             _ => S3ErrorCode::InternalError,
         };
 
@@ -192,6 +210,18 @@ mod tests {
         assert!(downcast_storage_error.is_some());
     }
 
+    #[test]
+    fn test_api_error_from_checksum_mismatch() {
+        let mismatch = rustfs_rio::ChecksumMismatch {
+            want: "AAAAAA==".to_string(),
+            got: "BBBBBB==".to_string(),
+        };
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, mismatch);
+        let api_error: ApiError = StorageError::from(io_error).into();
+
+        assert_eq!(api_error.code, S3ErrorCode::BadDigest);
+    }
+
     #[test]
     fn test_api_error_from_storage_error_mappings() {
         let test_cases = vec![
@@ -24,7 +24,10 @@ mod update;
 mod version;
 
 // Ensure the correct path for parse_license is imported
-use crate::server::{SHUTDOWN_TIMEOUT, ServiceState, ServiceStateManager, ShutdownSignal, start_http_server, wait_for_shutdown};
+use crate::server::{
+    SHUTDOWN_TIMEOUT, ServiceState, ServiceStateManager, freeze_services, restart_process, start_http_server, unfreeze_services,
+    wait_for_shutdown,
+};
 use chrono::Datelike;
 use clap::Parser;
 use license::init_license;
@@ -32,11 +35,15 @@ use rustfs_ahm::scanner::data_scanner::ScannerConfig;
 use rustfs_ahm::{Scanner, create_ahm_services_cancel_token, shutdown_ahm_services};
 use rustfs_common::globals::set_global_addr;
 use rustfs_config::DEFAULT_DELIMITER;
+use rustfs_ecstore::bucket::logging::{GLOBAL_ACCESS_LOG, init_access_log};
 use rustfs_ecstore::bucket::metadata_sys::init_bucket_metadata_sys;
 use rustfs_ecstore::cmd::bucket_replication::init_bucket_replication_pool;
+use rustfs_ecstore::cmd::site_replication::GLOBAL_SITE_REPLICATION_SYS;
 use rustfs_ecstore::config as ecconfig;
 use rustfs_ecstore::config::GLOBAL_ConfigSys;
-use rustfs_ecstore::config::GLOBAL_ServerConfig;
+use rustfs_ecstore::config::{get_global_server_config, scanner as scanner_config, subscribe_config_changes};
+use rustfs_ecstore::kms::{ClusterKeyStore, DirKeyStore, KeyStore, LocalKms, MasterKey};
+use rustfs_ecstore::service_signal::{ServiceSignal, subscribe_service_signal};
 use rustfs_ecstore::store_api::BucketOptions;
 use rustfs_ecstore::{
     StorageAPI,
@@ -49,9 +56,14 @@ use rustfs_ecstore::{
     update_erasure_type,
 };
 use rustfs_iam::init_iam_sys;
+use rustfs_iam::ldap::{LdapConfig, LdapProvider, init_ldap_provider};
+use rustfs_iam::openid::{OpenIdConfig, OpenIdProvider, init_openid_provider};
 use rustfs_obs::{init_obs, set_global_guard};
 use rustfs_utils::net::parse_and_resolve_address;
 use std::io::{Error, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, error, info, instrument, warn};
 
 #[cfg(all(target_os = "linux", target_env = "gnu"))]
@@ -105,6 +117,44 @@ async fn run(opt: config::Opt) -> Result<()> {
     // Set up AK and SK
     rustfs_ecstore::global::init_global_action_cred(Some(opt.access_key.clone()), Some(opt.secret_key.clone()));
 
+    // The KMS is set up once the object layer is ready, only validate its root key here
+    let kms_root = opt
+        .kms_secret_key
+        .as_deref()
+        .map(MasterKey::parse)
+        .transpose()
+        .map_err(Error::other)?;
+
+    // Set up the OpenID Connect provider for STS web identity federation
+    if opt.identity_openid_config_url.is_some() || opt.identity_openid_jwks_file.is_some() {
+        let provider = OpenIdProvider::new(OpenIdConfig {
+            config_url: opt.identity_openid_config_url.clone(),
+            jwks_file: opt.identity_openid_jwks_file.as_ref().map(PathBuf::from),
+            issuer: opt.identity_openid_issuer.clone(),
+            client_id: opt.identity_openid_client_id.clone().unwrap_or_default(),
+            claim_name: opt.identity_openid_claim_name.clone(),
+        })
+        .map_err(Error::other)?;
+        init_openid_provider(Arc::new(provider));
+    }
+
+    // Set up the LDAP provider for STS LDAP identity federation
+    if let Some(server_addr) = &opt.identity_ldap_server_addr {
+        let provider = LdapProvider::new(LdapConfig {
+            server_addr: server_addr.clone(),
+            lookup_bind_dn: opt.identity_ldap_lookup_bind_dn.clone(),
+            lookup_bind_password: opt.identity_ldap_lookup_bind_password.clone(),
+            user_dn_search_base_dn: opt.identity_ldap_user_dn_search_base_dn.clone(),
+            user_dn_search_filter: opt.identity_ldap_user_dn_search_filter.clone(),
+            group_search_base_dn: opt.identity_ldap_group_search_base_dn.clone(),
+            group_search_filter: opt.identity_ldap_group_search_filter.clone(),
+            start_tls: opt.identity_ldap_start_tls,
+            tls_skip_verify: opt.identity_ldap_tls_skip_verify,
+        })
+        .map_err(Error::other)?;
+        init_ldap_provider(Arc::new(provider));
+    }
+
     set_global_rustfs_port(server_port);
 
     set_global_addr(&opt.address).await;
@@ -137,6 +187,10 @@ async fn run(opt: config::Opt) -> Result<()> {
         }
     }
 
+    // Subscribe before serving requests so that no service signal from the admin API is missed
+    let mut service_signal_rx = subscribe_service_signal();
+    let config_change_rx = subscribe_config_changes();
+
     let state_manager = ServiceStateManager::new();
     // Update service status to Starting
     state_manager.update(ServiceState::Starting);
@@ -158,6 +212,17 @@ async fn run(opt: config::Opt) -> Result<()> {
     // config system configuration
     GLOBAL_ConfigSys.init(store.clone()).await?;
 
+    // Set up the built-in KMS for server-side encryption, its keys live in the cluster config
+    // unless a local key directory is given.
+    if let Some(root) = kms_root {
+        let key_store: Arc<dyn KeyStore> = match &opt.kms_key_dir {
+            Some(dir) => Arc::new(DirKeyStore::new(PathBuf::from(dir)).await.map_err(Error::other)?),
+            None => Arc::new(ClusterKeyStore),
+        };
+        let kms = LocalKms::new(root, Some(key_store)).await.map_err(Error::other)?;
+        rustfs_ecstore::kms::init_global_kms(Arc::new(kms));
+    }
+
     // Initialize event notifier
     init_event_notifier().await;
 
@@ -185,11 +250,21 @@ async fn run(opt: config::Opt) -> Result<()> {
     // init_data_scanner().await;
     // init_auto_heal().await;
     let _ = create_ahm_services_cancel_token();
-    let scanner = Scanner::new(Some(ScannerConfig::default()));
+    let scanner = Arc::new(Scanner::new(Some(ScannerConfig {
+        scan_interval: scanner_cycle().unwrap_or(ScannerConfig::default().scan_interval),
+        ..Default::default()
+    })));
     scanner.start().await?;
+    init_config_change_listener(config_change_rx, scanner.clone());
     print_server_info();
     init_bucket_replication_pool().await;
 
+    if let Err(err) = GLOBAL_SITE_REPLICATION_SYS.init(store.clone()).await {
+        error!("site replication init failed: {:?}", err);
+    }
+
+    init_access_log();
+
     // Async update check (optional)
     tokio::spawn(async {
         use crate::update::{UpdateCheckError, check_updates};
@@ -224,19 +299,40 @@ async fn run(opt: config::Opt) -> Result<()> {
 
     // Perform hibernation for 1 second
     tokio::time::sleep(SHUTDOWN_TIMEOUT).await;
-    // listen to the shutdown signal
-    match wait_for_shutdown().await {
-        #[cfg(unix)]
-        ShutdownSignal::CtrlC | ShutdownSignal::Sigint | ShutdownSignal::Sigterm => {
-            handle_shutdown(&state_manager, &shutdown_tx).await;
-        }
-        #[cfg(not(unix))]
-        ShutdownSignal::CtrlC => {
-            handle_shutdown(&state_manager, &shutdown_tx).await;
+    // listen to the shutdown signal and the service signals of the admin service API
+    let shutdown_signal = wait_for_shutdown();
+    tokio::pin!(shutdown_signal);
+    let mut restart = false;
+    loop {
+        tokio::select! {
+            signal = &mut shutdown_signal => {
+                info!("Received shutdown signal: {:?}", signal);
+                handle_shutdown(&state_manager, &shutdown_tx).await;
+                break;
+            }
+            Ok(signal) = service_signal_rx.recv() => {
+                info!("Received service signal: {:?}", signal);
+                match signal {
+                    ServiceSignal::Restart | ServiceSignal::Stop => {
+                        restart = signal == ServiceSignal::Restart;
+                        handle_shutdown(&state_manager, &shutdown_tx).await;
+                        break;
+                    }
+                    ServiceSignal::Freeze => {
+                        tokio::spawn(freeze_services());
+                    }
+                    ServiceSignal::Unfreeze => unfreeze_services(),
+                    // Config reloads are applied by the node RPC and never published here
+                    ServiceSignal::ReloadDynamic => {}
+                }
+            }
         }
     }
 
     info!("server is stopped state: {:?}", state_manager.current_state());
+    if restart {
+        return Err(restart_process());
+    }
     Ok(())
 }
 
@@ -257,6 +353,9 @@ async fn handle_shutdown(state_manager: &ServiceStateManager, shutdown_tx: &toki
     // Stop the notification system
     shutdown_event_notifier().await;
 
+    // Deliver the access logs still buffered
+    GLOBAL_ACCESS_LOG.flush().await;
+
     info!("Server is stopping...");
     let _ = shutdown_tx.send(());
 
@@ -273,8 +372,8 @@ pub(crate) async fn init_event_notifier() {
     info!("Initializing event notifier...");
 
     // 1. Get the global configuration loaded by ecstore
-    let server_config = match GLOBAL_ServerConfig.get() {
-        Some(config) => config.clone(), // Clone the config to pass ownership
+    let server_config = match get_global_server_config() {
+        Some(config) => config,
         None => {
             error!("Event notifier initialization failed: Global server config not loaded.");
             return;
@@ -307,6 +406,63 @@ pub(crate) async fn init_event_notifier() {
     });
 }
 
+/// Applies the config subsystems changed through the admin config API that are owned by the
+/// server process rather than ecstore: the notification targets and the scanner cycle.
+fn init_config_change_listener(mut rx: tokio::sync::broadcast::Receiver<String>, scanner: Arc<Scanner>) {
+    tokio::spawn(async move {
+        loop {
+            let sub_sys = match rx.recv().await {
+                Ok(sub_sys) => sub_sys,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            info!("config of sub-system {} changed", sub_sys);
+            match sub_sys.as_str() {
+                rustfs_config::notify::NOTIFY_WEBHOOK_SUB_SYS | rustfs_config::notify::NOTIFY_MQTT_SUB_SYS => {
+                    reload_event_notifier().await
+                }
+                scanner_config::SCANNER_SUB_SYS => {
+                    if let Some(cycle) = scanner_cycle() {
+                        scanner.set_scan_interval(cycle).await;
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Returns the scan cycle of the configured scanner speed.
+fn scanner_cycle() -> Option<Duration> {
+    let cfg = get_global_server_config()?;
+    let kvs = cfg
+        .get_value(scanner_config::SCANNER_SUB_SYS, DEFAULT_DELIMITER)
+        .unwrap_or_default();
+    match scanner_config::lookup_config(&kvs) {
+        Ok(cfg) => Some(cfg.cycle),
+        Err(e) => {
+            error!("invalid scanner config: {}", e);
+            None
+        }
+    }
+}
+
+/// Reloads the notification targets from the current server config
+async fn reload_event_notifier() {
+    let Some(system) = rustfs_notify::notification_system() else {
+        init_event_notifier().await;
+        return;
+    };
+    let Some(server_config) = get_global_server_config() else {
+        return;
+    };
+
+    if let Err(e) = system.reload_config(server_config).await {
+        error!("Failed to reload event notifier configuration: {}", e);
+    }
+}
+
 /// Shuts down the event notifier system gracefully
 pub async fn shutdown_event_notifier() {
     info!("Shutting down event notifier system...");
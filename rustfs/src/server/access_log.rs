@@ -0,0 +1,348 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::admin::router::is_admin_path;
+use chrono::Utc;
+use http::{HeaderMap, Method, Request as HttpRequest, Response, header};
+use rustfs_ecstore::bucket::logging::{AccessLogRecord, GLOBAL_ACCESS_LOG};
+use rustfs_utils::net::strip_port;
+use s3s::host::{MultiDomain, S3Host};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Prefix of the paths served by the node gRPC service.
+const GRPC_PATH_PREFIX: &str = "/node_service.";
+
+/// Query parameters naming a sub-resource, with the resource logged in the operation field.
+const SUB_RESOURCES: &[(&str, &str)] = &[
+    ("acl", "ACL"),
+    ("attributes", "OBJECT_ATTRIBUTES"),
+    ("cors", "CORS"),
+    ("delete", "MULTI_OBJECT_DELETE"),
+    ("encryption", "ENCRYPTION"),
+    ("legal-hold", "LEGAL_HOLD"),
+    ("lifecycle", "LIFECYCLE"),
+    ("location", "LOCATION"),
+    ("logging", "LOGGING_STATUS"),
+    ("notification", "NOTIFICATION"),
+    ("object-lock", "OBJECT_LOCK_CONFIGURATION"),
+    ("policy", "BUCKETPOLICY"),
+    ("policyStatus", "POLICY_STATUS"),
+    ("replication", "REPLICATION"),
+    ("restore", "RESTORE"),
+    ("retention", "RETENTION"),
+    ("select", "SELECT"),
+    ("tagging", "TAGGING"),
+    ("versioning", "VERSIONING"),
+    ("versions", "VERSIONS"),
+    ("website", "WEBSITE"),
+];
+
+/// Request extension the S3 access check fills with the access key the request was authenticated with.
+///
+/// The requester of a log record comes only from here, so unauthenticated requests are logged as anonymous.
+#[derive(Clone, Default)]
+pub struct AuthenticatedRequester(Arc<OnceLock<String>>);
+
+impl AuthenticatedRequester {
+    pub fn set(&self, access_key: &str) {
+        let _ = self.0.set(access_key.to_owned());
+    }
+
+    pub fn get(&self) -> Option<String> {
+        self.0.get().cloned()
+    }
+}
+
+/// Layer recording S3 requests for the server access logs of their bucket.
+#[derive(Clone)]
+pub struct AccessLogLayer {
+    host: Option<Arc<MultiDomain>>,
+    client: Option<SocketAddr>,
+}
+
+impl AccessLogLayer {
+    /// `host` resolves the bucket of virtual-hosted-style requests when `server_domains` are configured.
+    pub fn new(host: Option<MultiDomain>) -> Self {
+        Self {
+            host: host.map(Arc::new),
+            client: None,
+        }
+    }
+
+    /// Returns the layer for a connection from `client`.
+    pub fn with_client(&self, client: Option<SocketAddr>) -> Self {
+        Self {
+            host: self.host.clone(),
+            client,
+        }
+    }
+}
+
+impl<S> Layer<S> for AccessLogLayer {
+    type Service = AccessLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessLogService {
+            inner,
+            host: self.host.clone(),
+            client: self.client,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AccessLogService<S> {
+    inner: S,
+    host: Option<Arc<MultiDomain>>,
+    client: Option<SocketAddr>,
+}
+
+impl<S, ReqBody, ResBody> Service<HttpRequest<ReqBody>> for AccessLogService<S>
+where
+    S: Service<HttpRequest<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<ReqBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        let Some(record) = request_record(&req, self.host.as_deref(), self.client) else {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        };
+        let requester = AuthenticatedRequester::default();
+        req.extensions_mut().insert(requester.clone());
+
+        let start = Instant::now();
+        Box::pin(async move {
+            let res = inner.call(req).await.map_err(Into::into);
+
+            if let Ok(resp) = &res {
+                let mut record = record;
+                record.requester = requester.get();
+                let latency = start.elapsed().as_millis() as u64;
+                record.http_status = resp.status().as_u16();
+                record.total_time_ms = latency;
+                record.turn_around_time_ms = Some(latency);
+                record.bytes_sent = content_length(resp.headers());
+                record.version_id = header_value(resp.headers(), "x-amz-version-id");
+                if record.key.is_some() && record.object_size.is_none() {
+                    record.object_size = record.bytes_sent;
+                }
+                GLOBAL_ACCESS_LOG.log(record).await;
+            }
+
+            res
+        })
+    }
+}
+
+/// Builds the record of an S3 request on a bucket, the response fields are filled in once it is served.
+fn request_record<B>(req: &HttpRequest<B>, host: Option<&MultiDomain>, client: Option<SocketAddr>) -> Option<AccessLogRecord> {
+    let path = req.uri().path();
+    if path.starts_with(GRPC_PATH_PREFIX) || is_admin_path(path) {
+        return None;
+    }
+
+    let headers = req.headers();
+    let host_header = header_value(headers, header::HOST.as_str()).or_else(|| req.uri().authority().map(|a| a.to_string()));
+
+    // Virtual-hosted-style requests name the bucket in the host, path-style ones in the first path segment
+    let decoded = urlencoding::decode(path)
+        .map(|p| p.into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let virtual_bucket = match (host, &host_header) {
+        (Some(host), Some(host_header)) => host
+            .parse_host_header(strip_port(host_header))
+            .ok()
+            .and_then(|vh| vh.bucket().map(str::to_owned)),
+        _ => None,
+    };
+    let (bucket, key) = match virtual_bucket {
+        Some(bucket) => (bucket, decoded.trim_start_matches('/').to_string()),
+        None => {
+            let mut parts = decoded.trim_start_matches('/').splitn(2, '/');
+            (parts.next().unwrap_or_default().to_string(), parts.next().unwrap_or_default().to_string())
+        }
+    };
+    if bucket.is_empty() {
+        return None;
+    }
+    let key = (!key.is_empty()).then_some(key);
+
+    let query = req.uri().query();
+    let (signature_version, auth_type) = signature(headers, query);
+    let request_uri = match query {
+        Some(query) => format!("{} {}?{} {:?}", req.method(), path, query, req.version()),
+        None => format!("{} {} {:?}", req.method(), path, req.version()),
+    };
+    let object_size = if req.method() == Method::PUT || req.method() == Method::POST {
+        header_value(headers, "x-amz-decoded-content-length")
+            .and_then(|v| v.parse().ok())
+            .or_else(|| content_length(headers))
+    } else {
+        None
+    };
+
+    Some(AccessLogRecord {
+        bucket,
+        time: Utc::now(),
+        remote_ip: client.map(|addr| addr.ip().to_canonical().to_string()),
+        request_id: uuid::Uuid::new_v4().simple().to_string()[..16].to_uppercase(),
+        operation: operation(req.method(), key.is_some(), query, headers.contains_key("x-amz-copy-source")),
+        key,
+        request_uri,
+        object_size,
+        referer: header_value(headers, header::REFERER.as_str()),
+        user_agent: header_value(headers, header::USER_AGENT.as_str()),
+        signature_version,
+        auth_type,
+        host_header,
+        ..Default::default()
+    })
+}
+
+/// Names the operation like S3 does, such as `REST.GET.OBJECT` or `REST.PUT.VERSIONING`.
+fn operation(method: &Method, has_key: bool, query: Option<&str>, copy: bool) -> String {
+    let params: Vec<&str> = query
+        .map(|q| q.split('&').map(|kv| kv.split('=').next().unwrap_or_default()).collect())
+        .unwrap_or_default();
+    let has = |name: &str| params.contains(&name);
+
+    let resource = if has("uploadId") {
+        if has("partNumber") { "PART" } else { "UPLOAD" }
+    } else if has("uploads") {
+        "UPLOADS"
+    } else if let Some((_, resource)) = SUB_RESOURCES.iter().find(|(name, _)| has(name)) {
+        if has_key && *resource == "TAGGING" {
+            "OBJECT_TAGGING"
+        } else {
+            resource
+        }
+    } else if has_key {
+        "OBJECT"
+    } else {
+        "BUCKET"
+    };
+
+    let method = if copy && *method == Method::PUT {
+        "COPY"
+    } else {
+        method.as_str()
+    };
+    format!("REST.{method}.{resource}")
+}
+
+/// Returns the signature version and authentication type a request claims to be signed with.
+fn signature(headers: &HeaderMap, query: Option<&str>) -> (Option<String>, Option<String>) {
+    if let Some(auth) = header_value(headers, header::AUTHORIZATION.as_str()) {
+        if auth.starts_with("AWS4-HMAC-SHA256") {
+            return (Some("SigV4".to_string()), Some("AuthHeader".to_string()));
+        }
+        if auth.starts_with("AWS ") {
+            return (Some("SigV2".to_string()), Some("AuthHeader".to_string()));
+        }
+    }
+
+    for kv in query.unwrap_or_default().split('&') {
+        match kv.split_once('=').map_or(kv, |(name, _)| name) {
+            "X-Amz-Credential" => return (Some("SigV4".to_string()), Some("QueryString".to_string())),
+            "AWSAccessKeyId" => return (Some("SigV2".to_string()), Some("QueryString".to_string())),
+            _ => {}
+        }
+    }
+
+    (None, None)
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_owned)
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation() {
+        assert_eq!(operation(&Method::GET, true, None, false), "REST.GET.OBJECT");
+        assert_eq!(operation(&Method::GET, false, Some("list-type=2&prefix=a"), false), "REST.GET.BUCKET");
+        assert_eq!(operation(&Method::PUT, false, Some("versioning"), false), "REST.PUT.VERSIONING");
+        assert_eq!(operation(&Method::PUT, true, Some("tagging"), false), "REST.PUT.OBJECT_TAGGING");
+        assert_eq!(operation(&Method::PUT, true, Some("partNumber=1&uploadId=x"), false), "REST.PUT.PART");
+        assert_eq!(operation(&Method::POST, true, Some("uploads"), false), "REST.POST.UPLOADS");
+        assert_eq!(operation(&Method::PUT, true, None, true), "REST.COPY.OBJECT");
+        assert_eq!(operation(&Method::POST, false, Some("delete"), false), "REST.POST.MULTI_OBJECT_DELETE");
+    }
+
+    #[test]
+    fn test_request_record() {
+        let req = HttpRequest::builder()
+            .method(Method::GET)
+            .uri("/bucket/dir/a%20b.txt?versionId=1")
+            .header(
+                header::AUTHORIZATION,
+                "AWS4-HMAC-SHA256 Credential=AKIAEXAMPLE/20240101/us-east-1/s3/aws4_request, SignedHeaders=host, Signature=x",
+            )
+            .body(())
+            .unwrap();
+
+        let record = request_record(&req, None, Some("192.0.2.3:1234".parse().unwrap())).unwrap();
+        assert_eq!(record.bucket, "bucket");
+        assert_eq!(record.key.as_deref(), Some("dir/a b.txt"));
+        // The claimed access key is not trusted, the requester is set once the request is authenticated
+        assert_eq!(record.requester, None);
+        assert_eq!(record.signature_version.as_deref(), Some("SigV4"));
+        assert_eq!(record.remote_ip.as_deref(), Some("192.0.2.3"));
+        assert_eq!(record.operation, "REST.GET.OBJECT");
+        assert_eq!(record.request_uri, "GET /bucket/dir/a%20b.txt?versionId=1 HTTP/1.1");
+
+        // Virtual-hosted-style, the Host header usually carries the port
+        let host = MultiDomain::new(["s3.example.com"]).unwrap();
+        let req = HttpRequest::builder()
+            .uri("/dir/a.txt")
+            .header(header::HOST, "photos.s3.example.com:9000")
+            .body(())
+            .unwrap();
+        let record = request_record(&req, Some(&host), None).unwrap();
+        assert_eq!(record.bucket, "photos");
+        assert_eq!(record.key.as_deref(), Some("dir/a.txt"));
+
+        let req = HttpRequest::builder().uri("/rustfs/admin/v3/info").body(()).unwrap();
+        assert!(request_record(&req, None, None).is_none());
+        let req = HttpRequest::builder().uri("/").body(()).unwrap();
+        assert!(request_record(&req, None, None).is_none());
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the integrity headers of XML request bodies.
+//!
+//! S3 requires `Content-MD5` or a flexible checksum on a few configuration requests, but s3s parses
+//! their XML body before the handler runs. This service buffers those bodies, checks them against
+//! the headers and records the outcome as a [`BodyDigest`] request extension, which the handlers
+//! turn into a BadDigest error, see [`crate::storage::options::verify_content_md5`].
+
+use crate::admin::router::is_admin_path;
+use crate::storage::options::{get_content_checksum, get_content_md5};
+use http::{HeaderMap, HeaderValue, Method, Request as HttpRequest, header::CONTENT_LENGTH};
+use hyper::body::Incoming;
+use rustfs_rio::Checksum;
+use rustfs_utils::HashAlgorithm;
+use rustfs_utils::crypto::hex;
+use s3s::service::S3Service;
+use s3s::{Body, S3Error};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::Service;
+use tracing::debug;
+
+/// Sub-resources whose request body has to carry an integrity header.
+const DIGEST_SUB_RESOURCES: [&str; 4] = ["delete", "retention", "legal-hold", "object-lock"];
+
+/// Larger bodies are not buffered and fail the verification.
+const MAX_DIGEST_BODY_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Result of checking a request body against its integrity headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyDigest {
+    /// The body matches `Content-MD5` and the `x-amz-checksum-*` header that were sent.
+    Verified,
+    /// The body does not match one of the headers.
+    Mismatch,
+}
+
+/// Wraps the S3 service and verifies the body of requests to the sub-resources in [`DIGEST_SUB_RESOURCES`].
+#[derive(Clone)]
+pub struct BodyDigestService {
+    inner: S3Service,
+}
+
+impl BodyDigestService {
+    pub fn new(inner: S3Service) -> Self {
+        Self { inner }
+    }
+}
+
+impl Service<HttpRequest<Incoming>> for BodyDigestService {
+    type Response = http::Response<Body>;
+    type Error = S3Error;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let verify = needs_digest(&req);
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            let mut req = req.map(Body::from);
+            // Bodies that cannot be buffered get no digest and are rejected by the handler.
+            if verify && is_bufferable(req.headers()) {
+                match req.body_mut().store_all_unlimited().await {
+                    Ok(body) => {
+                        let digest = check_digest(req.headers(), &body);
+                        *req.body_mut() = Body::from(body);
+                        req.extensions_mut().insert(digest);
+                    }
+                    Err(e) => debug!("read request body failed: {e}"),
+                }
+            }
+            inner.call(req).await
+        })
+    }
+}
+
+/// Whether the request targets one of [`DIGEST_SUB_RESOURCES`] and sends an integrity header.
+fn needs_digest<B>(req: &HttpRequest<B>) -> bool {
+    if !matches!(*req.method(), Method::POST | Method::PUT) || is_admin_path(req.uri().path()) {
+        return false;
+    }
+    let Some(query) = req.uri().query() else {
+        return false;
+    };
+
+    let sub_resource = query
+        .split('&')
+        .map(|pair| pair.split_once('=').map_or(pair, |(name, _)| name))
+        .any(|name| DIGEST_SUB_RESOURCES.contains(&name));
+
+    sub_resource
+        && (matches!(get_content_md5(req.headers()), Ok(Some(_))) || matches!(get_content_checksum(req.headers()), Ok(Some(_))))
+}
+
+/// Whether the body has a declared length small enough to be buffered.
+fn is_bufferable(headers: &HeaderMap<HeaderValue>) -> bool {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .is_some_and(|len| len <= MAX_DIGEST_BODY_SIZE)
+}
+
+/// Checks `body` against every integrity header of the request.
+fn check_digest(headers: &HeaderMap<HeaderValue>, body: &[u8]) -> BodyDigest {
+    if let Ok(Some(md5)) = get_content_md5(headers) {
+        if hex(HashAlgorithm::Md5.hash_encode(body)) != md5 {
+            return BodyDigest::Mismatch;
+        }
+    }
+
+    if let Ok(Some(want)) = get_content_checksum(headers) {
+        if Checksum::compute(want.checksum_type, body).encoded != want.encoded {
+            return BodyDigest::Mismatch;
+        }
+    }
+
+    BodyDigest::Verified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64_simd::STANDARD;
+
+    fn content_md5(data: &[u8]) -> String {
+        STANDARD.encode_to_string(HashAlgorithm::Md5.hash_encode(data))
+    }
+
+    fn request(method: Method, uri: &str, content_md5: Option<&str>) -> HttpRequest<()> {
+        let mut builder = HttpRequest::builder().method(method).uri(uri);
+        if let Some(md5) = content_md5 {
+            builder = builder.header("content-md5", md5);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[test]
+    fn test_needs_digest() {
+        let md5 = content_md5(b"body");
+
+        assert!(needs_digest(&request(Method::POST, "/bucket?delete", Some(&md5))));
+        assert!(needs_digest(&request(Method::PUT, "/bucket/object?retention&versionId=1", Some(&md5))));
+        assert!(needs_digest(&request(Method::PUT, "/bucket/object?legal-hold=", Some(&md5))));
+        assert!(needs_digest(&request(Method::PUT, "/bucket?object-lock", Some(&md5))));
+
+        // Without an integrity header the handler rejects the request itself.
+        assert!(!needs_digest(&request(Method::POST, "/bucket?delete", None)));
+        assert!(!needs_digest(&request(Method::PUT, "/bucket/object", Some(&md5))));
+        assert!(!needs_digest(&request(Method::GET, "/bucket?object-lock", Some(&md5))));
+        assert!(!needs_digest(&request(Method::PUT, "/bucket/object?deleted", Some(&md5))));
+    }
+
+    #[test]
+    fn test_check_digest() {
+        let body = b"<Delete><Object><Key>a</Key></Object></Delete>";
+
+        let mut headers = HeaderMap::new();
+        headers.insert("content-md5", content_md5(body).parse().unwrap());
+        assert_eq!(check_digest(&headers, body), BodyDigest::Verified);
+        assert_eq!(check_digest(&headers, b"<Delete></Delete>"), BodyDigest::Mismatch);
+
+        // A well-formed digest of another body
+        headers.insert("content-md5", content_md5(b"other").parse().unwrap());
+        assert_eq!(check_digest(&headers, body), BodyDigest::Mismatch);
+
+        let mut headers = HeaderMap::new();
+        let crc = Checksum::compute(rustfs_rio::ChecksumType::Crc32, body);
+        headers.insert("x-amz-checksum-crc32", crc.value().parse().unwrap());
+        assert_eq!(check_digest(&headers, body), BodyDigest::Verified);
+        assert_eq!(check_digest(&headers, b"<Delete></Delete>"), BodyDigest::Mismatch);
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::admin::router::is_admin_path;
+use crate::server::hybrid::HybridBody;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, Method, Request as HttpRequest, Response, StatusCode, header};
+use hyper::body::Incoming;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_utils::net::strip_port;
+use rustfs_utils::string::match_simple;
+use s3s::dto::{CORSConfiguration, CORSRule};
+use s3s::host::{MultiDomain, S3Host};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::debug;
+
+const CORS_FORBIDDEN_MESSAGE: &str = "CORSResponse: This CORS request is not allowed. This is usually because the evaluation of Origin, request method / Access-Control-Request-Method or Access-Control-Request-Headers are not whitelisted by the resource's CORS spec.";
+
+/// CORS layer that evaluates the per-bucket CORS configuration
+#[derive(Clone)]
+pub struct CorsLayer {
+    host: Option<Arc<MultiDomain>>,
+}
+
+impl CorsLayer {
+    /// `host` holds the configured server domains used to resolve virtual-hosted-style buckets.
+    pub fn new(host: Option<MultiDomain>) -> Self {
+        Self {
+            host: host.map(Arc::new),
+        }
+    }
+}
+
+impl<S> Layer<S> for CorsLayer {
+    type Service = CorsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CorsService {
+            inner,
+            host: self.host.clone(),
+        }
+    }
+}
+
+/// Service implementation for bucket CORS handling
+#[derive(Clone)]
+pub struct CorsService<S> {
+    inner: S,
+    host: Option<Arc<MultiDomain>>,
+}
+
+impl<S, RestBody, GrpcBody> Service<HttpRequest<Incoming>> for CorsService<S>
+where
+    S: Service<HttpRequest<Incoming>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    RestBody: Default + From<Bytes> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        // Requests without an Origin header are not cross-origin requests
+        let origin = req
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let (Some(origin), Some(bucket)) = (origin, request_bucket(&req, self.host.as_deref())) else {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        };
+
+        let request_method = req
+            .headers()
+            .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+
+        if req.method() == Method::OPTIONS {
+            if let Some(request_method) = request_method {
+                let request_headers = parse_request_headers(req.headers());
+
+                return Box::pin(async move {
+                    let config = metadata_sys::get_cors_config(&bucket).await.ok().map(|(cfg, _)| cfg);
+                    let Some(rule) = config
+                        .as_ref()
+                        .and_then(|cfg| find_rule(cfg, &origin, &request_method, &request_headers))
+                    else {
+                        debug!(
+                            "CORS preflight rejected, bucket: {}, origin: {}, method: {}",
+                            bucket, origin, request_method
+                        );
+                        return Ok(forbidden_response());
+                    };
+
+                    let mut response = Response::builder()
+                        .status(StatusCode::OK)
+                        .body(HybridBody::Rest {
+                            rest_body: RestBody::default(),
+                        })
+                        .expect("failed to build preflight response");
+
+                    set_preflight_headers(response.headers_mut(), rule, &origin, &request_headers);
+
+                    Ok(response)
+                });
+            }
+        }
+
+        let method = req.method().as_str().to_owned();
+
+        Box::pin(async move {
+            let mut response = inner.call(req).await.map_err(Into::into)?;
+
+            if let Ok((config, _)) = metadata_sys::get_cors_config(&bucket).await {
+                if let Some(rule) = find_rule(&config, &origin, &method, &[]) {
+                    set_cors_headers(response.headers_mut(), rule, &origin);
+                }
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Resolve the bucket addressed by the request, either from the virtual host or the first path segment.
+fn request_bucket<B>(req: &HttpRequest<B>, host: Option<&MultiDomain>) -> Option<String> {
+    let path = req.uri().path();
+    if is_admin_path(path) {
+        return None;
+    }
+
+    let host_header = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| req.uri().authority().map(|a| a.as_str()));
+
+    if let (Some(host), Some(host_header)) = (host, host_header) {
+        if let Some(bucket) = host
+            .parse_host_header(strip_port(host_header))
+            .ok()
+            .and_then(|vh| vh.bucket().map(str::to_owned))
+        {
+            return Some(bucket);
+        }
+    }
+
+    path.trim_start_matches('/')
+        .split('/')
+        .next()
+        .filter(|bucket| !bucket.is_empty())
+        .map(str::to_owned)
+}
+
+fn parse_request_headers(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|h| h.trim().to_ascii_lowercase())
+        .filter(|h| !h.is_empty())
+        .collect()
+}
+
+/// Find the first rule allowing the origin, method and every requested header.
+fn find_rule<'a>(config: &'a CORSConfiguration, origin: &str, method: &str, request_headers: &[String]) -> Option<&'a CORSRule> {
+    config.cors_rules.iter().find(|rule| {
+        rule.allowed_origins.iter().any(|allowed| match_simple(allowed, origin))
+            && rule
+                .allowed_methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method))
+            && request_headers.iter().all(|h| {
+                rule.allowed_headers
+                    .as_ref()
+                    .is_some_and(|allowed| allowed.iter().any(|a| match_simple(&a.to_ascii_lowercase(), h)))
+            })
+    })
+}
+
+/// The origin echoed back to the client, `*` when the rule allows any origin.
+fn allow_origin(rule: &CORSRule, origin: &str) -> String {
+    if rule.allowed_origins.iter().any(|allowed| allowed == "*") {
+        "*".to_owned()
+    } else {
+        origin.to_owned()
+    }
+}
+
+fn set_cors_headers(headers: &mut HeaderMap, rule: &CORSRule, origin: &str) {
+    let allow_origin = allow_origin(rule, origin);
+
+    if allow_origin != "*" {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+    }
+    if let Ok(v) = HeaderValue::from_str(&allow_origin) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, v);
+    }
+    if let Some(expose_headers) = rule.expose_headers.as_ref().filter(|h| !h.is_empty()) {
+        if let Ok(v) = HeaderValue::from_str(&expose_headers.join(", ")) {
+            headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, v);
+        }
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
+
+fn set_preflight_headers(headers: &mut HeaderMap, rule: &CORSRule, origin: &str, request_headers: &[String]) {
+    set_cors_headers(headers, rule, origin);
+
+    if let Ok(v) = HeaderValue::from_str(&rule.allowed_methods.join(", ")) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, v);
+    }
+    if !request_headers.is_empty() {
+        if let Ok(v) = HeaderValue::from_str(&request_headers.join(", ")) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, v);
+        }
+    }
+    if let Some(max_age) = rule.max_age_seconds {
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Access-Control-Request-Method"));
+    headers.append(header::VARY, HeaderValue::from_static("Access-Control-Request-Headers"));
+}
+
+fn forbidden_response<RestBody, GrpcBody>() -> Response<HybridBody<RestBody, GrpcBody>>
+where
+    RestBody: From<Bytes>,
+{
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>AccessForbidden</Code><Message>{CORS_FORBIDDEN_MESSAGE}</Message></Error>"
+    );
+
+    Response::builder()
+        .status(StatusCode::FORBIDDEN)
+        .header(header::CONTENT_TYPE, "application/xml")
+        .body(HybridBody::Rest {
+            rest_body: RestBody::from(Bytes::from(body)),
+        })
+        .expect("failed to build CORS forbidden response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(origins: &[&str], methods: &[&str], headers: Option<&[&str]>) -> CORSRule {
+        CORSRule {
+            allowed_headers: headers.map(|h| h.iter().map(|s| s.to_string()).collect()),
+            allowed_methods: methods.iter().map(|s| s.to_string()).collect(),
+            allowed_origins: origins.iter().map(|s| s.to_string()).collect(),
+            expose_headers: Some(vec!["ETag".to_string()]),
+            id: None,
+            max_age_seconds: Some(3000),
+        }
+    }
+
+    #[test]
+    fn test_find_rule() {
+        let config = CORSConfiguration {
+            cors_rules: vec![
+                rule(&["https://*.example.com"], &["GET", "PUT"], Some(&["Content-*", "x-amz-date"])),
+                rule(&["*"], &["GET"], None),
+            ],
+        };
+
+        let r = find_rule(&config, "https://app.example.com", "PUT", &["content-type".to_string()]).unwrap();
+        assert_eq!(r.allowed_methods, vec!["GET", "PUT"]);
+
+        // Header not allowed by the first rule, and the second rule does not allow PUT
+        assert!(find_rule(&config, "https://app.example.com", "PUT", &["authorization".to_string()]).is_none());
+
+        let r = find_rule(&config, "https://other.org", "GET", &[]).unwrap();
+        assert_eq!(allow_origin(r, "https://other.org"), "*");
+
+        // The wildcard rule does not allow any request headers
+        assert!(find_rule(&config, "https://other.org", "GET", &["x-amz-date".to_string()]).is_none());
+        assert!(find_rule(&config, "https://other.org", "DELETE", &[]).is_none());
+    }
+
+    #[test]
+    fn test_set_preflight_headers() {
+        let r = rule(&["https://example.com"], &["GET", "PUT"], Some(&["*"]));
+        let mut headers = HeaderMap::new();
+        set_preflight_headers(
+            &mut headers,
+            &r,
+            "https://example.com",
+            &["content-type".to_string(), "x-amz-date".to_string()],
+        );
+
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "GET, PUT");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS], "content-type, x-amz-date");
+        assert_eq!(headers[header::ACCESS_CONTROL_EXPOSE_HEADERS], "ETag");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "3000");
+        assert_eq!(headers.get_all(header::VARY).iter().count(), 3);
+    }
+
+    #[test]
+    fn test_request_bucket() {
+        let host = MultiDomain::new(["s3.example.com"]).unwrap();
+        let domains = Some(&host);
+
+        let req = HttpRequest::builder()
+            .uri("/photos/a.jpg")
+            .header(header::HOST, "localhost:9000")
+            .body(())
+            .unwrap();
+        assert_eq!(request_bucket(&req, domains).as_deref(), Some("photos"));
+
+        let req = HttpRequest::builder()
+            .uri("/a.jpg")
+            .header(header::HOST, "photos.s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(request_bucket(&req, domains).as_deref(), Some("photos"));
+
+        let req = HttpRequest::builder()
+            .uri("/a.jpg")
+            .header(header::HOST, "photos.s3.example.com:9000")
+            .body(())
+            .unwrap();
+        assert_eq!(request_bucket(&req, domains).as_deref(), Some("photos"));
+
+        let req = HttpRequest::builder().uri("/rustfs/admin/v3/info").body(()).unwrap();
+        assert_eq!(request_bucket(&req, domains), None);
+
+        let req = HttpRequest::builder().uri("/").body(()).unwrap();
+        assert_eq!(request_bucket(&req, domains), None);
+    }
+}
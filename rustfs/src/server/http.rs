@@ -17,39 +17,48 @@ use crate::admin;
 // use crate::admin::console::{CONSOLE_CONFIG, init_console_cfg};
 use crate::auth::IAMAuth;
 use crate::config;
+use crate::server::access_log::AccessLogLayer;
+use crate::server::body_digest::BodyDigestService;
+use crate::server::cors::CorsLayer;
 use crate::server::hybrid::hybrid;
-use crate::server::layer::RedirectLayer;
+use crate::server::layer::{RedirectLayer, ServiceFreezeLayer};
+use crate::server::prometheus::PrometheusLayer;
+use crate::server::trace::HttpTraceLayer;
+use crate::server::website::WebsiteLayer;
 use crate::server::{ServiceState, ServiceStateManager};
 use crate::storage;
 use bytes::Bytes;
 use http::{HeaderMap, Request as HttpRequest, Response};
+use hyper::body::Incoming;
 use hyper_util::server::graceful::GracefulShutdown;
 use hyper_util::{
     rt::{TokioExecutor, TokioIo},
     server::conn::auto::Builder as ConnBuilder,
     service::TowerToHyperService,
 };
-use rustfs_config::{DEFAULT_ACCESS_KEY, DEFAULT_SECRET_KEY, RUSTFS_TLS_CERT, RUSTFS_TLS_KEY};
+use rustfs_config::{DEFAULT_ACCESS_KEY, DEFAULT_SECRET_KEY, RUSTFS_TLS_CA_CERT, RUSTFS_TLS_CERT, RUSTFS_TLS_KEY};
 use rustfs_ecstore::rpc::make_server;
 use rustfs_obs::SystemObserver;
+use rustfs_protos::auth::{VerifiedPeer, VerifyService};
 use rustfs_protos::proto_gen::node_service::node_service_server::NodeServiceServer;
 use rustfs_utils::net::parse_and_resolve_address;
-use rustls::ServerConfig;
+use rustls::server::WebPkiClientVerifier;
+use rustls::server::danger::ClientCertVerifier;
+use rustls::{RootCertStore, ServerConfig};
 use s3s::service::S3Service;
 use s3s::{host::MultiDomain, service::S3ServiceBuilder};
 use socket2::SockRef;
 use std::io::{Error, Result};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 #[cfg(unix)]
 use tokio::signal::unix::{SignalKind, signal};
 use tokio_rustls::TlsAcceptor;
-use tonic::{Request, Status, metadata::MetadataValue};
 use tower::ServiceBuilder;
 use tower_http::catch_panic::CatchPanicLayer;
-use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing::{Span, debug, error, info, instrument, warn};
 
@@ -150,6 +159,28 @@ pub async fn start_http_server(
         b.build()
     };
 
+    // Per-bucket CORS rules are resolved against the same server domains as virtual-hosted-style requests
+    let cors_layer = if opt.server_domains.is_empty() {
+        CorsLayer::new(None)
+    } else {
+        CorsLayer::new(Some(MultiDomain::new(&opt.server_domains).map_err(Error::other)?))
+    };
+
+    // Static websites are served on bucket virtual hosts, so they need the server domains as well
+    let website_layer = if opt.server_domains.is_empty() {
+        WebsiteLayer::new(None)
+    } else {
+        WebsiteLayer::new(Some(MultiDomain::new(&opt.server_domains).map_err(Error::other)?))
+    };
+
+    let access_log_layer = if opt.server_domains.is_empty() {
+        AccessLogLayer::new(None)
+    } else {
+        AccessLogLayer::new(Some(MultiDomain::new(&opt.server_domains).map_err(Error::other)?))
+    };
+
+    let prometheus_layer = PrometheusLayer::new(opt.prometheus_auth_type == "public");
+
     tokio::spawn(async move {
         // Record the PID-related metrics of the current process
         let meter = opentelemetry::global::meter("system");
@@ -255,7 +286,17 @@ pub async fn start_http_server(
                 warn!(?err, "Failed to set set_send_buffer_size");
             }
 
-            process_connection(socket, tls_acceptor.clone(), http_server.clone(), s3_service.clone(), graceful.clone());
+            process_connection(
+                socket,
+                tls_acceptor.clone(),
+                http_server.clone(),
+                s3_service.clone(),
+                cors_layer.clone(),
+                prometheus_layer.clone(),
+                website_layer.clone(),
+                access_log_layer.clone(),
+                graceful.clone(),
+            );
         }
 
         worker_state_manager.update(ServiceState::Stopping);
@@ -292,14 +333,19 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
 
     debug!("Found TLS directory, checking for certificates");
 
+    let client_verifier = setup_internode_mtls(tls_path).await?;
+    let server_config_builder = || match &client_verifier {
+        Some(verifier) => ServerConfig::builder().with_client_cert_verifier(verifier.clone()),
+        None => ServerConfig::builder().with_no_client_auth(),
+    };
+
     // 1. Try to load all certificates from the directory (multi-cert support)
     if let Ok(cert_key_pairs) = rustfs_utils::load_all_certs_from_directory(tls_path) {
         if !cert_key_pairs.is_empty() {
             debug!("Found {} certificates, creating multi-cert resolver", cert_key_pairs.len());
             let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
-            let mut server_config = ServerConfig::builder()
-                .with_no_client_auth()
-                .with_cert_resolver(Arc::new(rustfs_utils::create_multi_cert_resolver(cert_key_pairs)?));
+            let mut server_config =
+                server_config_builder().with_cert_resolver(Arc::new(rustfs_utils::create_multi_cert_resolver(cert_key_pairs)?));
             server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec(), b"http/1.0".to_vec()];
             return Ok(Some(TlsAcceptor::from(Arc::new(server_config))));
         }
@@ -313,8 +359,7 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
         let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
         let certs = rustfs_utils::load_certs(&cert_path).map_err(|e| rustfs_utils::certs_error(e.to_string()))?;
         let key = rustfs_utils::load_private_key(&key_path).map_err(|e| rustfs_utils::certs_error(e.to_string()))?;
-        let mut server_config = ServerConfig::builder()
-            .with_no_client_auth()
+        let mut server_config = server_config_builder()
             .with_single_cert(certs, key)
             .map_err(|e| rustfs_utils::certs_error(e.to_string()))?;
         server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec(), b"http/1.0".to_vec()];
@@ -325,6 +370,37 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
     Ok(None)
 }
 
+/// Enables mutual TLS between nodes when the TLS directory holds a CA certificate.
+///
+/// Client certificates stay optional at the TLS layer so S3 clients can still connect;
+/// internode RPC calls are rejected unless the peer presented a certificate signed by the CA.
+async fn setup_internode_mtls(tls_path: &str) -> Result<Option<Arc<dyn ClientCertVerifier>>> {
+    let ca_path = format!("{tls_path}/{RUSTFS_TLS_CA_CERT}");
+    let key_path = format!("{tls_path}/{RUSTFS_TLS_KEY}");
+    let cert_path = format!("{tls_path}/{RUSTFS_TLS_CERT}");
+    let Ok((ca, cert, key)) =
+        tokio::try_join!(tokio::fs::read(&ca_path), tokio::fs::read(&cert_path), tokio::fs::read(&key_path))
+    else {
+        debug!("No internode CA certificate found, internode mutual TLS is disabled");
+        return Ok(None);
+    };
+
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+    let mut roots = RootCertStore::empty();
+    for cert in rustfs_utils::load_certs(&ca_path).map_err(|e| rustfs_utils::certs_error(e.to_string()))? {
+        roots.add(cert).map_err(|e| rustfs_utils::certs_error(e.to_string()))?;
+    }
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+        .allow_unauthenticated()
+        .build()
+        .map_err(|e| rustfs_utils::certs_error(e.to_string()))?;
+
+    rustfs_protos::auth::init_rpc_tls(&ca, &cert, &key);
+    rustfs_rio::init_http_client_tls(&ca, &cert, &key)?;
+    info!("Internode mutual TLS is enabled");
+    Ok(Some(verifier))
+}
+
 /// Process a single incoming TCP connection.
 ///
 /// This function is executed in a new Tokio task and it will:
@@ -332,19 +408,35 @@ async fn setup_tls_acceptor(tls_path: &str) -> Result<Option<TlsAcceptor>> {
 /// 2. Build a complete service stack for this connection, including S3, RPC services, and all middleware.
 /// 3. Use Hyper to handle HTTP requests on this connection.
 /// 4. Incorporate connections into the management of elegant closures.
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, fields(peer_addr = %socket.peer_addr().map(|a| a.to_string()).unwrap_or_else(|_| "unknown".to_string())))]
 fn process_connection(
     socket: TcpStream,
     tls_acceptor: Option<Arc<TlsAcceptor>>,
     http_server: Arc<ConnBuilder<TokioExecutor>>,
     s3_service: S3Service,
+    cors_layer: CorsLayer,
+    prometheus_layer: PrometheusLayer,
+    website_layer: WebsiteLayer,
+    access_log_layer: AccessLogLayer,
     graceful: Arc<GracefulShutdown>,
 ) {
+    let peer_addr = socket.peer_addr().ok();
     tokio::spawn(async move {
         // Build services inside each connected task to avoid passing complex service types across tasks,
         // It also ensures that each connection has an independent service instance.
-        let rpc_service = NodeServiceServer::with_interceptor(make_server(), check_auth);
-        let service = hybrid(s3_service, rpc_service);
+        // Set once the TLS handshake has verified a client certificate against the internode CA.
+        let peer_verified = Arc::new(AtomicBool::new(false));
+        let rpc_peer_verified = peer_verified.clone();
+        let rpc_service = ServiceBuilder::new()
+            .map_request(move |mut req: HttpRequest<Incoming>| {
+                if rpc_peer_verified.load(Ordering::Relaxed) {
+                    req.extensions_mut().insert(VerifiedPeer);
+                }
+                req
+            })
+            .service(VerifyService::new(NodeServiceServer::new(make_server())));
+        let service = hybrid(BodyDigestService::new(s3_service), rpc_service);
 
         let hybrid_service = ServiceBuilder::new()
             .layer(CatchPanicLayer::new())
@@ -390,8 +482,13 @@ fn process_connection(
                         debug!("http request failure error: {:?} in {:?}", _error, latency)
                     }),
             )
-            .layer(CorsLayer::permissive())
+            .layer(HttpTraceLayer::new(peer_addr))
+            .layer(access_log_layer.with_client(peer_addr))
+            .layer(cors_layer)
+            .layer(prometheus_layer)
+            .layer(website_layer)
             .layer(RedirectLayer)
+            .layer(ServiceFreezeLayer)
             .service(service);
         let hybrid_service = TowerToHyperService::new(hybrid_service);
 
@@ -401,6 +498,14 @@ fn process_connection(
             match acceptor.accept(socket).await {
                 Ok(tls_socket) => {
                     debug!("TLS handshake successful");
+                    if tls_socket
+                        .get_ref()
+                        .1
+                        .peer_certificates()
+                        .is_some_and(|certs| !certs.is_empty())
+                    {
+                        peer_verified.store(true, Ordering::Relaxed);
+                    }
                     let stream = TokioIo::new(tls_socket);
                     let conn = http_server.serve_connection(stream, hybrid_service);
                     if let Err(err) = graceful.watch(conn).await {
@@ -448,16 +553,6 @@ fn handle_connection_error(err: &(dyn std::error::Error + 'static)) {
     }
 }
 
-#[allow(clippy::result_large_err)]
-fn check_auth(req: Request<()>) -> std::result::Result<Request<()>, Status> {
-    let token: MetadataValue<_> = "rustfs rpc".parse().unwrap();
-
-    match req.metadata().get("authorization") {
-        Some(t) if token == t => Ok(req),
-        _ => Err(Status::unauthenticated("No valid auth token")),
-    }
-}
-
 /// Determines the listen backlog size.
 ///
 /// It tries to read the system's maximum connection queue length (`somaxconn`).
@@ -12,15 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::admin::router::is_admin_path;
 use crate::server::hybrid::HybridBody;
+use crate::server::service_state::{FREEZE_WAIT_TIMEOUT, GLOBAL_SERVICE_FREEZE, InFlightGuard};
+use bytes::Bytes;
 use http::{Request as HttpRequest, Response, StatusCode};
+use http_body::{Frame, SizeHint};
 use hyper::body::Incoming;
+use pin_project_lite::pin_project;
+use s3s::Body;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use tower::{Layer, Service};
 use tracing::debug;
 
+const FREEZE_SLOW_DOWN_MESSAGE: &str = "The service is frozen, please retry later";
+
 /// Redirect layer that redirects browser requests to the console
 #[derive(Clone)]
 pub struct RedirectLayer;
@@ -89,3 +97,117 @@ where
         Box::pin(async move { inner.call(req).await.map_err(Into::into) })
     }
 }
+
+/// Layer holding back S3 API calls while the service is frozen by the admin service API.
+///
+/// Admin, console and internode requests are never held back, so the cluster can still be
+/// managed and unfrozen.
+#[derive(Clone)]
+pub struct ServiceFreezeLayer;
+
+impl<S> Layer<S> for ServiceFreezeLayer {
+    type Service = ServiceFreezeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ServiceFreezeService { inner }
+    }
+}
+
+#[derive(Clone)]
+pub struct ServiceFreezeService<S> {
+    inner: S,
+}
+
+impl<S, GrpcBody> Service<HttpRequest<Incoming>> for ServiceFreezeService<S>
+where
+    S: Service<HttpRequest<Incoming>, Response = Response<HybridBody<Body, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<Body, GrpcBody>>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let path = req.uri().path();
+        let exempt = is_admin_path(path) || path.starts_with("/node_service.");
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            if exempt {
+                return inner.call(req).await;
+            }
+
+            let Ok(guard) = tokio::time::timeout(FREEZE_WAIT_TIMEOUT, GLOBAL_SERVICE_FREEZE.enter()).await else {
+                debug!("Service still frozen after {:?}, rejecting {}", FREEZE_WAIT_TIMEOUT, req.uri().path());
+                return Ok(slow_down_response(req.uri().path()));
+            };
+
+            // The call stays in flight until its response body has been sent.
+            let resp = inner.call(req).await?;
+            Ok(resp.map(|body| match body {
+                HybridBody::Rest { rest_body } => HybridBody::Rest {
+                    rest_body: Body::http_body_unsync(InFlightBody {
+                        inner: rest_body,
+                        _guard: guard,
+                    }),
+                },
+                grpc => grpc,
+            }))
+        })
+    }
+}
+
+fn slow_down_response<GrpcBody>(resource: &str) -> Response<HybridBody<Body, GrpcBody>> {
+    let body = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>SlowDown</Code><Message>{FREEZE_SLOW_DOWN_MESSAGE}</Message><Resource>{}</Resource></Error>",
+        xml_escape(resource)
+    );
+
+    Response::builder()
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .header(http::header::CONTENT_TYPE, "application/xml")
+        .header(http::header::RETRY_AFTER, FREEZE_WAIT_TIMEOUT.as_secs())
+        .body(HybridBody::Rest {
+            rest_body: Body::from(Bytes::from(body)),
+        })
+        .expect("failed to build slow down response")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+pin_project! {
+    /// Response body that keeps its call registered as in flight until it has been dropped.
+    struct InFlightBody {
+        #[pin]
+        inner: Body,
+        _guard: InFlightGuard<'static>,
+    }
+}
+
+impl http_body::Body for InFlightBody {
+    type Data = Bytes;
+    type Error = <Body as http_body::Body>::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        self.project().inner.poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
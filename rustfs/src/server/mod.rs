@@ -12,13 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod access_log;
+mod body_digest;
+mod cors;
 mod http;
 mod hybrid;
 mod layer;
+mod prometheus;
 mod service_state;
+mod trace;
+mod website;
+pub(crate) use access_log::AuthenticatedRequester;
+pub(crate) use body_digest::BodyDigest;
 pub(crate) use http::start_http_server;
 pub(crate) use service_state::SHUTDOWN_TIMEOUT;
 pub(crate) use service_state::ServiceState;
 pub(crate) use service_state::ServiceStateManager;
-pub(crate) use service_state::ShutdownSignal;
+pub(crate) use service_state::freeze_services;
+pub(crate) use service_state::restart_process;
+pub(crate) use service_state::unfreeze_services;
 pub(crate) use service_state::wait_for_shutdown;
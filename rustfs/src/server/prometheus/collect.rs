@@ -0,0 +1,283 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rustfs_ecstore::bucket::quota::BucketQuotaSys;
+use rustfs_ecstore::cmd::bucket_replication::GLOBAL_REPLICATION_POOL;
+use rustfs_ecstore::global::GLOBAL_BOOT_TIME;
+use rustfs_ecstore::heal::data_scanner_metric::{ScannerMetric, globalScannerMetrics};
+use rustfs_ecstore::heal::data_usage::{DataUsageInfo, load_data_usage_from_backend};
+use rustfs_ecstore::heal::heal_commands::DRIVE_STATE_OK;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::StorageAPI;
+use rustfs_madmin::StorageInfo;
+use rustfs_obs::metrics::PrometheusEncoder;
+use rustfs_obs::metrics::bucket_replication::*;
+use rustfs_obs::metrics::cluster_config::*;
+use rustfs_obs::metrics::cluster_erasure_set::*;
+use rustfs_obs::metrics::cluster_health::*;
+use rustfs_obs::metrics::cluster_usage::*;
+use rustfs_obs::metrics::replication::*;
+use rustfs_obs::metrics::scanner::*;
+use rustfs_obs::metrics::system_drive::*;
+use rustfs_obs::metrics::system_process::*;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Collects the cluster wide metrics, `None` until the object layer is initialized.
+pub(super) async fn cluster_metrics() -> Option<String> {
+    let store = new_object_layer_fn()?;
+    let mut encoder = PrometheusEncoder::new();
+
+    let storage_info = store.storage_info().await;
+    add_cluster_health(&mut encoder, &storage_info);
+    add_erasure_sets(&mut encoder, &storage_info);
+    add_cluster_config(&mut encoder, &storage_info);
+
+    match load_data_usage_from_backend(store).await {
+        Ok(usage) => {
+            add_usage(&mut encoder, &usage);
+            add_bucket_usage(&mut encoder, &usage).await;
+            add_bucket_replication(&mut encoder, &usage);
+        }
+        Err(e) => warn!("load data usage for metrics failed, e: {:?}", e),
+    }
+
+    Some(encoder.encode())
+}
+
+/// Collects the metrics of this node, `None` until the object layer is initialized.
+pub(super) async fn node_metrics() -> Option<String> {
+    let store = new_object_layer_fn()?;
+    let mut encoder = PrometheusEncoder::new();
+
+    let storage_info = store.local_storage_info().await;
+    add_drives(&mut encoder, &storage_info);
+    add_scanner(&mut encoder).await;
+    add_process(&mut encoder);
+
+    if let Some(pool) = GLOBAL_REPLICATION_POOL.read().await.as_ref() {
+        encoder.add(&REPLICATION_CURRENT_ACTIVE_WORKERS_MD, &[], pool.active_workers() as f64);
+    }
+
+    Some(encoder.encode())
+}
+
+fn is_online(state: &str) -> bool {
+    state == DRIVE_STATE_OK
+}
+
+fn add_cluster_health(encoder: &mut PrometheusEncoder, storage_info: &StorageInfo) {
+    let online = storage_info.disks.iter().filter(|d| is_online(&d.state)).count();
+    let total = storage_info.disks.len();
+
+    encoder.add(&HEALTH_DRIVES_ONLINE_COUNT_MD, &[], online as f64);
+    encoder.add(&HEALTH_DRIVES_OFFLINE_COUNT_MD, &[], (total - online) as f64);
+    encoder.add(&HEALTH_DRIVES_COUNT_MD, &[], total as f64);
+}
+
+fn add_erasure_sets(encoder: &mut PrometheusEncoder, storage_info: &StorageInfo) {
+    // (online, healing) drives per (pool, set)
+    let mut sets: BTreeMap<(i32, i32), (usize, usize)> = BTreeMap::new();
+    for disk in storage_info.disks.iter() {
+        let set = sets.entry((disk.pool_index, disk.set_index)).or_default();
+        if is_online(&disk.state) {
+            set.0 += 1;
+        }
+        if disk.healing {
+            set.1 += 1;
+        }
+    }
+
+    let backend = &storage_info.backend;
+    let mut overall_write_quorum: Option<usize> = None;
+    let mut overall_healthy = true;
+    for ((pool_index, set_index), (online, healing)) in sets {
+        let Some(&data) = usize::try_from(pool_index).ok().and_then(|i| backend.standard_sc_data.get(i)) else {
+            continue;
+        };
+        let parity = backend.standard_sc_parity.unwrap_or_default();
+        let read_quorum = data;
+        let write_quorum = if data == parity { data + 1 } else { data };
+        let read_healthy = online >= read_quorum;
+        let write_healthy = online >= write_quorum;
+
+        overall_write_quorum = Some(overall_write_quorum.map_or(write_quorum, |q| q.min(write_quorum)));
+        overall_healthy &= write_healthy;
+
+        let pool = pool_index.to_string();
+        let set = set_index.to_string();
+        let labels = [pool.as_str(), set.as_str()];
+        encoder.add(&ERASURE_SET_READ_QUORUM_MD, &labels, read_quorum as f64);
+        encoder.add(&ERASURE_SET_WRITE_QUORUM_MD, &labels, write_quorum as f64);
+        encoder.add(&ERASURE_SET_ONLINE_DRIVES_COUNT_MD, &labels, online as f64);
+        encoder.add(&ERASURE_SET_HEALING_DRIVES_COUNT_MD, &labels, healing as f64);
+        encoder.add(&ERASURE_SET_HEALTH_MD, &labels, bool_value(write_healthy));
+        encoder.add(&ERASURE_SET_READ_TOLERANCE_MD, &labels, online as f64 - read_quorum as f64);
+        encoder.add(&ERASURE_SET_WRITE_TOLERANCE_MD, &labels, online as f64 - write_quorum as f64);
+        encoder.add(&ERASURE_SET_READ_HEALTH_MD, &labels, bool_value(read_healthy));
+        encoder.add(&ERASURE_SET_WRITE_HEALTH_MD, &labels, bool_value(write_healthy));
+    }
+
+    if let Some(write_quorum) = overall_write_quorum {
+        encoder.add(&ERASURE_SET_OVERALL_WRITE_QUORUM_MD, &[], write_quorum as f64);
+        encoder.add(&ERASURE_SET_OVERALL_HEALTH_MD, &[], bool_value(overall_healthy));
+    }
+}
+
+fn add_cluster_config(encoder: &mut PrometheusEncoder, storage_info: &StorageInfo) {
+    if let Some(parity) = storage_info.backend.standard_sc_parity {
+        encoder.add(&CONFIG_STANDARD_PARITY_MD, &[], parity as f64);
+    }
+    if let Some(parity) = storage_info.backend.rr_sc_parity {
+        encoder.add(&CONFIG_RRS_PARITY_MD, &[], parity as f64);
+    }
+}
+
+fn add_usage(encoder: &mut PrometheusEncoder, usage: &DataUsageInfo) {
+    if let Some(last_update) = usage.last_update {
+        let elapsed = SystemTime::now().duration_since(last_update).unwrap_or_default();
+        encoder.add(&USAGE_SINCE_LAST_UPDATE_SECONDS_MD, &[], elapsed.as_secs_f64());
+    }
+    encoder.add(&USAGE_TOTAL_BYTES_MD, &[], usage.objects_total_size as f64);
+    encoder.add(&USAGE_OBJECTS_COUNT_MD, &[], usage.objects_total_count as f64);
+    encoder.add(&USAGE_VERSIONS_COUNT_MD, &[], usage.versions_total_count as f64);
+    encoder.add(&USAGE_DELETE_MARKERS_COUNT_MD, &[], usage.delete_markers_total_count as f64);
+    encoder.add(&USAGE_BUCKETS_COUNT_MD, &[], usage.buckets_count as f64);
+
+    let mut sizes: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut versions: BTreeMap<&str, u64> = BTreeMap::new();
+    for bucket in usage.buckets_usage.values() {
+        for (range, count) in bucket.object_size_histogram.iter() {
+            *sizes.entry(range).or_default() += count;
+        }
+        for (range, count) in bucket.object_versions_histogram.iter() {
+            *versions.entry(range).or_default() += count;
+        }
+    }
+    for (range, count) in sizes {
+        encoder.add(&USAGE_OBJECTS_DISTRIBUTION_MD, &[range], count as f64);
+    }
+    for (range, count) in versions {
+        encoder.add(&USAGE_VERSIONS_DISTRIBUTION_MD, &[range], count as f64);
+    }
+}
+
+async fn add_bucket_usage(encoder: &mut PrometheusEncoder, usage: &DataUsageInfo) {
+    for (bucket, info) in sorted(&usage.buckets_usage) {
+        encoder.add(&USAGE_BUCKET_TOTAL_BYTES_MD, &[bucket], info.size as f64);
+        encoder.add(&USAGE_BUCKET_OBJECTS_TOTAL_MD, &[bucket], info.objects_count as f64);
+        encoder.add(&USAGE_BUCKET_VERSIONS_COUNT_MD, &[bucket], info.versions_count as f64);
+        encoder.add(&USAGE_BUCKET_DELETE_MARKERS_COUNT_MD, &[bucket], info.delete_markers_count as f64);
+
+        if let Ok(Some(limit)) = BucketQuotaSys::get(bucket).await.map(|q| q.and_then(|q| q.hard_limit())) {
+            encoder.add(&USAGE_BUCKET_QUOTA_TOTAL_BYTES_MD, &[bucket], limit as f64);
+        }
+
+        for (range, count) in sorted(&info.object_size_histogram) {
+            encoder.add(&USAGE_BUCKET_OBJECT_SIZE_DISTRIBUTION_MD, &[range, bucket], *count as f64);
+        }
+        for (range, count) in sorted(&info.object_versions_histogram) {
+            encoder.add(&USAGE_BUCKET_OBJECT_VERSION_COUNT_DISTRIBUTION_MD, &[range, bucket], *count as f64);
+        }
+    }
+}
+
+fn add_bucket_replication(encoder: &mut PrometheusEncoder, usage: &DataUsageInfo) {
+    for (bucket, info) in sorted(&usage.buckets_usage) {
+        if info.replication_info.is_empty() {
+            continue;
+        }
+
+        let targets = info.replication_info.values();
+        let (mut failed_bytes, mut failed_count, mut sent_bytes, mut sent_count) = (0, 0, 0, 0);
+        for target in targets {
+            failed_bytes += target.replication_failed_size;
+            failed_count += target.replication_failed_count;
+            sent_bytes += target.replicated_size;
+            sent_count += target.replicated_count;
+        }
+        encoder.add(&BUCKET_REPL_TOTAL_FAILED_BYTES_MD, &[bucket], failed_bytes as f64);
+        encoder.add(&BUCKET_REPL_TOTAL_FAILED_COUNT_MD, &[bucket], failed_count as f64);
+        encoder.add(&BUCKET_REPL_SENT_BYTES_MD, &[bucket], sent_bytes as f64);
+        encoder.add(&BUCKET_REPL_SENT_COUNT_MD, &[bucket], sent_count as f64);
+    }
+}
+
+fn add_drives(encoder: &mut PrometheusEncoder, storage_info: &StorageInfo) {
+    let mut online = 0;
+    for disk in storage_info.disks.iter() {
+        let pool = disk.pool_index.to_string();
+        let set = disk.set_index.to_string();
+        let index = disk.disk_index.to_string();
+        let labels = [disk.endpoint.as_str(), pool.as_str(), set.as_str(), index.as_str()];
+
+        let health = if !is_online(&disk.state) {
+            0.0
+        } else if disk.healing {
+            2.0
+        } else {
+            1.0
+        };
+        if health > 0.0 {
+            online += 1;
+        }
+        encoder.add(&DRIVE_HEALTH_MD, &labels, health);
+        encoder.add(&DRIVE_USED_BYTES_MD, &labels, disk.used_space as f64);
+        encoder.add(&DRIVE_FREE_BYTES_MD, &labels, disk.available_space as f64);
+        encoder.add(&DRIVE_TOTAL_BYTES_MD, &labels, disk.total_space as f64);
+        encoder.add(&DRIVE_USED_INODES_MD, &labels, disk.used_inodes as f64);
+        encoder.add(&DRIVE_FREE_INODES_MD, &labels, disk.free_inodes as f64);
+        encoder.add(&DRIVE_TOTAL_INODES_MD, &labels, (disk.used_inodes + disk.free_inodes) as f64);
+    }
+
+    let total = storage_info.disks.len();
+    encoder.add(&DRIVE_ONLINE_COUNT_MD, &[], online as f64);
+    encoder.add(&DRIVE_OFFLINE_COUNT_MD, &[], (total - online) as f64);
+    encoder.add(&DRIVE_COUNT_MD, &[], total as f64);
+}
+
+async fn add_scanner(encoder: &mut PrometheusEncoder) {
+    let metrics = globalScannerMetrics.report().await;
+    let ops = |metric: ScannerMetric| metrics.life_time_ops.get(metric.as_str()).copied().unwrap_or_default();
+
+    let finished = ops(ScannerMetric::ScanBucketDrive);
+    encoder.add(&SCANNER_BUCKET_SCANS_FINISHED_MD, &[], finished as f64);
+    encoder.add(&SCANNER_BUCKET_SCANS_STARTED_MD, &[], (finished + metrics.ongoing_buckets as u64) as f64);
+    encoder.add(&SCANNER_DIRECTORIES_SCANNED_MD, &[], ops(ScannerMetric::ScanFolder) as f64);
+    encoder.add(&SCANNER_OBJECTS_SCANNED_MD, &[], ops(ScannerMetric::ScanObject) as f64);
+    encoder.add(&SCANNER_VERSIONS_SCANNED_MD, &[], ops(ScannerMetric::ApplyVersion) as f64);
+}
+
+fn add_process(encoder: &mut PrometheusEncoder) {
+    let Some(boot_time) = GLOBAL_BOOT_TIME.get() else {
+        return;
+    };
+
+    let started = boot_time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let uptime = SystemTime::now().duration_since(*boot_time).unwrap_or_default();
+    encoder.add(&PROCESS_START_TIME_SECONDS_MD, &[], started.as_secs_f64());
+    encoder.add(&PROCESS_UPTIME_SECONDS_MD, &[], uptime.as_secs_f64());
+}
+
+fn bool_value(v: bool) -> f64 {
+    if v { 1.0 } else { 0.0 }
+}
+
+/// Iterates a map in key order, so scrapes list the series in a stable order.
+fn sorted<V>(map: &HashMap<String, V>) -> impl Iterator<Item = (&str, &V)> {
+    let mut entries: Vec<_> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    entries.sort_by_key(|(k, _)| *k);
+    entries.into_iter()
+}
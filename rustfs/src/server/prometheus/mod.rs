@@ -0,0 +1,194 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus scrape endpoints.
+//!
+//! `/rustfs/v2/metrics/cluster` reports the state of the whole cluster and `/rustfs/v2/metrics/node`
+//! the state of the node serving the request. Scrapes authenticate with `Authorization: Bearer <jwt>`,
+//! an HS512 token whose `sub` claim is an access key and which is signed with that key's secret key.
+//! The key needs the `admin:Prometheus` permission. With `--prometheus-auth-type public` no token is
+//! required.
+//!
+//! The endpoints are served before the S3 service, which would reject the bearer authorization.
+
+mod collect;
+
+use crate::auth::{check_key_valid, get_condition_values};
+use crate::server::hybrid::HybridBody;
+use http::{HeaderMap, Method, Request as HttpRequest, Response, StatusCode, header};
+use hyper::body::Incoming;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use rustfs_iam::utils::extract_claims;
+use rustfs_policy::policy::Args;
+use rustfs_policy::policy::action::{Action, AdminAction};
+use s3s::Body;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::debug;
+
+const CLUSTER_METRICS_PATH: &str = "/rustfs/v2/metrics/cluster";
+const NODE_METRICS_PATH: &str = "/rustfs/v2/metrics/node";
+
+/// Claims of a Prometheus bearer token
+#[derive(Debug, Deserialize)]
+struct PrometheusClaims {
+    sub: String,
+}
+
+/// Layer serving the Prometheus scrape endpoints
+#[derive(Clone)]
+pub struct PrometheusLayer {
+    public: bool,
+}
+
+impl PrometheusLayer {
+    /// Public endpoints can be scraped without a bearer token.
+    pub fn new(public: bool) -> Self {
+        Self { public }
+    }
+}
+
+impl<S> Layer<S> for PrometheusLayer {
+    type Service = PrometheusService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PrometheusService {
+            inner,
+            public: self.public,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PrometheusService<S> {
+    inner: S,
+    public: bool,
+}
+
+impl<S, RestBody, GrpcBody> Service<HttpRequest<Incoming>> for PrometheusService<S>
+where
+    S: Service<HttpRequest<Incoming>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    RestBody: From<Body> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        let path = req.uri().path().trim_end_matches('/');
+        let cluster = match path {
+            CLUSTER_METRICS_PATH => true,
+            NODE_METRICS_PATH => false,
+            _ => return Box::pin(async move { inner.call(req).await.map_err(Into::into) }),
+        };
+        if req.method() != Method::GET {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        }
+
+        let public = self.public;
+        Box::pin(async move {
+            let response = match serve_metrics(req.headers(), public, cluster).await {
+                Ok(metrics) => Response::builder()
+                    .status(StatusCode::OK)
+                    .header(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")
+                    .body(Body::from(metrics)),
+                Err((status, msg)) => {
+                    debug!("prometheus scrape of {} failed: {}", req.uri().path(), msg);
+                    Response::builder()
+                        .status(status)
+                        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                        .body(Body::from(msg))
+                }
+            }
+            .expect("failed to build metrics response");
+
+            Ok(response.map(|body| HybridBody::Rest {
+                rest_body: RestBody::from(body),
+            }))
+        })
+    }
+}
+
+async fn serve_metrics(headers: &HeaderMap, public: bool, cluster: bool) -> Result<String, (StatusCode, String)> {
+    if !public {
+        check_token(headers).await.map_err(|e| (StatusCode::FORBIDDEN, e))?;
+    }
+
+    let metrics = if cluster {
+        collect::cluster_metrics().await
+    } else {
+        collect::node_metrics().await
+    };
+    metrics.ok_or_else(|| (StatusCode::SERVICE_UNAVAILABLE, "server not initialized".to_string()))
+}
+
+/// Checks the bearer token and that its access key may read the metrics.
+async fn check_token(headers: &HeaderMap) -> Result<(), String> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| "bearer token is required".to_string())?;
+
+    // The subject names the key the token is signed with, so it is read before the signature is checked
+    let mut validation = Validation::new(Algorithm::HS512);
+    validation.insecure_disable_signature_validation();
+    let access_key = jsonwebtoken::decode::<PrometheusClaims>(token, &DecodingKey::from_secret(&[]), &validation)
+        .map_err(|e| format!("invalid token: {e}"))?
+        .claims
+        .sub;
+
+    let (cred, owner) = check_key_valid("", &access_key)
+        .await
+        .map_err(|e| e.message().unwrap_or("invalid access key").to_string())?;
+
+    extract_claims::<PrometheusClaims>(token, &cred.secret_key).map_err(|e| format!("invalid token: {e}"))?;
+
+    let Ok(iam_store) = rustfs_iam::get() else {
+        return Err("iam not init".to_string());
+    };
+
+    let conditions = get_condition_values(headers, &cred);
+    if !iam_store
+        .is_allowed(&Args {
+            account: &cred.access_key,
+            groups: &cred.groups,
+            action: Action::AdminAction(AdminAction::PrometheusAdminAction),
+            bucket: "",
+            conditions: &conditions,
+            is_owner: owner,
+            object: "",
+            claims: cred.claims.as_ref().unwrap_or(&HashMap::new()),
+            deny_only: false,
+        })
+        .await
+    {
+        return Err("access denied".to_string());
+    }
+
+    Ok(())
+}
@@ -13,14 +13,23 @@
 // limitations under the License.
 
 use atomic_enum::atomic_enum;
-use std::sync::Arc;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{info, warn};
 
 // a configurable shutdown timeout
 pub(crate) const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
 
+// how long a freeze waits for in-flight S3 calls before giving up on the drain
+const FREEZE_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// how long an S3 call waits for the service to be unfrozen before it is rejected with SlowDown
+pub(crate) const FREEZE_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub(crate) static GLOBAL_SERVICE_FREEZE: LazyLock<ServiceFreeze> = LazyLock::new(ServiceFreeze::new);
+
 #[cfg(target_os = "linux")]
 fn notify_systemd(state: &str) {
     use libsystemd::daemon::{NotifyState, notify};
@@ -156,6 +165,109 @@ impl Default for ServiceStateManager {
     }
 }
 
+/// Gate for S3 API calls used by the admin freeze/unfreeze service actions.
+///
+/// While frozen, new calls wait in [`ServiceFreeze::enter`] until the service is unfrozen, calls
+/// that were already running keep their [`InFlightGuard`] and complete normally. The S3 layer
+/// bounds the wait with [`FREEZE_WAIT_TIMEOUT`] and holds the guard until the response body is sent.
+pub(crate) struct ServiceFreeze {
+    frozen: watch::Sender<bool>,
+    in_flight: AtomicUsize,
+}
+
+pub(crate) struct InFlightGuard<'a> {
+    in_flight: &'a AtomicUsize,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ServiceFreeze {
+    fn new() -> Self {
+        Self {
+            frozen: watch::channel(false).0,
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until the service is not frozen and registers an in-flight call.
+    pub async fn enter(&self) -> InFlightGuard<'_> {
+        let mut frozen = self.frozen.subscribe();
+        loop {
+            // Count the call before checking the flag so that a concurrent freeze either sees it
+            // in flight or the call sees the freeze.
+            self.in_flight.fetch_add(1, Ordering::SeqCst);
+            if !*frozen.borrow_and_update() {
+                return InFlightGuard {
+                    in_flight: &self.in_flight,
+                };
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            let _ = frozen.wait_for(|frozen| !frozen).await;
+        }
+    }
+
+    /// Blocks new calls and waits up to `timeout` for the in-flight ones to complete.
+    pub async fn freeze(&self, timeout: Duration) {
+        self.frozen.send_replace(true);
+        info!("Service frozen, waiting for {} in-flight calls", self.in_flight());
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.in_flight() > 0 {
+            if tokio::time::Instant::now() >= deadline {
+                warn!("Service frozen with {} calls still in flight", self.in_flight());
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        info!("Service frozen, all in-flight calls completed");
+    }
+
+    pub fn unfreeze(&self) {
+        self.frozen.send_replace(false);
+        info!("Service unfrozen");
+    }
+}
+
+pub(crate) async fn freeze_services() {
+    GLOBAL_SERVICE_FREEZE.freeze(FREEZE_DRAIN_TIMEOUT).await;
+}
+
+pub(crate) fn unfreeze_services() {
+    GLOBAL_SERVICE_FREEZE.unfreeze();
+}
+
+/// Replaces the current process with a fresh instance started with the same arguments.
+pub(crate) fn restart_process() -> std::io::Error {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => return err,
+    };
+    let args: Vec<_> = std::env::args_os().skip(1).collect();
+    info!("Restarting {}", exe.display());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        std::process::Command::new(exe).args(args).exec()
+    }
+    #[cfg(not(unix))]
+    {
+        match std::process::Command::new(exe).args(args).spawn() {
+            Ok(_) => std::process::exit(0),
+            Err(err) => err,
+        }
+    }
+}
+
 // Example of use
 #[cfg(test)]
 mod tests {
@@ -180,4 +292,44 @@ mod tests {
         manager.update(ServiceState::Stopped);
         assert_eq!(manager.current_state(), ServiceState::Stopped);
     }
+
+    #[tokio::test]
+    async fn test_service_freeze() {
+        let freeze = Arc::new(ServiceFreeze::new());
+
+        let guard = freeze.enter().await;
+        assert_eq!(freeze.in_flight(), 1);
+
+        // The in-flight call is not drained within the timeout, but new calls are blocked.
+        freeze.freeze(Duration::from_millis(100)).await;
+        assert!(*freeze.frozen.borrow());
+
+        let waiting = tokio::spawn({
+            let freeze = freeze.clone();
+            async move {
+                let _guard = freeze.enter().await;
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(guard);
+        freeze.unfreeze();
+        waiting.await.unwrap();
+        assert_eq!(freeze.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_service_freeze_wait_timeout() {
+        let freeze = ServiceFreeze::new();
+        freeze.freeze(Duration::from_millis(10)).await;
+
+        // A call that gives up waiting is not left counted as in flight.
+        assert!(tokio::time::timeout(Duration::from_millis(50), freeze.enter()).await.is_err());
+        assert_eq!(freeze.in_flight(), 0);
+
+        freeze.unfreeze();
+        let _guard = tokio::time::timeout(Duration::from_millis(50), freeze.enter()).await.unwrap();
+        assert_eq!(freeze.in_flight(), 1);
+    }
 }
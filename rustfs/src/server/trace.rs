@@ -0,0 +1,171 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::Utc;
+use http::{HeaderMap, Request as HttpRequest, Response, header};
+use rustfs_ecstore::trace;
+use rustfs_madmin::trace::{TraceCallStats, TraceHTTPStats, TraceInfo, TraceRequestInfo, TraceResponseInfo, TraceType};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Prefix of the paths served by the node gRPC service.
+const GRPC_PATH_PREFIX: &str = "/node_service.";
+
+/// Request headers never included in trace events.
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "x-amz-security-token",
+    "x-amz-server-side-encryption-customer-key",
+    "x-amz-copy-source-server-side-encryption-customer-key",
+];
+
+/// Layer publishing an S3 or internal trace event for every request while a trace is running.
+#[derive(Clone)]
+pub struct HttpTraceLayer {
+    client: Option<SocketAddr>,
+}
+
+impl HttpTraceLayer {
+    /// `client` is the remote address of the connection the layer serves.
+    pub fn new(client: Option<SocketAddr>) -> Self {
+        Self { client }
+    }
+}
+
+impl<S> Layer<S> for HttpTraceLayer {
+    type Service = HttpTraceService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpTraceService {
+            inner,
+            client: self.client,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpTraceService<S> {
+    inner: S,
+    client: Option<SocketAddr>,
+}
+
+impl<S, ReqBody, ResBody> Service<HttpRequest<ReqBody>> for HttpTraceService<S>
+where
+    S: Service<HttpRequest<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<ReqBody>) -> Self::Future {
+        let trace_type = if req.uri().path().starts_with(GRPC_PATH_PREFIX) {
+            TraceType::INTERNAL
+        } else {
+            TraceType::S3
+        };
+
+        let mut inner = self.inner.clone();
+        if !trace::subscribed(trace_type) {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        }
+
+        let start = Instant::now();
+        let req_info = request_info(&req, self.client);
+        let input_bytes = content_length(req.headers());
+        Box::pin(async move {
+            let res = inner.call(req).await.map_err(Into::into);
+            let latency = start.elapsed();
+
+            let path = req_info.path.clone().unwrap_or_default();
+            let func_name = if trace_type.contains(&TraceType::INTERNAL) {
+                format!("grpc.{}", path.rsplit('/').next().unwrap_or_default())
+            } else {
+                format!("s3.{}", req_info.method)
+            };
+            let mut info = TraceInfo::new(trace_type, "", func_name);
+            info.path = path;
+            info.duration = latency;
+
+            let mut resp_info = TraceResponseInfo {
+                time: Utc::now(),
+                ..Default::default()
+            };
+            let mut output_bytes = 0;
+            match &res {
+                Ok(resp) => {
+                    resp_info.status_code = Some(resp.status().as_u16() as i32);
+                    resp_info.headers = Some(header_map(resp.headers()));
+                    output_bytes = content_length(resp.headers());
+                }
+                Err(err) => info.error = Some(err.to_string()),
+            }
+
+            info.http = Some(TraceHTTPStats {
+                call_stats: TraceCallStats {
+                    input_bytes,
+                    output_bytes,
+                    latency,
+                    time_to_first_byte: latency,
+                },
+                req_info,
+                resp_info,
+            });
+            trace::publish(info).await;
+
+            res
+        })
+    }
+}
+
+fn request_info<B>(req: &HttpRequest<B>, client: Option<SocketAddr>) -> TraceRequestInfo {
+    TraceRequestInfo {
+        time: Utc::now(),
+        proto: format!("{:?}", req.version()),
+        method: req.method().to_string(),
+        path: Some(req.uri().path().to_owned()),
+        raw_query: req.uri().query().map(str::to_owned),
+        headers: Some(header_map(req.headers())),
+        body: None,
+        client: client.map(|addr| addr.to_string()).unwrap_or_default(),
+    }
+}
+
+fn header_map(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter(|(name, _)| !REDACTED_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned()))
+        .collect()
+}
+
+fn content_length(headers: &HeaderMap) -> i32 {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default()
+}
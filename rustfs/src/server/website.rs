@@ -0,0 +1,579 @@
+// Copyright 2024 RustFS Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::admin::router::is_admin_path;
+use crate::server::hybrid::HybridBody;
+use crate::storage::access::ReqInfo;
+use crate::storage::ecfs::FS;
+use http::request::Parts;
+use http::{Extensions, HeaderValue, Method, Request as HttpRequest, Response, StatusCode, header};
+use hyper::body::Incoming;
+use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::new_object_layer_fn;
+use rustfs_ecstore::store_api::{ObjectOptions, StorageAPI};
+use rustfs_utils::net::strip_port;
+use s3s::access::S3Access;
+use s3s::dto::{GetObjectInput, Range, RoutingRule, Timestamp, TimestampFormat, WebsiteConfiguration};
+use s3s::host::{MultiDomain, S3Host};
+use s3s::{Body, S3, S3ErrorCode, S3Request, S3Result, s3_error};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::{debug, warn};
+
+/// Website layer that serves buckets with a website configuration on their virtual host
+#[derive(Clone)]
+pub struct WebsiteLayer {
+    host: Option<Arc<MultiDomain>>,
+}
+
+impl WebsiteLayer {
+    /// Website hosting is only available when `server_domains` are configured.
+    pub fn new(host: Option<MultiDomain>) -> Self {
+        Self {
+            host: host.map(Arc::new),
+        }
+    }
+}
+
+impl<S> Layer<S> for WebsiteLayer {
+    type Service = WebsiteService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        WebsiteService {
+            inner,
+            host: self.host.clone(),
+        }
+    }
+}
+
+/// Service implementation for static website hosting
+#[derive(Clone)]
+pub struct WebsiteService<S> {
+    inner: S,
+    host: Option<Arc<MultiDomain>>,
+}
+
+impl<S, RestBody, GrpcBody> Service<HttpRequest<Incoming>> for WebsiteService<S>
+where
+    S: Service<HttpRequest<Incoming>, Response = Response<HybridBody<RestBody, GrpcBody>>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>> + Send + 'static,
+    RestBody: From<Body> + Send + 'static,
+    GrpcBody: Send + 'static,
+{
+    type Response = Response<HybridBody<RestBody, GrpcBody>>;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: HttpRequest<Incoming>) -> Self::Future {
+        let mut inner = self.inner.clone();
+
+        let Some(bucket) = self.host.as_deref().and_then(|host| website_bucket(host, &req)) else {
+            return Box::pin(async move { inner.call(req).await.map_err(Into::into) });
+        };
+
+        Box::pin(async move {
+            // Buckets without a website configuration keep answering as a regular S3 endpoint
+            let Ok((config, _)) = metadata_sys::get_website_config(&bucket).await else {
+                return inner.call(req).await.map_err(Into::into);
+            };
+
+            let (parts, _) = req.into_parts();
+            let response = serve_website(&bucket, &config, &parts).await;
+
+            Ok(response.map(|body| HybridBody::Rest {
+                rest_body: RestBody::from(body),
+            }))
+        })
+    }
+}
+
+/// Returns the bucket when the request is an anonymous GET/HEAD on a bucket virtual host.
+fn website_bucket<B>(host: &MultiDomain, req: &HttpRequest<B>) -> Option<String> {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return None;
+    }
+
+    if is_admin_path(req.uri().path()) || req.headers().contains_key(header::AUTHORIZATION) {
+        return None;
+    }
+
+    // Presigned requests and requests with a sub-resource or API parameter (`?acl`, `?list-type=2`, ...)
+    // are S3 API requests
+    if req.uri().query().is_some_and(is_s3_api_query) {
+        return None;
+    }
+
+    let host_header = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| req.uri().authority().map(|a| a.as_str()))?;
+
+    let vh = host.parse_host_header(strip_port(host_header)).ok()?;
+    vh.bucket().map(str::to_owned)
+}
+
+/// Query parameters that only appear on S3 API requests.
+const S3_API_PARAMS: &[&str] = &[
+    "accelerate",
+    "acl",
+    "analytics",
+    "attributes",
+    "continuation-token",
+    "cors",
+    "delete",
+    "delimiter",
+    "encoding-type",
+    "encryption",
+    "fetch-owner",
+    "intelligent-tiering",
+    "inventory",
+    "key-marker",
+    "legal-hold",
+    "lifecycle",
+    "list-type",
+    "location",
+    "logging",
+    "marker",
+    "max-keys",
+    "max-uploads",
+    "metrics",
+    "notification",
+    "object-lock",
+    "ownershipControls",
+    "partNumber",
+    "policy",
+    "policyStatus",
+    "prefix",
+    "publicAccessBlock",
+    "replication",
+    "requestPayment",
+    "restore",
+    "retention",
+    "select",
+    "start-after",
+    "tagging",
+    "torrent",
+    "upload-id-marker",
+    "uploadId",
+    "uploads",
+    "version-id-marker",
+    "versionId",
+    "versioning",
+    "versions",
+    "website",
+    "AWSAccessKeyId",
+    "Signature",
+    "Expires",
+];
+
+fn is_s3_api_query(query: &str) -> bool {
+    query.split('&').any(|kv| {
+        let name = kv.split_once('=').map_or(kv, |(name, _)| name);
+        name.starts_with("X-Amz-") || name.starts_with("x-amz-") || name.starts_with("response-") || S3_API_PARAMS.contains(&name)
+    })
+}
+
+async fn serve_website(bucket: &str, config: &WebsiteConfiguration, parts: &Parts) -> Response<Body> {
+    if let Some(redirect_all) = &config.redirect_all_requests_to {
+        let path_and_query = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        let location = match &redirect_all.protocol {
+            Some(protocol) => format!("{}://{}{}", protocol.as_str(), redirect_all.host_name, path_and_query),
+            None => format!("//{}{}", redirect_all.host_name, path_and_query),
+        };
+        return redirect_response(StatusCode::MOVED_PERMANENTLY, &location);
+    }
+
+    let path = urlencoding::decode(parts.uri.path())
+        .map(|p| p.into_owned())
+        .unwrap_or_else(|_| parts.uri.path().to_owned());
+    let key = index_key(config, path.trim_start_matches('/'));
+
+    if let Some(rule) = find_routing_rule(config, &key, None) {
+        return routing_redirect(rule, &key);
+    }
+
+    let err = match get_object(bucket, &key, parts, true).await {
+        Ok(response) => return response,
+        Err(err) => err,
+    };
+
+    let status = error_status(err.code());
+    debug!("website request for {}/{} failed: {:?}", bucket, key, err);
+
+    // "dir" without a trailing slash is redirected to "dir/" when it has an index document
+    if status == StatusCode::NOT_FOUND && !key.is_empty() && !key.ends_with('/') {
+        if let Some(index) = &config.index_document {
+            if object_exists(bucket, &format!("{}/{}", key, index.suffix)).await {
+                return redirect_response(StatusCode::FOUND, &format!("/{}/", encode_key(&key)));
+            }
+        }
+    }
+
+    if let Some(rule) = find_routing_rule(config, &key, Some(status)) {
+        return routing_redirect(rule, &key);
+    }
+
+    if let Some(error_document) = &config.error_document {
+        match get_object(bucket, &error_document.key, parts, false).await {
+            Ok(mut response) => {
+                *response.status_mut() = status;
+                return response;
+            }
+            Err(err) => warn!("website error document {}/{} unavailable: {:?}", bucket, error_document.key, err),
+        }
+    }
+
+    error_response(status, err.code(), err.message().unwrap_or_default(), parts.method == Method::HEAD)
+}
+
+/// Appends the index document suffix to directory-style keys.
+fn index_key(config: &WebsiteConfiguration, key: &str) -> String {
+    match &config.index_document {
+        Some(index) if key.is_empty() || key.ends_with('/') => format!("{}{}", key, index.suffix),
+        _ => key.to_owned(),
+    }
+}
+
+/// Finds the first routing rule whose condition matches the key and, when given, the returned error code.
+fn find_routing_rule<'a>(config: &'a WebsiteConfiguration, key: &str, status: Option<StatusCode>) -> Option<&'a RoutingRule> {
+    config.routing_rules.as_ref()?.iter().find(|rule| {
+        let Some(condition) = &rule.condition else {
+            // A rule without a condition redirects every request
+            return status.is_none();
+        };
+
+        let prefix_matches = condition
+            .key_prefix_equals
+            .as_deref()
+            .is_none_or(|prefix| key.starts_with(prefix));
+
+        let code_matches = match (&condition.http_error_code_returned_equals, status) {
+            (Some(code), Some(status)) => code.trim() == status.as_str(),
+            (Some(_), None) => false,
+            (None, status) => status.is_none(),
+        };
+
+        prefix_matches && code_matches
+    })
+}
+
+/// Builds the redirect location for a matched routing rule.
+fn routing_location(rule: &RoutingRule, key: &str) -> String {
+    let redirect = &rule.redirect;
+
+    let new_key = if let Some(replace_key) = &redirect.replace_key_with {
+        replace_key.clone()
+    } else if let Some(replace_prefix) = &redirect.replace_key_prefix_with {
+        let prefix = rule
+            .condition
+            .as_ref()
+            .and_then(|c| c.key_prefix_equals.as_deref())
+            .unwrap_or_default();
+        format!("{}{}", replace_prefix, key.strip_prefix(prefix).unwrap_or(key))
+    } else {
+        key.to_owned()
+    };
+
+    let path = format!("/{}", encode_key(&new_key));
+
+    match (&redirect.host_name, &redirect.protocol) {
+        (Some(host), Some(protocol)) => format!("{}://{}{}", protocol.as_str(), host, path),
+        (Some(host), None) => format!("//{host}{path}"),
+        (None, _) => path,
+    }
+}
+
+fn routing_redirect(rule: &RoutingRule, key: &str) -> Response<Body> {
+    let status = rule
+        .redirect
+        .http_redirect_code
+        .as_deref()
+        .and_then(|code| code.trim().parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .filter(StatusCode::is_redirection)
+        .unwrap_or(StatusCode::MOVED_PERMANENTLY);
+
+    redirect_response(status, &routing_location(rule, key))
+}
+
+fn encode_key(key: &str) -> String {
+    key.split('/')
+        .map(|s| urlencoding::encode(s).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn error_status(code: &S3ErrorCode) -> StatusCode {
+    match code {
+        S3ErrorCode::NoSuchKey | S3ErrorCode::NoSuchBucket | S3ErrorCode::NoSuchVersion => StatusCode::NOT_FOUND,
+        S3ErrorCode::AccessDenied => StatusCode::FORBIDDEN,
+        S3ErrorCode::InvalidRange => StatusCode::RANGE_NOT_SATISFIABLE,
+        code => code.status_code().unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn object_exists(bucket: &str, key: &str) -> bool {
+    let Some(store) = new_object_layer_fn() else {
+        return false;
+    };
+
+    store
+        .get_object_info(bucket, key, &ObjectOptions::default())
+        .await
+        .is_ok_and(|info| !info.delete_marker)
+}
+
+/// Reads the object anonymously through the regular GetObject access check and read path.
+async fn get_object(bucket: &str, key: &str, parts: &Parts, with_range: bool) -> S3Result<Response<Body>> {
+    let range = parts
+        .headers
+        .get(header::RANGE)
+        .filter(|_| with_range)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Range::parse(v).ok());
+
+    let input = GetObjectInput::builder()
+        .bucket(bucket.to_owned())
+        .key(key.to_owned())
+        .range(range)
+        .build()
+        .map_err(|e| s3_error!(InternalError, "build GetObjectInput failed: {e}"))?;
+
+    let mut extensions = Extensions::new();
+    extensions.insert(ReqInfo::default());
+
+    let mut req = S3Request {
+        input,
+        method: Method::GET,
+        uri: parts.uri.clone(),
+        headers: parts.headers.clone(),
+        extensions,
+        credentials: None,
+        region: None,
+        service: None,
+    };
+
+    let fs = FS::new();
+    S3Access::get_object(&fs, &mut req).await?;
+    let output = S3::get_object(&fs, req).await?.output;
+
+    let mut response = Response::builder().status(if output.content_range.is_some() {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    });
+
+    if let Some(headers) = response.headers_mut() {
+        let mut insert = |name: header::HeaderName, value: Option<String>| {
+            if let Some(v) = value.and_then(|v| HeaderValue::from_str(&v).ok()) {
+                headers.insert(name, v);
+            }
+        };
+
+        insert(header::CONTENT_TYPE, output.content_type.map(|v| v.to_string()));
+        insert(header::CONTENT_LENGTH, output.content_length.map(|v| v.to_string()));
+        insert(header::CONTENT_RANGE, output.content_range);
+        insert(header::ACCEPT_RANGES, output.accept_ranges);
+        insert(header::CACHE_CONTROL, output.cache_control);
+        insert(header::CONTENT_ENCODING, output.content_encoding);
+        insert(header::CONTENT_DISPOSITION, output.content_disposition);
+        insert(
+            header::ETAG,
+            output
+                .e_tag
+                .map(|etag| if etag.starts_with('"') { etag } else { format!("\"{etag}\"") }),
+        );
+        insert(header::LAST_MODIFIED, output.last_modified.as_ref().and_then(format_http_date));
+    }
+
+    let body = match output.body {
+        Some(body) if parts.method != Method::HEAD => Body::from(body),
+        _ => Body::empty(),
+    };
+
+    response
+        .body(body)
+        .map_err(|e| s3_error!(InternalError, "build website response failed: {e}"))
+}
+
+fn format_http_date(ts: &Timestamp) -> Option<String> {
+    let mut buf = Vec::new();
+    ts.format(TimestampFormat::HttpDate, &mut buf).ok()?;
+    String::from_utf8(buf).ok()
+}
+
+fn redirect_response(status: StatusCode, location: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(header::LOCATION, location)
+        .body(Body::empty())
+        .expect("failed to build website redirect response")
+}
+
+fn error_response(status: StatusCode, code: &S3ErrorCode, message: &str, head: bool) -> Response<Body> {
+    let title = format!("{} {}", status.as_u16(), status.canonical_reason().unwrap_or_default());
+    let body = if head {
+        Body::empty()
+    } else {
+        Body::from(format!(
+            "<html>\n<head><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n<ul>\n<li>Code: {}</li>\n<li>Message: {}</li>\n</ul>\n</body>\n</html>\n",
+            code.as_str(),
+            message
+        ))
+    };
+
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .body(body)
+        .expect("failed to build website error response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use s3s::dto::{Condition, IndexDocument, Protocol, Redirect};
+
+    fn config(rules: Vec<RoutingRule>) -> WebsiteConfiguration {
+        WebsiteConfiguration {
+            error_document: None,
+            index_document: Some(IndexDocument {
+                suffix: "index.html".to_string(),
+            }),
+            redirect_all_requests_to: None,
+            routing_rules: Some(rules),
+        }
+    }
+
+    fn rule(prefix: Option<&str>, code: Option<&str>, redirect: Redirect) -> RoutingRule {
+        RoutingRule {
+            condition: Some(Condition {
+                http_error_code_returned_equals: code.map(str::to_owned),
+                key_prefix_equals: prefix.map(str::to_owned),
+            }),
+            redirect,
+        }
+    }
+
+    #[test]
+    fn test_index_key() {
+        let cfg = config(vec![]);
+        assert_eq!(index_key(&cfg, ""), "index.html");
+        assert_eq!(index_key(&cfg, "docs/"), "docs/index.html");
+        assert_eq!(index_key(&cfg, "docs/a.html"), "docs/a.html");
+    }
+
+    #[test]
+    fn test_find_routing_rule() {
+        let cfg = config(vec![
+            rule(
+                Some("docs/"),
+                None,
+                Redirect {
+                    replace_key_prefix_with: Some("documents/".to_string()),
+                    ..Default::default()
+                },
+            ),
+            rule(
+                None,
+                Some("404"),
+                Redirect {
+                    host_name: Some("example.com".to_string()),
+                    protocol: Some(Protocol::from_static(Protocol::HTTPS)),
+                    replace_key_with: Some("404.html".to_string()),
+                    http_redirect_code: Some("302".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let r = find_routing_rule(&cfg, "docs/a b.html", None).unwrap();
+        assert_eq!(routing_location(r, "docs/a b.html"), "/documents/a%20b.html");
+
+        assert!(find_routing_rule(&cfg, "images/a.png", None).is_none());
+
+        let r = find_routing_rule(&cfg, "images/a.png", Some(StatusCode::NOT_FOUND)).unwrap();
+        assert_eq!(routing_location(r, "images/a.png"), "https://example.com/404.html");
+        let response = routing_redirect(r, "images/a.png");
+        assert_eq!(response.status(), StatusCode::FOUND);
+
+        assert!(find_routing_rule(&cfg, "images/a.png", Some(StatusCode::FORBIDDEN)).is_none());
+    }
+
+    #[test]
+    fn test_website_bucket() {
+        let host = MultiDomain::new(["s3.example.com"]).unwrap();
+
+        let req = HttpRequest::builder()
+            .uri("/index.html")
+            .header(header::HOST, "site.s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req).as_deref(), Some("site"));
+
+        let req = HttpRequest::builder()
+            .uri("/index.html")
+            .header(header::HOST, "site.s3.example.com:9000")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req).as_deref(), Some("site"));
+
+        let req = HttpRequest::builder()
+            .uri("/index.html")
+            .header(header::HOST, "s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req), None);
+
+        let req = HttpRequest::builder()
+            .uri("/index.html?X-Amz-Credential=abc")
+            .header(header::HOST, "site.s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req), None);
+
+        let req = HttpRequest::builder()
+            .uri("/app.js?v=3")
+            .header(header::HOST, "site.s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req).as_deref(), Some("site"));
+
+        for query in ["list-type=2", "acl", "location", "versions", "uploads"] {
+            let req = HttpRequest::builder()
+                .uri(format!("/?{query}"))
+                .header(header::HOST, "site.s3.example.com")
+                .body(())
+                .unwrap();
+            assert_eq!(website_bucket(&host, &req), None);
+        }
+
+        let req = HttpRequest::builder()
+            .method(Method::PUT)
+            .uri("/index.html")
+            .header(header::HOST, "site.s3.example.com")
+            .body(())
+            .unwrap();
+        assert_eq!(website_bucket(&host, &req), None);
+    }
+}
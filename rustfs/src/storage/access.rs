@@ -15,6 +15,8 @@
 use super::ecfs::FS;
 use crate::auth::{check_key_valid, get_condition_values, get_session_token};
 use crate::license::license_check;
+use crate::server::AuthenticatedRequester;
+use rustfs_ecstore::bucket::inventory::destination_bucket as inventory_destination_bucket;
 use rustfs_ecstore::bucket::policy_sys::PolicySys;
 use rustfs_iam::error::Error as IamError;
 use rustfs_policy::auth;
@@ -50,7 +52,7 @@ pub async fn authorize_request<T>(req: &mut S3Request<T>, action: Action) -> S3R
         let claims = cred.claims.as_ref().unwrap_or(&default_claims);
         let conditions = get_condition_values(&req.headers, cred);
 
-        if action != Action::S3Action(S3Action::DeleteObjectAction)
+        if action == Action::S3Action(S3Action::DeleteObjectAction)
             && req_info.version_id.is_some()
             && iam_store
                 .is_allowed(&Args {
@@ -141,6 +143,43 @@ pub async fn authorize_request<T>(req: &mut S3Request<T>, action: Action) -> S3R
     Err(s3_error!(AccessDenied, "Access Denied"))
 }
 
+/// Authorizes the requester to have objects written under `prefix` in `bucket` on its behalf, e.g. access
+/// logs or inventory reports. PutObject must be granted by the requester's own policies or by the policy
+/// of the target bucket.
+pub async fn authorize_put_to_bucket<T>(req: &mut S3Request<T>, bucket: &str, prefix: &str) -> S3Result<()> {
+    let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+    req_info.bucket = Some(bucket.to_owned());
+    req_info.object = Some(prefix.to_owned());
+    req_info.version_id = None;
+
+    if authorize_request(req, Action::S3Action(S3Action::PutObjectAction))
+        .await
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    let req_info = req.extensions.get::<ReqInfo>().expect("ReqInfo not found");
+    if let Some(cred) = &req_info.cred {
+        let conditions = get_condition_values(&req.headers, cred);
+        if PolicySys::is_allowed(&BucketPolicyArgs {
+            bucket,
+            action: Action::S3Action(S3Action::PutObjectAction),
+            is_owner: req_info.is_owner,
+            account: &cred.access_key,
+            groups: &cred.groups,
+            conditions: &conditions,
+            object: prefix,
+        })
+        .await
+        {
+            return Ok(());
+        }
+    }
+
+    Err(s3_error!(AccessDenied, "Access Denied to the target bucket {}", bucket))
+}
+
 #[async_trait::async_trait]
 impl S3Access for FS {
     // /// Checks whether the current request has accesses to the resources.
@@ -173,6 +212,10 @@ impl S3Access for FS {
         let (cred, is_owner) = if let Some(input_cred) = cx.credentials() {
             let (cred, is_owner) =
                 check_key_valid(get_session_token(cx.uri(), cx.headers()).unwrap_or_default(), &input_cred.access_key).await?;
+            // Only an authenticated access key is recorded in the server access logs
+            if let Some(requester) = cx.extensions_mut().get::<AuthenticatedRequester>() {
+                requester.set(&cred.access_key);
+            }
             (Some(cred), is_owner)
         } else {
             (None, false)
@@ -321,9 +364,12 @@ impl S3Access for FS {
     /// This method returns `Ok(())` by default.
     async fn delete_bucket_inventory_configuration(
         &self,
-        _req: &mut S3Request<DeleteBucketInventoryConfigurationInput>,
+        req: &mut S3Request<DeleteBucketInventoryConfigurationInput>,
     ) -> S3Result<()> {
-        Ok(())
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::PutInventoryConfigurationAction)).await
     }
 
     /// Checks whether the DeleteBucketLifecycle request has accesses to the resources.
@@ -386,8 +432,11 @@ impl S3Access for FS {
     /// Checks whether the DeleteBucketWebsite request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn delete_bucket_website(&self, _req: &mut S3Request<DeleteBucketWebsiteInput>) -> S3Result<()> {
-        Ok(())
+    async fn delete_bucket_website(&self, req: &mut S3Request<DeleteBucketWebsiteInput>) -> S3Result<()> {
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::DeleteBucketWebsiteAction)).await
     }
 
     /// Checks whether the DeleteObject request has accesses to the resources.
@@ -493,9 +542,12 @@ impl S3Access for FS {
     /// This method returns `Ok(())` by default.
     async fn get_bucket_inventory_configuration(
         &self,
-        _req: &mut S3Request<GetBucketInventoryConfigurationInput>,
+        req: &mut S3Request<GetBucketInventoryConfigurationInput>,
     ) -> S3Result<()> {
-        Ok(())
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::GetInventoryConfigurationAction)).await
     }
 
     /// Checks whether the GetBucketLifecycleConfiguration request has accesses to the resources.
@@ -524,8 +576,11 @@ impl S3Access for FS {
     /// Checks whether the GetBucketLogging request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn get_bucket_logging(&self, _req: &mut S3Request<GetBucketLoggingInput>) -> S3Result<()> {
-        Ok(())
+    async fn get_bucket_logging(&self, req: &mut S3Request<GetBucketLoggingInput>) -> S3Result<()> {
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::GetBucketLoggingAction)).await
     }
 
     /// Checks whether the GetBucketMetricsConfiguration request has accesses to the resources.
@@ -615,8 +670,11 @@ impl S3Access for FS {
     /// Checks whether the GetBucketWebsite request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn get_bucket_website(&self, _req: &mut S3Request<GetBucketWebsiteInput>) -> S3Result<()> {
-        Ok(())
+    async fn get_bucket_website(&self, req: &mut S3Request<GetBucketWebsiteInput>) -> S3Result<()> {
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::GetBucketWebsiteAction)).await
     }
 
     /// Checks whether the GetObject request has accesses to the resources.
@@ -770,9 +828,12 @@ impl S3Access for FS {
     /// This method returns `Ok(())` by default.
     async fn list_bucket_inventory_configurations(
         &self,
-        _req: &mut S3Request<ListBucketInventoryConfigurationsInput>,
+        req: &mut S3Request<ListBucketInventoryConfigurationsInput>,
     ) -> S3Result<()> {
-        Ok(())
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::GetInventoryConfigurationAction)).await
     }
 
     /// Checks whether the ListBucketMetricsConfigurations request has accesses to the resources.
@@ -902,9 +963,16 @@ impl S3Access for FS {
     /// This method returns `Ok(())` by default.
     async fn put_bucket_inventory_configuration(
         &self,
-        _req: &mut S3Request<PutBucketInventoryConfigurationInput>,
+        req: &mut S3Request<PutBucketInventoryConfigurationInput>,
     ) -> S3Result<()> {
-        Ok(())
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::PutInventoryConfigurationAction)).await?;
+
+        // The requester must be allowed to write the reports to the destination bucket
+        let dest = req.input.inventory_configuration.destination.s3_bucket_destination.clone();
+        authorize_put_to_bucket(req, inventory_destination_bucket(&dest), dest.prefix.as_deref().unwrap_or_default()).await
     }
 
     /// Checks whether the PutBucketLifecycleConfiguration request has accesses to the resources.
@@ -923,8 +991,17 @@ impl S3Access for FS {
     /// Checks whether the PutBucketLogging request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn put_bucket_logging(&self, _req: &mut S3Request<PutBucketLoggingInput>) -> S3Result<()> {
-        Ok(())
+    async fn put_bucket_logging(&self, req: &mut S3Request<PutBucketLoggingInput>) -> S3Result<()> {
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::PutBucketLoggingAction)).await?;
+
+        // The requester must be allowed to write the logs to the target bucket
+        let Some(logging_enabled) = req.input.bucket_logging_status.logging_enabled.clone() else {
+            return Ok(());
+        };
+        authorize_put_to_bucket(req, &logging_enabled.target_bucket, &logging_enabled.target_prefix).await
     }
 
     /// Checks whether the PutBucketMetricsConfiguration request has accesses to the resources.
@@ -1004,8 +1081,11 @@ impl S3Access for FS {
     /// Checks whether the PutBucketWebsite request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn put_bucket_website(&self, _req: &mut S3Request<PutBucketWebsiteInput>) -> S3Result<()> {
-        Ok(())
+    async fn put_bucket_website(&self, req: &mut S3Request<PutBucketWebsiteInput>) -> S3Result<()> {
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+        req_info.bucket = Some(req.input.bucket.clone());
+
+        authorize_request(req, Action::S3Action(S3Action::PutBucketWebsiteAction)).await
     }
 
     /// Checks whether the PutObject request has accesses to the resources.
@@ -1124,8 +1204,30 @@ impl S3Access for FS {
     /// Checks whether the UploadPartCopy request has accesses to the resources.
     ///
     /// This method returns `Ok(())` by default.
-    async fn upload_part_copy(&self, _req: &mut S3Request<UploadPartCopyInput>) -> S3Result<()> {
-        Ok(())
+    async fn upload_part_copy(&self, req: &mut S3Request<UploadPartCopyInput>) -> S3Result<()> {
+        {
+            let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+            let (src_bucket, src_key, version_id) = match &req.input.copy_source {
+                CopySource::AccessPoint { .. } => return Err(s3_error!(NotImplemented)),
+                CopySource::Bucket { bucket, key, version_id } => {
+                    (bucket.to_string(), key.to_string(), version_id.as_ref().map(|v| v.to_string()))
+                }
+            };
+
+            req_info.bucket = Some(src_bucket);
+            req_info.object = Some(src_key);
+            req_info.version_id = version_id;
+
+            authorize_request(req, Action::S3Action(S3Action::GetObjectAction)).await?;
+        }
+
+        let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+
+        req_info.bucket = Some(req.input.bucket.clone());
+        req_info.object = Some(req.input.key.clone());
+        req_info.version_id = None;
+
+        authorize_request(req, Action::S3Action(S3Action::PutObjectAction)).await
     }
 
     /// Checks whether the WriteGetObjectResponse request has accesses to the resources.
@@ -13,14 +13,24 @@
 // limitations under the License.
 
 use super::access::authorize_request;
+use super::options::check_copy_source_preconditions;
+use super::options::check_preconditions;
+use super::options::checksum_dto;
 use super::options::del_opts;
 use super::options::extract_metadata;
+use super::options::get_object_lock_metadata;
+use super::options::get_write_preconditions;
+use super::options::object_checksum;
 use super::options::put_opts;
+use super::options::{
+    get_content_checksum, get_content_md5, get_multipart_checksum, get_trailing_checksum, require_content_md5, verify_content_md5,
+};
 use crate::auth::get_condition_values;
 use crate::error::ApiError;
 use crate::storage::access::ReqInfo;
 use crate::storage::options::copy_dst_opts;
 use crate::storage::options::copy_src_opts;
+use crate::storage::options::parse_copy_source_range;
 use crate::storage::options::{extract_metadata_from_mime, get_opts};
 use bytes::Bytes;
 use chrono::DateTime;
@@ -37,18 +47,32 @@ use rustfs_s3select_api::server::dbms::DatabaseManagerSystem;
 // use rustfs_ecstore::store_api::RESERVED_METADATA_PREFIX;
 use futures::StreamExt;
 use http::HeaderMap;
-use rustfs_ecstore::bucket::lifecycle::bucket_lifecycle_ops::validate_transition_tier;
+use rustfs_ecstore::bucket::inventory::report::{Field as InventoryField, ReportFormat};
+use rustfs_ecstore::bucket::inventory::{
+    MAX_INVENTORY_CONFIGURATIONS, destination_bucket as inventory_destination_bucket, get_inventory_configurations,
+};
+use rustfs_ecstore::bucket::lifecycle::bucket_lifecycle_ops::{RestoreObjectRequest, validate_transition_tier};
 use rustfs_ecstore::bucket::lifecycle::lifecycle::Lifecycle;
+use rustfs_ecstore::bucket::lifecycle::lifecycle::TRANSITION_COMPLETE;
+use rustfs_ecstore::bucket::metadata::BUCKET_CORS_CONFIG;
+use rustfs_ecstore::bucket::metadata::BUCKET_INVENTORY_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_LIFECYCLE_CONFIG;
+use rustfs_ecstore::bucket::metadata::BUCKET_LOGGING_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_NOTIFICATION_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_POLICY_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_REPLICATION_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_SSECONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_TAGGING_CONFIG;
 use rustfs_ecstore::bucket::metadata::BUCKET_VERSIONING_CONFIG;
+use rustfs_ecstore::bucket::metadata::BUCKET_WEBSITE_CONFIG;
 use rustfs_ecstore::bucket::metadata::OBJECT_LOCK_CONFIG;
 use rustfs_ecstore::bucket::metadata_sys;
+use rustfs_ecstore::bucket::object_lock::objectlock::{parse_legalhold_status, parse_ret_mode};
+use rustfs_ecstore::bucket::object_lock::objectlock_sys::{
+    BucketObjectLockSys, enforce_retention_bypass_for_deletion, is_retention_update_allowed,
+};
 use rustfs_ecstore::bucket::policy_sys::PolicySys;
+use rustfs_ecstore::bucket::quota::BucketQuotaSys;
 use rustfs_ecstore::bucket::tagging::decode_tags;
 use rustfs_ecstore::bucket::tagging::encode_tags;
 use rustfs_ecstore::bucket::utils::serialize;
@@ -58,9 +82,14 @@ use rustfs_ecstore::cmd::bucket_replication::ReplicationType;
 use rustfs_ecstore::cmd::bucket_replication::get_must_replicate_options;
 use rustfs_ecstore::cmd::bucket_replication::must_replicate;
 use rustfs_ecstore::cmd::bucket_replication::schedule_replication;
+use rustfs_ecstore::cmd::site_replication::GLOBAL_SITE_REPLICATION_SYS;
 use rustfs_ecstore::compress::MIN_COMPRESSIBLE_SIZE;
 use rustfs_ecstore::compress::is_compressible;
+use rustfs_ecstore::encryption;
+use rustfs_ecstore::encryption::SseType;
 use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::error::{is_err_object_not_found, is_err_version_not_found};
+use rustfs_ecstore::kms::get_global_kms;
 use rustfs_ecstore::new_object_layer_fn;
 use rustfs_ecstore::set_disk::DEFAULT_READ_BUFFER_SIZE;
 use rustfs_ecstore::store_api::BucketOptions;
@@ -76,15 +105,19 @@ use rustfs_ecstore::store_api::PutObjReader;
 use rustfs_ecstore::store_api::StorageAPI;
 use rustfs_filemeta::headers::RESERVED_METADATA_PREFIX_LOWER;
 use rustfs_filemeta::headers::{AMZ_DECODED_CONTENT_LENGTH, AMZ_OBJECT_TAGGING};
+use rustfs_filemeta::headers::{RUSTFS_MULTIPART_CHECKSUM, RUSTFS_MULTIPART_CHECKSUM_TYPE};
+use rustfs_filemeta::{X_AMZ_RESTORE_EXPIRY_DAYS, X_AMZ_RESTORE_REQUEST_DATE};
 use rustfs_notify::EventName;
 use rustfs_policy::auth;
 use rustfs_policy::policy::action::Action;
 use rustfs_policy::policy::action::S3Action;
 use rustfs_policy::policy::{BucketPolicy, BucketPolicyArgs, Validator};
+use rustfs_rio::ChecksumReader;
 use rustfs_rio::CompressReader;
 use rustfs_rio::EtagReader;
 use rustfs_rio::HashReader;
 use rustfs_rio::Reader;
+use rustfs_rio::TrailingChecksum;
 use rustfs_rio::WarpReader;
 use rustfs_s3select_query::instance::make_rustfsms;
 use rustfs_utils::CompressionAlgorithm;
@@ -94,7 +127,9 @@ use s3s::S3;
 use s3s::S3Error;
 use s3s::S3ErrorCode;
 use s3s::S3Result;
+use s3s::TrailingHeaders;
 use s3s::dto::*;
+use s3s::header::{X_AMZ_OBJECT_LOCK_LEGAL_HOLD, X_AMZ_OBJECT_LOCK_MODE, X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE, X_AMZ_RESTORE};
 use s3s::s3_error;
 use s3s::{S3Request, S3Response};
 use std::collections::HashMap;
@@ -105,6 +140,7 @@ use std::sync::Arc;
 use std::sync::LazyLock;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
+use tokio::io::AsyncReadExt;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_tar::Archive;
@@ -213,6 +249,10 @@ impl FS {
 
                 println!("Extracted: {fpath}, size {size}");
 
+                BucketQuotaSys::enforce_hard_quota(&bucket, size)
+                    .await
+                    .map_err(ApiError::from)?;
+
                 let mut reader: Box<dyn Reader> = Box::new(WarpReader::new(f));
 
                 let mut metadata = HashMap::new();
@@ -320,6 +360,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .make_bucket_hook(&bucket, object_lock_enabled_for_bucket.is_some_and(|v| v))
+            .await
+            .map_err(ApiError::from)?;
+
         let output = CreateBucketOutput::default();
 
         let event_args = rustfs_notify::event::EventArgs {
@@ -386,7 +431,7 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        let h = HeaderMap::new();
+        let h = encryption::copy_source_headers(&req.headers);
 
         let gr = store
             .get_object_reader(&src_bucket, &src_key, None, h, &get_opts)
@@ -395,6 +440,14 @@ impl S3 for FS {
 
         let mut src_info = gr.object_info.clone();
 
+        check_copy_source_preconditions(
+            &src_info,
+            req.input.copy_source_if_match.as_deref(),
+            req.input.copy_source_if_none_match.as_deref(),
+            req.input.copy_source_if_modified_since.clone(),
+            req.input.copy_source_if_unmodified_since.clone(),
+        )?;
+
         if cp_src_dst_same {
             src_info.metadata_only = true;
         }
@@ -432,11 +485,52 @@ impl S3 for FS {
                 .remove(&format!("{RESERVED_METADATA_PREFIX_LOWER}compression-size"));
         }
 
+        // Metadata-only copies keep the stored data, and with it the source encryption and object lock.
+        let mut object_encryption = None;
+        if !cp_src_dst_same {
+            for k in [
+                X_AMZ_OBJECT_LOCK_MODE,
+                X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE,
+                X_AMZ_OBJECT_LOCK_LEGAL_HOLD,
+            ] {
+                src_info.user_defined.remove(k.as_str());
+            }
+            let lock_metadata = get_object_lock_metadata(
+                &bucket,
+                req.input.object_lock_mode.as_ref().map(|v| v.as_str()),
+                req.input.object_lock_retain_until_date.clone(),
+                req.input.object_lock_legal_hold_status.as_ref().map(|v| v.as_str()),
+            )
+            .await?;
+            src_info.user_defined.extend(lock_metadata);
+
+            encryption::remove_encryption_metadata(&mut src_info.user_defined);
+
+            object_encryption = encryption::new_object_encryption(&bucket, &key, &req.headers)
+                .await
+                .map_err(ApiError::from)?;
+
+            if let Some((enc, enc_metadata)) = &object_encryption {
+                src_info.user_defined.extend(enc_metadata.clone());
+                src_info
+                    .user_defined
+                    .insert(format!("{RESERVED_METADATA_PREFIX_LOWER}actual-size",), actual_size.to_string());
+
+                reader = encryption::encrypt_reader(reader, length, actual_size, &enc.key).map_err(ApiError::from)?;
+                length = -1;
+            }
+        }
+
         let hrd = HashReader::new(reader, length, actual_size, None, false).map_err(ApiError::from)?;
 
         src_info.put_object_reader = Some(PutObjReader::new(hrd));
 
-        // check quota
+        if !src_info.metadata_only {
+            BucketQuotaSys::enforce_hard_quota(&bucket, actual_size)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
         // TODO: src metadada
 
         for (k, v) in compress_metadata {
@@ -458,8 +552,17 @@ impl S3 for FS {
             ..Default::default()
         };
 
+        let sse = match &object_encryption {
+            Some((enc, _)) => SseResponse::new_object(enc),
+            None => SseResponse::object(&oi.user_defined, &req.headers),
+        };
+
         let output = CopyObjectOutput {
             copy_object_result: Some(copy_object_result),
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
             ..Default::default()
         };
 
@@ -507,6 +610,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .delete_bucket_hook(&input.bucket)
+            .await
+            .map_err(ApiError::from)?;
+
         let event_args = rustfs_notify::event::EventArgs {
             event_name: EventName::BucketRemoved,
             bucket_name: input.bucket,
@@ -529,8 +637,13 @@ impl S3 for FS {
     /// Delete an object
     #[tracing::instrument(level = "debug", skip(self, req))]
     async fn delete_object(&self, req: S3Request<DeleteObjectInput>) -> S3Result<S3Response<DeleteObjectOutput>> {
+        let mut req = req;
         let DeleteObjectInput {
-            bucket, key, version_id, ..
+            bucket,
+            key,
+            version_id,
+            bypass_governance_retention,
+            ..
         } = req.input.clone();
 
         let metadata = extract_metadata(&req.headers);
@@ -539,6 +652,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        if BucketObjectLockSys::enabled(&bucket).await {
+            let bypass_governance = bypass_governance_allowed(&mut req, bypass_governance_retention, &bucket, &key).await;
+            check_object_lock_for_deletion(&bucket, &key, &opts, bypass_governance).await?;
+        }
+
         let version_id = opts.version_id.as_ref().map(|v| Uuid::parse_str(v).ok()).unwrap_or_default();
         let dobj = ObjectToDelete {
             object_name: key.clone(),
@@ -606,9 +724,17 @@ impl S3 for FS {
     async fn delete_objects(&self, req: S3Request<DeleteObjectsInput>) -> S3Result<S3Response<DeleteObjectsOutput>> {
         // info!("delete_objects args {:?}", req.input);
 
-        let DeleteObjectsInput { bucket, delete, .. } = req.input;
+        let mut req = req;
+        let DeleteObjectsInput {
+            bucket,
+            delete,
+            bypass_governance_retention,
+            ..
+        } = req.input.clone();
+
+        verify_content_md5(&req.headers, &req.extensions)?;
 
-        let objects: Vec<ObjectToDelete> = delete
+        let mut objects: Vec<ObjectToDelete> = delete
             .objects
             .iter()
             .map(|v| {
@@ -630,6 +756,29 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        let mut errors = Vec::new();
+        if BucketObjectLockSys::enabled(&bucket).await {
+            let mut allowed = Vec::with_capacity(objects.len());
+            for obj in objects {
+                let obj_opts = ObjectOptions {
+                    version_id: obj.version_id.map(|v| v.to_string()),
+                    ..opts.clone()
+                };
+                let bypass_governance =
+                    bypass_governance_allowed(&mut req, bypass_governance_retention, &bucket, &obj.object_name).await;
+                match check_object_lock_for_deletion(&bucket, &obj.object_name, &obj_opts, bypass_governance).await {
+                    Ok(()) => allowed.push(obj),
+                    Err(err) => errors.push(Error {
+                        code: Some(err.code().as_str().to_owned()),
+                        key: Some(obj.object_name),
+                        message: err.message().map(str::to_owned),
+                        version_id: obj_opts.version_id,
+                    }),
+                }
+            }
+            objects = allowed;
+        }
+
         let (dobjs, errs) = store.delete_objects(&bucket, objects, opts).await.map_err(ApiError::from)?;
 
         let deleted = dobjs
@@ -649,7 +798,7 @@ impl S3 for FS {
 
         let output = DeleteObjectsOutput {
             deleted: Some(deleted),
-            // errors,
+            errors: (!errors.is_empty()).then_some(errors),
             ..Default::default()
         };
         // Asynchronous call will not block the response of the current request
@@ -734,7 +883,8 @@ impl S3 for FS {
 
         // let range = HTTPRangeSpec::nil();
 
-        let h = HeaderMap::new();
+        // SSE-C keys are needed to decrypt the object.
+        let h = req.headers.clone();
 
         let part_number = part_number.map(|v| v as usize);
 
@@ -775,7 +925,19 @@ impl S3 for FS {
             .map_err(ApiError::from)?;
 
         let info = reader.object_info;
+        check_preconditions(
+            &info,
+            req.input.if_match.as_deref(),
+            req.input.if_none_match.as_deref(),
+            req.input.if_modified_since.clone(),
+            req.input.if_unmodified_since.clone(),
+        )?;
         let event_info = info.clone();
+        let checksum = if rs.is_none() {
+            object_checksum(&info, req.input.checksum_mode.as_ref(), part_number)
+        } else {
+            None
+        };
         let content_type = {
             if let Some(content_type) = &info.content_type {
                 match ContentType::from_str(content_type) {
@@ -816,13 +978,35 @@ impl S3 for FS {
             content_length as usize,
         )));
 
+        let sse = SseResponse::object(&info.user_defined, &req.headers);
+
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
+        } = checksum_dto(checksum.as_ref());
+
         let output = GetObjectOutput {
             body,
             content_length: Some(content_length),
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
             last_modified,
             content_type,
             accept_ranges: Some("bytes".to_string()),
             content_range,
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
+            restore: info.user_defined.get(X_AMZ_RESTORE.as_str()).cloned(),
             ..Default::default()
         };
 
@@ -913,6 +1097,19 @@ impl S3 for FS {
 
         let info = store.get_object_info(&bucket, &key, &opts).await.map_err(ApiError::from)?;
 
+        // Encrypted objects are only visible to requests that could decrypt them.
+        encryption::object_key(&bucket, &key, &info.user_defined, &req.headers)
+            .await
+            .map_err(ApiError::from)?;
+
+        check_preconditions(
+            &info,
+            req.input.if_match.as_deref(),
+            req.input.if_none_match.as_deref(),
+            req.input.if_modified_since.clone(),
+            req.input.if_unmodified_since.clone(),
+        )?;
+
         // warn!("head_object info {:?}", &info);
         let event_info = info.clone();
         let content_type = {
@@ -935,15 +1132,55 @@ impl S3 for FS {
 
         let content_length = info.get_actual_size().map_err(ApiError::from)?;
 
-        let metadata = info.user_defined;
+        let sse = SseResponse::object(&info.user_defined, &req.headers);
+
+        let checksum = if rs.is_none() {
+            object_checksum(&info, req.input.checksum_mode.as_ref(), part_number)
+        } else {
+            None
+        };
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
+        } = checksum_dto(checksum.as_ref());
+
+        let restore = info.user_defined.get(X_AMZ_RESTORE.as_str()).cloned();
+
+        // Internal metadata such as sealed encryption keys is never returned to clients,
+        // and the restore state is surfaced through its own header.
+        let metadata = info
+            .user_defined
+            .into_iter()
+            .filter(|(k, _)| {
+                !k.to_lowercase().starts_with(RESERVED_METADATA_PREFIX_LOWER)
+                    && ![X_AMZ_RESTORE.as_str(), X_AMZ_RESTORE_EXPIRY_DAYS, X_AMZ_RESTORE_REQUEST_DATE]
+                        .iter()
+                        .any(|h| h.eq_ignore_ascii_case(k))
+            })
+            .collect();
 
         let output = HeadObjectOutput {
             content_length: Some(content_length),
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
             content_type,
             last_modified,
             e_tag: info.etag,
             metadata: Some(metadata),
             version_id: info.version_id.map(|v| v.to_string()),
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
+            restore,
             // metadata: object_metadata,
             ..Default::default()
         };
@@ -1162,7 +1399,7 @@ impl S3 for FS {
                 ObjectVersion {
                     key: Some(v.name.to_owned()),
                     last_modified: v.mod_time.map(Timestamp::from),
-                    size: Some(v.size),
+                    size: Some(v.get_actual_size().unwrap_or_default()),
                     version_id: v.version_id.map(|v| v.to_string()),
                     is_latest: Some(v.is_latest),
                     e_tag: v.etag.clone(),
@@ -1220,11 +1457,31 @@ impl S3 for FS {
             tagging,
             metadata,
             version_id,
+            object_lock_mode,
+            object_lock_retain_until_date,
+            object_lock_legal_hold_status,
+            if_match,
+            if_none_match,
             ..
         } = input;
 
         let Some(body) = body else { return Err(s3_error!(IncompleteBody)) };
 
+        let write_preconditions = get_write_preconditions(if_match.as_deref(), if_none_match.as_deref())?;
+
+        // Object lock settings can only be applied to uploads carrying an integrity header.
+        if object_lock_mode.is_some() || object_lock_retain_until_date.is_some() || object_lock_legal_hold_status.is_some() {
+            require_content_md5(&req.headers)?;
+        }
+
+        let lock_metadata = get_object_lock_metadata(
+            &bucket,
+            object_lock_mode.as_ref().map(|v| v.as_str()),
+            object_lock_retain_until_date,
+            object_lock_legal_hold_status.as_ref().map(|v| v.as_str()),
+        )
+        .await?;
+
         let mut size = match content_length {
             Some(c) => c,
             None => {
@@ -1239,6 +1496,10 @@ impl S3 for FS {
             }
         };
 
+        BucketQuotaSys::enforce_hard_quota(&bucket, size)
+            .await
+            .map_err(ApiError::from)?;
+
         let body = StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))));
 
         // let body = Box::new(StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string())))));
@@ -1252,15 +1513,31 @@ impl S3 for FS {
         let mut metadata = metadata.unwrap_or_default();
 
         extract_metadata_from_mime(&req.headers, &mut metadata);
+        metadata.extend(lock_metadata);
 
         if let Some(tags) = tagging {
             metadata.insert(AMZ_OBJECT_TAGGING.to_owned(), tags);
         }
 
+        let want_checksum = get_content_checksum(&req.headers)?;
+        let trailing_checksum = get_trailing_checksum(&req.headers)?.map(TrailingChecksum::new);
+        let content_md5 = get_content_md5(&req.headers)?;
+
         let mut reader: Box<dyn Reader> = Box::new(WarpReader::new(body));
 
+        if let Some(checksum) = &want_checksum {
+            reader = Box::new(ChecksumReader::new(reader, checksum.clone()));
+        } else if let Some(trailer) = &trailing_checksum {
+            reader = trailing_checksum_reader(reader, trailer.clone(), req.trailing_headers.clone());
+        }
+
         let actual_size = size;
 
+        // Content-MD5 covers the data as sent, so it is verified before compression and encryption.
+        if content_md5.is_some() {
+            reader = Box::new(HashReader::new(reader, size, actual_size, content_md5, false).map_err(ApiError::from)?);
+        }
+
         if is_compressible(&req.headers, &key) && size > MIN_COMPRESSIBLE_SIZE as i64 {
             metadata.insert(
                 format!("{RESERVED_METADATA_PREFIX_LOWER}compression"),
@@ -1274,7 +1551,18 @@ impl S3 for FS {
             size = -1;
         }
 
-        // TODO: md5 check
+        let object_encryption = encryption::new_object_encryption(&bucket, &key, &req.headers)
+            .await
+            .map_err(ApiError::from)?;
+
+        if let Some((enc, enc_metadata)) = &object_encryption {
+            metadata.extend(enc_metadata.clone());
+            metadata.insert(format!("{RESERVED_METADATA_PREFIX_LOWER}actual-size",), actual_size.to_string());
+
+            reader = encryption::encrypt_reader(reader, size, actual_size, &enc.key).map_err(ApiError::from)?;
+            size = -1;
+        }
+
         let reader = HashReader::new(reader, size, actual_size, None, false).map_err(ApiError::from)?;
 
         let mut reader = PutObjReader::new(reader);
@@ -1285,6 +1573,9 @@ impl S3 for FS {
         let mut opts: ObjectOptions = put_opts(&bucket, &key, version_id, &req.headers, mt)
             .await
             .map_err(ApiError::from)?;
+        opts.want_checksum = want_checksum.clone();
+        opts.trailing_checksum = trailing_checksum.clone();
+        opts.write_preconditions = write_preconditions;
 
         let repoptions =
             get_must_replicate_options(&mt2, "", ReplicationStatusType::Unknown, ReplicationType::ObjectReplicationType, &opts);
@@ -1317,8 +1608,32 @@ impl S3 for FS {
             schedule_replication(obj_info, objectlayer.unwrap(), dsc, 1).await;
         }
 
+        let sse = match &object_encryption {
+            Some((enc, _)) => SseResponse::new_object(enc),
+            None => SseResponse::default(),
+        };
+
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
+        } = checksum_dto(want_checksum.or_else(|| trailing_checksum.and_then(|t| t.get())).as_ref());
+
         let output = PutObjectOutput {
             e_tag,
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
             ..Default::default()
         };
 
@@ -1351,9 +1666,24 @@ impl S3 for FS {
             key,
             tagging,
             version_id,
+            checksum_algorithm,
+            checksum_type,
+            object_lock_mode,
+            object_lock_retain_until_date,
+            object_lock_legal_hold_status,
             ..
         } = req.input.clone();
 
+        let checksum = get_multipart_checksum(checksum_algorithm.as_ref(), checksum_type.as_ref())?;
+
+        let lock_metadata = get_object_lock_metadata(
+            &bucket,
+            object_lock_mode.as_ref().map(|v| v.as_str()),
+            object_lock_retain_until_date,
+            object_lock_legal_hold_status.as_ref().map(|v| v.as_str()),
+        )
+        .await?;
+
         // mc cp step 3
 
         // debug!("create_multipart_upload meta {:?}", &metadata);
@@ -1363,6 +1693,7 @@ impl S3 for FS {
         };
 
         let mut metadata = extract_metadata(&req.headers);
+        metadata.extend(lock_metadata);
 
         if let Some(tags) = tagging {
             metadata.insert(AMZ_OBJECT_TAGGING.to_owned(), tags);
@@ -1375,6 +1706,24 @@ impl S3 for FS {
             );
         }
 
+        // The object key is sealed into the upload metadata, each part is encrypted with it.
+        let object_encryption = encryption::new_object_encryption(&bucket, &key, &req.headers)
+            .await
+            .map_err(ApiError::from)?;
+        if let Some((_, enc_metadata)) = &object_encryption {
+            metadata.extend(enc_metadata.clone());
+        }
+
+        if let Some((checksum_type, full_object)) = checksum {
+            metadata.insert(RUSTFS_MULTIPART_CHECKSUM.to_owned(), checksum_type.as_str().to_owned());
+            let checksum_type = if full_object {
+                ChecksumType::FULL_OBJECT
+            } else {
+                ChecksumType::COMPOSITE
+            };
+            metadata.insert(RUSTFS_MULTIPART_CHECKSUM_TYPE.to_owned(), checksum_type.to_owned());
+        }
+
         let opts: ObjectOptions = put_opts(&bucket, &key, version_id, &req.headers, metadata)
             .await
             .map_err(ApiError::from)?;
@@ -1385,10 +1734,26 @@ impl S3 for FS {
             .map_err(ApiError::from)?;
         let object_name = key.clone();
         let bucket_name = bucket.clone();
+        let sse = match &object_encryption {
+            Some((enc, _)) => SseResponse::new_object(enc),
+            None => SseResponse::default(),
+        };
         let output = CreateMultipartUploadOutput {
             bucket: Some(bucket),
             key: Some(key),
             upload_id: Some(upload_id),
+            checksum_algorithm: checksum.map(|(t, _)| ChecksumAlgorithm::from(t.as_str().to_owned())),
+            checksum_type: checksum.map(|(_, full_object)| {
+                ChecksumType::from_static(if full_object {
+                    ChecksumType::FULL_OBJECT
+                } else {
+                    ChecksumType::COMPOSITE
+                })
+            }),
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
             ..Default::default()
         };
 
@@ -1428,7 +1793,6 @@ impl S3 for FS {
             upload_id,
             part_number,
             content_length,
-            // content_md5,
             ..
         } = req.input;
 
@@ -1451,11 +1815,23 @@ impl S3 for FS {
             }
         };
 
+        BucketQuotaSys::enforce_hard_quota(&bucket, size)
+            .await
+            .map_err(ApiError::from)?;
+
         let body = StreamReader::new(body.map(|f| f.map_err(|e| std::io::Error::other(e.to_string()))));
 
         // mc cp step 4
 
-        let opts = ObjectOptions::default();
+        let want_checksum = get_content_checksum(&req.headers)?;
+        let trailing_checksum = get_trailing_checksum(&req.headers)?.map(TrailingChecksum::new);
+        let content_md5 = get_content_md5(&req.headers)?;
+
+        let opts = ObjectOptions {
+            want_checksum: want_checksum.clone(),
+            trailing_checksum: trailing_checksum.clone(),
+            ..Default::default()
+        };
 
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
@@ -1470,10 +1846,37 @@ impl S3 for FS {
             .user_defined
             .contains_key(format!("{RESERVED_METADATA_PREFIX_LOWER}compression").as_str());
 
+        // Parts of an upload created with a checksum algorithm must carry a checksum of that algorithm.
+        if let Some(algorithm) = fi.user_defined.get(RUSTFS_MULTIPART_CHECKSUM) {
+            let got = want_checksum
+                .as_ref()
+                .map(|c| c.checksum_type)
+                .or(trailing_checksum.as_ref().map(|t| t.checksum_type))
+                .map_or("null", |t| t.as_str());
+            if !algorithm.eq_ignore_ascii_case(got) {
+                return Err(s3_error!(
+                    InvalidRequest,
+                    "Checksum Type mismatch occurred, expected checksum Type: {}, actual checksum Type: {}",
+                    algorithm.to_lowercase(),
+                    got.to_lowercase()
+                ));
+            }
+        }
+
         let mut reader: Box<dyn Reader> = Box::new(WarpReader::new(body));
 
+        if let Some(checksum) = &want_checksum {
+            reader = Box::new(ChecksumReader::new(reader, checksum.clone()));
+        } else if let Some(trailer) = &trailing_checksum {
+            reader = trailing_checksum_reader(reader, trailer.clone(), req.trailing_headers.clone());
+        }
+
         let actual_size = size;
 
+        if content_md5.is_some() {
+            reader = Box::new(HashReader::new(reader, size, actual_size, content_md5, false).map_err(ApiError::from)?);
+        }
+
         if is_compressible {
             let hrd = HashReader::new(reader, size, actual_size, None, false).map_err(ApiError::from)?;
 
@@ -1481,7 +1884,14 @@ impl S3 for FS {
             size = -1;
         }
 
-        // TODO: md5 check
+        if let Some(object_key) = encryption::object_key(&bucket, &key, &fi.user_defined, &req.headers)
+            .await
+            .map_err(ApiError::from)?
+        {
+            reader = encryption::encrypt_reader(reader, size, actual_size, &object_key).map_err(ApiError::from)?;
+            size = -1;
+        }
+
         let reader = HashReader::new(reader, size, actual_size, None, false).map_err(ApiError::from)?;
 
         let mut reader = PutObjReader::new(reader);
@@ -1491,8 +1901,28 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        let sse = SseResponse::object(&fi.user_defined, &req.headers);
+
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            ..
+        } = checksum_dto(want_checksum.or_else(|| trailing_checksum.and_then(|t| t.get())).as_ref());
+
         let output = UploadPartOutput {
             e_tag: info.etag,
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            server_side_encryption: sse.server_side_encryption,
+            sse_customer_algorithm: sse.sse_customer_algorithm,
+            sse_customer_key_md5: sse.sse_customer_key_md5,
+            ssekms_key_id: sse.ssekms_key_id,
             ..Default::default()
         };
 
@@ -1501,11 +1931,197 @@ impl S3 for FS {
 
     #[tracing::instrument(level = "debug", skip(self, req))]
     async fn upload_part_copy(&self, req: S3Request<UploadPartCopyInput>) -> S3Result<S3Response<UploadPartCopyOutput>> {
-        let _input = req.input;
+        let UploadPartCopyInput {
+            bucket,
+            key,
+            copy_source,
+            copy_source_range,
+            copy_source_if_match,
+            copy_source_if_none_match,
+            copy_source_if_modified_since,
+            copy_source_if_unmodified_since,
+            part_number,
+            upload_id,
+            ..
+        } = req.input;
+
+        let (src_bucket, src_key, src_version_id) = match copy_source {
+            CopySource::AccessPoint { .. } => return Err(s3_error!(NotImplemented)),
+            CopySource::Bucket { bucket, key, version_id } => {
+                (bucket.to_string(), key.to_string(), version_id.map(|v| v.to_string()))
+            }
+        };
+
+        if part_number < 1 || part_number as usize > MAX_PARTS_COUNT {
+            return Err(s3_error!(
+                InvalidArgument,
+                "Part number must be an integer between 1 and {}",
+                MAX_PARTS_COUNT
+            ));
+        }
+        let part_id = part_number as usize;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let mut src_opts = get_opts(&src_bucket, &src_key, src_version_id, None, &req.headers)
+            .await
+            .map_err(ApiError::from)?;
+
+        let mut dst_opts = ObjectOptions::default();
+
+        let mi = store
+            .get_multipart_info(&bucket, &key, &upload_id, &dst_opts)
+            .await
+            .map_err(ApiError::from)?;
+
+        // Parts of an upload created with a checksum algorithm carry a checksum of that algorithm.
+        let part_checksum = mi
+            .user_defined
+            .get(RUSTFS_MULTIPART_CHECKSUM)
+            .and_then(|algorithm| rustfs_rio::ChecksumType::from_algorithm(algorithm))
+            .map(TrailingChecksum::new);
+        dst_opts.trailing_checksum = part_checksum.clone();
+
+        let src_info = store
+            .get_object_info(&src_bucket, &src_key, &src_opts)
+            .await
+            .map_err(ApiError::from)?;
+
+        if src_info.delete_marker {
+            return Err(s3_error!(NoSuchKey));
+        }
+
+        check_copy_source_preconditions(
+            &src_info,
+            copy_source_if_match.as_deref(),
+            copy_source_if_none_match.as_deref(),
+            copy_source_if_modified_since,
+            copy_source_if_unmodified_since,
+        )?;
+
+        let src_size = src_info.get_actual_size().map_err(ApiError::from)?;
+
+        let (start_offset, length) = match copy_source_range {
+            Some(range) => parse_copy_source_range(&range, src_size)?,
+            None => (0, src_size),
+        };
+
+        BucketQuotaSys::enforce_hard_quota(&bucket, length)
+            .await
+            .map_err(ApiError::from)?;
+
+        // Read the exact source version we validated above, even if a newer one lands meanwhile.
+        if src_opts.version_id.is_none() {
+            src_opts.version_id = src_info.version_id.map(|v| v.to_string());
+        }
+
+        let is_src_compressed = src_info.is_compressed();
+
+        // Compressed objects can only be read from the start, so the range is applied after decompression.
+        let rs = if is_src_compressed || length == src_size {
+            None
+        } else {
+            Some(HTTPRangeSpec {
+                is_suffix_length: false,
+                start: start_offset,
+                end: start_offset + length - 1,
+            })
+        };
+
+        let gr = store
+            .get_object_reader(&src_bucket, &src_key, rs, encryption::copy_source_headers(&req.headers), &src_opts)
+            .await
+            .map_err(ApiError::from)?;
+
+        let mut stream = gr.stream;
+
+        if is_src_compressed && start_offset > 0 {
+            tokio::io::copy(&mut (&mut stream).take(start_offset as u64), &mut tokio::io::sink())
+                .await
+                .map_err(ApiError::from)?;
+        }
+
+        let mut reader: Box<dyn Reader> = Box::new(WarpReader::new(stream.take(length as u64)));
+
+        if let Some(checksum) = &part_checksum {
+            reader = Box::new(ChecksumReader::computing(reader, checksum.clone()));
+        }
+
+        let mut size = length;
+        let actual_size = length;
+
+        let is_dst_compressed = mi
+            .user_defined
+            .contains_key(format!("{RESERVED_METADATA_PREFIX_LOWER}compression").as_str());
+
+        if is_dst_compressed {
+            let hrd = HashReader::new(reader, size, actual_size, None, false).map_err(ApiError::from)?;
+
+            reader = Box::new(CompressReader::new(hrd, CompressionAlgorithm::default()));
+            size = -1;
+        }
+
+        if let Some(object_key) = encryption::object_key(&bucket, &key, &mi.user_defined, &req.headers)
+            .await
+            .map_err(ApiError::from)?
+        {
+            reader = encryption::encrypt_reader(reader, size, actual_size, &object_key).map_err(ApiError::from)?;
+            size = -1;
+        }
+
+        let reader = HashReader::new(reader, size, actual_size, None, false).map_err(ApiError::from)?;
+
+        let mut src_info = src_info;
+        src_info.put_object_reader = Some(PutObjReader::new(reader));
+
+        let info = store
+            .copy_object_part(
+                &src_bucket,
+                &src_key,
+                &bucket,
+                &key,
+                &upload_id,
+                part_id,
+                &mut src_info,
+                &src_opts,
+                &dst_opts,
+            )
+            .await
+            .map_err(ApiError::from)?;
+
+        let copy_source_version_id = if src_opts.versioned || src_opts.version_suspended {
+            src_info.version_id.map(|v| v.to_string())
+        } else {
+            None
+        };
 
-        let _output = UploadPartCopyOutput { ..Default::default() };
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            ..
+        } = checksum_dto(part_checksum.and_then(|t| t.get()).as_ref());
+
+        let output = UploadPartCopyOutput {
+            copy_part_result: Some(CopyPartResult {
+                e_tag: info.etag,
+                last_modified: info.last_mod.map(Timestamp::from),
+                checksum_crc32,
+                checksum_crc32c,
+                checksum_crc64nvme,
+                checksum_sha1,
+                checksum_sha256,
+                ..Default::default()
+            }),
+            copy_source_version_id,
+            ..Default::default()
+        };
 
-        unimplemented!("upload_part_copy");
+        Ok(S3Response::new(output))
     }
 
     #[tracing::instrument(level = "debug", skip(self, req))]
@@ -1629,6 +2245,8 @@ impl S3 for FS {
             bucket,
             key,
             upload_id,
+            if_match,
+            if_none_match,
             ..
         } = req.input;
 
@@ -1637,7 +2255,11 @@ impl S3 for FS {
 
         let Some(multipart_upload) = multipart_upload else { return Err(s3_error!(InvalidPart)) };
 
-        let opts = &ObjectOptions::default();
+        let opts = &ObjectOptions {
+            want_checksum: get_content_checksum(&req.headers)?,
+            write_preconditions: get_write_preconditions(if_match.as_deref(), if_none_match.as_deref())?,
+            ..Default::default()
+        };
 
         let mut uploaded_parts = Vec::new();
 
@@ -1649,16 +2271,52 @@ impl S3 for FS {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
+        if BucketQuotaSys::get(&bucket).await.map_err(ApiError::from)?.is_some() {
+            let parts = store
+                .list_object_parts(&bucket, &key, &upload_id, None, MAX_PARTS_COUNT, opts)
+                .await
+                .map_err(ApiError::from)?;
+            let size: i64 = parts
+                .parts
+                .iter()
+                .filter(|p| uploaded_parts.iter().any(|u| u.part_num == p.part_num))
+                .map(|p| p.actual_size)
+                .sum();
+
+            BucketQuotaSys::enforce_hard_quota(&bucket, size)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
         let obj_info = store
             .complete_multipart_upload(&bucket, &key, &upload_id, uploaded_parts, opts)
             .await
             .map_err(ApiError::from)?;
 
+        let sse = SseResponse::object(&obj_info.user_defined, &req.headers);
+
+        let Checksum {
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
+        } = checksum_dto(obj_info.decoded_checksum().as_ref());
+
         let output = CompleteMultipartUploadOutput {
             bucket: Some(bucket.clone()),
             key: Some(key.clone()),
             e_tag: obj_info.etag.clone(),
+            checksum_crc32,
+            checksum_crc32c,
+            checksum_crc64nvme,
+            checksum_sha1,
+            checksum_sha256,
+            checksum_type,
             location: Some("us-east-1".to_string()),
+            server_side_encryption: sse.server_side_encryption,
+            ssekms_key_id: sse.ssekms_key_id,
             ..Default::default()
         };
 
@@ -1736,7 +2394,12 @@ impl S3 for FS {
 
         let data = try_!(serialize(&tagging));
 
-        metadata_sys::update(&bucket, BUCKET_TAGGING_CONFIG, data)
+        metadata_sys::update(&bucket, BUCKET_TAGGING_CONFIG, data.clone())
+            .await
+            .map_err(ApiError::from)?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_TAGGING_CONFIG, Some(&data))
             .await
             .map_err(ApiError::from)?;
 
@@ -1754,40 +2417,475 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_TAGGING_CONFIG, None)
+            .await
+            .map_err(ApiError::from)?;
+
         Ok(S3Response::new(DeleteBucketTaggingOutput {}))
     }
 
-    #[tracing::instrument(level = "debug", skip(self, req))]
-    async fn put_object_tagging(&self, req: S3Request<PutObjectTaggingInput>) -> S3Result<S3Response<PutObjectTaggingOutput>> {
-        let PutObjectTaggingInput {
-            bucket,
-            key: object,
-            tagging,
-            ..
-        } = req.input.clone();
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_bucket_cors(&self, req: S3Request<GetBucketCorsInput>) -> S3Result<S3Response<GetBucketCorsOutput>> {
+        let GetBucketCorsInput { bucket, .. } = req.input;
 
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        let tags = encode_tags(tagging.tag_set);
-
-        // TODO: getOpts
-        // TODO: Replicate
-
         store
-            .put_object_tags(&bucket, &object, &tags, &ObjectOptions::default())
+            .get_bucket_info(&bucket, &BucketOptions::default())
             .await
             .map_err(ApiError::from)?;
 
-        let version_id = match req.input.version_id {
-            Some(v) => v.to_string(),
-            None => String::new(),
+        let cfg = match metadata_sys::get_cors_config(&bucket).await {
+            Ok((cfg, _)) => cfg,
+            Err(err) => {
+                if err == StorageError::ConfigNotFound {
+                    return Err(s3_error!(NoSuchCORSConfiguration));
+                }
+                return Err(ApiError::from(err).into());
+            }
         };
-        let event_args = rustfs_notify::event::EventArgs {
-            event_name: EventName::ObjectCreatedPutTagging,
-            bucket_name: bucket.clone(),
-            object: rustfs_ecstore::store_api::ObjectInfo {
+
+        Ok(S3Response::new(GetBucketCorsOutput {
+            cors_rules: Some(cfg.cors_rules),
+        }))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn put_bucket_cors(&self, req: S3Request<PutBucketCorsInput>) -> S3Result<S3Response<PutBucketCorsOutput>> {
+        let PutBucketCorsInput {
+            bucket,
+            cors_configuration,
+            ..
+        } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        if cors_configuration.cors_rules.is_empty() || cors_configuration.cors_rules.len() > 100 {
+            return Err(s3_error!(MalformedXML, "CORS configuration must contain between 1 and 100 rules"));
+        }
+
+        for rule in cors_configuration.cors_rules.iter() {
+            if rule.allowed_origins.is_empty() || rule.allowed_methods.is_empty() {
+                return Err(s3_error!(MalformedXML, "CORS rule must specify AllowedOrigin and AllowedMethod"));
+            }
+
+            if let Some(method) = rule
+                .allowed_methods
+                .iter()
+                .find(|m| !matches!(m.as_str(), "GET" | "PUT" | "HEAD" | "POST" | "DELETE"))
+            {
+                return Err(s3_error!(
+                    InvalidRequest,
+                    "Found unsupported HTTP method in CORS config. Unsupported method is {}",
+                    method
+                ));
+            }
+
+            if rule.allowed_origins.iter().any(|o| o.matches('*').count() > 1) {
+                return Err(s3_error!(InvalidRequest, "AllowedOrigin can not have more than one wildcard"));
+            }
+        }
+
+        let data = try_!(serialize(&cors_configuration));
+
+        metadata_sys::update(&bucket, BUCKET_CORS_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(PutBucketCorsOutput::default()))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn delete_bucket_cors(&self, req: S3Request<DeleteBucketCorsInput>) -> S3Result<S3Response<DeleteBucketCorsOutput>> {
+        let DeleteBucketCorsInput { bucket, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        metadata_sys::delete(&bucket, BUCKET_CORS_CONFIG)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(DeleteBucketCorsOutput {}))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_bucket_website(&self, req: S3Request<GetBucketWebsiteInput>) -> S3Result<S3Response<GetBucketWebsiteOutput>> {
+        let GetBucketWebsiteInput { bucket, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let cfg = match metadata_sys::get_website_config(&bucket).await {
+            Ok((cfg, _)) => cfg,
+            Err(err) => {
+                if err == StorageError::ConfigNotFound {
+                    return Err(s3_error!(NoSuchWebsiteConfiguration));
+                }
+                return Err(ApiError::from(err).into());
+            }
+        };
+
+        Ok(S3Response::new(GetBucketWebsiteOutput {
+            error_document: cfg.error_document,
+            index_document: cfg.index_document,
+            redirect_all_requests_to: cfg.redirect_all_requests_to,
+            routing_rules: cfg.routing_rules,
+        }))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn put_bucket_website(&self, req: S3Request<PutBucketWebsiteInput>) -> S3Result<S3Response<PutBucketWebsiteOutput>> {
+        let PutBucketWebsiteInput {
+            bucket,
+            website_configuration,
+            ..
+        } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        if website_configuration.redirect_all_requests_to.is_some() {
+            if website_configuration.index_document.is_some()
+                || website_configuration.error_document.is_some()
+                || website_configuration.routing_rules.is_some()
+            {
+                return Err(s3_error!(
+                    InvalidArgument,
+                    "RedirectAllRequestsTo cannot be provided in conjunction with other Routing/Redirection rules"
+                ));
+            }
+        } else {
+            let Some(index) = &website_configuration.index_document else {
+                return Err(s3_error!(
+                    InvalidArgument,
+                    "A value for IndexDocument Suffix must be provided if RedirectAllRequestsTo is empty"
+                ));
+            };
+            if index.suffix.is_empty() || index.suffix.contains('/') {
+                return Err(s3_error!(InvalidArgument, "The IndexDocument Suffix is not well formed"));
+            }
+        }
+
+        let data = try_!(serialize(&website_configuration));
+
+        metadata_sys::update(&bucket, BUCKET_WEBSITE_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(PutBucketWebsiteOutput::default()))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn delete_bucket_website(
+        &self,
+        req: S3Request<DeleteBucketWebsiteInput>,
+    ) -> S3Result<S3Response<DeleteBucketWebsiteOutput>> {
+        let DeleteBucketWebsiteInput { bucket, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        metadata_sys::delete(&bucket, BUCKET_WEBSITE_CONFIG)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(DeleteBucketWebsiteOutput {}))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_bucket_logging(&self, req: S3Request<GetBucketLoggingInput>) -> S3Result<S3Response<GetBucketLoggingOutput>> {
+        let GetBucketLoggingInput { bucket, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        // Logging is disabled when no configuration is stored
+        let logging_enabled = match metadata_sys::get_logging_config(&bucket).await {
+            Ok((cfg, _)) => cfg.logging_enabled,
+            Err(err) => {
+                if err != StorageError::ConfigNotFound {
+                    return Err(ApiError::from(err).into());
+                }
+                None
+            }
+        };
+
+        Ok(S3Response::new(GetBucketLoggingOutput { logging_enabled }))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn put_bucket_logging(&self, req: S3Request<PutBucketLoggingInput>) -> S3Result<S3Response<PutBucketLoggingOutput>> {
+        let PutBucketLoggingInput {
+            bucket,
+            bucket_logging_status,
+            ..
+        } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        // An empty BucketLoggingStatus disables logging
+        let Some(logging_enabled) = &bucket_logging_status.logging_enabled else {
+            metadata_sys::delete(&bucket, BUCKET_LOGGING_CONFIG)
+                .await
+                .map_err(ApiError::from)?;
+            return Ok(S3Response::new(PutBucketLoggingOutput::default()));
+        };
+
+        if store
+            .get_bucket_info(&logging_enabled.target_bucket, &BucketOptions::default())
+            .await
+            .is_err()
+        {
+            return Err(s3_error!(InvalidTargetBucketForLogging, "The target bucket for logging does not exist"));
+        }
+
+        let data = try_!(serialize(&bucket_logging_status));
+
+        metadata_sys::update(&bucket, BUCKET_LOGGING_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(PutBucketLoggingOutput::default()))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn get_bucket_inventory_configuration(
+        &self,
+        req: S3Request<GetBucketInventoryConfigurationInput>,
+    ) -> S3Result<S3Response<GetBucketInventoryConfigurationOutput>> {
+        let GetBucketInventoryConfigurationInput { bucket, id, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let configs = get_inventory_configurations(&bucket).await.map_err(ApiError::from)?;
+        let Some(inventory_configuration) = configs.into_iter().find(|c| c.id == id) else {
+            return Err(no_such_inventory_configuration());
+        };
+
+        Ok(S3Response::new(GetBucketInventoryConfigurationOutput {
+            inventory_configuration: Some(inventory_configuration),
+        }))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn list_bucket_inventory_configurations(
+        &self,
+        req: S3Request<ListBucketInventoryConfigurationsInput>,
+    ) -> S3Result<S3Response<ListBucketInventoryConfigurationsOutput>> {
+        let ListBucketInventoryConfigurationsInput { bucket, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        // All configurations fit in one page
+        let configs = get_inventory_configurations(&bucket).await.map_err(ApiError::from)?;
+
+        Ok(S3Response::new(ListBucketInventoryConfigurationsOutput {
+            inventory_configuration_list: if configs.is_empty() { None } else { Some(configs) },
+            is_truncated: Some(false),
+            ..Default::default()
+        }))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn put_bucket_inventory_configuration(
+        &self,
+        req: S3Request<PutBucketInventoryConfigurationInput>,
+    ) -> S3Result<S3Response<PutBucketInventoryConfigurationOutput>> {
+        let PutBucketInventoryConfigurationInput {
+            bucket,
+            id,
+            inventory_configuration,
+            ..
+        } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        if inventory_configuration.id != id {
+            return Err(s3_error!(
+                InvalidArgument,
+                "The inventory configuration id does not match the id parameter"
+            ));
+        }
+
+        let dest = &inventory_configuration.destination.s3_bucket_destination;
+        if ReportFormat::parse(dest.format.as_str()).is_none() {
+            return Err(s3_error!(InvalidArgument, "Unsupported inventory format: {}", dest.format.as_str()));
+        }
+        for field in inventory_configuration.optional_fields.iter().flatten() {
+            if InventoryField::from_optional(field.as_str()).is_none() {
+                return Err(s3_error!(InvalidArgument, "Unsupported inventory optional field: {}", field.as_str()));
+            }
+        }
+        if store
+            .get_bucket_info(inventory_destination_bucket(dest), &BucketOptions::default())
+            .await
+            .is_err()
+        {
+            return Err(s3_error!(InvalidArgument, "The destination bucket of the inventory does not exist"));
+        }
+
+        let mut configs = get_inventory_configurations(&bucket).await.map_err(ApiError::from)?;
+        configs.retain(|c| c.id != id);
+        if configs.len() >= MAX_INVENTORY_CONFIGURATIONS {
+            return Err(S3Error::with_message(
+                S3ErrorCode::Custom("TooManyConfigurations".into()),
+                format!("A bucket can have at most {MAX_INVENTORY_CONFIGURATIONS} inventory configurations"),
+            ));
+        }
+        configs.push(inventory_configuration);
+
+        let data = try_!(serialize(&ListBucketInventoryConfigurationsOutput {
+            inventory_configuration_list: Some(configs),
+            ..Default::default()
+        }));
+
+        metadata_sys::update(&bucket, BUCKET_INVENTORY_CONFIG, data)
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(S3Response::new(PutBucketInventoryConfigurationOutput::default()))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn delete_bucket_inventory_configuration(
+        &self,
+        req: S3Request<DeleteBucketInventoryConfigurationInput>,
+    ) -> S3Result<S3Response<DeleteBucketInventoryConfigurationOutput>> {
+        let DeleteBucketInventoryConfigurationInput { bucket, id, .. } = req.input;
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        store
+            .get_bucket_info(&bucket, &BucketOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let mut configs = get_inventory_configurations(&bucket).await.map_err(ApiError::from)?;
+        let before = configs.len();
+        configs.retain(|c| c.id != id);
+        if configs.len() == before {
+            return Err(no_such_inventory_configuration());
+        }
+
+        if configs.is_empty() {
+            metadata_sys::delete(&bucket, BUCKET_INVENTORY_CONFIG)
+                .await
+                .map_err(ApiError::from)?;
+        } else {
+            let data = try_!(serialize(&ListBucketInventoryConfigurationsOutput {
+                inventory_configuration_list: Some(configs),
+                ..Default::default()
+            }));
+            metadata_sys::update(&bucket, BUCKET_INVENTORY_CONFIG, data)
+                .await
+                .map_err(ApiError::from)?;
+        }
+
+        Ok(S3Response::new(DeleteBucketInventoryConfigurationOutput::default()))
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, req))]
+    async fn put_object_tagging(&self, req: S3Request<PutObjectTaggingInput>) -> S3Result<S3Response<PutObjectTaggingOutput>> {
+        let PutObjectTaggingInput {
+            bucket,
+            key: object,
+            tagging,
+            ..
+        } = req.input.clone();
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let tags = encode_tags(tagging.tag_set);
+
+        // TODO: getOpts
+        // TODO: Replicate
+
+        store
+            .put_object_tags(&bucket, &object, &tags, &ObjectOptions::default())
+            .await
+            .map_err(ApiError::from)?;
+
+        let version_id = match req.input.version_id {
+            Some(v) => v.to_string(),
+            None => String::new(),
+        };
+        let event_args = rustfs_notify::event::EventArgs {
+            event_name: EventName::ObjectCreatedPutTagging,
+            bucket_name: bucket.clone(),
+            object: rustfs_ecstore::store_api::ObjectInfo {
                 name: object.clone(),
                 bucket,
                 ..Default::default()
@@ -1909,17 +3007,31 @@ impl S3 for FS {
         } = req.input;
 
         // TODO: check other sys
-        // check site replication enable
         // check bucket object lock enable
         // check replication suspended
 
+        if GLOBAL_SITE_REPLICATION_SYS.is_enabled().await
+            && versioning_configuration
+                .status
+                .as_ref()
+                .is_some_and(|v| v.as_str() == BucketVersioningStatus::SUSPENDED)
+        {
+            return Err(s3_error!(
+                InvalidBucketState,
+                "versioning cannot be suspended while site replication is enabled"
+            ));
+        }
+
         let data = try_!(serialize(&versioning_configuration));
 
-        metadata_sys::update(&bucket, BUCKET_VERSIONING_CONFIG, data)
+        metadata_sys::update(&bucket, BUCKET_VERSIONING_CONFIG, data.clone())
             .await
             .map_err(ApiError::from)?;
 
-        // TODO: globalSiteReplicationSys.BucketMetaHook
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_VERSIONING_CONFIG, Some(&data))
+            .await
+            .map_err(ApiError::from)?;
 
         Ok(S3Response::new(PutBucketVersioningOutput {}))
     }
@@ -2025,7 +3137,12 @@ impl S3 for FS {
 
         let data = serde_json::to_vec(&cfg).map_err(|e| s3_error!(InternalError, "parse policy failed {:?}", e))?;
 
-        metadata_sys::update(&bucket, BUCKET_POLICY_CONFIG, data)
+        metadata_sys::update(&bucket, BUCKET_POLICY_CONFIG, data.clone())
+            .await
+            .map_err(ApiError::from)?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_POLICY_CONFIG, Some(&data))
             .await
             .map_err(ApiError::from)?;
 
@@ -2051,6 +3168,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_POLICY_CONFIG, None)
+            .await
+            .map_err(ApiError::from)?;
+
         Ok(S3Response::new(DeleteBucketPolicyOutput {}))
     }
 
@@ -2127,7 +3249,12 @@ impl S3 for FS {
         }
 
         let data = try_!(serialize(&input_cfg));
-        metadata_sys::update(&bucket, BUCKET_LIFECYCLE_CONFIG, data)
+        metadata_sys::update(&bucket, BUCKET_LIFECYCLE_CONFIG, data.clone())
+            .await
+            .map_err(ApiError::from)?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_LIFECYCLE_CONFIG, Some(&data))
             .await
             .map_err(ApiError::from)?;
 
@@ -2154,6 +3281,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_LIFECYCLE_CONFIG, None)
+            .await
+            .map_err(ApiError::from)?;
+
         Ok(S3Response::new(DeleteBucketLifecycleOutput::default()))
     }
 
@@ -2209,10 +3341,36 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
-        // TODO: check kms
+        for rule in &server_side_encryption_configuration.rules {
+            let Some(by_default) = &rule.apply_server_side_encryption_by_default else {
+                continue;
+            };
+            let algo = by_default.sse_algorithm.as_str();
+            if algo != ServerSideEncryption::AES256 && algo != ServerSideEncryption::AWS_KMS {
+                return Err(s3_error!(InvalidArgument, "The encryption method specified is not supported: {algo}"));
+            }
+
+            // Both SSE-S3 and SSE-KMS objects get their keys from the KMS.
+            let Some(kms) = get_global_kms() else {
+                return Err(ApiError::from(StorageError::KmsNotConfigured).into());
+            };
+            if let Some(key_id) = by_default.kms_master_key_id.as_deref().filter(|id| !id.is_empty()) {
+                if algo != ServerSideEncryption::AWS_KMS {
+                    return Err(s3_error!(InvalidArgument, "KMSMasterKeyID can only be set for aws:kms encryption"));
+                }
+                if !kms.key_exists(key_id).await.map_err(ApiError::from)? {
+                    return Err(ApiError::from(StorageError::KmsKeyNotFound(key_id.to_owned())).into());
+                }
+            }
+        }
 
         let data = try_!(serialize(&server_side_encryption_configuration));
-        metadata_sys::update(&bucket, BUCKET_SSECONFIG, data)
+        metadata_sys::update(&bucket, BUCKET_SSECONFIG, data.clone())
+            .await
+            .map_err(ApiError::from)?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_SSECONFIG, Some(&data))
             .await
             .map_err(ApiError::from)?;
         Ok(S3Response::new(PutBucketEncryptionOutput::default()))
@@ -2236,6 +3394,11 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, BUCKET_SSECONFIG, None)
+            .await
+            .map_err(ApiError::from)?;
+
         Ok(S3Response::new(DeleteBucketEncryptionOutput::default()))
     }
 
@@ -2272,6 +3435,8 @@ impl S3 for FS {
             ..
         } = req.input;
 
+        verify_content_md5(&req.headers, &req.extensions)?;
+
         let Some(input_cfg) = object_lock_configuration else { return Err(s3_error!(InvalidArgument)) };
 
         let Some(store) = new_object_layer_fn() else {
@@ -2285,7 +3450,12 @@ impl S3 for FS {
 
         let data = try_!(serialize(&input_cfg));
 
-        metadata_sys::update(&bucket, OBJECT_LOCK_CONFIG, data)
+        metadata_sys::update(&bucket, OBJECT_LOCK_CONFIG, data.clone())
+            .await
+            .map_err(ApiError::from)?;
+
+        GLOBAL_SITE_REPLICATION_SYS
+            .bucket_meta_hook(&bucket, OBJECT_LOCK_CONFIG, Some(&data))
             .await
             .map_err(ApiError::from)?;
 
@@ -2579,22 +3749,97 @@ impl S3 for FS {
         &self,
         req: S3Request<GetObjectAttributesInput>,
     ) -> S3Result<S3Response<GetObjectAttributesOutput>> {
-        let GetObjectAttributesInput { bucket, key, .. } = req.input.clone();
+        let GetObjectAttributesInput {
+            bucket,
+            key,
+            version_id,
+            max_parts,
+            part_number_marker,
+            object_attributes,
+            ..
+        } = req.input.clone();
 
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
-        if let Err(e) = store
-            .get_object_reader(&bucket, &key, None, HeaderMap::new(), &ObjectOptions::default())
+        let opts: ObjectOptions = get_opts(&bucket, &key, version_id, None, &req.headers)
             .await
-        {
-            return Err(S3Error::with_message(S3ErrorCode::InternalError, format!("{e}")));
-        }
+            .map_err(ApiError::from)?;
+
+        let info = store.get_object_info(&bucket, &key, &opts).await.map_err(ApiError::from)?;
+
+        // The attributes may also arrive as a single comma separated header value.
+        let wants = |attr: &str| {
+            object_attributes
+                .iter()
+                .flat_map(|v| v.as_str().split(','))
+                .any(|v| v.trim() == attr)
+        };
+
+        let checksum = info.decoded_checksum();
+
+        let object_parts = if wants(ObjectAttributes::OBJECT_PARTS) && info.is_multipart() {
+            let marker = part_number_marker.unwrap_or_default();
+            let max_parts = max_parts.unwrap_or(MAX_PARTS_COUNT as i32).max(0) as usize;
+            let remaining: Vec<_> = info
+                .parts
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.number as i32 > marker)
+                .collect();
+
+            let parts: Vec<ObjectPart> = remaining
+                .iter()
+                .take(max_parts)
+                .map(|(i, p)| {
+                    let part_checksum = checksum
+                        .as_ref()
+                        .and_then(|c| rustfs_rio::Checksum::new(c.checksum_type, c.parts.get(*i)?));
+                    let Checksum {
+                        checksum_crc32,
+                        checksum_crc32c,
+                        checksum_crc64nvme,
+                        checksum_sha1,
+                        checksum_sha256,
+                        ..
+                    } = checksum_dto(part_checksum.as_ref());
+
+                    ObjectPart {
+                        part_number: Some(p.number as i32),
+                        size: Some(p.actual_size),
+                        checksum_crc32,
+                        checksum_crc32c,
+                        checksum_crc64nvme,
+                        checksum_sha1,
+                        checksum_sha256,
+                    }
+                })
+                .collect();
+
+            Some(GetObjectAttributesParts {
+                is_truncated: Some(remaining.len() > parts.len()),
+                max_parts: Some(max_parts as i32),
+                next_part_number_marker: parts.last().and_then(|p| p.part_number),
+                part_number_marker: Some(marker),
+                total_parts_count: Some(info.parts.len() as i32),
+                parts: Some(parts),
+            })
+        } else {
+            None
+        };
 
         let output = GetObjectAttributesOutput {
-            delete_marker: None,
-            object_parts: None,
+            checksum: wants(ObjectAttributes::CHECKSUM)
+                .then(|| checksum.as_ref().map(|c| checksum_dto(Some(c))))
+                .flatten(),
+            e_tag: wants(ObjectAttributes::ETAG).then(|| info.etag.clone()).flatten(),
+            object_size: wants(ObjectAttributes::OBJECT_SIZE)
+                .then(|| info.get_actual_size().ok())
+                .flatten(),
+            last_modified: info.mod_time.map(Timestamp::from),
+            version_id: info.version_id.map(|v| v.to_string()),
+            object_parts,
             ..Default::default()
         };
         let version_id = match req.input.version_id {
@@ -2814,6 +4059,8 @@ impl S3 for FS {
             ..
         } = req.input.clone();
 
+        verify_content_md5(&req.headers, &req.extensions)?;
+
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
@@ -2830,11 +4077,18 @@ impl S3 for FS {
             .await
             .map_err(ApiError::from)?;
 
+        if !BucketObjectLockSys::enabled(&bucket).await {
+            return Err(s3_error!(InvalidRequest, "Bucket is missing ObjectLockConfiguration"));
+        }
+
         let mut eval_metadata = HashMap::new();
         let legal_hold = legal_hold
             .map(|v| v.status.map(|v| v.as_str().to_string()))
             .unwrap_or_default()
             .unwrap_or("OFF".to_string());
+        if parse_legalhold_status(&legal_hold).is_none() {
+            return Err(s3_error!(MalformedXML, "Unknown legal hold status"));
+        }
 
         let now = OffsetDateTime::now_utc();
         eval_metadata.insert("x-amz-object-lock-legal-hold".to_string(), legal_hold);
@@ -2881,6 +4135,119 @@ impl S3 for FS {
         Ok(S3Response::new(output))
     }
 
+    #[tracing::instrument(level = "debug", skip(self, req))]
+    async fn restore_object(&self, req: S3Request<RestoreObjectInput>) -> S3Result<S3Response<RestoreObjectOutput>> {
+        let RestoreObjectInput {
+            bucket,
+            key,
+            version_id,
+            restore_request,
+            ..
+        } = req.input.clone();
+
+        let Some(restore_request) = restore_request else {
+            return Err(s3_error!(MalformedXML, "RestoreRequest is required"));
+        };
+        if restore_request.type_.is_some()
+            || restore_request.select_parameters.is_some()
+            || restore_request.output_location.is_some()
+        {
+            return Err(s3_error!(NotImplemented, "Only restoring the object data is supported"));
+        }
+        let days = match restore_request.days {
+            Some(days) if days > 0 => days,
+            _ => return Err(s3_error!(InvalidArgument, "Days must be a positive integer")),
+        };
+
+        let Some(store) = new_object_layer_fn() else {
+            return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+        };
+
+        let mut opts: ObjectOptions = get_opts(&bucket, &key, version_id, None, &req.headers)
+            .await
+            .map_err(ApiError::from)?;
+
+        let info = store.get_object_info(&bucket, &key, &opts).await.map_err(ApiError::from)?;
+        if info.transitioned_object.status != TRANSITION_COMPLETE {
+            return Err(s3_error!(InvalidObjectState, "Object has not been transitioned to a remote tier"));
+        }
+        if info.restore_ongoing {
+            return Err(s3_error!(RestoreAlreadyInProgress, "Object restore is already in progress"));
+        }
+
+        // A restored copy only gets a new expiry; otherwise the data is copied back in the background.
+        let already_restored = info.restore_expires.is_some();
+        let rreq = RestoreObjectRequest {
+            days: days as i64,
+            ..Default::default()
+        };
+        let now = OffsetDateTime::now_utc();
+        let status = if already_restored {
+            rustfs_filemeta::RestoreStatus::completed(rreq.expiry(now))
+        } else {
+            rustfs_filemeta::RestoreStatus::ongoing()
+        };
+
+        let mut eval_metadata = HashMap::new();
+        eval_metadata.insert(X_AMZ_RESTORE.as_str().to_string(), status.to_string());
+        eval_metadata.insert(X_AMZ_RESTORE_EXPIRY_DAYS.to_string(), days.to_string());
+        eval_metadata.insert(X_AMZ_RESTORE_REQUEST_DATE.to_string(), now.format(&Rfc3339).unwrap_or_default());
+
+        let popts = ObjectOptions {
+            mod_time: info.mod_time,
+            version_id: info.version_id.filter(|v| !v.is_nil()).map(|v| v.to_string()),
+            versioned: opts.versioned,
+            version_suspended: opts.version_suspended,
+            eval_metadata: Some(eval_metadata),
+            ..Default::default()
+        };
+        let info = store
+            .put_object_metadata(&bucket, &key, &popts)
+            .await
+            .map_err(ApiError::from)?;
+
+        let output = RestoreObjectOutput::default();
+        let version_id = info.version_id.map(|v| v.to_string()).unwrap_or_default();
+        let event_args = rustfs_notify::event::EventArgs {
+            event_name: EventName::ObjectRestorePost,
+            bucket_name: bucket.clone(),
+            object: info,
+            req_params: rustfs_utils::extract_req_params_header(&req.headers),
+            resp_elements: rustfs_utils::extract_resp_elements(&S3Response::new(output.clone())),
+            version_id,
+            host: rustfs_utils::get_request_host(&req.headers),
+            user_agent: rustfs_utils::get_request_user_agent(&req.headers),
+        };
+        let mut completed_args = event_args.clone();
+
+        // Asynchronous call will not block the response of the current request
+        tokio::spawn(async move {
+            rustfs_notify::global::notifier_instance().notify(event_args).await;
+        });
+
+        if already_restored {
+            return Ok(S3Response::new(output));
+        }
+
+        opts.version_id = popts.version_id;
+        opts.transition.restore_request = rreq;
+        tokio::spawn(async move {
+            if let Err(err) = store.restore_transitioned_object(&bucket, &key, &opts).await {
+                error!("restore_transitioned_object {}/{} failed: {:?}", bucket, key, err);
+                return;
+            }
+            if let Ok(info) = store.get_object_info(&bucket, &key, &opts).await {
+                completed_args.object = info;
+            }
+            completed_args.event_name = EventName::ObjectRestoreCompleted;
+            rustfs_notify::global::notifier_instance().notify(completed_args).await;
+        });
+
+        let mut resp = S3Response::new(output);
+        resp.status = Some(http::StatusCode::ACCEPTED);
+        Ok(resp)
+    }
+
     async fn get_object_retention(
         &self,
         req: S3Request<GetObjectRetentionInput>,
@@ -2908,6 +4275,7 @@ impl S3 for FS {
         let mode = object_info
             .user_defined
             .get("x-amz-object-lock-mode")
+            .filter(|v| !v.is_empty())
             .map(|v| ObjectLockRetentionMode::from(v.as_str().to_string()));
 
         let retain_until_date = object_info
@@ -2946,43 +4314,66 @@ impl S3 for FS {
         &self,
         req: S3Request<PutObjectRetentionInput>,
     ) -> S3Result<S3Response<PutObjectRetentionOutput>> {
+        let mut req = req;
         let PutObjectRetentionInput {
             bucket,
             key,
             retention,
             version_id,
+            bypass_governance_retention,
             ..
         } = req.input.clone();
 
+        verify_content_md5(&req.headers, &req.extensions)?;
+
         let Some(store) = new_object_layer_fn() else {
             return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
         };
 
         // check object lock
         let _ = metadata_sys::get_object_lock_config(&bucket).await.map_err(ApiError::from)?;
+        if !BucketObjectLockSys::enabled(&bucket).await {
+            return Err(s3_error!(InvalidRequest, "Bucket is missing ObjectLockConfiguration"));
+        }
 
-        // TODO: check allow
-
-        let mut eval_metadata = HashMap::new();
-
-        if let Some(v) = retention {
-            let mode = v.mode.map(|v| v.as_str().to_string()).unwrap_or_default();
-            let retain_until_date = v
-                .retain_until_date
-                .map(|v| OffsetDateTime::from(v).format(&Rfc3339).unwrap())
-                .unwrap_or_default();
-            let now = OffsetDateTime::now_utc();
-            eval_metadata.insert("x-amz-object-lock-mode".to_string(), mode);
-            eval_metadata.insert("x-amz-object-lock-retain-until-date".to_string(), retain_until_date);
-            eval_metadata.insert(
-                format!("{}{}", RESERVED_METADATA_PREFIX_LOWER, "objectlock-retention-timestamp"),
-                format!("{}.{:09}Z", now.format(&Rfc3339).unwrap(), now.nanosecond()),
-            );
+        let (mode, retain_until_date) = match retention {
+            Some(v) => (v.mode, v.retain_until_date.map(OffsetDateTime::from)),
+            None => (None, None),
+        };
+        if mode.is_some() != retain_until_date.is_some() {
+            return Err(s3_error!(MalformedXML, "Retention must specify both Mode and RetainUntilDate"));
+        }
+        if let Some(mode) = &mode {
+            if parse_ret_mode(mode.as_str()).is_none() {
+                return Err(s3_error!(MalformedXML, "Unknown wormMode directive"));
+            }
+        }
+        let now = OffsetDateTime::now_utc();
+        if retain_until_date.is_some_and(|until| until <= now) {
+            return Err(s3_error!(InvalidArgument, "The retain until date must be in the future"));
         }
 
         let mut opts: ObjectOptions = get_opts(&bucket, &key, version_id, None, &req.headers)
             .await
             .map_err(ApiError::from)?;
+
+        let current = store.get_object_info(&bucket, &key, &opts).await.map_err(ApiError::from)?;
+        let bypass_governance = bypass_governance_allowed(&mut req, bypass_governance_retention, &bucket, &key).await;
+        if !is_retention_update_allowed(&current, mode.as_ref(), retain_until_date, bypass_governance) {
+            return Err(s3_error!(AccessDenied, "Object is WORM protected and cannot be overwritten or deleted"));
+        }
+
+        // An empty mode and date remove the retention.
+        let mode = mode.map(|v| v.as_str().to_string()).unwrap_or_default();
+        let retain_until_date = retain_until_date.map(|v| v.format(&Rfc3339).unwrap()).unwrap_or_default();
+        let mut eval_metadata = HashMap::new();
+        eval_metadata.insert("x-amz-object-lock-mode".to_string(), mode);
+        eval_metadata.insert("x-amz-object-lock-retain-until-date".to_string(), retain_until_date);
+        eval_metadata.insert(
+            format!("{}{}", RESERVED_METADATA_PREFIX_LOWER, "objectlock-retention-timestamp"),
+            format!("{}.{:09}Z", now.format(&Rfc3339).unwrap(), now.nanosecond()),
+        );
+        opts.mod_time = current.mod_time;
         opts.eval_metadata = Some(eval_metadata);
 
         let object_info = store.put_object_metadata(&bucket, &key, &opts).await.map_err(|e| {
@@ -3017,3 +4408,127 @@ impl S3 for FS {
         Ok(S3Response::new(output))
     }
 }
+
+/// Response fields describing the server-side encryption of an object.
+#[derive(Debug, Default)]
+struct SseResponse {
+    server_side_encryption: Option<ServerSideEncryption>,
+    sse_customer_algorithm: Option<SSECustomerAlgorithm>,
+    sse_customer_key_md5: Option<SSECustomerKeyMD5>,
+    ssekms_key_id: Option<SSEKMSKeyId>,
+}
+
+impl SseResponse {
+    fn new(sse: Option<SseType>, customer_key_md5: Option<String>, kms_key_id: Option<String>) -> Self {
+        match sse {
+            Some(SseType::S3) => Self {
+                server_side_encryption: Some(ServerSideEncryption::from_static(ServerSideEncryption::AES256)),
+                ..Default::default()
+            },
+            Some(SseType::Kms) => Self {
+                server_side_encryption: Some(ServerSideEncryption::from_static(ServerSideEncryption::AWS_KMS)),
+                ssekms_key_id: kms_key_id,
+                ..Default::default()
+            },
+            Some(SseType::C) => Self {
+                sse_customer_algorithm: Some(ServerSideEncryption::AES256.to_owned()),
+                sse_customer_key_md5: customer_key_md5,
+                ..Default::default()
+            },
+            None => Self::default(),
+        }
+    }
+
+    fn new_object(enc: &encryption::ObjectEncryption) -> Self {
+        Self::new(Some(enc.sse), enc.customer_key_md5.clone(), enc.kms_key_id.clone())
+    }
+
+    /// Fields for reading an existing object, echoing the SSE-C key MD5 of the request.
+    fn object(metadata: &HashMap<String, String>, headers: &HeaderMap) -> Self {
+        let customer_key_md5 = encryption::parse_customer_key(headers, false)
+            .ok()
+            .flatten()
+            .map(|key| key.key_md5().to_owned());
+        Self::new(
+            SseType::from_metadata(metadata),
+            customer_key_md5,
+            encryption::kms_key_id(metadata).map(str::to_owned),
+        )
+    }
+}
+
+/// Verifies the body against the checksum sent in the trailer of an aws-chunked upload. s3s collects
+/// the trailer into `trailing_headers` while it decodes the body, so the value is read at EOF.
+fn trailing_checksum_reader(
+    reader: Box<dyn Reader>,
+    trailer: TrailingChecksum,
+    trailing_headers: Option<TrailingHeaders>,
+) -> Box<dyn Reader> {
+    let header = trailer.checksum_type.header();
+    Box::new(ChecksumReader::with_trailer(reader, trailer, move || {
+        trailing_headers
+            .as_ref()?
+            .read(|headers| headers.get(header).and_then(|v| v.to_str().ok()).map(str::to_owned))
+            .flatten()
+    }))
+}
+
+fn no_such_inventory_configuration() -> S3Error {
+    let mut err = S3Error::with_message(
+        S3ErrorCode::Custom("NoSuchConfiguration".into()),
+        "The specified configuration does not exist.".to_string(),
+    );
+    err.set_status_code(http::StatusCode::NOT_FOUND);
+    err
+}
+
+/// Returns whether the request asks to bypass governance retention and is allowed to.
+async fn bypass_governance_allowed<T>(req: &mut S3Request<T>, requested: Option<bool>, bucket: &str, object: &str) -> bool {
+    if requested != Some(true) {
+        return false;
+    }
+
+    let req_info = req.extensions.get_mut::<ReqInfo>().expect("ReqInfo not found");
+    req_info.bucket = Some(bucket.to_owned());
+    req_info.object = Some(object.to_owned());
+
+    authorize_request(req, Action::S3Action(S3Action::BypassGovernanceRetentionAction))
+        .await
+        .is_ok()
+}
+
+/// Refuses to permanently remove an object version protected by a legal hold or retention.
+///
+/// Deletes that only add a delete marker are always allowed.
+async fn check_object_lock_for_deletion(
+    bucket: &str,
+    object: &str,
+    opts: &ObjectOptions,
+    bypass_governance: bool,
+) -> S3Result<()> {
+    if opts.version_id.is_none() && opts.versioned {
+        return Ok(());
+    }
+
+    let Some(store) = new_object_layer_fn() else {
+        return Err(S3Error::with_message(S3ErrorCode::InternalError, "Not init".to_string()));
+    };
+
+    let get_opts = ObjectOptions {
+        version_id: opts.version_id.clone(),
+        versioned: opts.versioned,
+        version_suspended: opts.version_suspended,
+        ..Default::default()
+    };
+    let info = match store.get_object_info(bucket, object, &get_opts).await {
+        Ok(info) => info,
+        Err(err) if is_err_object_not_found(&err) || is_err_version_not_found(&err) => return Ok(()),
+        Err(err) => return Err(ApiError::from(err).into()),
+    };
+
+    if enforce_retention_bypass_for_deletion(&info, bypass_governance) {
+        return Err(s3_error!(AccessDenied, "Object is WORM protected and cannot be overwritten or deleted"));
+    }
+
+    Ok(())
+}
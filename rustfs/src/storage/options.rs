@@ -12,16 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use http::{HeaderMap, HeaderValue};
+use crate::server::BodyDigest;
+use http::{Extensions, HeaderMap, HeaderValue};
+use rustfs_ecstore::bucket::object_lock::objectlock::{parse_legalhold_status, parse_ret_mode};
+use rustfs_ecstore::bucket::object_lock::objectlock_sys::{BucketObjectLockSys, default_retention_until};
 use rustfs_ecstore::bucket::versioning_sys::BucketVersioningSys;
 use rustfs_ecstore::error::Result;
 use rustfs_ecstore::error::StorageError;
+use rustfs_ecstore::store_api::ObjectInfo;
 use rustfs_ecstore::store_api::ObjectOptions;
+use rustfs_ecstore::store_api::WritePreconditions;
+use rustfs_rio::{Checksum, ChecksumType};
+use rustfs_utils::crypto::hex;
 use rustfs_utils::path::is_dir_object;
+use rustfs_utils::path::trim_etag;
+use s3s::S3ErrorCode;
+use s3s::S3Result;
+use s3s::dto::{self, ChecksumAlgorithm, ChecksumMode, Range, Timestamp};
+use s3s::header::{X_AMZ_OBJECT_LOCK_LEGAL_HOLD, X_AMZ_OBJECT_LOCK_MODE, X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE};
+use s3s::s3_error;
 use std::collections::HashMap;
 use std::sync::LazyLock;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use uuid::Uuid;
 
+const CONTENT_MD5: &str = "content-md5";
+
+/// Names the headers sent in the trailer of an aws-chunked body.
+const AMZ_TRAILER: &str = "x-amz-trailer";
+
 /// Creates options for deleting an object in a bucket.
 pub async fn del_opts(
     bucket: &str,
@@ -229,6 +249,344 @@ static SUPPORTED_HEADERS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
     ]
 });
 
+/// Parses an `x-amz-copy-source-range` header (`bytes=first-last`) against the source size.
+///
+/// Returns the start offset and length of the requested range.
+pub fn parse_copy_source_range(range: &str, object_size: i64) -> S3Result<(i64, i64)> {
+    let Ok(Range::Int { first, last: Some(last) }) = Range::parse(range) else {
+        return Err(s3_error!(InvalidArgument, "Copy Source Range must be in the format bytes=first-last"));
+    };
+
+    if object_size <= 0 || last as i64 >= object_size {
+        return Err(s3_error!(InvalidRange, "The requested range is not satisfiable"));
+    }
+
+    Ok((first as i64, (last - first) as i64 + 1))
+}
+
+/// Evaluates the `If-Match`, `If-None-Match`, `If-Modified-Since` and `If-Unmodified-Since` headers of a GET or HEAD.
+///
+/// A failed `If-Match` or `If-Unmodified-Since` returns `PreconditionFailed`, a failed `If-None-Match` or
+/// `If-Modified-Since` returns `NotModified`. A matching ETag condition takes precedence over its date counterpart.
+pub fn check_preconditions(
+    info: &ObjectInfo,
+    if_match: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<Timestamp>,
+    if_unmodified_since: Option<Timestamp>,
+) -> S3Result<()> {
+    let etag = info.etag.as_deref().map(trim_etag).unwrap_or_default();
+    // HTTP dates only carry second precision.
+    let mod_time = info.mod_time.map(|t| t.replace_nanosecond(0).unwrap_or(t));
+
+    let etag_matches = |cond: &str| cond.split(',').map(|v| v.trim()).any(|v| v == "*" || trim_etag(v) == etag);
+
+    if let Some(cond) = if_match {
+        if !etag_matches(cond) {
+            return Err(s3_error!(PreconditionFailed));
+        }
+    } else if let (Some(since), Some(mod_time)) = (if_unmodified_since, mod_time) {
+        if mod_time > OffsetDateTime::from(since) {
+            return Err(s3_error!(PreconditionFailed));
+        }
+    }
+
+    let not_modified = || {
+        let mut err = s3_error!(NotModified);
+        if let Ok(value) = HeaderValue::from_str(&format!("\"{etag}\"")) {
+            let mut headers = HeaderMap::new();
+            headers.insert(http::header::ETAG, value);
+            err.set_headers(headers);
+        }
+        err
+    };
+
+    if let Some(cond) = if_none_match {
+        if etag_matches(cond) {
+            return Err(not_modified());
+        }
+    } else if let (Some(since), Some(mod_time)) = (if_modified_since, mod_time) {
+        if mod_time <= OffsetDateTime::from(since) {
+            return Err(not_modified());
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluates the `x-amz-copy-source-if-*` preconditions against the source object.
+///
+/// Unlike a GET, every failed condition is reported as `PreconditionFailed`.
+pub fn check_copy_source_preconditions(
+    info: &ObjectInfo,
+    if_match: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<Timestamp>,
+    if_unmodified_since: Option<Timestamp>,
+) -> S3Result<()> {
+    check_preconditions(info, if_match, if_none_match, if_modified_since, if_unmodified_since).map_err(|err| {
+        if *err.code() == S3ErrorCode::NotModified {
+            s3_error!(PreconditionFailed)
+        } else {
+            err
+        }
+    })
+}
+
+/// Builds the conditional write checks for a PUT or CompleteMultipartUpload.
+///
+/// Only `If-None-Match: *` is supported; other `If-None-Match` values are rejected.
+pub fn get_write_preconditions(if_match: Option<&str>, if_none_match: Option<&str>) -> S3Result<Option<WritePreconditions>> {
+    let if_none_match = match if_none_match.map(str::trim) {
+        None => false,
+        Some("*") => true,
+        Some(_) => return Err(s3_error!(NotImplemented, "If-None-Match only supports the value *")),
+    };
+
+    if if_match.is_none() && !if_none_match {
+        return Ok(None);
+    }
+
+    Ok(Some(WritePreconditions {
+        if_match: if_match.map(str::to_owned),
+        if_none_match,
+    }))
+}
+
+/// Builds the object lock metadata of a new object from the `x-amz-object-lock-*` headers.
+///
+/// Without retention headers the bucket default retention applies. Buckets without object lock reject the headers.
+pub async fn get_object_lock_metadata(
+    bucket: &str,
+    mode: Option<&str>,
+    retain_until_date: Option<Timestamp>,
+    legal_hold: Option<&str>,
+) -> S3Result<HashMap<String, String>> {
+    let mut metadata = HashMap::new();
+
+    let requested = mode.is_some() || retain_until_date.is_some() || legal_hold.is_some();
+    if !BucketObjectLockSys::enabled(bucket).await {
+        if requested {
+            return Err(s3_error!(InvalidRequest, "Bucket is missing ObjectLockConfiguration"));
+        }
+        return Ok(metadata);
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let retention = match (mode, retain_until_date) {
+        (Some(mode), Some(until)) => {
+            let mode = parse_ret_mode(mode).ok_or_else(|| s3_error!(InvalidArgument, "Unknown wormMode directive"))?;
+            let until = OffsetDateTime::from(until);
+            if until <= now {
+                return Err(s3_error!(InvalidArgument, "The retain until date must be in the future"));
+            }
+            Some((mode, until))
+        }
+        (None, None) => BucketObjectLockSys::get(bucket)
+            .await
+            .and_then(|retention| default_retention_until(&retention, now)),
+        _ => {
+            return Err(s3_error!(
+                InvalidArgument,
+                "x-amz-object-lock-retain-until-date and x-amz-object-lock-mode must both be supplied"
+            ));
+        }
+    };
+
+    if let Some((mode, until)) = retention {
+        let until = until
+            .format(&Rfc3339)
+            .map_err(|e| s3_error!(InternalError, "format retain until date failed: {}", e))?;
+        metadata.insert(X_AMZ_OBJECT_LOCK_MODE.as_str().to_owned(), mode.as_str().to_owned());
+        metadata.insert(X_AMZ_OBJECT_LOCK_RETAIN_UNTIL_DATE.as_str().to_owned(), until);
+    }
+
+    if let Some(legal_hold) = legal_hold {
+        let status = parse_legalhold_status(legal_hold).ok_or_else(|| s3_error!(InvalidArgument, "Unknown legal hold status"))?;
+        metadata.insert(X_AMZ_OBJECT_LOCK_LEGAL_HOLD.as_str().to_owned(), status.as_str().to_owned());
+    }
+
+    Ok(metadata)
+}
+
+/// Reads the checksum sent in an `x-amz-checksum-*` header.
+///
+/// The `-N` part count suffix of a composite multipart checksum is dropped.
+pub fn get_content_checksum(headers: &HeaderMap<HeaderValue>) -> S3Result<Option<Checksum>> {
+    let mut checksum = None;
+    for checksum_type in ChecksumType::ALL {
+        let Some(value) = headers.get(checksum_type.header()) else {
+            continue;
+        };
+
+        if checksum.is_some() {
+            return Err(s3_error!(InvalidRequest, "Expecting a single x-amz-checksum- header"));
+        }
+
+        let value = value.to_str().unwrap_or_default();
+        let encoded = value.split_once('-').map_or(value, |(encoded, _)| encoded);
+        checksum = Some(
+            Checksum::new(checksum_type, encoded)
+                .ok_or_else(|| s3_error!(InvalidArgument, "Value for {} header is invalid", checksum_type.header()))?,
+        );
+    }
+
+    // Trailing checksums are announced without a header value.
+    if checksum.is_none() && headers.contains_key("x-amz-sdk-checksum-algorithm") && !headers.contains_key(AMZ_TRAILER) {
+        return Err(s3_error!(
+            InvalidRequest,
+            "x-amz-sdk-checksum-algorithm specified, but no corresponding x-amz-checksum-* header was found"
+        ));
+    }
+
+    Ok(checksum)
+}
+
+/// Reads the checksum type announced in `x-amz-trailer`, whose value is sent after an aws-chunked body.
+pub fn get_trailing_checksum(headers: &HeaderMap<HeaderValue>) -> S3Result<Option<ChecksumType>> {
+    let Some(trailer) = headers.get(AMZ_TRAILER) else {
+        return Ok(None);
+    };
+
+    let mut checksum = None;
+    for name in trailer.to_str().unwrap_or_default().split(',').map(str::trim) {
+        if name.is_empty() {
+            continue;
+        }
+        let Some(checksum_type) = ChecksumType::ALL.into_iter().find(|t| name.eq_ignore_ascii_case(t.header())) else {
+            return Err(s3_error!(
+                InvalidRequest,
+                "The value specified in the x-amz-trailer header is not supported"
+            ));
+        };
+        if checksum.replace(checksum_type).is_some() {
+            return Err(s3_error!(InvalidRequest, "Expecting a single x-amz-checksum- header"));
+        }
+    }
+
+    if checksum.is_some() && ChecksumType::ALL.iter().any(|t| headers.contains_key(t.header())) {
+        return Err(s3_error!(InvalidRequest, "Expecting a single x-amz-checksum- header"));
+    }
+
+    Ok(checksum)
+}
+
+/// Reads the `Content-MD5` header as the hex encoded md5 the body has to match.
+pub fn get_content_md5(headers: &HeaderMap<HeaderValue>) -> S3Result<Option<String>> {
+    let Some(value) = headers.get(CONTENT_MD5) else {
+        return Ok(None);
+    };
+
+    match base64_simd::STANDARD.decode_to_vec(value.as_bytes()) {
+        Ok(md5) if md5.len() == 16 => Ok(Some(hex(md5))),
+        _ => Err(s3_error!(InvalidDigest)),
+    }
+}
+
+/// Checks the integrity header S3 requires for some requests, either `Content-MD5` or a flexible checksum.
+pub fn require_content_md5(headers: &HeaderMap<HeaderValue>) -> S3Result<()> {
+    let has_checksum = get_content_checksum(headers)?.is_some();
+    if get_content_md5(headers)?.is_none() && !has_checksum {
+        return Err(s3_error!(InvalidRequest, "Missing required header for this request: Content-Md5"));
+    }
+    Ok(())
+}
+
+/// Like [`require_content_md5`] for an XML request body, which also has to match the header. The body
+/// is checked before s3s parses it, see [`BodyDigest`].
+pub fn verify_content_md5(headers: &HeaderMap<HeaderValue>, extensions: &Extensions) -> S3Result<()> {
+    require_content_md5(headers)?;
+    match extensions.get::<BodyDigest>() {
+        Some(BodyDigest::Verified) => Ok(()),
+        _ => Err(s3_error!(
+            BadDigest,
+            "The Content-MD5 or checksum you specified did not match what was received"
+        )),
+    }
+}
+
+/// Validates the checksum requested for a multipart upload.
+///
+/// Returns the checksum type and whether it covers the full object, CRC64NVME defaults to a full
+/// object checksum and the other algorithms to a composite one.
+pub fn get_multipart_checksum(
+    algorithm: Option<&ChecksumAlgorithm>,
+    checksum_type: Option<&dto::ChecksumType>,
+) -> S3Result<Option<(ChecksumType, bool)>> {
+    let Some(algorithm) = algorithm else {
+        if checksum_type.is_some() {
+            return Err(s3_error!(InvalidRequest, "x-amz-checksum-type requires x-amz-checksum-algorithm"));
+        }
+        return Ok(None);
+    };
+
+    let Some(algorithm) = ChecksumType::from_algorithm(algorithm.as_str()) else {
+        return Err(s3_error!(InvalidArgument, "Invalid checksum algorithm {}", algorithm.as_str()));
+    };
+
+    let full_object = match checksum_type.map(|v| v.as_str()) {
+        None => algorithm == ChecksumType::Crc64Nvme,
+        Some(dto::ChecksumType::FULL_OBJECT) => true,
+        Some(dto::ChecksumType::COMPOSITE) => false,
+        Some(v) => return Err(s3_error!(InvalidArgument, "Invalid checksum type {}", v)),
+    };
+
+    if (full_object && !algorithm.can_merge()) || (!full_object && algorithm == ChecksumType::Crc64Nvme) {
+        return Err(s3_error!(
+            InvalidRequest,
+            "The {} checksum algorithm does not support the requested checksum type",
+            algorithm.as_str()
+        ));
+    }
+
+    Ok(Some((algorithm, full_object)))
+}
+
+/// Checksum returned by GetObject and HeadObject when `x-amz-checksum-mode` is `ENABLED`.
+///
+/// With a part number only the checksum of that part is returned.
+pub fn object_checksum(info: &ObjectInfo, mode: Option<&ChecksumMode>, part_number: Option<usize>) -> Option<Checksum> {
+    if mode.is_none_or(|v| v.as_str() != ChecksumMode::ENABLED) {
+        return None;
+    }
+
+    let checksum = info.decoded_checksum()?;
+    let Some(part_number) = part_number else {
+        return Some(checksum);
+    };
+
+    if checksum.parts.is_empty() {
+        return (part_number == 1).then_some(checksum);
+    }
+
+    let idx = info.parts.iter().position(|p| p.number == part_number)?;
+    Checksum::new(checksum.checksum_type, checksum.parts.get(idx)?)
+}
+
+/// Checksum fields of a response, empty without a checksum.
+pub fn checksum_dto(checksum: Option<&Checksum>) -> dto::Checksum {
+    let mut dto = dto::Checksum::default();
+    let Some(checksum) = checksum else {
+        return dto;
+    };
+
+    let value = Some(checksum.value());
+    match checksum.checksum_type {
+        ChecksumType::Crc32 => dto.checksum_crc32 = value,
+        ChecksumType::Crc32c => dto.checksum_crc32c = value,
+        ChecksumType::Sha1 => dto.checksum_sha1 = value,
+        ChecksumType::Sha256 => dto.checksum_sha256 = value,
+        ChecksumType::Crc64Nvme => dto.checksum_crc64nvme = value,
+    }
+    dto.checksum_type = Some(dto::ChecksumType::from_static(if checksum.full_object || checksum.parts.is_empty() {
+        dto::ChecksumType::FULL_OBJECT
+    } else {
+        dto::ChecksumType::COMPOSITE
+    }));
+
+    dto
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -646,4 +1004,214 @@ mod tests {
         assert_eq!(metadata.get("cache-control"), Some(&"public".to_string()));
         assert!(!metadata.contains_key("authorization"));
     }
+
+    #[test]
+    fn test_parse_copy_source_range() {
+        assert_eq!(parse_copy_source_range("bytes=0-99", 1000).unwrap(), (0, 100));
+        assert_eq!(parse_copy_source_range("bytes=500-999", 1000).unwrap(), (500, 500));
+
+        let err = parse_copy_source_range("bytes=500-1000", 1000).unwrap_err();
+        assert_eq!(*err.code(), s3s::S3ErrorCode::InvalidRange);
+
+        for invalid in ["bytes=-100", "bytes=100-", "0-99", "bytes=99-0"] {
+            let err = parse_copy_source_range(invalid, 1000).unwrap_err();
+            assert_eq!(*err.code(), s3s::S3ErrorCode::InvalidArgument, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_check_copy_source_preconditions() {
+        let mod_time = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let info = ObjectInfo {
+            etag: Some("abc123".to_string()),
+            mod_time: Some(mod_time),
+            ..Default::default()
+        };
+        let before = Timestamp::from(mod_time - time::Duration::hours(1));
+        let after = Timestamp::from(mod_time + time::Duration::hours(1));
+
+        assert!(check_copy_source_preconditions(&info, None, None, None, None).is_ok());
+        assert!(check_copy_source_preconditions(&info, Some("\"abc123\""), None, None, None).is_ok());
+        assert!(check_copy_source_preconditions(&info, Some("*"), None, None, None).is_ok());
+        assert!(check_copy_source_preconditions(&info, Some("other"), None, None, None).is_err());
+        assert!(check_copy_source_preconditions(&info, None, Some("abc123"), None, None).is_err());
+        assert!(check_copy_source_preconditions(&info, None, Some("other"), None, None).is_ok());
+        assert!(check_copy_source_preconditions(&info, None, None, Some(before.clone()), None).is_ok());
+        assert!(check_copy_source_preconditions(&info, None, None, Some(after.clone()), None).is_err());
+        assert!(check_copy_source_preconditions(&info, None, None, None, Some(after)).is_ok());
+        assert!(check_copy_source_preconditions(&info, None, None, None, Some(before.clone())).is_err());
+        // If-Match takes precedence over If-Unmodified-Since.
+        assert!(check_copy_source_preconditions(&info, Some("abc123"), None, None, Some(before)).is_ok());
+    }
+
+    #[test]
+    fn test_check_preconditions() {
+        let mod_time = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let info = ObjectInfo {
+            etag: Some("abc123".to_string()),
+            mod_time: Some(mod_time),
+            ..Default::default()
+        };
+        let before = Timestamp::from(mod_time - time::Duration::hours(1));
+        let after = Timestamp::from(mod_time + time::Duration::hours(1));
+        let code = |res: S3Result<()>| res.unwrap_err().code().clone();
+
+        assert!(check_preconditions(&info, Some("abc123"), Some("other"), None, None).is_ok());
+        assert_eq!(
+            code(check_preconditions(&info, Some("other"), None, None, None)),
+            S3ErrorCode::PreconditionFailed
+        );
+        assert_eq!(
+            code(check_preconditions(&info, None, None, None, Some(before.clone()))),
+            S3ErrorCode::PreconditionFailed
+        );
+        assert_eq!(
+            code(check_preconditions(&info, None, Some("\"abc123\""), None, None)),
+            S3ErrorCode::NotModified
+        );
+        assert_eq!(
+            code(check_preconditions(&info, None, None, Some(after.clone()), None)),
+            S3ErrorCode::NotModified
+        );
+        // If-None-Match takes precedence over If-Modified-Since.
+        assert!(check_preconditions(&info, None, Some("other"), Some(after), None).is_ok());
+        assert!(check_preconditions(&info, None, None, Some(before), None).is_ok());
+    }
+
+    #[test]
+    fn test_get_write_preconditions() {
+        assert!(get_write_preconditions(None, None).unwrap().is_none());
+
+        let create_only = get_write_preconditions(None, Some("*")).unwrap().unwrap();
+        assert!(create_only.if_none_match);
+        assert!(create_only.if_match.is_none());
+
+        let cas = get_write_preconditions(Some("\"abc123\""), None).unwrap().unwrap();
+        assert_eq!(cas.if_match.as_deref(), Some("\"abc123\""));
+        assert!(!cas.if_none_match);
+
+        let err = get_write_preconditions(None, Some("\"abc123\"")).unwrap_err();
+        assert_eq!(*err.code(), S3ErrorCode::NotImplemented);
+    }
+
+    #[test]
+    fn test_get_content_checksum() {
+        let mut headers = HeaderMap::new();
+        assert!(get_content_checksum(&headers).unwrap().is_none());
+
+        let crc32 = Checksum::compute(ChecksumType::Crc32, b"data");
+        headers.insert("x-amz-checksum-crc32", HeaderValue::from_str(&crc32.encoded).unwrap());
+        assert_eq!(get_content_checksum(&headers).unwrap(), Some(crc32.clone()));
+
+        headers.insert("x-amz-checksum-crc32", HeaderValue::from_str(&format!("{}-3", crc32.encoded)).unwrap());
+        assert_eq!(get_content_checksum(&headers).unwrap(), Some(crc32.clone()));
+
+        headers.insert("x-amz-checksum-sha256", HeaderValue::from_str(&crc32.encoded).unwrap());
+        assert_eq!(*get_content_checksum(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidRequest);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-checksum-sha256", HeaderValue::from_str(&crc32.encoded).unwrap());
+        assert_eq!(*get_content_checksum(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidArgument);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-sdk-checksum-algorithm", HeaderValue::from_static("CRC32"));
+        assert!(get_content_checksum(&headers).is_err());
+
+        // The value follows in the trailer
+        headers.insert("x-amz-trailer", HeaderValue::from_static("x-amz-checksum-crc32"));
+        assert!(get_content_checksum(&headers).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_trailing_checksum() {
+        let mut headers = HeaderMap::new();
+        assert!(get_trailing_checksum(&headers).unwrap().is_none());
+
+        headers.insert("x-amz-trailer", HeaderValue::from_static("x-amz-checksum-crc64nvme"));
+        assert_eq!(get_trailing_checksum(&headers).unwrap(), Some(ChecksumType::Crc64Nvme));
+
+        headers.insert("x-amz-trailer", HeaderValue::from_static("x-amz-checksum-crc32,x-amz-checksum-sha1"));
+        assert_eq!(*get_trailing_checksum(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidRequest);
+
+        headers.insert("x-amz-trailer", HeaderValue::from_static("x-amz-meta-foo"));
+        assert_eq!(*get_trailing_checksum(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidRequest);
+
+        let crc32 = Checksum::compute(ChecksumType::Crc32, b"data");
+        headers.insert("x-amz-trailer", HeaderValue::from_static("x-amz-checksum-crc32"));
+        headers.insert("x-amz-checksum-crc32", HeaderValue::from_str(&crc32.encoded).unwrap());
+        assert!(get_trailing_checksum(&headers).is_err());
+    }
+
+    #[test]
+    fn test_get_content_md5() {
+        let mut headers = HeaderMap::new();
+        assert!(get_content_md5(&headers).unwrap().is_none());
+        assert_eq!(*require_content_md5(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidRequest);
+
+        // md5 of "hello world"
+        headers.insert("content-md5", HeaderValue::from_static("XrY7u+Ae7tCTyyK7j1rNww=="));
+        assert_eq!(get_content_md5(&headers).unwrap().as_deref(), Some("5eb63bbbe01eeed093cb22bb8f5acdc3"));
+        assert!(require_content_md5(&headers).is_ok());
+
+        headers.insert("content-md5", HeaderValue::from_static("not-base64"));
+        assert_eq!(*get_content_md5(&headers).unwrap_err().code(), s3s::S3ErrorCode::InvalidDigest);
+
+        let mut headers = HeaderMap::new();
+        let crc32 = Checksum::compute(ChecksumType::Crc32, b"data");
+        headers.insert("x-amz-checksum-crc32", HeaderValue::from_str(&crc32.encoded).unwrap());
+        assert!(require_content_md5(&headers).is_ok());
+    }
+
+    #[test]
+    fn test_verify_content_md5() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-md5", HeaderValue::from_static("XrY7u+Ae7tCTyyK7j1rNww=="));
+
+        let mut extensions = Extensions::new();
+        extensions.insert(BodyDigest::Verified);
+        assert!(verify_content_md5(&headers, &extensions).is_ok());
+
+        // The body did not match the digest it was sent with
+        extensions.insert(BodyDigest::Mismatch);
+        assert_eq!(
+            *verify_content_md5(&headers, &extensions).unwrap_err().code(),
+            s3s::S3ErrorCode::BadDigest
+        );
+
+        // The body could not be checked
+        assert_eq!(
+            *verify_content_md5(&headers, &Extensions::new()).unwrap_err().code(),
+            s3s::S3ErrorCode::BadDigest
+        );
+
+        // A missing header is reported before the digest
+        assert_eq!(
+            *verify_content_md5(&HeaderMap::new(), &extensions).unwrap_err().code(),
+            s3s::S3ErrorCode::InvalidRequest
+        );
+    }
+
+    #[test]
+    fn test_get_multipart_checksum() {
+        let algorithm = |v: &'static str| ChecksumAlgorithm::from_static(v);
+        let checksum_type = |v: &'static str| dto::ChecksumType::from_static(v);
+
+        assert_eq!(get_multipart_checksum(None, None).unwrap(), None);
+        assert!(get_multipart_checksum(None, Some(&checksum_type("COMPOSITE"))).is_err());
+        assert_eq!(
+            get_multipart_checksum(Some(&algorithm("crc32")), None).unwrap(),
+            Some((ChecksumType::Crc32, false))
+        );
+        assert_eq!(
+            get_multipart_checksum(Some(&algorithm("CRC64NVME")), None).unwrap(),
+            Some((ChecksumType::Crc64Nvme, true))
+        );
+        assert_eq!(
+            get_multipart_checksum(Some(&algorithm("CRC32C")), Some(&checksum_type("FULL_OBJECT"))).unwrap(),
+            Some((ChecksumType::Crc32c, true))
+        );
+        assert!(get_multipart_checksum(Some(&algorithm("SHA256")), Some(&checksum_type("FULL_OBJECT"))).is_err());
+        assert!(get_multipart_checksum(Some(&algorithm("CRC64NVME")), Some(&checksum_type("COMPOSITE"))).is_err());
+        assert!(get_multipart_checksum(Some(&algorithm("MD5")), None).is_err());
+    }
 }
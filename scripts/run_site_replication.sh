@@ -0,0 +1,96 @@
+#!/bin/bash -e
+# Copyright 2024 RustFS Team
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+# Starts two local RustFS sites, links them with site replication and
+# checks that a bucket created on the first site shows up on the second.
+
+if [ -z "$SKIP_BUILD" ]; then
+    cargo build -p rustfs --bins
+fi
+
+ACCESS_KEY=${ACCESS_KEY:-rustfsadmin}
+SECRET_KEY=${SECRET_KEY:-rustfsadmin}
+SITE1="http://127.0.0.1:9100"
+SITE2="http://127.0.0.1:9200"
+
+export RUSTFS_ACCESS_KEY=$ACCESS_KEY
+export RUSTFS_SECRET_KEY=$SECRET_KEY
+export RUSTFS_CONSOLE_ENABLE=false
+export RUST_LOG=${RUST_LOG:-"rustfs=info,ecstore=info"}
+
+pids=()
+cleanup() {
+    for pid in "${pids[@]}"; do
+        kill -9 "$pid" 2>/dev/null || true
+    done
+}
+trap cleanup EXIT
+
+start_site() {
+    local name=$1 port=$2
+    mkdir -p ./target/volume/sr-$name
+    RUSTFS_VOLUMES="./target/volume/sr-$name" RUSTFS_ADDRESS=":$port" \
+        ./target/debug/rustfs > ./target/sr-$name.log 2>&1 &
+    pids+=($!)
+}
+
+admin() {
+    local method=$1 url=$2
+    shift 2
+    curl -sf -X "$method" --aws-sigv4 "aws:amz:us-east-1:s3" --user "$ACCESS_KEY:$SECRET_KEY" \
+        -H "x-amz-content-sha256: UNSIGNED-PAYLOAD" "$url" "$@"
+}
+
+wait_ready() {
+    for _ in $(seq 1 60); do
+        if curl -sf -o /dev/null "$1/rustfs/health/ready"; then
+            return 0
+        fi
+        sleep 1
+    done
+    echo "site $1 did not start"
+    exit 1
+}
+
+start_site site1 9100
+start_site site2 9200
+wait_ready $SITE1
+wait_ready $SITE2
+
+echo "Linking sites"
+admin PUT "$SITE1/rustfs/admin/v3/site-replication/add" -H "Content-Type: application/json" -d "[
+    {\"name\": \"site1\", \"endpoint\": \"$SITE1\", \"accessKey\": \"$ACCESS_KEY\", \"secretKey\": \"$SECRET_KEY\"},
+    {\"name\": \"site2\", \"endpoint\": \"$SITE2\", \"accessKey\": \"$ACCESS_KEY\", \"secretKey\": \"$SECRET_KEY\"}
+]"
+echo
+
+echo "Creating bucket on site1"
+admin PUT "$SITE1/sr-test-bucket"
+
+echo "Status on site2"
+admin GET "$SITE2/rustfs/admin/v3/site-replication/status"
+echo
+
+if admin HEAD "$SITE2/sr-test-bucket" -I > /dev/null; then
+    echo "bucket replicated to site2"
+else
+    echo "bucket missing on site2"
+    exit 1
+fi
+
+if [ -z "$KEEP_RUNNING" ]; then
+    admin PUT "$SITE1/rustfs/admin/v3/site-replication/remove" -H "Content-Type: application/json" -d '{"removeAll": true}'
+    echo
+fi